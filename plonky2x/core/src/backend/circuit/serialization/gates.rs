@@ -28,6 +28,7 @@ use starkyx::plonky2::cubic::mul_gate::MulCubicGate;
 
 use super::registry::{SerializationRegistry, Serializer};
 use super::PlonkParameters;
+use crate::frontend::hash::poseidon::poseidon2::gate::Poseidon2Gate;
 use crate::frontend::uint::num::u32::gates::add_many_u32::U32AddManyGate;
 use crate::frontend::uint::num::u32::gates::arithmetic_u32::U32ArithmeticGate;
 use crate::frontend::uint::num::u32::gates::comparison::ComparisonGate;
@@ -94,6 +95,7 @@ impl<L: PlonkParameters<D>, const D: usize> GateRegistry<L, D> {
         r.register::<NoopGate>();
         r.register::<PoseidonMdsGate<L::Field, D>>();
         r.register::<PoseidonGate<L::Field, D>>();
+        r.register::<Poseidon2Gate<L::Field, D>>();
         r.register::<PublicInputGate>();
         r.register::<RandomAccessGate<L::Field, D>>();
         r.register::<ReducingExtensionGate<D>>();
@@ -29,6 +29,9 @@ use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use plonky2::recursion::dummy_circuit::DummyProofGenerator;
 use plonky2::util::serialization::{Buffer, IoResult, Read, WitnessGeneratorSerializer, Write};
+use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+use starkyx::chip::ec::weierstrass::bn254::Bn254;
+use starkyx::chip::ec::weierstrass::secp256k1::Secp256K1;
 use starkyx::machine::hash::blake::blake2b::BLAKE2B;
 use starkyx::machine::hash::sha::sha256::SHA256;
 use starkyx::machine::hash::sha::sha512::SHA512;
@@ -38,8 +41,21 @@ use starkyx::plonky2::cubic::mul_gate::MulCubicGenerator;
 use super::registry::{SerializationRegistry, Serializer};
 use super::PlonkParameters;
 use crate as plonky2x;
-use crate::frontend::ecc::curve25519::curta::proof_hint::EcOpProofHint;
-use crate::frontend::ecc::curve25519::curta::result_hint::EcOpResultHint;
+use crate::frontend::builder::permutation::SortHint;
+use crate::frontend::ecc::babyjubjub::params::BabyJubjub;
+use crate::frontend::ecc::bls12_381::fp::{Bls12381FpDivHint, Bls12381FpSqrtHint};
+use crate::frontend::ecc::bls12_381::fp12::Bls12381Fp12InverseHint;
+use crate::frontend::ecc::bls12_381::fp2::{Bls12381Fp2DivHint, Bls12381Fp2SqrtHint};
+use crate::frontend::ecc::bls12_381::hash_to_curve::Bls12381SswuSqrtHint;
+use crate::frontend::ecc::bn254::fp::Bn254FpDivHint;
+use crate::frontend::ecc::bn254::fp12::Bn254Fp12InverseHint;
+use crate::frontend::ecc::bn254::fp2::Bn254Fp2DivHint;
+use crate::frontend::ecc::curta::proof_hint::EcOpProofHint;
+use crate::frontend::ecc::curta::result_hint::EcOpResultHint;
+use crate::frontend::ecc::secp256k1::ecdsa::{Secp256k1RecoverHint, Secp256k1ScalarInverseHint};
+use crate::frontend::ecc::secp256k1::schnorr::Secp256k1LiftXHint;
+use crate::frontend::ecc::secp256r1::ecdsa::Secp256r1ScalarInverseHint;
+use crate::frontend::ecc::secp256r1::params::Secp256R1;
 use crate::frontend::eth::beacon::generators::{
     BeaconAllWithdrawalsHint, BeaconBalanceBatchWitnessHint, BeaconBalanceGenerator,
     BeaconBalanceWitnessHint, BeaconBalancesGenerator, BeaconBlockRootsHint, BeaconGraffitiHint,
@@ -53,11 +69,12 @@ use crate::frontend::eth::beacon::vars::{
     BeaconValidatorsVariable, BeaconWithdrawalVariable, BeaconWithdrawalsVariable,
 };
 use crate::frontend::eth::storage::generators::{
-    EthBlockGenerator, EthLogGenerator, EthStorageKeyGenerator, EthStorageProofHint,
+    EthAccountProofHint, EthBlockGenerator, EthFullStorageProofHint, EthHeaderRlpHint,
+    EthLogGenerator, EthStorageKeyGenerator, EthStorageProofHint,
 };
 use crate::frontend::hash::curta::digest_hint::HashDigestHint;
 use crate::frontend::hash::curta::proof_hint::HashProofHint;
-use crate::frontend::hash::keccak::keccak256::Keccak256Generator;
+use crate::frontend::hash::poseidon::poseidon2::gate::Poseidon2Generator;
 use crate::frontend::hash::poseidon::poseidon256::PoseidonHashOutVariable;
 use crate::frontend::hint::asynchronous::generator::{AsyncHintDataRef, AsyncHintRef};
 use crate::frontend::hint::asynchronous::hint::AsyncHint;
@@ -71,8 +88,12 @@ use crate::frontend::uint::num::u32::gates::arithmetic_u32::U32ArithmeticGenerat
 use crate::frontend::uint::num::u32::gates::comparison::ComparisonGenerator;
 use crate::frontend::uint::num::u32::gates::range_check_u32::U32RangeCheckGenerator;
 use crate::frontend::uint::num::u32::gates::subtraction_u32::U32SubtractionGenerator;
+use crate::frontend::uint::uint128::U128Variable;
+use crate::frontend::uint::uint512::U512Variable;
 use crate::frontend::uint::uint64::U64Variable;
-use crate::frontend::vars::{Bytes32Variable, SubArrayExtractorHint, U256Variable};
+use crate::frontend::vars::{
+    Bytes32Variable, MemoryGenerator, SubArrayExtractorHint, U256Variable,
+};
 use crate::prelude::{ArrayVariable, BoolVariable, U32Variable, Variable};
 
 pub trait HintSerializer<L: PlonkParameters<D>, const D: usize>:
@@ -332,9 +353,6 @@ where
         let eth_storage_key_generator_id = EthStorageKeyGenerator::<L, D>::id();
         r.register_simple::<EthStorageKeyGenerator<L, D>>(eth_storage_key_generator_id);
 
-        let keccak256_generator_id = Keccak256Generator::<L, D>::id();
-        r.register_simple::<Keccak256Generator<L, D>>(keccak256_generator_id);
-
         let beacon_balance_generator_id = BeaconBalanceGenerator::<L, D>::id();
         r.register_simple::<BeaconBalanceGenerator<L, D>>(beacon_balance_generator_id);
 
@@ -374,6 +392,9 @@ where
         r.register_async_hint::<BeaconHeaderHint>();
         r.register_async_hint::<BeaconHistoricalBlockHint>();
         r.register_async_hint::<EthStorageProofHint<L, D>>();
+        r.register_async_hint::<EthFullStorageProofHint<L, D>>();
+        r.register_async_hint::<EthAccountProofHint<L, D>>();
+        r.register_async_hint::<EthHeaderRlpHint<L, D>>();
         r.register_async_hint::<BeaconValidatorsHint>();
 
         register_powers_of_two!(r, BeaconBalanceBatchWitnessHint);
@@ -386,6 +407,9 @@ where
         let id = U32RangeCheckGenerator::<L::Field, D>::id();
         r.register_simple::<U32RangeCheckGenerator<L::Field, D>>(id);
 
+        let id = Poseidon2Generator::<L::Field, D>::id();
+        r.register_simple::<Poseidon2Generator<L::Field, D>>(id);
+
         let id = ArithmeticCubicGenerator::<L::Field, D>::id();
         r.register_simple::<ArithmeticCubicGenerator<L::Field, D>>(id);
 
@@ -394,6 +418,14 @@ where
 
         r.register_hint::<SubArrayExtractorHint>();
 
+        r.register_hint::<SortHint<U32Variable>>();
+        r.register_hint::<SortHint<U64Variable>>();
+        r.register_hint::<SortHint<U128Variable>>();
+        r.register_hint::<SortHint<U256Variable>>();
+        r.register_hint::<SortHint<U512Variable>>();
+
+        r.register_generator::<MemoryGenerator<L::Field, D>>("MemoryGenerator".to_string());
+
         r.register_hint::<BeaconBlockRootsHint>();
 
         r.register_hint::<BeaconGraffitiHint>();
@@ -416,11 +448,51 @@ where
         r.register_hint::<HashProofHint<BLAKE2B, 96, true, 4>>();
         r.register_async_hint::<Async<HashProofHint<BLAKE2B, 96, true, 4>>>();
 
-        r.register_hint::<EcOpProofHint>();
-        r.register_async_hint::<Async<EcOpProofHint>>();
+        r.register_hint::<EcOpProofHint<Ed25519>>();
+        r.register_async_hint::<Async<EcOpProofHint<Ed25519>>>();
+
+        r.register_hint::<EcOpResultHint<Ed25519>>();
+        r.register_async_hint::<Async<EcOpResultHint<Ed25519>>>();
+
+        r.register_hint::<EcOpProofHint<Secp256K1>>();
+        r.register_async_hint::<Async<EcOpProofHint<Secp256K1>>>();
+
+        r.register_hint::<EcOpResultHint<Secp256K1>>();
+        r.register_async_hint::<Async<EcOpResultHint<Secp256K1>>>();
+
+        r.register_hint::<EcOpProofHint<Secp256R1>>();
+        r.register_async_hint::<Async<EcOpProofHint<Secp256R1>>>();
+
+        r.register_hint::<EcOpResultHint<Secp256R1>>();
+        r.register_async_hint::<Async<EcOpResultHint<Secp256R1>>>();
+
+        r.register_hint::<EcOpProofHint<Bn254>>();
+        r.register_async_hint::<Async<EcOpProofHint<Bn254>>>();
+
+        r.register_hint::<EcOpResultHint<Bn254>>();
+        r.register_async_hint::<Async<EcOpResultHint<Bn254>>>();
+
+        r.register_hint::<EcOpProofHint<BabyJubjub>>();
+        r.register_async_hint::<Async<EcOpProofHint<BabyJubjub>>>();
+
+        r.register_hint::<EcOpResultHint<BabyJubjub>>();
+        r.register_async_hint::<Async<EcOpResultHint<BabyJubjub>>>();
+
+        r.register_hint::<Secp256k1ScalarInverseHint>();
+        r.register_hint::<Secp256k1RecoverHint>();
+        r.register_hint::<Secp256k1LiftXHint>();
+        r.register_hint::<Secp256r1ScalarInverseHint>();
+
+        r.register_hint::<Bls12381FpDivHint>();
+        r.register_hint::<Bls12381FpSqrtHint>();
+        r.register_hint::<Bls12381Fp2DivHint>();
+        r.register_hint::<Bls12381Fp2SqrtHint>();
+        r.register_hint::<Bls12381Fp12InverseHint>();
+        r.register_hint::<Bls12381SswuSqrtHint>();
 
-        r.register_hint::<EcOpResultHint>();
-        r.register_async_hint::<Async<EcOpResultHint>>();
+        r.register_hint::<Bn254FpDivHint>();
+        r.register_hint::<Bn254Fp2DivHint>();
+        r.register_hint::<Bn254Fp12InverseHint>();
 
         let dummy_proof_generator_id =
             DummyProofGenerator::<L::Field, L::Config, D>::default().id();
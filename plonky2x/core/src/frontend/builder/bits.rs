@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use plonky2::iop::target::{BoolTarget, Target};
+
+use super::CircuitBuilder;
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::vars::{BoolVariable, CircuitVariable, U32Variable, Variable};
+
+/// A cache of bit decompositions and recompositions, scoped to a `CircuitBuilder`.
+///
+/// Decomposing a variable into bits with `split_le` costs a base-sum gate and a range check every
+/// time it is called, so chains of bitwise operations that repeatedly convert between words and
+/// bits waste a lot of rows. The cache remembers both directions: decomposing a variable that was
+/// itself recomposed from bits returns those bits for free, and vice versa.
+#[derive(Debug, Clone, Default)]
+pub struct BitsCache {
+    /// Maps `(variable, nb_bits)` to its little-endian bit decomposition.
+    decompositions: HashMap<(Target, usize), Vec<BoolVariable>>,
+    /// Maps a little-endian list of bits to the variable they sum to.
+    compositions: HashMap<Vec<Target>, Variable>,
+}
+
+impl BitsCache {
+    fn insert(&mut self, variable: Variable, bits: &[BoolVariable]) {
+        let bit_targets = bits.iter().map(|b| b.variable.0).collect_vec();
+        self.decompositions
+            .insert((variable.0, bits.len()), bits.to_vec());
+        self.compositions.insert(bit_targets, variable);
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Decomposes `variable` into `nb_bits` little-endian bits, reusing a previous decomposition
+    /// (or recomposition) of the same variable if there is one.
+    pub fn split_le_cached(&mut self, variable: Variable, nb_bits: usize) -> Vec<BoolVariable> {
+        if let Some(bits) = self.bits_cache.decompositions.get(&(variable.0, nb_bits)) {
+            return bits.clone();
+        }
+        let bits = self
+            .api
+            .split_le(variable.0, nb_bits)
+            .into_iter()
+            .map(BoolVariable::from)
+            .collect_vec();
+        self.bits_cache.insert(variable, &bits);
+        bits
+    }
+
+    /// Computes `sum_i bits[i] * 2^i`, reusing a previous computation for the same bits (or the
+    /// variable the bits were decomposed from) if there is one.
+    pub fn le_sum_cached(&mut self, bits: &[BoolVariable]) -> Variable {
+        let targets = bits.iter().map(|b| b.variable.0).collect_vec();
+        if let Some(variable) = self.bits_cache.compositions.get(&targets) {
+            return *variable;
+        }
+        let bit_targets: Vec<BoolTarget> = bits.iter().map(|b| (*b).into()).collect_vec();
+        let variable = Variable(self.api.le_sum(bit_targets.into_iter()));
+        self.bits_cache.insert(variable, bits);
+        variable
+    }
+}
+
+/// A lazily materialized view of an unsigned integer as both u32 limbs and bits.
+///
+/// Bitwise operations on a view only produce bits, and the limbs are only recomposed when they
+/// are requested, so a chain of operations such as `rotr(x, 7) ^ rotr(x, 18) ^ (x >> 3)` costs a
+/// single decomposition of `x` and a single recomposition of the result.
+#[derive(Debug, Clone)]
+pub struct BitView {
+    nb_bits: usize,
+    /// The little-endian u32 limbs of the value.
+    limbs: Option<Vec<U32Variable>>,
+    /// The little-endian bits of the value.
+    bits: Option<Vec<BoolVariable>>,
+}
+
+impl BitView {
+    /// Creates a view from little-endian u32 limbs.
+    pub fn from_limbs(limbs: &[U32Variable]) -> Self {
+        Self {
+            nb_bits: limbs.len() * 32,
+            limbs: Some(limbs.to_vec()),
+            bits: None,
+        }
+    }
+
+    /// Creates a view from little-endian bits. The number of bits must be a multiple of 32.
+    pub fn from_le_bits(bits: &[BoolVariable]) -> Self {
+        assert_eq!(bits.len() % 32, 0);
+        Self {
+            nb_bits: bits.len(),
+            limbs: None,
+            bits: Some(bits.to_vec()),
+        }
+    }
+
+    /// Creates a view from big-endian bits. The number of bits must be a multiple of 32.
+    pub fn from_be_bits(bits: &[BoolVariable]) -> Self {
+        let mut bits = bits.to_vec();
+        bits.reverse();
+        Self::from_le_bits(&bits)
+    }
+
+    /// The number of bits in the view.
+    pub fn nb_bits(&self) -> usize {
+        self.nb_bits
+    }
+
+    /// Returns the little-endian u32 limbs, recomposing them from the bits if necessary.
+    pub fn limbs<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Vec<U32Variable> {
+        if let Some(limbs) = &self.limbs {
+            return limbs.clone();
+        }
+        let bits = self.bits.as_ref().unwrap();
+        // The sum of 32 bits is always less than 2^32, so the limbs are valid u32s.
+        let limbs = bits
+            .chunks(32)
+            .map(|chunk| U32Variable::from_variables_unsafe(&[builder.le_sum_cached(chunk)]))
+            .collect_vec();
+        self.limbs = Some(limbs.clone());
+        limbs
+    }
+
+    /// Returns the little-endian bits, decomposing the limbs if necessary.
+    pub fn le_bits<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Vec<BoolVariable> {
+        if let Some(bits) = &self.bits {
+            return bits.clone();
+        }
+        let limbs = self.limbs.as_ref().unwrap();
+        let bits = limbs
+            .iter()
+            .flat_map(|limb| builder.split_le_cached(limb.variable, 32))
+            .collect_vec();
+        self.bits = Some(bits.clone());
+        bits
+    }
+
+    /// Returns the big-endian bits, decomposing the limbs if necessary.
+    pub fn be_bits<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Vec<BoolVariable> {
+        let mut bits = self.le_bits(builder);
+        bits.reverse();
+        bits
+    }
+
+    fn zip_bits<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        other: &mut Self,
+        builder: &mut CircuitBuilder<L, D>,
+        f: impl Fn(&mut CircuitBuilder<L, D>, BoolVariable, BoolVariable) -> BoolVariable,
+    ) -> Self {
+        assert_eq!(self.nb_bits, other.nb_bits);
+        let lhs = self.le_bits(builder);
+        let rhs = other.le_bits(builder);
+        let bits = lhs
+            .into_iter()
+            .zip(rhs)
+            .map(|(a, b)| f(builder, a, b))
+            .collect_vec();
+        Self::from_le_bits(&bits)
+    }
+
+    /// The bitwise AND of two views.
+    pub fn and<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        other: &mut Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        self.zip_bits(other, builder, |builder, a, b| builder.and(a, b))
+    }
+
+    /// The bitwise OR of two views.
+    pub fn or<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        other: &mut Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        self.zip_bits(other, builder, |builder, a, b| builder.or(a, b))
+    }
+
+    /// The bitwise XOR of two views.
+    pub fn xor<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        other: &mut Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        self.zip_bits(other, builder, |builder, a, b| builder.xor(a, b))
+    }
+
+    /// The bitwise NOT of a view.
+    pub fn not<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        let bits = self
+            .le_bits(builder)
+            .into_iter()
+            .map(|b| builder.not(b))
+            .collect_vec();
+        Self::from_le_bits(&bits)
+    }
+
+    /// Rotates the bits of the view to the right. This is free once the bits are materialized.
+    pub fn rotate_right<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        shift: usize,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        let bits = self.le_bits(builder);
+        let n = self.nb_bits;
+        let rotated = (0..n).map(|i| bits[(i + shift) % n]).collect_vec();
+        Self::from_le_bits(&rotated)
+    }
+
+    /// Rotates the bits of the view to the left. This is free once the bits are materialized.
+    pub fn rotate_left<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        shift: usize,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        let n = self.nb_bits;
+        self.rotate_right(n - shift % n, builder)
+    }
+
+    /// Shifts the bits of the view to the right, filling in zeros.
+    pub fn shr<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        shift: usize,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        let bits = self.le_bits(builder);
+        let f = builder._false();
+        let shifted = (0..self.nb_bits)
+            .map(|i| {
+                if i + shift < self.nb_bits {
+                    bits[i + shift]
+                } else {
+                    f
+                }
+            })
+            .collect_vec();
+        Self::from_le_bits(&shifted)
+    }
+
+    /// Shifts the bits of the view to the left, filling in zeros.
+    pub fn shl<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        shift: usize,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        let bits = self.le_bits(builder);
+        let f = builder._false();
+        let shifted = (0..self.nb_bits)
+            .map(|i| if i >= shift { bits[i - shift] } else { f })
+            .collect_vec();
+        Self::from_le_bits(&shifted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_bits_cache_reuses_decompositions() {
+        let mut builder = DefaultBuilder::new();
+        let x = builder.read::<U32Variable>();
+
+        let bits = builder.split_le_cached(x.variable, 32);
+        let num_gates = builder.api.num_gates();
+        let bits_again = builder.split_le_cached(x.variable, 32);
+        let recomposed = builder.le_sum_cached(&bits);
+        assert_eq!(builder.api.num_gates(), num_gates);
+        assert_eq!(bits.len(), bits_again.len());
+        assert_eq!(recomposed.0, x.variable.0);
+    }
+
+    #[test]
+    fn test_bit_view() {
+        let mut builder = DefaultBuilder::new();
+        let x = builder.read::<U32Variable>();
+        let y = builder.read::<U32Variable>();
+
+        // sigma0(x) ^ (x & y) ^ !y << 3
+        let mut x_view = BitView::from_limbs(&[x]);
+        let mut y_view = BitView::from_limbs(&[y]);
+        let mut r7 = x_view.rotate_right(7, &mut builder);
+        let mut r18 = x_view.rotate_right(18, &mut builder);
+        let mut s3 = x_view.shr(3, &mut builder);
+        let mut sigma = r7.xor(&mut r18, &mut builder);
+        let mut sigma = sigma.xor(&mut s3, &mut builder);
+        let mut x_and_y = x_view.and(&mut y_view, &mut builder);
+        let mut not_y = y_view.not(&mut builder);
+        let mut not_y_shl = not_y.shl(3, &mut builder);
+        let mut result = sigma.xor(&mut x_and_y, &mut builder);
+        let mut result = result.xor(&mut not_y_shl, &mut builder);
+        let limbs = result.limbs(&mut builder);
+        builder.write(limbs[0]);
+
+        let circuit = builder.build();
+
+        let (x, y) = (0x12345678u32, 0x9abcdef0u32);
+        let mut input = circuit.input();
+        input.write::<U32Variable>(x);
+        input.write::<U32Variable>(y);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let expected = (x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)) ^ (x & y) ^ (!y << 3);
+        assert_eq!(output.read::<U32Variable>(), expected);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod bits;
 mod boolean;
 pub mod io;
 pub mod permutation;
@@ -16,13 +17,19 @@ use plonky2::iop::generator::{SimpleGenerator, WitnessGeneratorRef};
 use plonky2::iop::target::{BoolTarget, Target};
 use plonky2::plonk::circuit_builder::CircuitBuilder as CircuitAPI;
 use plonky2::plonk::circuit_data::CircuitConfig;
+use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+use starkyx::chip::ec::weierstrass::bn254::Bn254;
+use starkyx::chip::ec::weierstrass::secp256k1::Secp256K1;
 use starkyx::machine::hash::blake::blake2b::BLAKE2B;
 use starkyx::machine::hash::sha::sha256::SHA256;
 use starkyx::machine::hash::sha::sha512::SHA512;
 use tokio::runtime::Runtime;
 
+pub use self::bits::BitsCache;
 pub use self::io::CircuitIO;
-use super::ecc::curve25519::curta::accelerator::EcOpAccelerator;
+use super::ecc::babyjubjub::params::BabyJubjub;
+use super::ecc::curta::accelerator::EcAccelerator;
+use super::ecc::secp256r1::params::Secp256R1;
 use super::hash::blake2::curta::BLAKE2BAccelerator;
 use super::hash::sha::sha256::curta::SHA256Accelerator;
 use super::hash::sha::sha512::curta::SHA512Accelerator;
@@ -30,7 +37,7 @@ use super::hint::HintGenerator;
 use super::vars::EvmVariable;
 use crate::backend::circuit::{CircuitBuild, DefaultParameters, MockCircuitBuild, PlonkParameters};
 use crate::frontend::hint::asynchronous::generator::AsyncHintDataRef;
-use crate::frontend::vars::{BoolVariable, CircuitVariable, Variable};
+use crate::frontend::vars::{BoolVariable, CircuitVariable, MemoryConstraint, Variable};
 use crate::prelude::ArrayVariable;
 use crate::utils::eth::beacon::BeaconClient;
 
@@ -46,11 +53,17 @@ pub struct CircuitBuilder<L: PlonkParameters<D>, const D: usize> {
     pub(crate) hints: Vec<Box<dyn HintGenerator<L, D>>>,
     pub(crate) async_hints: Vec<AsyncHintDataRef<L, D>>,
     pub(crate) async_hints_indices: Vec<usize>,
+    pub(crate) bits_cache: BitsCache,
+    pub(crate) memories: Vec<Box<dyn MemoryConstraint<L, D>>>,
 
     pub blake2b_accelerator: Option<BLAKE2BAccelerator>,
     pub sha256_accelerator: Option<SHA256Accelerator>,
     pub sha512_accelerator: Option<SHA512Accelerator>,
-    pub ec_25519_ops_accelerator: Option<EcOpAccelerator>,
+    pub ec_25519_ops_accelerator: Option<EcAccelerator<Ed25519>>,
+    pub ec_secp256k1_ops_accelerator: Option<EcAccelerator<Secp256K1>>,
+    pub ec_secp256r1_ops_accelerator: Option<EcAccelerator<Secp256R1>>,
+    pub ec_bn254_ops_accelerator: Option<EcAccelerator<Bn254>>,
+    pub ec_babyjubjub_ops_accelerator: Option<EcAccelerator<BabyJubjub>>,
 }
 
 /// The universal api for building circuits using `plonky2x` with default parameters.
@@ -79,10 +92,16 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             hints: Vec::new(),
             async_hints: Vec::new(),
             async_hints_indices: Vec::new(),
+            bits_cache: BitsCache::default(),
+            memories: Vec::new(),
             blake2b_accelerator: None,
             sha256_accelerator: None,
             sha512_accelerator: None,
             ec_25519_ops_accelerator: None,
+            ec_secp256k1_ops_accelerator: None,
+            ec_secp256r1_ops_accelerator: None,
+            ec_bn254_ops_accelerator: None,
+            ec_babyjubjub_ops_accelerator: None,
         };
 
         if let Ok(rpc_url) = env::var("CONSENSUS_RPC_URL") {
@@ -133,6 +152,11 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
     /// Adds all the constraints nedded before building the circuit and registering hints.
     fn pre_build(&mut self) {
+        let memories = core::mem::take(&mut self.memories);
+        for memory in memories.iter() {
+            memory.constrain(self);
+        }
+
         let blake2b_accelerator = self.blake2b_accelerator.clone();
         if let Some(accelerator) = blake2b_accelerator {
             self.curta_constrain_hash::<BLAKE2B, 96, true, 4>(accelerator);
@@ -150,7 +174,27 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
         let ec_ops_accelerator = self.ec_25519_ops_accelerator.clone();
         if let Some(accelerator) = ec_ops_accelerator {
-            self.curta_constrain_ec_op(accelerator);
+            self.curta_constrain_ec_op::<Ed25519>(accelerator);
+        }
+
+        let ec_secp256k1_ops_accelerator = self.ec_secp256k1_ops_accelerator.clone();
+        if let Some(accelerator) = ec_secp256k1_ops_accelerator {
+            self.curta_constrain_ec_op::<Secp256K1>(accelerator);
+        }
+
+        let ec_secp256r1_ops_accelerator = self.ec_secp256r1_ops_accelerator.clone();
+        if let Some(accelerator) = ec_secp256r1_ops_accelerator {
+            self.curta_constrain_ec_op::<Secp256R1>(accelerator);
+        }
+
+        let ec_bn254_ops_accelerator = self.ec_bn254_ops_accelerator.clone();
+        if let Some(accelerator) = ec_bn254_ops_accelerator {
+            self.curta_constrain_ec_op::<Bn254>(accelerator);
+        }
+
+        let ec_babyjubjub_ops_accelerator = self.ec_babyjubjub_ops_accelerator.clone();
+        if let Some(accelerator) = ec_babyjubjub_ops_accelerator {
+            self.curta_constrain_ec_op::<BabyJubjub>(accelerator);
         }
 
         for (index, gen_ref) in self
@@ -1,12 +1,21 @@
+use core::marker::PhantomData;
+
+use itertools::Itertools;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 use serde::{Deserialize, Serialize};
 
 use super::CircuitBuilder;
 use crate::frontend::extension::CubicExtensionVariable;
 use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::uint128::U128Variable;
+use crate::frontend::uint::uint256::U256Variable;
 use crate::frontend::uint::uint32::U32Variable;
-use crate::frontend::vars::{EvmVariable, ValueStream, VariableStream};
-use crate::prelude::{ArrayVariable, PlonkParameters};
+use crate::frontend::uint::uint512::U512Variable;
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{CircuitVariable, EvmVariable, ValueStream, Variable, VariableStream};
+use crate::prelude::{ArrayVariable, LessThanOrEqual, PlonkParameters};
 use crate::utils::hash::sha256;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +48,49 @@ impl<L: PlonkParameters<D>, const D: usize, const B: usize> Hint<L, D>
     }
 }
 
+/// The key types supported by `CircuitBuilder::sort`.
+///
+/// `SortHint` is registered in the hint registry for each of these types, which is what allows
+/// circuits that sort to be serialized. To sort by another key, map it to one of these types.
+pub trait SortKey: CircuitVariable {}
+
+impl SortKey for U32Variable {}
+impl SortKey for U64Variable {}
+impl SortKey for U128Variable {}
+impl SortKey for U256Variable {}
+impl SortKey for U512Variable {}
+
+/// Sorts a list of values by their keys.
+///
+/// The values are passed as raw field elements so that the hint only depends on the key type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SortHint<K> {
+    len: usize,
+    value_size: usize,
+    _marker: PhantomData<K>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize, K: CircuitVariable> Hint<L, D> for SortHint<K>
+where
+    K::ValueType<L::Field>: Ord,
+{
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let keys = input_stream.read_vec::<K>(self.len);
+        let values = input_stream
+            .read_exact(self.len * self.value_size)
+            .chunks_exact(self.value_size)
+            .map(|chunk| chunk.to_vec())
+            .collect_vec();
+
+        let mut entries = keys.into_iter().zip(values).collect_vec();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (_, value) in entries {
+            output_stream.write_slice(&value);
+        }
+    }
+}
+
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     // @no-audit-okay
     pub fn permute_with_dummy<const B: usize>(
@@ -119,6 +171,116 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
         permuted_inputs
     }
+
+    /// Asserts that `b` is a permutation of `a`.
+    ///
+    /// The check is a grand-product argument over the cubic extension: each value is compressed
+    /// to a single element `c = v_0 + alpha * v_1 + ... + alpha^(k-1) * v_(k-1)` and we assert
+    /// that `prod_i (gamma - c(a_i)) == prod_i (gamma - c(b_i))`. The challenges `alpha` and
+    /// `gamma` are derived with Fiat-Shamir from a Poseidon hash of both lists, so the cost is
+    /// linear in the number of elements.
+    pub fn assert_is_permutation<V: CircuitVariable>(&mut self, a: &[V], b: &[V]) {
+        assert_eq!(a.len(), b.len(), "lists must have the same length");
+        if a.is_empty() {
+            return;
+        }
+
+        let mut challenger = RecursiveChallenger::<L::Field, PoseidonHash, D>::new(&mut self.api);
+        for value in a.iter().chain(b.iter()) {
+            challenger.observe_elements(&value.targets());
+        }
+        let challenges = challenger
+            .get_n_challenges(&mut self.api, 6)
+            .into_iter()
+            .map(Variable::from)
+            .collect_vec();
+        let alpha = CubicExtensionVariable::new(challenges[0], challenges[1], challenges[2]);
+        let gamma = CubicExtensionVariable::new(challenges[3], challenges[4], challenges[5]);
+
+        let a_acc = self.permutation_grand_product(a, alpha, gamma);
+        let b_acc = self.permutation_grand_product(b, alpha, gamma);
+        self.assert_is_equal(a_acc, b_acc);
+    }
+
+    /// Computes `prod_i (gamma - c(values_i))` where `c` is the random linear combination of the
+    /// variables of each value with powers of `alpha`.
+    fn permutation_grand_product<V: CircuitVariable>(
+        &mut self,
+        values: &[V],
+        alpha: CubicExtensionVariable,
+        gamma: CubicExtensionVariable,
+    ) -> CubicExtensionVariable {
+        let mut acc = self.one::<CubicExtensionVariable>();
+        for value in values.iter() {
+            let mut compressed = self.zero::<CubicExtensionVariable>();
+            for variable in value.variables().into_iter().rev() {
+                let variable_extension = variable.as_cubic_extension(self);
+                compressed = self.mul(compressed, alpha);
+                compressed = self.add(compressed, variable_extension);
+            }
+            let term = self.sub(gamma, compressed);
+            acc = self.mul(acc, term);
+        }
+        acc
+    }
+
+    /// Asserts that the keys are sorted in non-decreasing order.
+    pub fn assert_is_sorted<K>(&mut self, keys: &[K])
+    where
+        K: LessThanOrEqual<L, D> + Clone,
+    {
+        let t = self._true();
+        for i in 1..keys.len() {
+            let lte = self.lte(keys[i - 1].clone(), keys[i].clone());
+            self.assert_is_equal(lte, t);
+        }
+    }
+
+    /// Asserts that the keys are sorted in strictly increasing order (i.e., sorted and without
+    /// duplicates).
+    pub fn assert_is_strictly_sorted<K>(&mut self, keys: &[K])
+    where
+        K: LessThanOrEqual<L, D> + Clone,
+    {
+        let t = self._true();
+        for i in 1..keys.len() {
+            let lt = self.lt(keys[i - 1].clone(), keys[i].clone());
+            self.assert_is_equal(lt, t);
+        }
+    }
+
+    /// Sorts `inputs` in non-decreasing order of `key_fn`.
+    ///
+    /// The sorted list is computed in a hint. The circuit then checks that the keys of the output
+    /// are in order and that the output is a permutation of the input, so the cost is linear in
+    /// the number of elements (plus one evaluation of `key_fn` per element on each side). The key
+    /// must be one of the `SortKey` types.
+    pub fn sort<V, K, F>(&mut self, inputs: &[V], key_fn: F) -> Vec<V>
+    where
+        V: CircuitVariable,
+        K: SortKey + LessThanOrEqual<L, D>,
+        K::ValueType<L::Field>: Ord,
+        F: Fn(&mut Self, &V) -> K,
+    {
+        let keys = inputs.iter().map(|v| key_fn(self, v)).collect_vec();
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write_slice(&keys);
+        input_stream.write_slice(inputs);
+        let hint = SortHint::<K> {
+            len: inputs.len(),
+            value_size: V::nb_elements(),
+            _marker: PhantomData,
+        };
+        let output_stream = self.hint(input_stream, hint);
+        let sorted = output_stream.read_vec::<V>(self, inputs.len());
+
+        let sorted_keys = sorted.iter().map(|v| key_fn(self, v)).collect_vec();
+        self.assert_is_sorted(&sorted_keys);
+        self.assert_is_permutation(inputs, &sorted);
+
+        sorted
+    }
 }
 
 #[cfg(test)]
@@ -156,4 +318,67 @@ pub(crate) mod tests {
         let (proof, output) = circuit.prove(&input);
         circuit.verify(&proof, &input, &output);
     }
+
+    #[test]
+    fn test_sort() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+
+        let inputs = builder.read::<ArrayVariable<U64Variable, 6>>();
+        let sorted = builder.sort(inputs.as_slice(), |_, x| *x);
+        builder.write(ArrayVariable::<U64Variable, 6>::new(sorted));
+
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<U64Variable, 6>>(vec![5, 3, 9, 3, 0, 7]);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let sorted = output.read::<ArrayVariable<U64Variable, 6>>();
+        assert_eq!(sorted, vec![0, 3, 3, 5, 7, 9]);
+    }
+
+    #[test]
+    fn test_sort_by_key_and_dedupe() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+
+        // Sort (index, balance) pairs by balance and check that the indices are distinct.
+        let indices = builder.constant::<ArrayVariable<U32Variable, 4>>(vec![4, 1, 3, 2]);
+        let balances = builder.constant::<ArrayVariable<U64Variable, 4>>(vec![40, 10, 30, 20]);
+        let pairs = indices
+            .as_slice()
+            .iter()
+            .zip(balances.as_slice().iter())
+            .map(|(i, b)| (*i, *b))
+            .collect::<Vec<_>>();
+        let sorted = builder.sort(pairs.as_slice(), |_, pair| pair.1);
+
+        let sorted_indices = sorted.iter().map(|pair| pair.0).collect::<Vec<_>>();
+        builder.assert_is_strictly_sorted(&sorted_indices);
+        let expected = builder.constant::<ArrayVariable<U32Variable, 4>>(vec![1, 2, 3, 4]);
+        builder.assert_is_equal(ArrayVariable::new(sorted_indices), expected);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_is_permutation_fails() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+
+        let a = builder.constant::<ArrayVariable<U32Variable, 3>>(vec![1, 2, 3]);
+        let b = builder.constant::<ArrayVariable<U32Variable, 3>>(vec![3, 2, 2]);
+        builder.assert_is_permutation(a.as_slice(), b.as_slice());
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
@@ -0,0 +1,22 @@
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use starkyx::chip::field::instruction::FpInstruction;
+use starkyx::chip::AirParameters;
+
+use crate::frontend::ecc::babyjubjub::params::BabyJubjubBaseField;
+use crate::prelude::PlonkParameters;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BabyJubjubAirParameters<L, const D: usize>(PhantomData<L>);
+
+impl<L: PlonkParameters<D>, const D: usize> AirParameters for BabyJubjubAirParameters<L, D> {
+    type Field = L::Field;
+    type CubicParams = L::CubicParams;
+
+    type Instruction = FpInstruction<BabyJubjubBaseField>;
+
+    const NUM_ARITHMETIC_COLUMNS: usize = 1632;
+    const NUM_FREE_COLUMNS: usize = 20;
+    const EXTENDED_COLUMNS: usize = 2502;
+}
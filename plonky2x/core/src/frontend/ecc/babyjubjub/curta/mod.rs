@@ -0,0 +1,74 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use num::BigUint;
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::point::CompressedPointRegister;
+use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
+use starkyx::chip::ec::scalar::ECScalarRegister;
+use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::field::register::FieldRegister;
+use starkyx::machine::ec::builder::EllipticCurveBuilder;
+use starkyx::machine::emulated::builder::EmulatedBuilder;
+
+use self::air_parameters::BabyJubjubAirParameters;
+use super::params::BabyJubjub;
+use crate::frontend::ecc::curta::accelerator::EcAccelerator;
+use crate::frontend::ecc::curta::{CurtaCurve, EcOpCurve, Unsupported};
+use crate::prelude::{CircuitBuilder, PlonkParameters};
+
+pub mod air_parameters;
+
+impl EcOpCurve for BabyJubjub {
+    type DecompressOps = Unsupported;
+}
+
+/// Baby Jubjub supports additions and scalar multiplications. Its points are compressed differently
+/// from Ed25519, so decompression and validity checks are not supported.
+impl<L: PlonkParameters<D>, const D: usize> CurtaCurve<L, D> for BabyJubjub {
+    type AirParameters = BabyJubjubAirParameters<L, D>;
+
+    const NAME: &'static str = "babyjubjub";
+
+    fn accelerator(builder: &mut CircuitBuilder<L, D>) -> &mut Option<EcAccelerator<Self>> {
+        &mut builder.ec_babyjubjub_ops_accelerator
+    }
+
+    fn air_add(
+        builder: &mut AirBuilder<Self::AirParameters>,
+        a: AffinePointRegister<Self>,
+        b: AffinePointRegister<Self>,
+    ) -> AffinePointRegister<Self> {
+        builder.add(a, b)
+    }
+
+    fn air_scalar_mul_batch(
+        builder: &mut EmulatedBuilder<Self::AirParameters>,
+        points: &[AffinePointRegister<Self>],
+        scalars: &[ECScalarRegister<Self>],
+        results: &[AffinePointRegister<Self>],
+    ) {
+        builder.scalar_mul_batch(points, scalars, results);
+    }
+
+    fn air_assert_valid(
+        ops: Unsupported,
+        _: &mut AirBuilder<Self::AirParameters>,
+        _: &AffinePointRegister<Self>,
+    ) {
+        match ops {}
+    }
+
+    fn air_decompress(
+        ops: Unsupported,
+        _: &mut AirBuilder<Self::AirParameters>,
+    ) -> (
+        CompressedPointRegister,
+        AffinePointRegister<Self>,
+        FieldRegister<<Self as EllipticCurveParameters>::BaseField>,
+    ) {
+        match ops {}
+    }
+
+    fn decompress(ops: Unsupported, _: &CompressedEdwardsY) -> (AffinePoint<Self>, BigUint) {
+        match ops {}
+    }
+}
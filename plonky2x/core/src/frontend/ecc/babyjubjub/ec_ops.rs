@@ -0,0 +1,85 @@
+use super::params::BabyJubjub;
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::prelude::{CircuitBuilder, PlonkParameters, U256Variable};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Add two points on Baby Jubjub.
+    pub fn curta_babyjubjub_add(
+        &mut self,
+        a: AffinePointVariable<BabyJubjub>,
+        b: AffinePointVariable<BabyJubjub>,
+    ) -> AffinePointVariable<BabyJubjub> {
+        self.curta_ec_add(a, b)
+    }
+
+    /// Multiply a point on Baby Jubjub by a scalar.
+    pub fn curta_babyjubjub_scalar_mul(
+        &mut self,
+        scalar: U256Variable,
+        point: AffinePointVariable<BabyJubjub>,
+    ) -> AffinePointVariable<BabyJubjub> {
+        self.curta_ec_scalar_mul(scalar, point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+    use num::{BigUint, Num};
+
+    use super::*;
+    use crate::frontend::ecc::babyjubjub::params::{
+        babyjubjub_from_standard, babyjubjub_to_standard,
+    };
+    use crate::prelude::DefaultBuilder;
+    use crate::utils;
+
+    fn from_decimal(value: &str) -> BigUint {
+        BigUint::from_str_radix(value, 10).unwrap()
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_curta_babyjubjub_scalar_mul() {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        let scalar = builder.read::<U256Variable>();
+        let point = builder.read::<AffinePointVariable<BabyJubjub>>();
+        let result = builder.curta_babyjubjub_scalar_mul(scalar, point.clone());
+        let sum = builder.curta_babyjubjub_add(result.clone(), point);
+        builder.write(result);
+        builder.write(sum);
+        let circuit = builder.build();
+
+        // The generator `G` and the base point `B8 = 8 G` of EIP-2494.
+        let generator = babyjubjub_from_standard(
+            &from_decimal(
+                "995203441582195749578291179787384436505546430278305826713579947235728471134",
+            ),
+            &from_decimal(
+                "5472060717959818805561601436314318772137091100104008585924551046643952123905",
+            ),
+        );
+        let base_point = (
+            from_decimal(
+                "5299619240641551281634865583518297030282874472190772894086521144482721001553",
+            ),
+            from_decimal(
+                "16950150798460657717958625567821834550301663161624707787222815936182638968203",
+            ),
+        );
+
+        let mut input = circuit.input();
+        input.write::<U256Variable>(U256::from(8));
+        input.write::<AffinePointVariable<BabyJubjub>>(generator.clone());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let result = output.read::<AffinePointVariable<BabyJubjub>>();
+        assert_eq!(babyjubjub_to_standard(&result), base_point);
+        let sum = output.read::<AffinePointVariable<BabyJubjub>>();
+        let expected = generator * BigUint::from(9u32);
+        assert_eq!((sum.x, sum.y), (expected.x, expected.y));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod curta;
+pub mod ec_ops;
+pub mod params;
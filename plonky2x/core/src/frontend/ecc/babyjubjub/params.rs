@@ -0,0 +1,153 @@
+use lazy_static::lazy_static;
+use num::{BigUint, Num};
+use serde::{Deserialize, Serialize};
+use starkyx::chip::ec::edwards::{EdwardsCurve, EdwardsParameters};
+use starkyx::chip::ec::point::AffinePoint;
+use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::field::parameters::{FieldParameters, MAX_NB_LIMBS};
+
+/// Baby Jubjub (EIP-2494), the twisted Edwards curve `168700 x^2 + y^2 = 1 + 168696 x^2 y^2` over
+/// the scalar field of BN254.
+///
+/// Curta constrains twisted Edwards curves with `a = -1`, so the points are represented on the
+/// isomorphic curve `-u^2 + v^2 = 1 + d u^2 v^2` with `d = -168696 / 168700`, by
+/// `(u, v) = (s x, y)` where `s` is a square root of `-168700`. Use [`babyjubjub_from_standard`]
+/// and [`babyjubjub_to_standard`] to convert the points.
+pub type BabyJubjub = EdwardsCurve<BabyJubjubParameters>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BabyJubjubParameters;
+
+/// The base field of Baby Jubjub, which is the scalar field of BN254.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BabyJubjubBaseField;
+
+lazy_static! {
+    /// The square root of `-168700` which maps the standard form of the curve to its `a = -1`
+    /// form.
+    static ref BABYJUBJUB_SCALE: BigUint = BigUint::from_str_radix(
+        "0e0ff35005d6e54b2c7ac0ce84e6117774a5f6ca321ea1176c7be2c1d8b2d768",
+        16,
+    )
+    .unwrap();
+}
+
+impl FieldParameters for BabyJubjubBaseField {
+    const NB_BITS_PER_LIMB: usize = 16;
+    const NB_LIMBS: usize = 16;
+    const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+    const MODULUS: [u16; MAX_NB_LIMBS] = [
+        0x0001, 0xf000, 0xf593, 0x43e1, 0x7091, 0x79b9, 0xe848, 0x2833, 0x585d, 0x8181, 0x45b6,
+        0xb850, 0xa029, 0xe131, 0x4e72, 0x3064, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    const WITNESS_OFFSET: usize = 1usize << 20;
+
+    fn modulus() -> BigUint {
+        BigUint::from_str_radix(
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+            16,
+        )
+        .unwrap()
+    }
+}
+
+impl EllipticCurveParameters for BabyJubjubParameters {
+    type BaseField = BabyJubjubBaseField;
+}
+
+impl EdwardsParameters for BabyJubjubParameters {
+    /// The coefficient `d = -168696 / 168700` of the `a = -1` form of the curve.
+    const D: [u16; MAX_NB_LIMBS] = [
+        0xeb8e, 0xf4d7, 0xca8c, 0xd075, 0xc867, 0xebb7, 0x2959, 0x039b, 0x11fc, 0x99fd, 0x72d7,
+        0x3df0, 0x8969, 0x5f21, 0x90f1, 0x1aee, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    /// The base point `B8` of EIP-2494, which generates the subgroup of prime order.
+    fn generator() -> (BigUint, BigUint) {
+        let x = BigUint::from_str_radix(
+            "1b024eef745002f42e018e11bfe76f10f0b773ad92c6ce1951e8dc38f1aac062",
+            16,
+        )
+        .unwrap();
+        let y = BigUint::from_str_radix(
+            "25797203f7a0b24925572e1cd16bf9edfce0051fb9e133774b3c257a872d7d8b",
+            16,
+        )
+        .unwrap();
+        (x, y)
+    }
+
+    fn prime_group_order() -> BigUint {
+        BigUint::from_str_radix(
+            "060c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1",
+            16,
+        )
+        .unwrap()
+    }
+}
+
+/// Maps a point `(x, y)` of the standard form of Baby Jubjub to the curve used by Curta.
+pub fn babyjubjub_from_standard(x: &BigUint, y: &BigUint) -> AffinePoint<BabyJubjub> {
+    let p = BabyJubjubBaseField::modulus();
+    AffinePoint::new((x * &*BABYJUBJUB_SCALE) % &p, y % &p)
+}
+
+/// Maps a point of the curve used by Curta to the standard form of Baby Jubjub.
+pub fn babyjubjub_to_standard(point: &AffinePoint<BabyJubjub>) -> (BigUint, BigUint) {
+    let p = BabyJubjubBaseField::modulus();
+    let scale_inverse = BABYJUBJUB_SCALE.modpow(&(&p - 2u32), &p);
+    ((&point.x * scale_inverse) % &p, point.y.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_limbs(limbs: &[u16]) -> BigUint {
+        limbs
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, limb| (acc << 16) + *limb)
+    }
+
+    fn from_decimal(value: &str) -> BigUint {
+        BigUint::from_str_radix(value, 10).unwrap()
+    }
+
+    #[test]
+    fn test_babyjubjub_parameters() {
+        let p = BabyJubjubBaseField::modulus();
+        assert_eq!(from_limbs(&BabyJubjubBaseField::MODULUS), p);
+
+        // The scale is a square root of `-a` and maps `d` to `-d / a`.
+        let a = BigUint::from(168700u32);
+        let d = BigUint::from(168696u32);
+        assert_eq!(
+            (&*BABYJUBJUB_SCALE * &*BABYJUBJUB_SCALE + &a) % &p,
+            BigUint::from(0u32)
+        );
+        let d_scaled = from_limbs(&BabyJubjubParameters::D);
+        assert_eq!((&d_scaled * &a + &d) % &p, BigUint::from(0u32));
+
+        // The generator is the base point `B8` of EIP-2494.
+        let (x, y) = babyjubjub_to_standard(&AffinePoint::new(
+            BabyJubjubParameters::generator().0,
+            BabyJubjubParameters::generator().1,
+        ));
+        assert_eq!(
+            x,
+            from_decimal(
+                "5299619240641551281634865583518297030282874472190772894086521144482721001553"
+            )
+        );
+        assert_eq!(
+            y,
+            from_decimal(
+                "16950150798460657717958625567821834550301663161624707787222815936182638968203"
+            )
+        );
+        let (u, v) = BabyJubjubParameters::generator();
+        let (u2, v2) = ((&u * &u) % &p, (&v * &v) % &p);
+        assert_eq!((&v2 + &p - &u2) % &p, (&d_scaled * &u2 * &v2 + 1u32) % &p);
+    }
+}
@@ -0,0 +1,289 @@
+use itertools::Itertools;
+use num::{BigUint, One, Zero};
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::{
+    BoolVariable, CircuitBuilder, CircuitVariable, PlonkParameters, U32Variable, ValueStream,
+    Variable, VariableStream,
+};
+use crate::utils::bls12_381::{fp_inverse, fp_sqrt, BLS12_381_MODULUS};
+
+/// The number of 32-bit limbs of an element of the base field.
+const NB_LIMBS: usize = 12;
+
+/// An element of the BLS12-381 base field, as little-endian 32-bit limbs.
+///
+/// Valid variables are always reduced, so that the outputs of the arithmetic can be compared limb
+/// by limb.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381FpVariable {
+    pub limbs: [U32Variable; NB_LIMBS],
+}
+
+impl CircuitVariable for Bls12381FpVariable {
+    type ValueType<F: RichField> = BigUint;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            limbs: core::array::from_fn(|_| U32Variable::init_unsafe(builder)),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.limbs.iter().map(|limb| limb.variable).collect()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        assert_eq!(variables.len(), NB_LIMBS);
+        Self {
+            limbs: core::array::from_fn(|i| U32Variable::from_variables_unsafe(&[variables[i]])),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        for limb in self.limbs.iter() {
+            limb.assert_is_valid(builder);
+        }
+        builder.bls12_381_fp_assert_reduced(self);
+    }
+
+    fn nb_elements() -> usize {
+        NB_LIMBS
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut limbs = value.to_u32_digits();
+        assert!(
+            limbs.len() <= NB_LIMBS,
+            "value does not fit in the base field"
+        );
+        limbs.resize(NB_LIMBS, 0);
+        limbs.into_iter().map(F::from_canonical_u32).collect()
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let limbs = elements
+            .iter()
+            .map(|element| element.to_canonical_u64() as u32)
+            .collect_vec();
+        BigUint::from_slice(&limbs)
+    }
+}
+
+/// Computes `a / b` in the BLS12-381 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381FpDivHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381FpDivHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bls12381FpVariable>();
+        let b = input_stream.read_value::<Bls12381FpVariable>();
+        let quotient = (a * fp_inverse(&b)) % &*BLS12_381_MODULUS;
+        output_stream.write_value::<Bls12381FpVariable>(quotient);
+    }
+}
+
+/// Computes a square root in the BLS12-381 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381FpSqrtHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381FpSqrtHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bls12381FpVariable>();
+        let root = fp_sqrt(&a).expect("not a square in the base field");
+        output_stream.write_value::<Bls12381FpVariable>(root);
+    }
+}
+
+/// A term of a sum of products in the base field, `a * b` or `-(a * b)` when `negate` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381FpProduct {
+    pub a: Bls12381FpVariable,
+    pub b: Bls12381FpVariable,
+    pub negate: bool,
+}
+
+impl Bls12381FpProduct {
+    pub fn new(a: Bls12381FpVariable, b: Bls12381FpVariable, negate: bool) -> Self {
+        Self { a, b, negate }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    pub(crate) fn bls12_381_fp_to_biguint(&mut self, a: &Bls12381FpVariable) -> BigUintTarget {
+        BigUintTarget {
+            limbs: a.limbs.iter().map(|limb| U32Target::from(*limb)).collect(),
+        }
+    }
+
+    /// Converts the output of `rem_biguint` by the modulus back into a base field element.
+    fn bls12_381_fp_from_reduced_biguint(&mut self, a: BigUintTarget) -> Bls12381FpVariable {
+        assert!(a.limbs.len() <= NB_LIMBS);
+        let mut limbs = [self.constant::<U32Variable>(0); NB_LIMBS];
+        for (limb, a_limb) in limbs.iter_mut().zip(a.limbs) {
+            *limb = a_limb.into();
+        }
+        Bls12381FpVariable { limbs }
+    }
+
+    /// Returns `(sum(positive) - sum(negative)) mod p`, where every negative term is assumed to be
+    /// less than `bound`.
+    pub(crate) fn bls12_381_fp_reduce(
+        &mut self,
+        positive: Vec<BigUintTarget>,
+        negative: Vec<BigUintTarget>,
+        bound: &BigUint,
+    ) -> Bls12381FpVariable {
+        let reduced = self.biguint_signed_sum_rem(&positive, &negative, bound, &BLS12_381_MODULUS);
+        self.bls12_381_fp_from_reduced_biguint(reduced)
+    }
+
+    /// Returns `sum(+-a * b)` for the given products, with a single modular reduction.
+    pub fn bls12_381_fp_sum_of_products(
+        &mut self,
+        products: &[Bls12381FpProduct],
+    ) -> Bls12381FpVariable {
+        let mut positive = vec![];
+        let mut negative = vec![];
+        for product in products.iter() {
+            let a = self.bls12_381_fp_to_biguint(&product.a);
+            let b = self.bls12_381_fp_to_biguint(&product.b);
+            let ab = self.api.mul_biguint(&a, &b);
+            if product.negate {
+                negative.push(ab);
+            } else {
+                positive.push(ab);
+            }
+        }
+        let bound = &*BLS12_381_MODULUS * &*BLS12_381_MODULUS;
+        self.bls12_381_fp_reduce(positive, negative, &bound)
+    }
+
+    /// Returns `sum(positive) - sum(negative)` in the base field.
+    pub fn bls12_381_fp_linear_combination(
+        &mut self,
+        positive: &[Bls12381FpVariable],
+        negative: &[Bls12381FpVariable],
+    ) -> Bls12381FpVariable {
+        let positive = positive
+            .iter()
+            .map(|a| self.bls12_381_fp_to_biguint(a))
+            .collect();
+        let negative = negative
+            .iter()
+            .map(|a| self.bls12_381_fp_to_biguint(a))
+            .collect();
+        let bound = BLS12_381_MODULUS.clone();
+        self.bls12_381_fp_reduce(positive, negative, &bound)
+    }
+
+    pub fn bls12_381_fp_add(
+        &mut self,
+        a: &Bls12381FpVariable,
+        b: &Bls12381FpVariable,
+    ) -> Bls12381FpVariable {
+        self.bls12_381_fp_linear_combination(&[*a, *b], &[])
+    }
+
+    pub fn bls12_381_fp_sub(
+        &mut self,
+        a: &Bls12381FpVariable,
+        b: &Bls12381FpVariable,
+    ) -> Bls12381FpVariable {
+        self.bls12_381_fp_linear_combination(&[*a], &[*b])
+    }
+
+    pub fn bls12_381_fp_neg(&mut self, a: &Bls12381FpVariable) -> Bls12381FpVariable {
+        self.bls12_381_fp_linear_combination(&[], &[*a])
+    }
+
+    pub fn bls12_381_fp_mul(
+        &mut self,
+        a: &Bls12381FpVariable,
+        b: &Bls12381FpVariable,
+    ) -> Bls12381FpVariable {
+        self.bls12_381_fp_sum_of_products(&[Bls12381FpProduct::new(*a, *b, false)])
+    }
+
+    /// Returns `a / b`, which constrains `b` to be non-zero.
+    pub fn bls12_381_fp_div(
+        &mut self,
+        a: &Bls12381FpVariable,
+        b: &Bls12381FpVariable,
+    ) -> Bls12381FpVariable {
+        self.bls12_381_fp_assert_not_zero(b);
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        input_stream.write(b);
+        let output_stream = self.hint(input_stream, Bls12381FpDivHint);
+        let quotient = output_stream.read::<Bls12381FpVariable>(self);
+
+        let product = self.bls12_381_fp_mul(&quotient, b);
+        self.assert_is_equal(product, *a);
+        quotient
+    }
+
+    /// Returns a square root of `a`, which constrains `a` to be a square.
+    pub fn bls12_381_fp_sqrt(&mut self, a: &Bls12381FpVariable) -> Bls12381FpVariable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        let output_stream = self.hint(input_stream, Bls12381FpSqrtHint);
+        let root = output_stream.read::<Bls12381FpVariable>(self);
+
+        let square = self.bls12_381_fp_mul(&root, &root);
+        self.assert_is_equal(square, *a);
+        root
+    }
+
+    pub fn bls12_381_fp_is_zero(&mut self, a: &Bls12381FpVariable) -> BoolVariable {
+        let zero = self.constant::<Bls12381FpVariable>(BigUint::zero());
+        self.is_equal(*a, zero)
+    }
+
+    pub fn bls12_381_fp_assert_not_zero(&mut self, a: &Bls12381FpVariable) {
+        let is_zero = self.bls12_381_fp_is_zero(a);
+        let false_val = self._false();
+        self.assert_is_equal(is_zero, false_val);
+    }
+
+    /// Asserts that the limbs of `a` encode an integer less than the modulus.
+    pub fn bls12_381_fp_assert_reduced(&mut self, a: &Bls12381FpVariable) {
+        let a = self.bls12_381_fp_to_biguint(a);
+        let max = self
+            .api
+            .constant_biguint(&(&*BLS12_381_MODULUS - BigUint::one()));
+        let is_reduced = self.api.cmp_biguint(&a, &max);
+        let is_reduced = BoolVariable::from(is_reduced);
+        let true_val = self._true();
+        self.assert_is_equal(is_reduced, true_val);
+    }
+
+    /// Returns whether `a > (p - 1) / 2`, i.e. whether `a` is larger than `-a`, which is the sign
+    /// used in compressed encodings.
+    pub fn bls12_381_fp_is_lexicographically_largest(
+        &mut self,
+        a: &Bls12381FpVariable,
+    ) -> BoolVariable {
+        let a = self.bls12_381_fp_to_biguint(a);
+        let half = self
+            .api
+            .constant_biguint(&((&*BLS12_381_MODULUS - BigUint::one()) / 2u32));
+        let is_smallest = self.api.cmp_biguint(&a, &half);
+        let is_smallest = BoolVariable::from(is_smallest);
+        self.not(is_smallest)
+    }
+
+    /// Returns the parity of `a`, which is its sign in the sense of RFC 9380.
+    pub fn bls12_381_fp_sgn0(&mut self, a: &Bls12381FpVariable) -> BoolVariable {
+        self.to_le_bits(a.limbs[0])[0]
+    }
+}
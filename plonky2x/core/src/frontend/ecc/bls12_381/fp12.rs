@@ -0,0 +1,177 @@
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+
+use super::fp2::{Bls12381Fp2Product, Bls12381Fp2Variable};
+use crate::frontend::hint::simple::hint::Hint;
+use crate::prelude::{
+    CircuitBuilder, CircuitVariable, PlonkParameters, ValueStream, Variable, VariableStream,
+};
+use crate::utils::bls12_381::{
+    Fp12, BLS12_381_FROBENIUS2_COEFFS, BLS12_381_FROBENIUS_COEFFS, BLS12_381_X_ABS,
+};
+
+/// An element `sum c_i w^i` of the extension `Fp2[w] / (w^6 - (u + 1))` of degree 12 of the
+/// BLS12-381 base field, which contains the values of the pairing.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381Fp12Variable {
+    pub coeffs: [Bls12381Fp2Variable; 6],
+}
+
+impl CircuitVariable for Bls12381Fp12Variable {
+    type ValueType<F: RichField> = Fp12;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            coeffs: core::array::from_fn(|_| Bls12381Fp2Variable::init_unsafe(builder)),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.coeffs.iter().flat_map(|c| c.variables()).collect()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bls12381Fp2Variable::nb_elements();
+        assert_eq!(variables.len(), 6 * n);
+        Self {
+            coeffs: core::array::from_fn(|i| {
+                Bls12381Fp2Variable::from_variables_unsafe(&variables[i * n..(i + 1) * n])
+            }),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        for c in self.coeffs.iter() {
+            c.assert_is_valid(builder);
+        }
+    }
+
+    fn nb_elements() -> usize {
+        6 * Bls12381Fp2Variable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        value
+            .0
+            .into_iter()
+            .flat_map(Bls12381Fp2Variable::elements::<F>)
+            .collect()
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bls12381Fp2Variable::nb_elements();
+        Fp12(core::array::from_fn(|i| {
+            Bls12381Fp2Variable::from_elements::<F>(&elements[i * n..(i + 1) * n])
+        }))
+    }
+}
+
+/// Computes the inverse of a non-zero element of the degree 12 extension of the BLS12-381 base
+/// field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381Fp12InverseHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381Fp12InverseHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bls12381Fp12Variable>();
+        output_stream.write_value::<Bls12381Fp12Variable>(a.inverse());
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Multiplies `a` by `b`, skipping the coefficients of `b` which are known to be zero.
+    pub fn bls12_381_fp12_mul_sparse(
+        &mut self,
+        a: &Bls12381Fp12Variable,
+        b: &[Option<Bls12381Fp2Variable>; 6],
+    ) -> Bls12381Fp12Variable {
+        let mut products: [Vec<Bls12381Fp2Product>; 6] = Default::default();
+        for (i, a_i) in a.coeffs.iter().enumerate() {
+            for (j, b_j) in b.iter().enumerate() {
+                if let Some(b_j) = b_j {
+                    // Reduce with w^6 = u + 1.
+                    let mut product = Bls12381Fp2Product::new(*a_i, *b_j);
+                    product.mul_by_xi = i + j >= 6;
+                    products[(i + j) % 6].push(product);
+                }
+            }
+        }
+        Bls12381Fp12Variable {
+            coeffs: core::array::from_fn(|k| self.bls12_381_fp2_sum_of_products(&products[k])),
+        }
+    }
+
+    pub fn bls12_381_fp12_mul(
+        &mut self,
+        a: &Bls12381Fp12Variable,
+        b: &Bls12381Fp12Variable,
+    ) -> Bls12381Fp12Variable {
+        self.bls12_381_fp12_mul_sparse(a, &b.coeffs.map(Some))
+    }
+
+    pub fn bls12_381_fp12_square(&mut self, a: &Bls12381Fp12Variable) -> Bls12381Fp12Variable {
+        self.bls12_381_fp12_mul(a, a)
+    }
+
+    /// Returns `a^(p^6)`, which is the inverse of the elements of the cyclotomic subgroup.
+    pub fn bls12_381_fp12_conjugate(&mut self, a: &Bls12381Fp12Variable) -> Bls12381Fp12Variable {
+        let mut coeffs = a.coeffs;
+        for c in coeffs.iter_mut().skip(1).step_by(2) {
+            *c = self.bls12_381_fp2_neg(c);
+        }
+        Bls12381Fp12Variable { coeffs }
+    }
+
+    /// Returns `a^p`.
+    pub fn bls12_381_fp12_frobenius(&mut self, a: &Bls12381Fp12Variable) -> Bls12381Fp12Variable {
+        let mut coeffs = a.coeffs;
+        for (c, gamma) in coeffs.iter_mut().zip(BLS12_381_FROBENIUS_COEFFS.iter()) {
+            let conjugate = self.bls12_381_fp2_conjugate(c);
+            *c = self.bls12_381_fp2_mul_by_constant(&conjugate, gamma);
+        }
+        Bls12381Fp12Variable { coeffs }
+    }
+
+    /// Returns `a^(p^2)`.
+    pub fn bls12_381_fp12_frobenius2(&mut self, a: &Bls12381Fp12Variable) -> Bls12381Fp12Variable {
+        let mut coeffs = a.coeffs;
+        for (c, gamma) in coeffs.iter_mut().zip(BLS12_381_FROBENIUS2_COEFFS.iter()) {
+            *c = self.bls12_381_fp2_mul_by_constant(c, gamma);
+        }
+        Bls12381Fp12Variable { coeffs }
+    }
+
+    /// Returns the inverse of `a`, which constrains `a` to be non-zero.
+    pub fn bls12_381_fp12_inverse(&mut self, a: &Bls12381Fp12Variable) -> Bls12381Fp12Variable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        let output_stream = self.hint(input_stream, Bls12381Fp12InverseHint);
+        let inverse = output_stream.read::<Bls12381Fp12Variable>(self);
+
+        let product = self.bls12_381_fp12_mul(a, &inverse);
+        let one = self.constant::<Bls12381Fp12Variable>(Fp12::one());
+        self.assert_is_equal(product, one);
+        inverse
+    }
+
+    /// Returns `a^x` for `a` in the cyclotomic subgroup, where `x` is the (negative) BLS
+    /// parameter.
+    pub fn bls12_381_fp12_cyclotomic_exp_by_x(
+        &mut self,
+        a: &Bls12381Fp12Variable,
+    ) -> Bls12381Fp12Variable {
+        let mut result = *a;
+        for i in (0..63).rev() {
+            result = self.bls12_381_fp12_square(&result);
+            if (BLS12_381_X_ABS >> i) & 1 == 1 {
+                result = self.bls12_381_fp12_mul(&result, a);
+            }
+        }
+        self.bls12_381_fp12_conjugate(&result)
+    }
+}
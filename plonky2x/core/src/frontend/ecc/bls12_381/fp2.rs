@@ -0,0 +1,294 @@
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+
+use super::fp::{Bls12381FpProduct, Bls12381FpVariable};
+use crate::frontend::hint::simple::hint::Hint;
+use crate::prelude::{
+    BoolVariable, CircuitBuilder, CircuitVariable, PlonkParameters, ValueStream, Variable,
+    VariableStream,
+};
+use crate::utils::bls12_381::Fp2;
+
+/// An element `c0 + c1 u` of the quadratic extension `Fp[u] / (u^2 + 1)` of the BLS12-381 base
+/// field.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381Fp2Variable {
+    pub c0: Bls12381FpVariable,
+    pub c1: Bls12381FpVariable,
+}
+
+impl CircuitVariable for Bls12381Fp2Variable {
+    type ValueType<F: RichField> = Fp2;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            c0: Bls12381FpVariable::init_unsafe(builder),
+            c1: Bls12381FpVariable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.c0.variables();
+        variables.extend(self.c1.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bls12381FpVariable::nb_elements();
+        assert_eq!(variables.len(), 2 * n);
+        Self {
+            c0: Bls12381FpVariable::from_variables_unsafe(&variables[..n]),
+            c1: Bls12381FpVariable::from_variables_unsafe(&variables[n..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.c0.assert_is_valid(builder);
+        self.c1.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        2 * Bls12381FpVariable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut elements = Bls12381FpVariable::elements::<F>(value.c0);
+        elements.extend(Bls12381FpVariable::elements::<F>(value.c1));
+        elements
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bls12381FpVariable::nb_elements();
+        Fp2::new(
+            Bls12381FpVariable::from_elements::<F>(&elements[..n]),
+            Bls12381FpVariable::from_elements::<F>(&elements[n..]),
+        )
+    }
+}
+
+/// Computes `a / b` in the quadratic extension of the BLS12-381 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381Fp2DivHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381Fp2DivHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bls12381Fp2Variable>();
+        let b = input_stream.read_value::<Bls12381Fp2Variable>();
+        output_stream.write_value::<Bls12381Fp2Variable>(a.mul(&b.inverse()));
+    }
+}
+
+/// Computes a square root in the quadratic extension of the BLS12-381 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381Fp2SqrtHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381Fp2SqrtHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bls12381Fp2Variable>();
+        let root = a.sqrt().expect("not a square in Fp2");
+        output_stream.write_value::<Bls12381Fp2Variable>(root);
+    }
+}
+
+/// A term of a sum of products in `Fp2`, `a * b` or `-(a * b)` when `negate` is set, optionally
+/// multiplied by the non-residue `u + 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381Fp2Product {
+    pub a: Bls12381Fp2Variable,
+    pub b: Bls12381Fp2Variable,
+    pub negate: bool,
+    pub mul_by_xi: bool,
+}
+
+impl Bls12381Fp2Product {
+    pub fn new(a: Bls12381Fp2Variable, b: Bls12381Fp2Variable) -> Self {
+        Self {
+            a,
+            b,
+            negate: false,
+            mul_by_xi: false,
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns `sum(+-a * b)` for the given products, with one modular reduction per coefficient.
+    pub fn bls12_381_fp2_sum_of_products(
+        &mut self,
+        products: &[Bls12381Fp2Product],
+    ) -> Bls12381Fp2Variable {
+        let mut c0 = vec![];
+        let mut c1 = vec![];
+        for product in products.iter() {
+            let (a, b, negate) = (product.a, product.b, product.negate);
+            // (a0 + a1 u)(b0 + b1 u) = (a0 b0 - a1 b1) + (a0 b1 + a1 b0) u.
+            let real = [
+                Bls12381FpProduct::new(a.c0, b.c0, negate),
+                Bls12381FpProduct::new(a.c1, b.c1, !negate),
+            ];
+            let imaginary = [
+                Bls12381FpProduct::new(a.c0, b.c1, negate),
+                Bls12381FpProduct::new(a.c1, b.c0, negate),
+            ];
+            if product.mul_by_xi {
+                // (x0 + x1 u)(1 + u) = (x0 - x1) + (x0 + x1) u.
+                c0.extend(real);
+                c0.extend(imaginary.map(|p| Bls12381FpProduct::new(p.a, p.b, !p.negate)));
+                c1.extend(real);
+                c1.extend(imaginary);
+            } else {
+                c0.extend(real);
+                c1.extend(imaginary);
+            }
+        }
+        Bls12381Fp2Variable {
+            c0: self.bls12_381_fp_sum_of_products(&c0),
+            c1: self.bls12_381_fp_sum_of_products(&c1),
+        }
+    }
+
+    pub fn bls12_381_fp2_add(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+        b: &Bls12381Fp2Variable,
+    ) -> Bls12381Fp2Variable {
+        Bls12381Fp2Variable {
+            c0: self.bls12_381_fp_add(&a.c0, &b.c0),
+            c1: self.bls12_381_fp_add(&a.c1, &b.c1),
+        }
+    }
+
+    pub fn bls12_381_fp2_sub(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+        b: &Bls12381Fp2Variable,
+    ) -> Bls12381Fp2Variable {
+        Bls12381Fp2Variable {
+            c0: self.bls12_381_fp_sub(&a.c0, &b.c0),
+            c1: self.bls12_381_fp_sub(&a.c1, &b.c1),
+        }
+    }
+
+    pub fn bls12_381_fp2_neg(&mut self, a: &Bls12381Fp2Variable) -> Bls12381Fp2Variable {
+        Bls12381Fp2Variable {
+            c0: self.bls12_381_fp_neg(&a.c0),
+            c1: self.bls12_381_fp_neg(&a.c1),
+        }
+    }
+
+    pub fn bls12_381_fp2_conjugate(&mut self, a: &Bls12381Fp2Variable) -> Bls12381Fp2Variable {
+        Bls12381Fp2Variable {
+            c0: a.c0,
+            c1: self.bls12_381_fp_neg(&a.c1),
+        }
+    }
+
+    pub fn bls12_381_fp2_mul(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+        b: &Bls12381Fp2Variable,
+    ) -> Bls12381Fp2Variable {
+        self.bls12_381_fp2_sum_of_products(&[Bls12381Fp2Product::new(*a, *b)])
+    }
+
+    pub fn bls12_381_fp2_mul_by_fp(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+        b: &Bls12381FpVariable,
+    ) -> Bls12381Fp2Variable {
+        Bls12381Fp2Variable {
+            c0: self.bls12_381_fp_mul(&a.c0, b),
+            c1: self.bls12_381_fp_mul(&a.c1, b),
+        }
+    }
+
+    pub fn bls12_381_fp2_mul_by_constant(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+        b: &Fp2,
+    ) -> Bls12381Fp2Variable {
+        let b = self.constant::<Bls12381Fp2Variable>(b.clone());
+        self.bls12_381_fp2_mul(a, &b)
+    }
+
+    /// Returns `a / b`, which constrains `b` to be non-zero.
+    pub fn bls12_381_fp2_div(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+        b: &Bls12381Fp2Variable,
+    ) -> Bls12381Fp2Variable {
+        self.bls12_381_fp2_assert_not_zero(b);
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        input_stream.write(b);
+        let output_stream = self.hint(input_stream, Bls12381Fp2DivHint);
+        let quotient = output_stream.read::<Bls12381Fp2Variable>(self);
+
+        let product = self.bls12_381_fp2_mul(&quotient, b);
+        self.assert_is_equal(product, *a);
+        quotient
+    }
+
+    /// Returns a square root of `a`, which constrains `a` to be a square.
+    pub fn bls12_381_fp2_sqrt(&mut self, a: &Bls12381Fp2Variable) -> Bls12381Fp2Variable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        let output_stream = self.hint(input_stream, Bls12381Fp2SqrtHint);
+        let root = output_stream.read::<Bls12381Fp2Variable>(self);
+
+        let square = self.bls12_381_fp2_mul(&root, &root);
+        self.assert_is_equal(square, *a);
+        root
+    }
+
+    pub fn bls12_381_fp2_is_zero(&mut self, a: &Bls12381Fp2Variable) -> BoolVariable {
+        let c0_is_zero = self.bls12_381_fp_is_zero(&a.c0);
+        let c1_is_zero = self.bls12_381_fp_is_zero(&a.c1);
+        self.and(c0_is_zero, c1_is_zero)
+    }
+
+    pub fn bls12_381_fp2_assert_not_zero(&mut self, a: &Bls12381Fp2Variable) {
+        let is_zero = self.bls12_381_fp2_is_zero(a);
+        let false_val = self._false();
+        self.assert_is_equal(is_zero, false_val);
+    }
+
+    /// Returns whether `a` is larger than `-a` in the order used by compressed G2 points, which
+    /// compares the `c1` coefficients first.
+    pub fn bls12_381_fp2_is_lexicographically_largest(
+        &mut self,
+        a: &Bls12381Fp2Variable,
+    ) -> BoolVariable {
+        let c0_is_largest = self.bls12_381_fp_is_lexicographically_largest(&a.c0);
+        let c1_is_largest = self.bls12_381_fp_is_lexicographically_largest(&a.c1);
+        let c1_is_zero = self.bls12_381_fp_is_zero(&a.c1);
+        self.select(c1_is_zero, c0_is_largest, c1_is_largest)
+    }
+
+    /// Returns the sign of `a` in the sense of RFC 9380, i.e. the parity of `c0`, or of `c1` if
+    /// `c0` is zero.
+    pub fn bls12_381_fp2_sgn0(&mut self, a: &Bls12381Fp2Variable) -> BoolVariable {
+        let c0_sign = self.bls12_381_fp_sgn0(&a.c0);
+        let c0_is_zero = self.bls12_381_fp_is_zero(&a.c0);
+        let c1_sign = self.bls12_381_fp_sgn0(&a.c1);
+        let c1_sign = self.and(c0_is_zero, c1_sign);
+        self.or(c0_sign, c1_sign)
+    }
+}
+
+impl Bls12381Fp2Variable {
+    /// Returns the constant `c0 + c1 u` for small coefficients.
+    pub fn constant_u32<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        c0: u32,
+        c1: u32,
+    ) -> Self {
+        builder.constant::<Self>(Fp2::new(BigUint::from(c0), BigUint::from(c1)))
+    }
+}
@@ -0,0 +1,373 @@
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+
+use super::fp::{Bls12381FpProduct, Bls12381FpVariable};
+use crate::frontend::eth::vars::BLSPubkeyVariable;
+use crate::frontend::vars::EvmVariable;
+use crate::prelude::{
+    ArrayVariable, BoolVariable, ByteVariable, CircuitBuilder, CircuitVariable, PlonkParameters,
+    U32Variable, Variable,
+};
+use crate::utils::bls12_381::{
+    g1_mul, G1Affine, BLS12_381_G1_BETA, BLS12_381_G1_GENERATOR, BLS12_381_X_ABS,
+};
+
+lazy_static! {
+    /// The discrete logarithm of the offset point used by the aggregation.
+    static ref BLS12_381_AGGREGATION_OFFSET: BigUint =
+        BigUint::from_bytes_be(b"plonky2x-bls12-381-aggregation");
+}
+
+/// An affine point of G1, on `y^2 = x^3 + 4` over the BLS12-381 base field.
+///
+/// The point at infinity has no representation.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381G1AffineVariable {
+    pub x: Bls12381FpVariable,
+    pub y: Bls12381FpVariable,
+}
+
+impl CircuitVariable for Bls12381G1AffineVariable {
+    type ValueType<F: RichField> = G1Affine;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            x: Bls12381FpVariable::init_unsafe(builder),
+            y: Bls12381FpVariable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.x.variables();
+        variables.extend(self.y.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bls12381FpVariable::nb_elements();
+        assert_eq!(variables.len(), 2 * n);
+        Self {
+            x: Bls12381FpVariable::from_variables_unsafe(&variables[..n]),
+            y: Bls12381FpVariable::from_variables_unsafe(&variables[n..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.x.assert_is_valid(builder);
+        self.y.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        2 * Bls12381FpVariable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut elements = Bls12381FpVariable::elements::<F>(value.x);
+        elements.extend(Bls12381FpVariable::elements::<F>(value.y));
+        elements
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bls12381FpVariable::nb_elements();
+        G1Affine {
+            x: Bls12381FpVariable::from_elements::<F>(&elements[..n]),
+            y: Bls12381FpVariable::from_elements::<F>(&elements[n..]),
+        }
+    }
+}
+
+/// The flags stored in the three most significant bits of a compressed point.
+pub(crate) struct Bls12381CompressionFlags {
+    pub(crate) is_compressed: BoolVariable,
+    pub(crate) is_infinity: BoolVariable,
+    pub(crate) is_largest: BoolVariable,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Splits the flags off a big-endian encoded base field element, and asserts that the
+    /// remaining integer is less than the modulus.
+    pub(crate) fn bls12_381_decode_flagged_fp(
+        &mut self,
+        bytes: &[ByteVariable],
+    ) -> (Bls12381FpVariable, Bls12381CompressionFlags) {
+        assert_eq!(bytes.len(), 48);
+        let mut bits = bytes[0].as_be_bits();
+        let flags = Bls12381CompressionFlags {
+            is_compressed: bits[0],
+            is_infinity: bits[1],
+            is_largest: bits[2],
+        };
+        let false_val = self._false();
+        bits[..3].iter_mut().for_each(|bit| *bit = false_val);
+        let mut bytes = bytes.to_vec();
+        bytes[0] = ByteVariable::from_be_bits(bits);
+
+        let limbs = bytes
+            .chunks(4)
+            .rev()
+            .map(|chunk| U32Variable::decode(self, chunk))
+            .collect_vec();
+        let x = Bls12381FpVariable {
+            limbs: limbs.try_into().unwrap(),
+        };
+        self.bls12_381_fp_assert_reduced(&x);
+        (x, flags)
+    }
+
+    /// Asserts that the flags are those of a compressed point which is not the point at
+    /// infinity, and returns whether the y coordinate is the largest of the two candidates.
+    pub(crate) fn bls12_381_assert_compressed_finite(
+        &mut self,
+        flags: &Bls12381CompressionFlags,
+    ) -> BoolVariable {
+        let true_val = self._true();
+        let false_val = self._false();
+        self.assert_is_equal(flags.is_compressed, true_val);
+        self.assert_is_equal(flags.is_infinity, false_val);
+        flags.is_largest
+    }
+
+    /// Decompresses a public key in the ZCash format used by Ethereum.
+    ///
+    /// The point at infinity is rejected. The public key is not checked to be in the subgroup of
+    /// order `r`, which is the responsibility of whoever registered it (e.g. the deposit contract
+    /// flow for validator keys).
+    pub fn bls12_381_g1_decompress(
+        &mut self,
+        pubkey: &BLSPubkeyVariable,
+    ) -> Bls12381G1AffineVariable {
+        self.bls12_381_g1_decompress_bytes(&pubkey.0 .0)
+    }
+
+    /// Decompresses a 48 byte point in the ZCash format, rejecting the point at infinity.
+    pub(crate) fn bls12_381_g1_decompress_bytes(
+        &mut self,
+        bytes: &[ByteVariable],
+    ) -> Bls12381G1AffineVariable {
+        let (x, flags) = self.bls12_381_decode_flagged_fp(bytes);
+        let is_largest = self.bls12_381_assert_compressed_finite(&flags);
+        self.bls12_381_g1_recover_y(x, is_largest)
+    }
+
+    /// Decompresses a 48 byte point in the ZCash format which may be the point at infinity, whose
+    /// only valid encoding is `0xc0` followed by zero bytes. The generator is returned in place of
+    /// the point at infinity, together with whether the point is the point at infinity.
+    pub(crate) fn bls12_381_g1_decompress_bytes_or_infinity(
+        &mut self,
+        bytes: &[ByteVariable],
+    ) -> (Bls12381G1AffineVariable, BoolVariable) {
+        let (x, flags) = self.bls12_381_decode_flagged_fp(bytes);
+        let true_val = self._true();
+        let false_val = self._false();
+        self.assert_is_equal(flags.is_compressed, true_val);
+        let infinity_is_largest = self.and(flags.is_infinity, flags.is_largest);
+        self.assert_is_equal(infinity_is_largest, false_val);
+        let x_is_zero = self.bls12_381_fp_is_zero(&x);
+        let x_is_non_zero = self.not(x_is_zero);
+        let infinity_is_non_zero = self.and(flags.is_infinity, x_is_non_zero);
+        self.assert_is_equal(infinity_is_non_zero, false_val);
+
+        // For x = 0 the right hand side is 4, so a square root exists even for the point at
+        // infinity.
+        let point = self.bls12_381_g1_recover_y(x, flags.is_largest);
+        let generator = self.constant::<Bls12381G1AffineVariable>(BLS12_381_G1_GENERATOR.clone());
+        let point = self.select(flags.is_infinity, generator, point);
+        (point, flags.is_infinity)
+    }
+
+    /// Returns the point of the curve with the x coordinate `x`, whose y coordinate is the largest
+    /// of the two candidates if and only if `is_largest` is set. This constrains `x^3 + 4` to be a
+    /// square.
+    fn bls12_381_g1_recover_y(
+        &mut self,
+        x: Bls12381FpVariable,
+        is_largest: BoolVariable,
+    ) -> Bls12381G1AffineVariable {
+        let four = self.constant::<Bls12381FpVariable>(BigUint::from(4u32));
+        let one = self.constant::<Bls12381FpVariable>(BigUint::from(1u32));
+        let x2 = self.bls12_381_fp_mul(&x, &x);
+        let rhs = self.bls12_381_fp_sum_of_products(&[
+            Bls12381FpProduct::new(x2, x, false),
+            Bls12381FpProduct::new(four, one, false),
+        ]);
+        let y = self.bls12_381_fp_sqrt(&rhs);
+        let neg_y = self.bls12_381_fp_neg(&y);
+        let y_is_largest = self.bls12_381_fp_is_lexicographically_largest(&y);
+        let sign_matches = self.is_equal(y_is_largest, is_largest);
+        let y = self.select(sign_matches, y, neg_y);
+
+        Bls12381G1AffineVariable { x, y }
+    }
+
+    /// Asserts that `point` is on the curve `y^2 = x^3 + 4`.
+    pub fn bls12_381_g1_assert_on_curve(&mut self, point: &Bls12381G1AffineVariable) {
+        let four = self.constant::<Bls12381FpVariable>(BigUint::from(4u32));
+        let one = self.constant::<Bls12381FpVariable>(BigUint::from(1u32));
+        let x2 = self.bls12_381_fp_mul(&point.x, &point.x);
+        let zero = self.bls12_381_fp_sum_of_products(&[
+            Bls12381FpProduct::new(x2, point.x, false),
+            Bls12381FpProduct::new(four, one, false),
+            Bls12381FpProduct::new(point.y, point.y, true),
+        ]);
+        let is_zero = self.bls12_381_fp_is_zero(&zero);
+        let true_val = self._true();
+        self.assert_is_equal(is_zero, true_val);
+    }
+
+    pub fn bls12_381_g1_neg(&mut self, a: &Bls12381G1AffineVariable) -> Bls12381G1AffineVariable {
+        Bls12381G1AffineVariable {
+            x: a.x,
+            y: self.bls12_381_fp_neg(&a.y),
+        }
+    }
+
+    /// Adds two points with distinct x coordinates, which is constrained. The addition therefore
+    /// never silently returns a wrong result, but it cannot be proven for `a = +-b`.
+    pub fn bls12_381_g1_add(
+        &mut self,
+        a: &Bls12381G1AffineVariable,
+        b: &Bls12381G1AffineVariable,
+    ) -> Bls12381G1AffineVariable {
+        let dy = self.bls12_381_fp_sub(&b.y, &a.y);
+        let dx = self.bls12_381_fp_sub(&b.x, &a.x);
+        let slope = self.bls12_381_fp_div(&dy, &dx);
+        let one = self.constant::<Bls12381FpVariable>(BigUint::from(1u32));
+        let x = self.bls12_381_fp_sum_of_products(&[
+            Bls12381FpProduct::new(slope, slope, false),
+            Bls12381FpProduct::new(a.x, one, true),
+            Bls12381FpProduct::new(b.x, one, true),
+        ]);
+        let dx = self.bls12_381_fp_sub(&a.x, &x);
+        let y = self.bls12_381_fp_sum_of_products(&[
+            Bls12381FpProduct::new(slope, dx, false),
+            Bls12381FpProduct::new(a.y, one, true),
+        ]);
+        Bls12381G1AffineVariable { x, y }
+    }
+
+    /// Doubles a point whose y coordinate is non-zero, which is constrained.
+    pub fn bls12_381_g1_double(
+        &mut self,
+        a: &Bls12381G1AffineVariable,
+    ) -> Bls12381G1AffineVariable {
+        let three = self.constant::<Bls12381FpVariable>(BigUint::from(3u32));
+        let x2 = self.bls12_381_fp_mul(&a.x, &a.x);
+        let numerator = self.bls12_381_fp_mul(&three, &x2);
+        let denominator = self.bls12_381_fp_add(&a.y, &a.y);
+        let slope = self.bls12_381_fp_div(&numerator, &denominator);
+        let one = self.constant::<Bls12381FpVariable>(BigUint::from(1u32));
+        let x = self.bls12_381_fp_sum_of_products(&[
+            Bls12381FpProduct::new(slope, slope, false),
+            Bls12381FpProduct::new(a.x, one, true),
+            Bls12381FpProduct::new(a.x, one, true),
+        ]);
+        let dx = self.bls12_381_fp_sub(&a.x, &x);
+        let y = self.bls12_381_fp_sum_of_products(&[
+            Bls12381FpProduct::new(slope, dx, false),
+            Bls12381FpProduct::new(a.y, one, true),
+        ]);
+        Bls12381G1AffineVariable { x, y }
+    }
+
+    /// Asserts that `point` is on the curve and in the subgroup of order `r`, using the criterion
+    /// `sigma(P) = [-x^2] P` of Scott (https://eprint.iacr.org/2021/1130), where `sigma` is the
+    /// endomorphism `(x, y) -> (beta x, y)`.
+    pub fn bls12_381_g1_assert_in_subgroup(&mut self, point: &Bls12381G1AffineVariable) {
+        self.bls12_381_g1_assert_on_curve(point);
+
+        let x2 = BigUint::from(BLS12_381_X_ABS).pow(2);
+        let mut multiple = *point;
+        for i in (0..x2.bits() - 1).rev() {
+            multiple = self.bls12_381_g1_double(&multiple);
+            if x2.bit(i) {
+                multiple = self.bls12_381_g1_add(&multiple, point);
+            }
+        }
+        let multiple = self.bls12_381_g1_neg(&multiple);
+        let beta = self.constant::<Bls12381FpVariable>(BLS12_381_G1_BETA.clone());
+        let sigma = Bls12381G1AffineVariable {
+            x: self.bls12_381_fp_mul(&point.x, &beta),
+            y: point.y,
+        };
+        self.assert_is_equal(sigma, multiple);
+    }
+
+    /// Aggregates the public keys whose participation bit is set.
+    ///
+    /// The sum starts from a fixed offset point, which is subtracted at the end, so that the
+    /// accumulator is never the point at infinity. At least one public key must participate.
+    pub fn bls12_381_g1_aggregate<const N: usize>(
+        &mut self,
+        pubkeys: &ArrayVariable<Bls12381G1AffineVariable, N>,
+        participation: &ArrayVariable<BoolVariable, N>,
+    ) -> Bls12381G1AffineVariable {
+        let offset = g1_mul(&BLS12_381_G1_GENERATOR, &BLS12_381_AGGREGATION_OFFSET)
+            .expect("the offset is not the point at infinity");
+        let offset = self.constant::<Bls12381G1AffineVariable>(offset);
+
+        let mut acc = offset;
+        for (pubkey, bit) in pubkeys.as_vec().iter().zip(participation.as_vec()) {
+            let sum = self.bls12_381_g1_add(&acc, pubkey);
+            acc = self.select(bit, sum, acc);
+        }
+        let neg_offset = self.bls12_381_g1_neg(&offset);
+        self.bls12_381_g1_add(&acc, &neg_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+    use crate::utils;
+    use crate::utils::bls12_381::g1_add;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_g1_decompress_and_aggregate() {
+        utils::setup_logger();
+
+        const N: usize = 4;
+        let mut builder = DefaultBuilder::new();
+        let pubkeys = builder.read::<ArrayVariable<BLSPubkeyVariable, N>>();
+        let participation = builder.read::<ArrayVariable<BoolVariable, N>>();
+        let points = pubkeys
+            .as_vec()
+            .iter()
+            .map(|pubkey| builder.bls12_381_g1_decompress(pubkey))
+            .collect::<Vec<_>>();
+        for point in points.iter() {
+            builder.bls12_381_g1_assert_on_curve(point);
+        }
+        let points = ArrayVariable::<Bls12381G1AffineVariable, N>::new(points);
+        let aggregate = builder.bls12_381_g1_aggregate(&points, &participation);
+        builder.write(aggregate);
+        let circuit = builder.build();
+
+        let keys =
+            [3u32, 5, 8, 13].map(|sk| g1_mul(&BLS12_381_G1_GENERATOR, &BigUint::from(sk)).unwrap());
+        let participation = vec![true, true, false, true];
+        let expected = keys
+            .iter()
+            .zip(participation.iter())
+            .filter(|(_, bit)| **bit)
+            .fold(None, |acc, (key, _)| g1_add(&acc, &Some(key.clone())))
+            .unwrap();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BLSPubkeyVariable, N>>(
+            keys.iter().map(|key| key.to_compressed()).collect(),
+        );
+        input.write::<ArrayVariable<BoolVariable, N>>(participation);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<Bls12381G1AffineVariable>(), expected);
+    }
+}
@@ -0,0 +1,228 @@
+use plonky2::hash::hash_types::RichField;
+
+use super::fp2::{Bls12381Fp2Product, Bls12381Fp2Variable};
+use crate::frontend::eth::vars::BLSSignatureVariable;
+use crate::prelude::{CircuitBuilder, CircuitVariable, PlonkParameters, Variable};
+use crate::utils::bls12_381::{G2Affine, BLS12_381_PSI_COEFFS, BLS12_381_X_ABS};
+
+/// An affine point of G2, on the twist `y^2 = x^3 + 4(u + 1)` over `Fp2`.
+///
+/// The point at infinity has no representation.
+#[derive(Debug, Clone, Copy)]
+pub struct Bls12381G2AffineVariable {
+    pub x: Bls12381Fp2Variable,
+    pub y: Bls12381Fp2Variable,
+}
+
+impl CircuitVariable for Bls12381G2AffineVariable {
+    type ValueType<F: RichField> = G2Affine;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            x: Bls12381Fp2Variable::init_unsafe(builder),
+            y: Bls12381Fp2Variable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.x.variables();
+        variables.extend(self.y.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bls12381Fp2Variable::nb_elements();
+        assert_eq!(variables.len(), 2 * n);
+        Self {
+            x: Bls12381Fp2Variable::from_variables_unsafe(&variables[..n]),
+            y: Bls12381Fp2Variable::from_variables_unsafe(&variables[n..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.x.assert_is_valid(builder);
+        self.y.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        2 * Bls12381Fp2Variable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut elements = Bls12381Fp2Variable::elements::<F>(value.x);
+        elements.extend(Bls12381Fp2Variable::elements::<F>(value.y));
+        elements
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bls12381Fp2Variable::nb_elements();
+        G2Affine {
+            x: Bls12381Fp2Variable::from_elements::<F>(&elements[..n]),
+            y: Bls12381Fp2Variable::from_elements::<F>(&elements[n..]),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns `x^3 + 4(u + 1)`.
+    fn bls12_381_g2_curve_rhs(&mut self, x: &Bls12381Fp2Variable) -> Bls12381Fp2Variable {
+        let four = Bls12381Fp2Variable::constant_u32(self, 4, 0);
+        let one = Bls12381Fp2Variable::constant_u32(self, 1, 0);
+        let x2 = self.bls12_381_fp2_mul(x, x);
+        let mut b = Bls12381Fp2Product::new(four, one);
+        b.mul_by_xi = true;
+        self.bls12_381_fp2_sum_of_products(&[Bls12381Fp2Product::new(x2, *x), b])
+    }
+
+    /// Decompresses a signature in the ZCash format used by Ethereum, i.e. `x.c1 || x.c0` with the
+    /// flags in the three most significant bits.
+    ///
+    /// The point at infinity is rejected. The signature is not checked to be in the subgroup of
+    /// order `r`, see [`Self::bls12_381_g2_assert_in_subgroup`].
+    pub fn bls12_381_g2_decompress(
+        &mut self,
+        signature: &BLSSignatureVariable,
+    ) -> Bls12381G2AffineVariable {
+        let (c1, flags) = self.bls12_381_decode_flagged_fp(&signature.0 .0[..48]);
+        let is_largest = self.bls12_381_assert_compressed_finite(&flags);
+        let (c0, c0_flags) = self.bls12_381_decode_flagged_fp(&signature.0 .0[48..]);
+        let false_val = self._false();
+        self.assert_is_equal(c0_flags.is_compressed, false_val);
+        self.assert_is_equal(c0_flags.is_infinity, false_val);
+        self.assert_is_equal(c0_flags.is_largest, false_val);
+        let x = Bls12381Fp2Variable { c0, c1 };
+
+        let rhs = self.bls12_381_g2_curve_rhs(&x);
+        let y = self.bls12_381_fp2_sqrt(&rhs);
+        let neg_y = self.bls12_381_fp2_neg(&y);
+        let y_is_largest = self.bls12_381_fp2_is_lexicographically_largest(&y);
+        let sign_matches = self.is_equal(y_is_largest, is_largest);
+        let y = self.select(sign_matches, y, neg_y);
+
+        Bls12381G2AffineVariable { x, y }
+    }
+
+    /// Asserts that `point` is on the twist `y^2 = x^3 + 4(u + 1)`.
+    pub fn bls12_381_g2_assert_on_curve(&mut self, point: &Bls12381G2AffineVariable) {
+        let rhs = self.bls12_381_g2_curve_rhs(&point.x);
+        let y2 = self.bls12_381_fp2_mul(&point.y, &point.y);
+        self.assert_is_equal(rhs, y2);
+    }
+
+    pub fn bls12_381_g2_neg(&mut self, a: &Bls12381G2AffineVariable) -> Bls12381G2AffineVariable {
+        Bls12381G2AffineVariable {
+            x: a.x,
+            y: self.bls12_381_fp2_neg(&a.y),
+        }
+    }
+
+    /// Returns the point `(slope^2 - a.x - b.x, slope (a.x - x) - a.y)` on the line through `a`.
+    fn bls12_381_g2_chord(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+        b_x: &Bls12381Fp2Variable,
+        slope: &Bls12381Fp2Variable,
+    ) -> Bls12381G2AffineVariable {
+        let one = Bls12381Fp2Variable::constant_u32(self, 1, 0);
+        let mut a_x = Bls12381Fp2Product::new(a.x, one);
+        a_x.negate = true;
+        let mut b_x = Bls12381Fp2Product::new(*b_x, one);
+        b_x.negate = true;
+        let x = self.bls12_381_fp2_sum_of_products(&[
+            Bls12381Fp2Product::new(*slope, *slope),
+            a_x,
+            b_x,
+        ]);
+        let dx = self.bls12_381_fp2_sub(&a.x, &x);
+        let mut a_y = Bls12381Fp2Product::new(a.y, one);
+        a_y.negate = true;
+        let y = self.bls12_381_fp2_sum_of_products(&[Bls12381Fp2Product::new(*slope, dx), a_y]);
+        Bls12381G2AffineVariable { x, y }
+    }
+
+    /// Adds two points with distinct x coordinates, and returns the sum together with the slope
+    /// of the line through them.
+    pub(crate) fn bls12_381_g2_add_with_slope(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+        b: &Bls12381G2AffineVariable,
+    ) -> (Bls12381G2AffineVariable, Bls12381Fp2Variable) {
+        let dy = self.bls12_381_fp2_sub(&b.y, &a.y);
+        let dx = self.bls12_381_fp2_sub(&b.x, &a.x);
+        let slope = self.bls12_381_fp2_div(&dy, &dx);
+        (self.bls12_381_g2_chord(a, &b.x, &slope), slope)
+    }
+
+    /// Doubles a point whose y coordinate is non-zero, and returns the double together with the
+    /// slope of the tangent.
+    pub(crate) fn bls12_381_g2_double_with_slope(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+    ) -> (Bls12381G2AffineVariable, Bls12381Fp2Variable) {
+        let three = Bls12381Fp2Variable::constant_u32(self, 3, 0);
+        let x2 = self.bls12_381_fp2_mul(&a.x, &a.x);
+        let numerator = self.bls12_381_fp2_mul(&three, &x2);
+        let denominator = self.bls12_381_fp2_add(&a.y, &a.y);
+        let slope = self.bls12_381_fp2_div(&numerator, &denominator);
+        (self.bls12_381_g2_chord(a, &a.x, &slope), slope)
+    }
+
+    /// Adds two points with distinct x coordinates, which is constrained.
+    pub fn bls12_381_g2_add(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+        b: &Bls12381G2AffineVariable,
+    ) -> Bls12381G2AffineVariable {
+        self.bls12_381_g2_add_with_slope(a, b).0
+    }
+
+    pub fn bls12_381_g2_double(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+    ) -> Bls12381G2AffineVariable {
+        self.bls12_381_g2_double_with_slope(a).0
+    }
+
+    /// The endomorphism `psi = twist^-1 o frobenius o twist` of the twist.
+    pub fn bls12_381_g2_psi(&mut self, a: &Bls12381G2AffineVariable) -> Bls12381G2AffineVariable {
+        let (cx, cy) = &*BLS12_381_PSI_COEFFS;
+        let x = self.bls12_381_fp2_conjugate(&a.x);
+        let y = self.bls12_381_fp2_conjugate(&a.y);
+        Bls12381G2AffineVariable {
+            x: self.bls12_381_fp2_mul_by_constant(&x, cx),
+            y: self.bls12_381_fp2_mul_by_constant(&y, cy),
+        }
+    }
+
+    /// Asserts that `point` is on the twist and in the subgroup of order `r`, using the criterion
+    /// `psi(P) = [x] P` of Scott (https://eprint.iacr.org/2021/1130).
+    pub fn bls12_381_g2_assert_in_subgroup(&mut self, point: &Bls12381G2AffineVariable) {
+        self.bls12_381_g2_assert_on_curve(point);
+        let multiple = self.bls12_381_g2_mul_by_x(point);
+        let psi = self.bls12_381_g2_psi(point);
+        self.assert_is_equal(psi, multiple);
+    }
+
+    /// Returns `[x] a` for the BLS parameter `x`, with a double-and-add over `|x|`. None of the
+    /// intermediate multiples may be `a`, `-a` or the point at infinity, which only happens for
+    /// points of small order.
+    pub fn bls12_381_g2_mul_by_x(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+    ) -> Bls12381G2AffineVariable {
+        let mut multiple = *a;
+        for i in (0..63).rev() {
+            multiple = self.bls12_381_g2_double(&multiple);
+            if (BLS12_381_X_ABS >> i) & 1 == 1 {
+                multiple = self.bls12_381_g2_add(&multiple, a);
+            }
+        }
+        // The parameter `x` is negative.
+        self.bls12_381_g2_neg(&multiple)
+    }
+}
@@ -0,0 +1,306 @@
+//! Hashing to G2 with the suite `BLS12381G2_XMD:SHA-256_SSWU_RO_` of RFC 9380, which is the one
+//! used by the BLS signatures of Ethereum.
+//!
+//! The additions are incomplete, so the prover fails on the exceptional inputs for which two
+//! summands coincide or the sum is the point at infinity, which a hash output only hits with
+//! negligible probability.
+
+use itertools::Itertools;
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use super::fp::Bls12381FpVariable;
+use super::fp2::{Bls12381Fp2Product, Bls12381Fp2Variable};
+use super::g2::Bls12381G2AffineVariable;
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::BigUintTarget;
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::{
+    BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters, U32Variable, ValueStream,
+    VariableStream,
+};
+use crate::utils::bls12_381::{
+    Fp2, BLS12_381_ISO3_X_DEN, BLS12_381_ISO3_X_NUM, BLS12_381_ISO3_Y_DEN, BLS12_381_ISO3_Y_NUM,
+    BLS12_381_SSWU_A, BLS12_381_SSWU_B, BLS12_381_SSWU_Z,
+};
+
+/// Given `g(x1)` and `g(x2)` of the simplified SWU map, returns whether `g(x1)` is a square and a
+/// square root of `g(x1)` if it is, of `g(x2)` otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bls12381SswuSqrtHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bls12381SswuSqrtHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let gx1 = input_stream.read_value::<Bls12381Fp2Variable>();
+        let gx2 = input_stream.read_value::<Bls12381Fp2Variable>();
+        let (is_square, root) = match gx1.sqrt() {
+            Some(root) => (true, root),
+            None => (
+                false,
+                gx2.sqrt().expect("neither g(x1) nor g(x2) is a square"),
+            ),
+        };
+        output_stream.write_value::<BoolVariable>(is_square);
+        output_stream.write_value::<Bls12381Fp2Variable>(root);
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Expands `msg` into `len` uniform bytes with `expand_message_xmd` over SHA-256 (RFC 9380,
+    /// section 5.3.1).
+    pub fn bls12_381_expand_message_xmd(
+        &mut self,
+        msg: &[ByteVariable],
+        dst: &[u8],
+        len: usize,
+    ) -> Vec<ByteVariable> {
+        assert!(dst.len() < 256 && len < 256 * 32);
+        let dst_prime = [dst, &[dst.len() as u8]]
+            .concat()
+            .into_iter()
+            .map(|byte| self.constant::<ByteVariable>(byte))
+            .collect_vec();
+
+        let mut input = (0..64)
+            .map(|_| self.constant::<ByteVariable>(0))
+            .collect_vec();
+        input.extend_from_slice(msg);
+        for byte in (len as u16).to_be_bytes().into_iter().chain([0]) {
+            input.push(self.constant::<ByteVariable>(byte));
+        }
+        input.extend_from_slice(&dst_prime);
+        let b_0 = self.curta_sha256(&input).as_bytes();
+
+        let mut input = b_0.to_vec();
+        input.push(self.constant::<ByteVariable>(1));
+        input.extend_from_slice(&dst_prime);
+        let mut b_i = self.curta_sha256(&input).as_bytes();
+        let mut bytes = b_i.to_vec();
+        for i in 2..=(len + 31) / 32 {
+            let mut input = b_0
+                .iter()
+                .zip(b_i.iter())
+                .map(|(a, b)| self.xor(*a, *b))
+                .collect_vec();
+            input.push(self.constant::<ByteVariable>(i as u8));
+            input.extend_from_slice(&dst_prime);
+            b_i = self.curta_sha256(&input).as_bytes();
+            bytes.extend_from_slice(&b_i);
+        }
+        bytes.truncate(len);
+        bytes
+    }
+
+    /// Hashes `msg` to two elements of `Fp2`, reducing 64 byte big-endian chunks modulo `p`.
+    pub fn bls12_381_hash_to_field(
+        &mut self,
+        msg: &[ByteVariable],
+        dst: &[u8],
+    ) -> [Bls12381Fp2Variable; 2] {
+        let bytes = self.bls12_381_expand_message_xmd(msg, dst, 256);
+        let elements = bytes
+            .chunks(64)
+            .map(|chunk| {
+                let limbs = chunk
+                    .chunks(4)
+                    .rev()
+                    .map(|limb| U32Target::from(U32Variable::decode(self, limb)))
+                    .collect();
+                let bound = BigUint::default();
+                self.bls12_381_fp_reduce(vec![BigUintTarget { limbs }], vec![], &bound)
+            })
+            .collect::<Vec<Bls12381FpVariable>>();
+        [
+            Bls12381Fp2Variable {
+                c0: elements[0],
+                c1: elements[1],
+            },
+            Bls12381Fp2Variable {
+                c0: elements[2],
+                c1: elements[3],
+            },
+        ]
+    }
+
+    /// Returns `x^3 + A' x + B'`, the right hand side of the curve `E'` of the SWU map.
+    fn bls12_381_sswu_rhs(&mut self, x: &Bls12381Fp2Variable) -> Bls12381Fp2Variable {
+        let a = self.constant::<Bls12381Fp2Variable>(BLS12_381_SSWU_A.clone());
+        let b = self.constant::<Bls12381Fp2Variable>(BLS12_381_SSWU_B.clone());
+        let one = Bls12381Fp2Variable::constant_u32(self, 1, 0);
+        let x2 = self.bls12_381_fp2_mul(x, x);
+        self.bls12_381_fp2_sum_of_products(&[
+            Bls12381Fp2Product::new(x2, *x),
+            Bls12381Fp2Product::new(a, *x),
+            Bls12381Fp2Product::new(b, one),
+        ])
+    }
+
+    /// The simplified SWU map to the curve `E'` which is 3-isogenous to the twist (RFC 9380,
+    /// section 6.6.2).
+    ///
+    /// The square root is hinted together with whether `g(x1)` is a square. When it is not, the
+    /// root of `g(x2) = Z^3 u^6 g(x1)` proves it, since `Z` is not a square, as long as `g(x1)`
+    /// and `u` are non-zero.
+    pub fn bls12_381_map_to_curve_sswu(
+        &mut self,
+        u: &Bls12381Fp2Variable,
+    ) -> (Bls12381Fp2Variable, Bls12381Fp2Variable) {
+        let (a, b, z) = (&*BLS12_381_SSWU_A, &*BLS12_381_SSWU_B, &*BLS12_381_SSWU_Z);
+        let one = Bls12381Fp2Variable::constant_u32(self, 1, 0);
+
+        let u2 = self.bls12_381_fp2_mul(u, u);
+        let z_u2 = self.bls12_381_fp2_mul_by_constant(&u2, z);
+        let tv1 = self.bls12_381_fp2_sum_of_products(&[
+            Bls12381Fp2Product::new(z_u2, z_u2),
+            Bls12381Fp2Product::new(z_u2, one),
+        ]);
+
+        // x1 = B / (Z A) if tv1 is zero, and -B / A (1 + 1 / tv1) otherwise.
+        let tv1_is_zero = self.bls12_381_fp2_is_zero(&tv1);
+        let denominator = self.select(tv1_is_zero, one, tv1);
+        let inverse = self.bls12_381_fp2_div(&one, &denominator);
+        let x1 = self.bls12_381_fp2_add(&one, &inverse);
+        let x1 = self.bls12_381_fp2_mul_by_constant(&x1, &b.neg().mul(&a.inverse()));
+        let exceptional_x1 = self.constant::<Bls12381Fp2Variable>(b.mul(&z.mul(a).inverse()));
+        let x1 = self.select(tv1_is_zero, exceptional_x1, x1);
+        let gx1 = self.bls12_381_sswu_rhs(&x1);
+        let x2 = self.bls12_381_fp2_mul(&z_u2, &x1);
+        let gx2 = self.bls12_381_sswu_rhs(&x2);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&gx1);
+        input_stream.write(&gx2);
+        let output_stream = self.hint(input_stream, Bls12381SswuSqrtHint);
+        let is_square = output_stream.read::<BoolVariable>(self);
+        let y = output_stream.read::<Bls12381Fp2Variable>(self);
+
+        let gx = self.select(is_square, gx1, gx2);
+        let y2 = self.bls12_381_fp2_mul(&y, &y);
+        self.assert_is_equal(y2, gx);
+        let gx1_is_zero = self.bls12_381_fp2_is_zero(&gx1);
+        let is_degenerate = self.or(gx1_is_zero, tv1_is_zero);
+        let is_not_square = self.not(is_square);
+        let is_unproven = self.and(is_not_square, is_degenerate);
+        let false_val = self._false();
+        self.assert_is_equal(is_unproven, false_val);
+
+        let x = self.select(is_square, x1, x2);
+        let y_sign = self.bls12_381_fp2_sgn0(&y);
+        let u_sign = self.bls12_381_fp2_sgn0(u);
+        let sign_matches = self.is_equal(y_sign, u_sign);
+        let neg_y = self.bls12_381_fp2_neg(&y);
+        let y = self.select(sign_matches, y, neg_y);
+        (x, y)
+    }
+
+    /// Evaluates the polynomial with constant coefficients `coeffs` at `x`, given the powers
+    /// `1, x, x^2, ...`.
+    fn bls12_381_fp2_poly_eval(
+        &mut self,
+        coeffs: &[Fp2],
+        powers: &[Bls12381Fp2Variable],
+    ) -> Bls12381Fp2Variable {
+        let products = coeffs
+            .iter()
+            .zip(powers.iter())
+            .map(|(coeff, power)| {
+                let coeff = self.constant::<Bls12381Fp2Variable>(coeff.clone());
+                Bls12381Fp2Product::new(coeff, *power)
+            })
+            .collect_vec();
+        self.bls12_381_fp2_sum_of_products(&products)
+    }
+
+    /// The 3-isogeny from `E'` to the twist (RFC 9380, appendix E.3).
+    pub fn bls12_381_iso_map(
+        &mut self,
+        x: &Bls12381Fp2Variable,
+        y: &Bls12381Fp2Variable,
+    ) -> Bls12381G2AffineVariable {
+        let one = Bls12381Fp2Variable::constant_u32(self, 1, 0);
+        let x2 = self.bls12_381_fp2_mul(x, x);
+        let x3 = self.bls12_381_fp2_mul(&x2, x);
+        let powers = [one, *x, x2, x3];
+        let x_num = self.bls12_381_fp2_poly_eval(&*BLS12_381_ISO3_X_NUM, &powers);
+        let x_den = self.bls12_381_fp2_poly_eval(&*BLS12_381_ISO3_X_DEN, &powers);
+        let y_num = self.bls12_381_fp2_poly_eval(&*BLS12_381_ISO3_Y_NUM, &powers);
+        let y_den = self.bls12_381_fp2_poly_eval(&*BLS12_381_ISO3_Y_DEN, &powers);
+        let y_num = self.bls12_381_fp2_mul(y, &y_num);
+        Bls12381G2AffineVariable {
+            x: self.bls12_381_fp2_div(&x_num, &x_den),
+            y: self.bls12_381_fp2_div(&y_num, &y_den),
+        }
+    }
+
+    fn bls12_381_g2_sub(
+        &mut self,
+        a: &Bls12381G2AffineVariable,
+        b: &Bls12381G2AffineVariable,
+    ) -> Bls12381G2AffineVariable {
+        let neg_b = self.bls12_381_g2_neg(b);
+        self.bls12_381_g2_add(a, &neg_b)
+    }
+
+    /// Clears the cofactor of a point of the twist with the method of Budroni and Pintore (RFC
+    /// 9380, appendix G.3), which maps it to G2.
+    pub fn bls12_381_g2_clear_cofactor(
+        &mut self,
+        point: &Bls12381G2AffineVariable,
+    ) -> Bls12381G2AffineVariable {
+        let t1 = self.bls12_381_g2_mul_by_x(point);
+        let t2 = self.bls12_381_g2_psi(point);
+        let t3 = self.bls12_381_g2_double(point);
+        let t3 = self.bls12_381_g2_psi(&t3);
+        let t3 = self.bls12_381_g2_psi(&t3);
+        let t3 = self.bls12_381_g2_sub(&t3, &t2);
+        let t2 = self.bls12_381_g2_add(&t1, &t2);
+        let t2 = self.bls12_381_g2_mul_by_x(&t2);
+        let t3 = self.bls12_381_g2_add(&t3, &t2);
+        let t3 = self.bls12_381_g2_sub(&t3, &t1);
+        self.bls12_381_g2_sub(&t3, point)
+    }
+
+    /// Hashes `msg` to G2 with the domain separation tag `dst`.
+    pub fn bls12_381_hash_to_g2(
+        &mut self,
+        msg: &[ByteVariable],
+        dst: &[u8],
+    ) -> Bls12381G2AffineVariable {
+        let [u0, u1] = self.bls12_381_hash_to_field(msg, dst);
+        let (x0, y0) = self.bls12_381_map_to_curve_sswu(&u0);
+        let q0 = self.bls12_381_iso_map(&x0, &y0);
+        let (x1, y1) = self.bls12_381_map_to_curve_sswu(&u1);
+        let q1 = self.bls12_381_iso_map(&x1, &y1);
+        let sum = self.bls12_381_g2_add(&q0, &q1);
+        self.bls12_381_g2_clear_cofactor(&sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{BytesVariable, DefaultBuilder};
+    use crate::utils;
+    use crate::utils::bls12_381::hash_to_g2;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_hash_to_g2() {
+        utils::setup_logger();
+
+        // RFC 9380, appendix J.10.1.
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let mut builder = DefaultBuilder::new();
+        let msg = builder.read::<BytesVariable<3>>();
+        let point = builder.bls12_381_hash_to_g2(&msg.0, dst);
+        builder.write::<Bls12381G2AffineVariable>(point);
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<BytesVariable<3>>(*b"abc");
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        let point = output.read::<Bls12381G2AffineVariable>();
+        assert_eq!(point, hash_to_g2(b"abc", dst).unwrap());
+    }
+}
@@ -0,0 +1,380 @@
+use ethers::types::U256;
+use lazy_static::lazy_static;
+use num::BigUint;
+
+use super::fp12::Bls12381Fp12Variable;
+use super::g1::Bls12381G1AffineVariable;
+use super::g2::Bls12381G2AffineVariable;
+use crate::prelude::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitBuilder, PlonkParameters,
+    U256Variable,
+};
+use crate::utils::bls12_381::{
+    g1_add, g1_mul, Fp12, G2Affine, BLS12_381_G1_GENERATOR, BLS12_381_G2_GENERATOR, BLS12_381_ORDER,
+};
+
+/// The version byte of the versioned hashes of KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The number of bits of the scalars of the point evaluation, which are less than `r < 2^255`.
+const KZG_SCALAR_BITS: usize = 255;
+
+lazy_static! {
+    /// The point `[tau] G2` of the EIP-4844 trusted setup.
+    pub static ref KZG_SETUP_G2_TAU: G2Affine = G2Affine::from_compressed(
+        &hex::decode(
+            "b5bfd7dd8cdeb128843bc287230af38926187075cbfbefa81009a2ce615ac53d2914e5870cb452d2afaaab24f3499f72185cbfee53492714734429b7b38608e23926c911cceceac9a36851477ba4c60b087041de621000edc98edada20c1def2"
+        )
+        .unwrap()
+        .try_into()
+        .unwrap()
+    )
+    .unwrap();
+    /// The discrete logarithm of the initial value of the accumulator of the point evaluation.
+    static ref KZG_ACCUMULATOR_OFFSET: BigUint =
+        BigUint::from_bytes_be(b"plonky2x-bls12-381-kzg-accumulator");
+    /// The discrete logarithm of the point added at every step of the point evaluation.
+    static ref KZG_STEP_OFFSET: BigUint = BigUint::from_bytes_be(b"plonky2x-bls12-381-kzg-step");
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes the versioned hash `VERSIONED_HASH_VERSION_KZG || sha256(commitment)[1..]` of a
+    /// KZG commitment, as referenced by blob transactions.
+    pub fn kzg_to_versioned_hash(&mut self, commitment: &BytesVariable<48>) -> Bytes32Variable {
+        let mut hash = self.curta_sha256(&commitment.0).as_bytes();
+        hash[0] = self.constant::<ByteVariable>(VERSIONED_HASH_VERSION_KZG);
+        Bytes32Variable(BytesVariable(hash))
+    }
+
+    /// Checks the relation of the EIP-4844 point evaluation precompile, i.e. that the versioned
+    /// hash is that of the commitment and that `proof` proves `p(z) = y` for the polynomial `p`
+    /// committed to by `commitment`.
+    pub fn kzg_point_evaluation(
+        &mut self,
+        versioned_hash: &Bytes32Variable,
+        z: &Bytes32Variable,
+        y: &Bytes32Variable,
+        commitment: &BytesVariable<48>,
+        proof: &BytesVariable<48>,
+    ) {
+        let commitment_hash = self.kzg_to_versioned_hash(commitment);
+        self.assert_is_equal(commitment_hash, *versioned_hash);
+        self.bls12_381_verify_kzg_proof(commitment, z, y, proof);
+    }
+
+    /// Verifies a KZG proof against the EIP-4844 trusted setup, as in `verify_kzg_proof` of the
+    /// Deneb polynomial commitments spec.
+    ///
+    /// The evaluation point `z` and the value `y` are big-endian encoded scalars, which must be
+    /// less than the order `r`. The commitment and the proof are compressed points of G1, which
+    /// are checked to be in the subgroup of order `r`. Either may be the point at infinity, which
+    /// is the commitment of the zero polynomial and the proof of any constant polynomial.
+    pub fn bls12_381_verify_kzg_proof(
+        &mut self,
+        commitment: &BytesVariable<48>,
+        z: &Bytes32Variable,
+        y: &Bytes32Variable,
+        proof: &BytesVariable<48>,
+    ) {
+        self.bls12_381_verify_kzg_proof_with_setup(&KZG_SETUP_G2_TAU, commitment, z, y, proof);
+    }
+
+    /// Verifies a KZG proof against the trusted setup whose secret is `tau`, given `[tau] G2`.
+    ///
+    /// The pairing check `e(C - [y] G1, -G2) e(proof, [tau - z] G2) = 1` is rearranged into
+    /// `e(C - [y] G1 + [z] proof, G2) e(-proof, [tau] G2) = 1`, so that all the scalar
+    /// multiplications are in G1. They are computed jointly by a double-and-add which starts from
+    /// an offset point and also adds an offset point at every step, so that the incomplete
+    /// additions never meet the point at infinity. The offsets are removed at the end.
+    ///
+    /// The point at infinity is replaced by the generator in the arithmetic, and its terms are
+    /// dropped with selects. If exactly one of `C - [y] G1 + [z] proof` and `proof` is the point
+    /// at infinity, the product of the pairings cannot be one, and if both are, it trivially is.
+    pub fn bls12_381_verify_kzg_proof_with_setup(
+        &mut self,
+        tau_g2: &G2Affine,
+        commitment: &BytesVariable<48>,
+        z: &Bytes32Variable,
+        y: &Bytes32Variable,
+        proof: &BytesVariable<48>,
+    ) {
+        let (commitment, commitment_is_infinity) =
+            self.bls12_381_g1_decompress_bytes_or_infinity(&commitment.0);
+        self.bls12_381_g1_assert_in_subgroup(&commitment);
+        let (proof, proof_is_infinity) = self.bls12_381_g1_decompress_bytes_or_infinity(&proof.0);
+        self.bls12_381_g1_assert_in_subgroup(&proof);
+        let proof_is_finite = self.not(proof_is_infinity);
+
+        let z_bits = self.kzg_scalar_bits(z);
+        let y_bits = self.kzg_scalar_bits(y);
+
+        // The accumulator starts at Q, and H is added at every step, so that after the last step
+        // it is [2^n] Q + [2^n - 1] H + [z] proof - [y] G1.
+        let accumulator_offset = g1_mul(&BLS12_381_G1_GENERATOR, &KZG_ACCUMULATOR_OFFSET)
+            .expect("the offset is not the point at infinity");
+        let step_offset = g1_mul(&BLS12_381_G1_GENERATOR, &KZG_STEP_OFFSET)
+            .expect("the offset is not the point at infinity");
+        let step_offset_minus_g1 = g1_add(
+            &Some(step_offset.clone()),
+            &Some(BLS12_381_G1_GENERATOR.neg()),
+        )
+        .expect("the offset is not the generator");
+        let pow_2_n = BigUint::from(1u32) << KZG_SCALAR_BITS;
+        let total_offset = g1_add(
+            &g1_mul(&accumulator_offset, &pow_2_n),
+            &g1_mul(&step_offset, &(&pow_2_n - 1u32)),
+        )
+        .expect("the total offset is not the point at infinity");
+        let total_offset_plus_g1 = g1_add(
+            &Some(total_offset.clone()),
+            &Some(BLS12_381_G1_GENERATOR.clone()),
+        )
+        .expect("the total offset is not the negated generator");
+
+        let step_offset = self.constant::<Bls12381G1AffineVariable>(step_offset);
+        let step_offset_minus_g1 = self.constant::<Bls12381G1AffineVariable>(step_offset_minus_g1);
+        let step_offset_plus_proof = self.bls12_381_g1_add(&step_offset, &proof);
+        let step_offset_minus_g1_plus_proof = self.bls12_381_g1_add(&step_offset_minus_g1, &proof);
+
+        let mut acc = self.constant::<Bls12381G1AffineVariable>(accumulator_offset);
+        for (z_bit, y_bit) in z_bits.into_iter().zip(y_bits) {
+            acc = self.bls12_381_g1_double(&acc);
+            // The multiples of the point at infinity are skipped.
+            let z_bit = self.and(z_bit, proof_is_finite);
+            let entry_without_proof = self.select(y_bit, step_offset_minus_g1, step_offset);
+            let entry_with_proof = self.select(
+                y_bit,
+                step_offset_minus_g1_plus_proof,
+                step_offset_plus_proof,
+            );
+            let entry = self.select(z_bit, entry_with_proof, entry_without_proof);
+            acc = self.bls12_381_g1_add(&acc, &entry);
+        }
+        let acc_plus_commitment = self.bls12_381_g1_add(&acc, &commitment);
+        let acc = self.select(commitment_is_infinity, acc, acc_plus_commitment);
+
+        // The accumulator is now the total offset plus `C - [y] G1 + [z] proof`, which is the
+        // point at infinity exactly when the accumulator is the total offset.
+        let neg_total_offset = self.constant::<Bls12381G1AffineVariable>(total_offset.neg());
+        let total_offset = self.constant::<Bls12381G1AffineVariable>(total_offset);
+        let lhs_is_infinity = self.is_equal(acc, total_offset);
+        let total_offset_plus_g1 = self.constant::<Bls12381G1AffineVariable>(total_offset_plus_g1);
+        let acc = self.select(lhs_is_infinity, total_offset_plus_g1, acc);
+        let lhs = self.bls12_381_g1_add(&acc, &neg_total_offset);
+        self.assert_is_equal(lhs_is_infinity, proof_is_infinity);
+
+        let g2_generator =
+            self.constant::<Bls12381G2AffineVariable>(BLS12_381_G2_GENERATOR.clone());
+        let tau_g2 = self.constant::<Bls12381G2AffineVariable>(tau_g2.clone());
+        let neg_proof = self.bls12_381_g1_neg(&proof);
+        let f = self.bls12_381_miller_loop(&[(lhs, g2_generator), (neg_proof, tau_g2)]);
+        let result = self.bls12_381_final_exponentiation(&f);
+        let one = self.constant::<Bls12381Fp12Variable>(Fp12::one());
+        let result_is_one = self.is_equal(result, one);
+        let is_valid = self.or(result_is_one, proof_is_infinity);
+        let true_val = self._true();
+        self.assert_is_equal(is_valid, true_val);
+    }
+
+    /// Asserts that a big-endian encoded scalar is less than the order `r`, and returns its
+    /// `KZG_SCALAR_BITS` least significant bits from the most significant one.
+    fn kzg_scalar_bits(&mut self, scalar: &Bytes32Variable) -> Vec<BoolVariable> {
+        let order = U256::from_little_endian(&BLS12_381_ORDER.to_bytes_le());
+        let order = self.constant::<U256Variable>(order);
+        let scalar_u256 = scalar.as_u256(self);
+        let is_reduced = self.lt(scalar_u256, order);
+        let true_val = self._true();
+        self.assert_is_equal(is_reduced, true_val);
+
+        let bits = scalar
+            .as_bytes()
+            .iter()
+            .flat_map(|byte| byte.as_be_bits())
+            .collect::<Vec<_>>();
+        bits[256 - KZG_SCALAR_BITS..].to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+    use crate::utils;
+    use crate::utils::bls12_381::g2_mul;
+    use crate::utils::{bytes, bytes32};
+
+    /// The secret of the test setup.
+    const TAU: u64 = 0x1234_5678_9abc_def0;
+
+    fn scalar_bytes(scalar: &BigUint) -> H256 {
+        let mut bytes = [0u8; 32];
+        let scalar = scalar.to_bytes_be();
+        bytes[32 - scalar.len()..].copy_from_slice(&scalar);
+        H256(bytes)
+    }
+
+    #[test]
+    fn test_kzg_setup_g2_tau() {
+        assert!(KZG_SETUP_G2_TAU.is_on_curve());
+        assert!(g2_mul(&KZG_SETUP_G2_TAU, &BLS12_381_ORDER).is_none());
+    }
+
+    /// Proves the point evaluation of `commitment` at `z` against the setup `[tau] G2`.
+    fn verify_kzg_proof(
+        tau_g2: &G2Affine,
+        commitment: [u8; 48],
+        z: H256,
+        y: H256,
+        proof: [u8; 48],
+    ) {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        let versioned_hash = builder.read::<Bytes32Variable>();
+        let z_var = builder.read::<Bytes32Variable>();
+        let y_var = builder.read::<Bytes32Variable>();
+        let commitment_var = builder.read::<BytesVariable<48>>();
+        let proof_var = builder.read::<BytesVariable<48>>();
+        let commitment_hash = builder.kzg_to_versioned_hash(&commitment_var);
+        builder.assert_is_equal(commitment_hash, versioned_hash);
+        builder.bls12_381_verify_kzg_proof_with_setup(
+            tau_g2,
+            &commitment_var,
+            &z_var,
+            &y_var,
+            &proof_var,
+        );
+        let circuit = builder.build();
+
+        let mut versioned_hash: [u8; 32] = Sha256::digest(commitment).into();
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(H256(versioned_hash));
+        input.write::<Bytes32Variable>(z);
+        input.write::<Bytes32Variable>(y);
+        input.write::<BytesVariable<48>>(commitment);
+        input.write::<BytesVariable<48>>(proof);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    fn test_bls12_381_verify_kzg_proof(tamper: bool) {
+        let tau_g2 = g2_mul(&BLS12_381_G2_GENERATOR, &BigUint::from(TAU)).unwrap();
+
+        // p(X) = 3 + 5X + 7X^2, and the proof commits to q(X) = (p(X) - p(z)) / (X - z).
+        let r = &*BLS12_381_ORDER;
+        let p = |x: &BigUint| (BigUint::from(3u32) + x * 5u32 + x * x * 7u32) % r;
+        let tau = BigUint::from(TAU);
+        let z = BigUint::from_bytes_be(b"evaluation point") % r;
+        let y = p(&z);
+        let q_tau = ((p(&tau) + r - &y) * (&tau + r - &z).modpow(&(r - 2u32), r)) % r;
+        let commitment = g1_mul(&BLS12_381_G1_GENERATOR, &p(&tau))
+            .unwrap()
+            .to_compressed();
+        let proof = g1_mul(&BLS12_381_G1_GENERATOR, &q_tau)
+            .unwrap()
+            .to_compressed();
+        let y = if tamper { (y + 1u32) % r } else { y };
+
+        verify_kzg_proof(
+            &tau_g2,
+            commitment,
+            scalar_bytes(&z),
+            scalar_bytes(&y),
+            proof,
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_success() {
+        test_bls12_381_verify_kzg_proof(false);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_failure() {
+        test_bls12_381_verify_kzg_proof(true);
+    }
+
+    /// The vector of the point evaluation precompile tests of go-ethereum
+    /// (`core/vm/testdata/precompiles/pointEvaluation.json`), checked against the EIP-4844 trusted
+    /// setup.
+    fn test_bls12_381_verify_kzg_proof_eip4844(tamper: bool) {
+        let versioned_hash =
+            bytes32!("0x01e798154708fe7789429634053cbf9f99b619f9f084048927333fce637f549b");
+        let z = bytes32!("0x564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d36306");
+        let y = bytes32!("0x24d25032e67a7e6a4910df5834b8fe70e6bcfeeac0352434196bdf4b2485d5a1");
+        let commitment: [u8; 48] = bytes!("0x8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7");
+        let proof: [u8; 48] = bytes!("0x873033e038326e87ed3e1276fd140253fa08e9fc25fb2d9a98527fc22a2c9612fbeafdad446cbc7bcdbdcd780af2c16a");
+
+        let mut expected_hash: [u8; 32] = Sha256::digest(commitment).into();
+        expected_hash[0] = VERSIONED_HASH_VERSION_KZG;
+        assert_eq!(H256(expected_hash), versioned_hash);
+
+        let y = if tamper {
+            let y = BigUint::from_bytes_be(y.as_bytes()) + 1u32;
+            scalar_bytes(&(y % &*BLS12_381_ORDER))
+        } else {
+            y
+        };
+        verify_kzg_proof(&KZG_SETUP_G2_TAU, commitment, z, y, proof);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_eip4844_success() {
+        test_bls12_381_verify_kzg_proof_eip4844(false);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_eip4844_failure() {
+        test_bls12_381_verify_kzg_proof_eip4844(true);
+    }
+
+    /// The compressed encoding of the point at infinity.
+    fn infinity() -> [u8; 48] {
+        let mut bytes = [0u8; 48];
+        bytes[0] = 0xc0;
+        bytes
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_zero_polynomial() {
+        // The commitment of the zero blob, whose evaluations are all zero.
+        let z = scalar_bytes(&BigUint::from_bytes_be(b"evaluation point"));
+        let y = scalar_bytes(&BigUint::from(0u32));
+        verify_kzg_proof(&KZG_SETUP_G2_TAU, infinity(), z, y, infinity());
+    }
+
+    /// The constant polynomial `p(X) = c` is committed to by `[c] G1`, and since the quotient
+    /// `(p(X) - p(z)) / (X - z)` is zero, its proofs are the point at infinity.
+    fn test_bls12_381_verify_kzg_proof_constant_polynomial(y: u32) {
+        let c = BigUint::from(42u32);
+        let commitment = g1_mul(&BLS12_381_G1_GENERATOR, &c).unwrap().to_compressed();
+        let z = scalar_bytes(&BigUint::from_bytes_be(b"evaluation point"));
+        let y = scalar_bytes(&BigUint::from(y));
+        verify_kzg_proof(&KZG_SETUP_G2_TAU, commitment, z, y, infinity());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_constant_polynomial_success() {
+        test_bls12_381_verify_kzg_proof_constant_polynomial(42);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_kzg_proof_constant_polynomial_failure() {
+        test_bls12_381_verify_kzg_proof_constant_polynomial(43);
+    }
+}
@@ -0,0 +1,113 @@
+use num::{BigUint, Zero};
+
+use super::fp::{Bls12381FpProduct, Bls12381FpVariable};
+use super::fp12::Bls12381Fp12Variable;
+use super::fp2::{Bls12381Fp2Product, Bls12381Fp2Variable};
+use super::g1::Bls12381G1AffineVariable;
+use super::g2::Bls12381G2AffineVariable;
+use crate::prelude::{CircuitBuilder, CircuitVariable, PlonkParameters};
+use crate::utils::bls12_381::{Fp12, BLS12_381_X_ABS};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Multiplies `f` by the line through `t` with slope `slope`, evaluated at `p` and scaled by
+    /// `w^3`, i.e. by `(slope x_t - y_t) - slope x_p w^2 + y_p w^3`.
+    fn bls12_381_mul_by_line(
+        &mut self,
+        f: &Bls12381Fp12Variable,
+        slope: &Bls12381Fp2Variable,
+        t: &Bls12381G2AffineVariable,
+        p: &Bls12381G1AffineVariable,
+    ) -> Bls12381Fp12Variable {
+        let one = Bls12381Fp2Variable::constant_u32(self, 1, 0);
+        let mut y_t = Bls12381Fp2Product::new(t.y, one);
+        y_t.negate = true;
+        let l0 = self.bls12_381_fp2_sum_of_products(&[Bls12381Fp2Product::new(*slope, t.x), y_t]);
+        let l2 = Bls12381Fp2Variable {
+            c0: self.bls12_381_fp_sum_of_products(&[Bls12381FpProduct::new(slope.c0, p.x, true)]),
+            c1: self.bls12_381_fp_sum_of_products(&[Bls12381FpProduct::new(slope.c1, p.x, true)]),
+        };
+        let l3 = Bls12381Fp2Variable {
+            c0: p.y,
+            c1: self.constant::<Bls12381FpVariable>(BigUint::zero()),
+        };
+        self.bls12_381_fp12_mul_sparse(f, &[Some(l0), None, Some(l2), Some(l3), None, None])
+    }
+
+    /// Computes the product of the Miller loops of the optimal ate pairing for the given pairs,
+    /// sharing the squarings of the accumulator.
+    ///
+    /// The points of G2 must be in the subgroup of order `r`, so that none of the intermediate
+    /// additions and doublings are degenerate.
+    pub fn bls12_381_miller_loop(
+        &mut self,
+        pairs: &[(Bls12381G1AffineVariable, Bls12381G2AffineVariable)],
+    ) -> Bls12381Fp12Variable {
+        let mut f = self.constant::<Bls12381Fp12Variable>(Fp12::one());
+        let mut ts = pairs.iter().map(|(_, q)| *q).collect::<Vec<_>>();
+        for i in (0..63).rev() {
+            // The accumulator is one before the first iteration.
+            if i != 62 {
+                f = self.bls12_381_fp12_square(&f);
+            }
+            for ((p, _), t) in pairs.iter().zip(ts.iter_mut()) {
+                let (double, slope) = self.bls12_381_g2_double_with_slope(t);
+                f = self.bls12_381_mul_by_line(&f, &slope, t, p);
+                *t = double;
+            }
+            if (BLS12_381_X_ABS >> i) & 1 == 1 {
+                for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+                    let (sum, slope) = self.bls12_381_g2_add_with_slope(t, q);
+                    f = self.bls12_381_mul_by_line(&f, &slope, t, p);
+                    *t = sum;
+                }
+            }
+        }
+        // The parameter `x` is negative.
+        self.bls12_381_fp12_conjugate(&f)
+    }
+
+    /// Raises the output of the Miller loop to the power `3 (p^12 - 1) / r`, which is a cube of
+    /// the usual final exponentiation and is equivalent to it for pairing checks.
+    pub fn bls12_381_final_exponentiation(
+        &mut self,
+        f: &Bls12381Fp12Variable,
+    ) -> Bls12381Fp12Variable {
+        // The easy part, `(p^6 - 1)(p^2 + 1)`.
+        let inverse = self.bls12_381_fp12_inverse(f);
+        let conjugate = self.bls12_381_fp12_conjugate(f);
+        let f = self.bls12_381_fp12_mul(&conjugate, &inverse);
+        let frobenius = self.bls12_381_fp12_frobenius2(&f);
+        let f = self.bls12_381_fp12_mul(&frobenius, &f);
+
+        // The hard part, `3 (p^4 - p^2 + 1) / r = (x - 1)^2 (x + p) (x^2 + p^2 - 1) + 3`.
+        let a = self.bls12_381_fp12_cyclotomic_exp_by_x(&f);
+        let f_conjugate = self.bls12_381_fp12_conjugate(&f);
+        let a = self.bls12_381_fp12_mul(&a, &f_conjugate);
+        let a_x = self.bls12_381_fp12_cyclotomic_exp_by_x(&a);
+        let a_conjugate = self.bls12_381_fp12_conjugate(&a);
+        let a = self.bls12_381_fp12_mul(&a_x, &a_conjugate);
+        let a_x = self.bls12_381_fp12_cyclotomic_exp_by_x(&a);
+        let a_frobenius = self.bls12_381_fp12_frobenius(&a);
+        let b = self.bls12_381_fp12_mul(&a_x, &a_frobenius);
+        let b_x = self.bls12_381_fp12_cyclotomic_exp_by_x(&b);
+        let b_x2 = self.bls12_381_fp12_cyclotomic_exp_by_x(&b_x);
+        let b_frobenius2 = self.bls12_381_fp12_frobenius2(&b);
+        let b_conjugate = self.bls12_381_fp12_conjugate(&b);
+        let c = self.bls12_381_fp12_mul(&b_x2, &b_frobenius2);
+        let c = self.bls12_381_fp12_mul(&c, &b_conjugate);
+        let f2 = self.bls12_381_fp12_square(&f);
+        let f3 = self.bls12_381_fp12_mul(&f2, &f);
+        self.bls12_381_fp12_mul(&c, &f3)
+    }
+
+    /// Asserts that the product of the pairings of the given pairs is one.
+    pub fn bls12_381_assert_pairing_product_is_one(
+        &mut self,
+        pairs: &[(Bls12381G1AffineVariable, Bls12381G2AffineVariable)],
+    ) {
+        let f = self.bls12_381_miller_loop(pairs);
+        let result = self.bls12_381_final_exponentiation(&f);
+        let one = self.constant::<Bls12381Fp12Variable>(Fp12::one());
+        self.assert_is_equal(result, one);
+    }
+}
@@ -0,0 +1,119 @@
+use super::g1::Bls12381G1AffineVariable;
+use crate::frontend::eth::vars::{BLSPubkeyVariable, BLSSignatureVariable};
+use crate::prelude::{ArrayVariable, BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters};
+use crate::utils::bls12_381::{BLS12_381_ETH_DST, BLS12_381_G1_GENERATOR};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Verifies a BLS signature in the minimal public key size variant, i.e. checks that
+    /// `e(pubkey, H(message)) = e(G1, signature)`.
+    ///
+    /// The message is hashed to G2 in the circuit with the proof-of-possession ciphersuite used by
+    /// Ethereum, see [`Self::bls12_381_hash_to_g2`]. The hash is in G2 by construction, so only
+    /// the signature needs a subgroup check.
+    pub fn bls12_381_verify_signature(
+        &mut self,
+        pubkey: &Bls12381G1AffineVariable,
+        message: &[ByteVariable],
+        signature: &BLSSignatureVariable,
+    ) {
+        let signature = self.bls12_381_g2_decompress(signature);
+        self.bls12_381_g2_assert_in_subgroup(&signature);
+        let message = self.bls12_381_hash_to_g2(message, BLS12_381_ETH_DST);
+
+        let generator = self.constant::<Bls12381G1AffineVariable>(BLS12_381_G1_GENERATOR.clone());
+        let neg_generator = self.bls12_381_g1_neg(&generator);
+        self.bls12_381_assert_pairing_product_is_one(&[
+            (*pubkey, message),
+            (neg_generator, signature),
+        ]);
+    }
+
+    /// Verifies the aggregate signature of the participating members of a sync committee.
+    ///
+    /// The public keys are decompressed and summed over the participation bits, and the aggregate
+    /// key is checked against the signature with [`Self::bls12_381_verify_signature`]. At least
+    /// one member must participate.
+    pub fn bls12_381_verify_aggregate_signature<const N: usize>(
+        &mut self,
+        pubkeys: &ArrayVariable<BLSPubkeyVariable, N>,
+        participation: &ArrayVariable<BoolVariable, N>,
+        message: &[ByteVariable],
+        signature: &BLSSignatureVariable,
+    ) {
+        let points = pubkeys
+            .as_vec()
+            .iter()
+            .map(|pubkey| self.bls12_381_g1_decompress(pubkey))
+            .collect::<Vec<_>>();
+        let points = ArrayVariable::<Bls12381G1AffineVariable, N>::new(points);
+        let aggregate = self.bls12_381_g1_aggregate(&points, participation);
+        self.bls12_381_verify_signature(&aggregate, message, signature);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Bytes32Variable, DefaultBuilder};
+    use crate::utils;
+
+    /// The `fast_aggregate_verify` vector of the Ethereum consensus specs for the message
+    /// `0xabab..ab`, signed by three keys.
+    const PUBKEYS: [&str; 3] = [
+        "a491d1b0ecd9bb917989f0e74f0dea0422eac4a873e5e2644f368dffb9a6e20fd6e10c1b77654d067c0618f6e5a7f79a",
+        "b301803f8b5ac4a1133581fc676dfedc60d891dd5fa99028805e5ea5b08d3491af75d0707adab3b70c6a6a580217bf81",
+        "b53d21a4cfd562c469cc81514d4ce5a6b577d8403d32a394dc265dd190b47fa9f829fdd7963afdf972e5e77854051f6f",
+    ];
+    const SIGNATURE: &str = "9712c3edd73a209c742b8250759db12549b3eaf43b5ca61376d9f30e2747dbcf842d8b2ac0901d2a093713e20284a7670fcf6954e9ab93de991bb9b313e664785a075fc285806fa5224c82bde146561b446ccfc706a64b8579513cfc4ff1d930";
+
+    fn verify_aggregate_signature(participation: Vec<bool>, message: [u8; 32]) {
+        utils::setup_logger();
+
+        const N: usize = 3;
+        let mut builder = DefaultBuilder::new();
+        let pubkeys = builder.read::<ArrayVariable<BLSPubkeyVariable, N>>();
+        let participation_bits = builder.read::<ArrayVariable<BoolVariable, N>>();
+        let message_bytes = builder.read::<Bytes32Variable>();
+        let signature = builder.read::<BLSSignatureVariable>();
+        builder.bls12_381_verify_aggregate_signature(
+            &pubkeys,
+            &participation_bits,
+            &message_bytes.as_bytes(),
+            &signature,
+        );
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BLSPubkeyVariable, N>>(
+            PUBKEYS
+                .iter()
+                .map(|pubkey| hex::decode(pubkey).unwrap().try_into().unwrap())
+                .collect(),
+        );
+        input.write::<ArrayVariable<BoolVariable, N>>(participation);
+        input.write::<Bytes32Variable>(message.into());
+        input.write::<BLSSignatureVariable>(hex::decode(SIGNATURE).unwrap().try_into().unwrap());
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_aggregate_signature() {
+        verify_aggregate_signature(vec![true, true, true], [0xab; 32]);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_aggregate_signature_wrong_message() {
+        verify_aggregate_signature(vec![true, true, true], [0xac; 32]);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bls12_381_verify_aggregate_signature_missing_signer() {
+        verify_aggregate_signature(vec![true, false, true], [0xab; 32]);
+    }
+}
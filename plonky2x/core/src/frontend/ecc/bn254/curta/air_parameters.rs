@@ -0,0 +1,22 @@
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use starkyx::chip::ec::weierstrass::bn254::Bn254BaseField;
+use starkyx::chip::field::instruction::FpInstruction;
+use starkyx::chip::AirParameters;
+
+use crate::prelude::PlonkParameters;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254AirParameters<L, const D: usize>(PhantomData<L>);
+
+impl<L: PlonkParameters<D>, const D: usize> AirParameters for Bn254AirParameters<L, D> {
+    type Field = L::Field;
+    type CubicParams = L::CubicParams;
+
+    type Instruction = FpInstruction<Bn254BaseField>;
+
+    const NUM_ARITHMETIC_COLUMNS: usize = 1632;
+    const NUM_FREE_COLUMNS: usize = 20;
+    const EXTENDED_COLUMNS: usize = 2502;
+}
@@ -0,0 +1,94 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use num::BigUint;
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::point::CompressedPointRegister;
+use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
+use starkyx::chip::ec::scalar::ECScalarRegister;
+use starkyx::chip::ec::weierstrass::bn254::Bn254;
+use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::field::register::FieldRegister;
+use starkyx::machine::ec::builder::EllipticCurveBuilder;
+use starkyx::machine::emulated::builder::EmulatedBuilder;
+
+use self::air_parameters::Bn254AirParameters;
+use crate::frontend::ecc::curta::accelerator::EcAccelerator;
+use crate::frontend::ecc::curta::{CurtaCurve, EcOpCurve, Unsupported};
+use crate::prelude::{CircuitBuilder, PlonkParameters};
+
+pub mod air_parameters;
+
+impl EcOpCurve for Bn254 {
+    type DecompressOps = Unsupported;
+}
+
+/// The G1 group of BN254, on `y^2 = x^3 + 3` over the base field.
+impl<L: PlonkParameters<D>, const D: usize> CurtaCurve<L, D> for Bn254 {
+    type AirParameters = Bn254AirParameters<L, D>;
+
+    const NAME: &'static str = "bn254";
+
+    fn accelerator(builder: &mut CircuitBuilder<L, D>) -> &mut Option<EcAccelerator<Self>> {
+        &mut builder.ec_bn254_ops_accelerator
+    }
+
+    fn air_add(
+        builder: &mut AirBuilder<Self::AirParameters>,
+        a: AffinePointRegister<Self>,
+        b: AffinePointRegister<Self>,
+    ) -> AffinePointRegister<Self> {
+        builder.add(a, b)
+    }
+
+    fn air_scalar_mul_batch(
+        builder: &mut EmulatedBuilder<Self::AirParameters>,
+        points: &[AffinePointRegister<Self>],
+        scalars: &[ECScalarRegister<Self>],
+        results: &[AffinePointRegister<Self>],
+    ) {
+        builder.scalar_mul_batch(points, scalars, results);
+    }
+
+    fn air_assert_valid(
+        ops: Unsupported,
+        _: &mut AirBuilder<Self::AirParameters>,
+        _: &AffinePointRegister<Self>,
+    ) {
+        match ops {}
+    }
+
+    fn air_decompress(
+        ops: Unsupported,
+        _: &mut AirBuilder<Self::AirParameters>,
+    ) -> (
+        CompressedPointRegister,
+        AffinePointRegister<Self>,
+        FieldRegister<<Self as EllipticCurveParameters>::BaseField>,
+    ) {
+        match ops {}
+    }
+
+    fn decompress(ops: Unsupported, _: &CompressedEdwardsY) -> (AffinePoint<Self>, BigUint) {
+        match ops {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+    use starkyx::chip::ec::weierstrass::bn254::{Bn254BaseField, Bn254Parameters};
+    use starkyx::chip::ec::weierstrass::WeierstrassParameters;
+    use starkyx::chip::field::parameters::FieldParameters;
+
+    use crate::utils::bn254::{BN254_G1_GENERATOR, BN254_MODULUS, BN254_ORDER};
+
+    #[test]
+    fn test_bn254_curta_parameters() {
+        assert_eq!(Bn254BaseField::modulus(), *BN254_MODULUS);
+        assert_eq!(Bn254Parameters::prime_group_order(), *BN254_ORDER);
+        assert_eq!(Bn254Parameters::a_int(), BigUint::from(0u32));
+        assert_eq!(Bn254Parameters::b_int(), BigUint::from(3u32));
+        let (x, y) = Bn254Parameters::generator();
+        assert_eq!(x, BN254_G1_GENERATOR.x);
+        assert_eq!(y, BN254_G1_GENERATOR.y);
+    }
+}
@@ -0,0 +1,151 @@
+use ethers::types::U256;
+use plonky2::field::types::Field;
+use starkyx::chip::ec::weierstrass::bn254::{Bn254, Bn254BaseField};
+
+use super::fp::Bn254FpVariable;
+use super::g1::Bn254G1AffineVariable;
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::frontend::curta::field::variable::FieldVariable;
+use crate::prelude::{
+    ArrayVariable, CircuitBuilder, CircuitVariable, PlonkParameters, U256Variable, U32Variable,
+    Variable,
+};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Add two distinct points of G1, neither of which is the negation of the other.
+    pub fn curta_bn254_g1_add(
+        &mut self,
+        a: AffinePointVariable<Bn254>,
+        b: AffinePointVariable<Bn254>,
+    ) -> AffinePointVariable<Bn254> {
+        self.curta_ec_add(a, b)
+    }
+
+    /// Multiply a point of G1 by a scalar.
+    pub fn curta_bn254_g1_scalar_mul(
+        &mut self,
+        scalar: U256Variable,
+        point: AffinePointVariable<Bn254>,
+    ) -> AffinePointVariable<Bn254> {
+        self.curta_ec_scalar_mul(scalar, point)
+    }
+
+    /// Returns `base + sum(scalars[i] * points[i])`, proven by the BN254 accelerator.
+    ///
+    /// The scalars must be reduced modulo the order of G1. A zero scalar is replaced by one and
+    /// the corresponding sum is discarded, so that no product is the point at infinity. As the
+    /// additions are incomplete, no partial sum may be equal or opposite to the next product,
+    /// which only happens if discrete logarithms between the points are known. The result must
+    /// not be the point at infinity.
+    pub fn curta_bn254_g1_msm<const N: usize>(
+        &mut self,
+        base: &Bn254G1AffineVariable,
+        points: &ArrayVariable<Bn254G1AffineVariable, N>,
+        scalars: &ArrayVariable<U256Variable, N>,
+    ) -> Bn254G1AffineVariable {
+        let zero = self.constant::<U256Variable>(U256::zero());
+        let one = self.constant::<U256Variable>(U256::one());
+
+        let mut acc = self.bn254_g1_to_curta(base);
+        for (point, scalar) in points.as_vec().iter().zip(scalars.as_vec()) {
+            let is_zero = self.is_equal(scalar, zero);
+            let scalar = self.select(is_zero, one, scalar);
+            let point = self.bn254_g1_to_curta(point);
+            let product = self.curta_bn254_g1_scalar_mul(scalar, point);
+            let sum = self.curta_bn254_g1_add(acc.clone(), product);
+            acc = self.select(is_zero, acc, sum);
+        }
+        self.bn254_g1_from_curta(&acc)
+    }
+
+    /// Converts a point of G1 to the 16-bit limbs used by the BN254 accelerator.
+    pub fn bn254_g1_to_curta(&mut self, a: &Bn254G1AffineVariable) -> AffinePointVariable<Bn254> {
+        AffinePointVariable {
+            x: self.bn254_fp_to_curta(&a.x),
+            y: self.bn254_fp_to_curta(&a.y),
+        }
+    }
+
+    /// Converts a point of the BN254 accelerator back to a point of G1, checking that its
+    /// coordinates are reduced.
+    pub fn bn254_g1_from_curta(&mut self, a: &AffinePointVariable<Bn254>) -> Bn254G1AffineVariable {
+        Bn254G1AffineVariable {
+            x: self.bn254_fp_from_curta(&a.x),
+            y: self.bn254_fp_from_curta(&a.y),
+        }
+    }
+
+    fn bn254_fp_to_curta(&mut self, a: &Bn254FpVariable) -> FieldVariable<Bn254BaseField> {
+        let mut limbs = Vec::new();
+        for limb in a.limbs.iter() {
+            let (low, high) = self.api.split_low_high(limb.variable.0, 16, 32);
+            limbs.push(Variable::from(low));
+            limbs.push(Variable::from(high));
+        }
+        FieldVariable::new(limbs)
+    }
+
+    fn bn254_fp_from_curta(&mut self, a: &FieldVariable<Bn254BaseField>) -> Bn254FpVariable {
+        let limbs = a.variables();
+        for limb in limbs.iter() {
+            self.api.range_check(limb.0, 16);
+        }
+        let shift = self.api.constant(L::Field::from_canonical_u32(1 << 16));
+        let result = Bn254FpVariable {
+            limbs: core::array::from_fn(|i| {
+                let limb = self.api.mul_add(limbs[2 * i + 1].0, shift, limbs[2 * i].0);
+                U32Variable::from_variables_unsafe(&[Variable::from(limb)])
+            }),
+        };
+        self.bn254_fp_assert_reduced(&result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+    use crate::utils;
+    use crate::utils::bn254::{g1_add, g1_mul, BN254_G1_GENERATOR};
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_curta_bn254_g1_msm() {
+        utils::setup_logger();
+
+        const N: usize = 3;
+        let mut builder = DefaultBuilder::new();
+        let base = builder.read::<Bn254G1AffineVariable>();
+        let points = builder.read::<ArrayVariable<Bn254G1AffineVariable, N>>();
+        let scalars = builder.read::<ArrayVariable<U256Variable, N>>();
+        let result = builder.curta_bn254_g1_msm(&base, &points, &scalars);
+        builder.write(result);
+        let circuit = builder.build();
+
+        let base = g1_mul(&BN254_G1_GENERATOR, &BigUint::from(7u32)).unwrap();
+        let points = [2u32, 3, 5]
+            .map(|dlog| g1_mul(&BN254_G1_GENERATOR, &BigUint::from(dlog)).unwrap())
+            .to_vec();
+        let scalars = [U256::from(0xdeadbeefu64), U256::zero(), U256::MAX >> 3];
+        let expected = points
+            .iter()
+            .zip(scalars.iter())
+            .fold(Some(base.clone()), |acc, (point, scalar)| {
+                let mut bytes = [0u8; 32];
+                scalar.to_big_endian(&mut bytes);
+                g1_add(&acc, &g1_mul(point, &BigUint::from_bytes_be(&bytes)))
+            })
+            .unwrap();
+
+        let mut input = circuit.input();
+        input.write::<Bn254G1AffineVariable>(base);
+        input.write::<ArrayVariable<Bn254G1AffineVariable, N>>(points);
+        input.write::<ArrayVariable<U256Variable, N>>(scalars.to_vec());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<Bn254G1AffineVariable>(), expected);
+    }
+}
@@ -0,0 +1,232 @@
+use itertools::Itertools;
+use num::{BigUint, One, Zero};
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::{
+    BoolVariable, CircuitBuilder, CircuitVariable, PlonkParameters, U32Variable, ValueStream,
+    Variable, VariableStream,
+};
+use crate::utils::bn254::{fp_inverse, BN254_MODULUS};
+
+/// The number of 32-bit limbs of an element of the base field.
+const NB_LIMBS: usize = 8;
+
+/// An element of the BN254 base field, as little-endian 32-bit limbs.
+///
+/// Valid variables are always reduced, so that the outputs of the arithmetic can be compared limb
+/// by limb.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254FpVariable {
+    pub limbs: [U32Variable; NB_LIMBS],
+}
+
+impl CircuitVariable for Bn254FpVariable {
+    type ValueType<F: RichField> = BigUint;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            limbs: core::array::from_fn(|_| U32Variable::init_unsafe(builder)),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.limbs.iter().map(|limb| limb.variable).collect()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        assert_eq!(variables.len(), NB_LIMBS);
+        Self {
+            limbs: core::array::from_fn(|i| U32Variable::from_variables_unsafe(&[variables[i]])),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        for limb in self.limbs.iter() {
+            limb.assert_is_valid(builder);
+        }
+        builder.bn254_fp_assert_reduced(self);
+    }
+
+    fn nb_elements() -> usize {
+        NB_LIMBS
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut limbs = value.to_u32_digits();
+        assert!(
+            limbs.len() <= NB_LIMBS,
+            "value does not fit in the base field"
+        );
+        limbs.resize(NB_LIMBS, 0);
+        limbs.into_iter().map(F::from_canonical_u32).collect()
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let limbs = elements
+            .iter()
+            .map(|element| element.to_canonical_u64() as u32)
+            .collect_vec();
+        BigUint::from_slice(&limbs)
+    }
+}
+
+/// Computes `a / b` in the BN254 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254FpDivHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bn254FpDivHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bn254FpVariable>();
+        let b = input_stream.read_value::<Bn254FpVariable>();
+        let quotient = (a * fp_inverse(&b)) % &*BN254_MODULUS;
+        output_stream.write_value::<Bn254FpVariable>(quotient);
+    }
+}
+
+/// A term of a sum of products in the base field, `scale * a * b`, negated when `negate` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254FpProduct {
+    pub a: Bn254FpVariable,
+    pub b: Bn254FpVariable,
+    pub negate: bool,
+    pub scale: u32,
+}
+
+impl Bn254FpProduct {
+    pub fn new(a: Bn254FpVariable, b: Bn254FpVariable, negate: bool) -> Self {
+        Self {
+            a,
+            b,
+            negate,
+            scale: 1,
+        }
+    }
+
+    pub fn scaled(self, scale: u32) -> Self {
+        Self { scale, ..self }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    pub(crate) fn bn254_fp_to_biguint(&mut self, a: &Bn254FpVariable) -> BigUintTarget {
+        BigUintTarget {
+            limbs: a.limbs.iter().map(|limb| U32Target::from(*limb)).collect(),
+        }
+    }
+
+    /// Converts the output of `rem_biguint` by the modulus back into a base field element.
+    fn bn254_fp_from_reduced_biguint(&mut self, a: BigUintTarget) -> Bn254FpVariable {
+        assert!(a.limbs.len() <= NB_LIMBS);
+        let mut limbs = [self.constant::<U32Variable>(0); NB_LIMBS];
+        for (limb, a_limb) in limbs.iter_mut().zip(a.limbs) {
+            *limb = a_limb.into();
+        }
+        Bn254FpVariable { limbs }
+    }
+
+    /// Returns `sum(+-scale * a * b)` for the given products, with a single modular reduction.
+    pub fn bn254_fp_sum_of_products(&mut self, products: &[Bn254FpProduct]) -> Bn254FpVariable {
+        let mut positive = vec![];
+        let mut negative = vec![];
+        let mut max_scale = 1;
+        for product in products.iter() {
+            let a = self.bn254_fp_to_biguint(&product.a);
+            let b = self.bn254_fp_to_biguint(&product.b);
+            let mut ab = self.api.mul_biguint(&a, &b);
+            if product.scale != 1 {
+                let scale = self.api.constant_biguint(&BigUint::from(product.scale));
+                ab = self.api.mul_biguint(&ab, &scale);
+            }
+            if product.negate {
+                max_scale = max_scale.max(product.scale);
+                negative.push(ab);
+            } else {
+                positive.push(ab);
+            }
+        }
+        let bound = &*BN254_MODULUS * &*BN254_MODULUS * max_scale;
+        let reduced = self.biguint_signed_sum_rem(&positive, &negative, &bound, &BN254_MODULUS);
+        self.bn254_fp_from_reduced_biguint(reduced)
+    }
+
+    /// Returns `sum(positive) - sum(negative)` in the base field.
+    pub fn bn254_fp_linear_combination(
+        &mut self,
+        positive: &[Bn254FpVariable],
+        negative: &[Bn254FpVariable],
+    ) -> Bn254FpVariable {
+        let positive = positive
+            .iter()
+            .map(|a| self.bn254_fp_to_biguint(a))
+            .collect_vec();
+        let negative = negative
+            .iter()
+            .map(|a| self.bn254_fp_to_biguint(a))
+            .collect_vec();
+        let reduced =
+            self.biguint_signed_sum_rem(&positive, &negative, &BN254_MODULUS, &BN254_MODULUS);
+        self.bn254_fp_from_reduced_biguint(reduced)
+    }
+
+    pub fn bn254_fp_add(&mut self, a: &Bn254FpVariable, b: &Bn254FpVariable) -> Bn254FpVariable {
+        self.bn254_fp_linear_combination(&[*a, *b], &[])
+    }
+
+    pub fn bn254_fp_sub(&mut self, a: &Bn254FpVariable, b: &Bn254FpVariable) -> Bn254FpVariable {
+        self.bn254_fp_linear_combination(&[*a], &[*b])
+    }
+
+    pub fn bn254_fp_neg(&mut self, a: &Bn254FpVariable) -> Bn254FpVariable {
+        self.bn254_fp_linear_combination(&[], &[*a])
+    }
+
+    pub fn bn254_fp_mul(&mut self, a: &Bn254FpVariable, b: &Bn254FpVariable) -> Bn254FpVariable {
+        self.bn254_fp_sum_of_products(&[Bn254FpProduct::new(*a, *b, false)])
+    }
+
+    /// Returns `a / b`, which constrains `b` to be non-zero.
+    pub fn bn254_fp_div(&mut self, a: &Bn254FpVariable, b: &Bn254FpVariable) -> Bn254FpVariable {
+        self.bn254_fp_assert_not_zero(b);
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        input_stream.write(b);
+        let output_stream = self.hint(input_stream, Bn254FpDivHint);
+        let quotient = output_stream.read::<Bn254FpVariable>(self);
+
+        let product = self.bn254_fp_mul(&quotient, b);
+        self.assert_is_equal(product, *a);
+        quotient
+    }
+
+    pub fn bn254_fp_is_zero(&mut self, a: &Bn254FpVariable) -> BoolVariable {
+        let zero = self.constant::<Bn254FpVariable>(BigUint::zero());
+        self.is_equal(*a, zero)
+    }
+
+    pub fn bn254_fp_assert_not_zero(&mut self, a: &Bn254FpVariable) {
+        let is_zero = self.bn254_fp_is_zero(a);
+        let false_val = self._false();
+        self.assert_is_equal(is_zero, false_val);
+    }
+
+    /// Asserts that the limbs of `a` encode an integer less than the modulus.
+    pub fn bn254_fp_assert_reduced(&mut self, a: &Bn254FpVariable) {
+        let a = self.bn254_fp_to_biguint(a);
+        let max = self
+            .api
+            .constant_biguint(&(&*BN254_MODULUS - BigUint::one()));
+        let is_reduced = self.api.cmp_biguint(&a, &max);
+        let is_reduced = BoolVariable::from(is_reduced);
+        let true_val = self._true();
+        self.assert_is_equal(is_reduced, true_val);
+    }
+}
@@ -0,0 +1,171 @@
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+
+use super::fp2::{Bn254Fp2Product, Bn254Fp2Variable};
+use crate::frontend::hint::simple::hint::Hint;
+use crate::prelude::{
+    CircuitBuilder, CircuitVariable, PlonkParameters, ValueStream, Variable, VariableStream,
+};
+use crate::utils::bn254::{Fp12, BN254_FROBENIUS_COEFFS, BN254_X};
+
+/// An element `sum c_i w^i` of the extension `Fp2[w] / (w^6 - (u + 9))` of degree 12 of the BN254
+/// base field, which contains the values of the pairing.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254Fp12Variable {
+    pub coeffs: [Bn254Fp2Variable; 6],
+}
+
+impl CircuitVariable for Bn254Fp12Variable {
+    type ValueType<F: RichField> = Fp12;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            coeffs: core::array::from_fn(|_| Bn254Fp2Variable::init_unsafe(builder)),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.coeffs.iter().flat_map(|c| c.variables()).collect()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bn254Fp2Variable::nb_elements();
+        assert_eq!(variables.len(), 6 * n);
+        Self {
+            coeffs: core::array::from_fn(|i| {
+                Bn254Fp2Variable::from_variables_unsafe(&variables[i * n..(i + 1) * n])
+            }),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        for c in self.coeffs.iter() {
+            c.assert_is_valid(builder);
+        }
+    }
+
+    fn nb_elements() -> usize {
+        6 * Bn254Fp2Variable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        value
+            .0
+            .into_iter()
+            .flat_map(Bn254Fp2Variable::elements::<F>)
+            .collect()
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bn254Fp2Variable::nb_elements();
+        Fp12(core::array::from_fn(|i| {
+            Bn254Fp2Variable::from_elements::<F>(&elements[i * n..(i + 1) * n])
+        }))
+    }
+}
+
+/// Computes the inverse of a non-zero element of the degree 12 extension of the BN254 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254Fp12InverseHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bn254Fp12InverseHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bn254Fp12Variable>();
+        output_stream.write_value::<Bn254Fp12Variable>(a.inverse());
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Multiplies `a` by `b`, skipping the coefficients of `b` which are known to be zero.
+    pub fn bn254_fp12_mul_sparse(
+        &mut self,
+        a: &Bn254Fp12Variable,
+        b: &[Option<Bn254Fp2Variable>; 6],
+    ) -> Bn254Fp12Variable {
+        let mut products: [Vec<Bn254Fp2Product>; 6] = Default::default();
+        for (i, a_i) in a.coeffs.iter().enumerate() {
+            for (j, b_j) in b.iter().enumerate() {
+                if let Some(b_j) = b_j {
+                    // Reduce with w^6 = u + 9.
+                    let mut product = Bn254Fp2Product::new(*a_i, *b_j);
+                    product.mul_by_xi = i + j >= 6;
+                    products[(i + j) % 6].push(product);
+                }
+            }
+        }
+        Bn254Fp12Variable {
+            coeffs: core::array::from_fn(|k| self.bn254_fp2_sum_of_products(&products[k])),
+        }
+    }
+
+    pub fn bn254_fp12_mul(
+        &mut self,
+        a: &Bn254Fp12Variable,
+        b: &Bn254Fp12Variable,
+    ) -> Bn254Fp12Variable {
+        self.bn254_fp12_mul_sparse(a, &b.coeffs.map(Some))
+    }
+
+    pub fn bn254_fp12_square(&mut self, a: &Bn254Fp12Variable) -> Bn254Fp12Variable {
+        self.bn254_fp12_mul(a, a)
+    }
+
+    /// Returns `a^(p^6)`, which is the inverse of the elements of the cyclotomic subgroup.
+    pub fn bn254_fp12_conjugate(&mut self, a: &Bn254Fp12Variable) -> Bn254Fp12Variable {
+        let mut coeffs = a.coeffs;
+        for c in coeffs.iter_mut().skip(1).step_by(2) {
+            *c = self.bn254_fp2_neg(c);
+        }
+        Bn254Fp12Variable { coeffs }
+    }
+
+    /// Returns `a^(p^k)` for `k` in `1..=3`.
+    pub fn bn254_fp12_frobenius_map(
+        &mut self,
+        a: &Bn254Fp12Variable,
+        k: usize,
+    ) -> Bn254Fp12Variable {
+        let mut coeffs = a.coeffs;
+        for (c, gamma) in coeffs.iter_mut().zip(BN254_FROBENIUS_COEFFS[k - 1].iter()) {
+            if k % 2 == 1 {
+                *c = self.bn254_fp2_conjugate(c);
+            }
+            *c = self.bn254_fp2_mul_by_constant(c, gamma);
+        }
+        Bn254Fp12Variable { coeffs }
+    }
+
+    /// Returns the inverse of `a`, which constrains `a` to be non-zero.
+    pub fn bn254_fp12_inverse(&mut self, a: &Bn254Fp12Variable) -> Bn254Fp12Variable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        let output_stream = self.hint(input_stream, Bn254Fp12InverseHint);
+        let inverse = output_stream.read::<Bn254Fp12Variable>(self);
+
+        let product = self.bn254_fp12_mul(a, &inverse);
+        let one = self.constant::<Bn254Fp12Variable>(Fp12::one());
+        self.assert_is_equal(product, one);
+        inverse
+    }
+
+    /// Returns `a^-x` for `a` in the cyclotomic subgroup, where `x` is the BN parameter.
+    pub fn bn254_fp12_cyclotomic_exp_by_neg_x(
+        &mut self,
+        a: &Bn254Fp12Variable,
+    ) -> Bn254Fp12Variable {
+        let nb_bits = 64 - BN254_X.leading_zeros() as usize;
+        let mut result = *a;
+        for i in (0..nb_bits - 1).rev() {
+            result = self.bn254_fp12_square(&result);
+            if (BN254_X >> i) & 1 == 1 {
+                result = self.bn254_fp12_mul(&result, a);
+            }
+        }
+        self.bn254_fp12_conjugate(&result)
+    }
+}
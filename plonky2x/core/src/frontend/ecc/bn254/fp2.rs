@@ -0,0 +1,229 @@
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+
+use super::fp::{Bn254FpProduct, Bn254FpVariable};
+use crate::frontend::hint::simple::hint::Hint;
+use crate::prelude::{
+    BoolVariable, CircuitBuilder, CircuitVariable, PlonkParameters, ValueStream, Variable,
+    VariableStream,
+};
+use crate::utils::bn254::Fp2;
+
+/// An element `c0 + c1 u` of the quadratic extension `Fp[u] / (u^2 + 1)` of the BN254 base field.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254Fp2Variable {
+    pub c0: Bn254FpVariable,
+    pub c1: Bn254FpVariable,
+}
+
+impl CircuitVariable for Bn254Fp2Variable {
+    type ValueType<F: RichField> = Fp2;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            c0: Bn254FpVariable::init_unsafe(builder),
+            c1: Bn254FpVariable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.c0.variables();
+        variables.extend(self.c1.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bn254FpVariable::nb_elements();
+        assert_eq!(variables.len(), 2 * n);
+        Self {
+            c0: Bn254FpVariable::from_variables_unsafe(&variables[..n]),
+            c1: Bn254FpVariable::from_variables_unsafe(&variables[n..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.c0.assert_is_valid(builder);
+        self.c1.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        2 * Bn254FpVariable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut elements = Bn254FpVariable::elements::<F>(value.c0);
+        elements.extend(Bn254FpVariable::elements::<F>(value.c1));
+        elements
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bn254FpVariable::nb_elements();
+        Fp2::new(
+            Bn254FpVariable::from_elements::<F>(&elements[..n]),
+            Bn254FpVariable::from_elements::<F>(&elements[n..]),
+        )
+    }
+}
+
+/// Computes `a / b` in the quadratic extension of the BN254 base field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bn254Fp2DivHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Bn254Fp2DivHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let a = input_stream.read_value::<Bn254Fp2Variable>();
+        let b = input_stream.read_value::<Bn254Fp2Variable>();
+        output_stream.write_value::<Bn254Fp2Variable>(a.mul(&b.inverse()));
+    }
+}
+
+/// A term of a sum of products in `Fp2`, `a * b` or `-(a * b)` when `negate` is set, optionally
+/// multiplied by the non-residue `u + 9`.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254Fp2Product {
+    pub a: Bn254Fp2Variable,
+    pub b: Bn254Fp2Variable,
+    pub negate: bool,
+    pub mul_by_xi: bool,
+}
+
+impl Bn254Fp2Product {
+    pub fn new(a: Bn254Fp2Variable, b: Bn254Fp2Variable) -> Self {
+        Self {
+            a,
+            b,
+            negate: false,
+            mul_by_xi: false,
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns `sum(+-a * b)` for the given products, with one modular reduction per coefficient.
+    pub fn bn254_fp2_sum_of_products(&mut self, products: &[Bn254Fp2Product]) -> Bn254Fp2Variable {
+        let mut c0 = vec![];
+        let mut c1 = vec![];
+        for product in products.iter() {
+            let (a, b, negate) = (product.a, product.b, product.negate);
+            // (a0 + a1 u)(b0 + b1 u) = (a0 b0 - a1 b1) + (a0 b1 + a1 b0) u.
+            let real = [
+                Bn254FpProduct::new(a.c0, b.c0, negate),
+                Bn254FpProduct::new(a.c1, b.c1, !negate),
+            ];
+            let imaginary = [
+                Bn254FpProduct::new(a.c0, b.c1, negate),
+                Bn254FpProduct::new(a.c1, b.c0, negate),
+            ];
+            if product.mul_by_xi {
+                // (x0 + x1 u)(9 + u) = (9 x0 - x1) + (x0 + 9 x1) u.
+                c0.extend(real.map(|p| p.scaled(9)));
+                c0.extend(imaginary.map(|p| Bn254FpProduct::new(p.a, p.b, !p.negate)));
+                c1.extend(real);
+                c1.extend(imaginary.map(|p| p.scaled(9)));
+            } else {
+                c0.extend(real);
+                c1.extend(imaginary);
+            }
+        }
+        Bn254Fp2Variable {
+            c0: self.bn254_fp_sum_of_products(&c0),
+            c1: self.bn254_fp_sum_of_products(&c1),
+        }
+    }
+
+    pub fn bn254_fp2_add(
+        &mut self,
+        a: &Bn254Fp2Variable,
+        b: &Bn254Fp2Variable,
+    ) -> Bn254Fp2Variable {
+        Bn254Fp2Variable {
+            c0: self.bn254_fp_add(&a.c0, &b.c0),
+            c1: self.bn254_fp_add(&a.c1, &b.c1),
+        }
+    }
+
+    pub fn bn254_fp2_sub(
+        &mut self,
+        a: &Bn254Fp2Variable,
+        b: &Bn254Fp2Variable,
+    ) -> Bn254Fp2Variable {
+        Bn254Fp2Variable {
+            c0: self.bn254_fp_sub(&a.c0, &b.c0),
+            c1: self.bn254_fp_sub(&a.c1, &b.c1),
+        }
+    }
+
+    pub fn bn254_fp2_neg(&mut self, a: &Bn254Fp2Variable) -> Bn254Fp2Variable {
+        Bn254Fp2Variable {
+            c0: self.bn254_fp_neg(&a.c0),
+            c1: self.bn254_fp_neg(&a.c1),
+        }
+    }
+
+    pub fn bn254_fp2_conjugate(&mut self, a: &Bn254Fp2Variable) -> Bn254Fp2Variable {
+        Bn254Fp2Variable {
+            c0: a.c0,
+            c1: self.bn254_fp_neg(&a.c1),
+        }
+    }
+
+    pub fn bn254_fp2_mul(
+        &mut self,
+        a: &Bn254Fp2Variable,
+        b: &Bn254Fp2Variable,
+    ) -> Bn254Fp2Variable {
+        self.bn254_fp2_sum_of_products(&[Bn254Fp2Product::new(*a, *b)])
+    }
+
+    pub fn bn254_fp2_mul_by_constant(&mut self, a: &Bn254Fp2Variable, b: &Fp2) -> Bn254Fp2Variable {
+        let b = self.constant::<Bn254Fp2Variable>(b.clone());
+        self.bn254_fp2_mul(a, &b)
+    }
+
+    /// Returns `a / b`, which constrains `b` to be non-zero.
+    pub fn bn254_fp2_div(
+        &mut self,
+        a: &Bn254Fp2Variable,
+        b: &Bn254Fp2Variable,
+    ) -> Bn254Fp2Variable {
+        self.bn254_fp2_assert_not_zero(b);
+        let mut input_stream = VariableStream::new();
+        input_stream.write(a);
+        input_stream.write(b);
+        let output_stream = self.hint(input_stream, Bn254Fp2DivHint);
+        let quotient = output_stream.read::<Bn254Fp2Variable>(self);
+
+        let product = self.bn254_fp2_mul(&quotient, b);
+        self.assert_is_equal(product, *a);
+        quotient
+    }
+
+    pub fn bn254_fp2_is_zero(&mut self, a: &Bn254Fp2Variable) -> BoolVariable {
+        let c0_is_zero = self.bn254_fp_is_zero(&a.c0);
+        let c1_is_zero = self.bn254_fp_is_zero(&a.c1);
+        self.and(c0_is_zero, c1_is_zero)
+    }
+
+    pub fn bn254_fp2_assert_not_zero(&mut self, a: &Bn254Fp2Variable) {
+        let is_zero = self.bn254_fp2_is_zero(a);
+        let false_val = self._false();
+        self.assert_is_equal(is_zero, false_val);
+    }
+}
+
+impl Bn254Fp2Variable {
+    /// Returns the constant `c0 + c1 u` for small coefficients.
+    pub fn constant_u32<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        c0: u32,
+        c1: u32,
+    ) -> Self {
+        builder.constant::<Self>(Fp2::new(BigUint::from(c0), BigUint::from(c1)))
+    }
+}
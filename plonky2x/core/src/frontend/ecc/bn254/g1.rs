@@ -0,0 +1,231 @@
+use lazy_static::lazy_static;
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+
+use super::fp::{Bn254FpProduct, Bn254FpVariable};
+use crate::frontend::vars::EvmVariable;
+use crate::prelude::{
+    ArrayVariable, CircuitBuilder, CircuitVariable, PlonkParameters, U256Variable, Variable,
+};
+use crate::utils::bn254::{g1_mul, G1Affine, BN254_G1_GENERATOR};
+
+lazy_static! {
+    /// The discrete logarithm of the offset point used by the multi-scalar multiplication.
+    static ref BN254_MSM_OFFSET: BigUint = BigUint::from_bytes_be(b"plonky2x-bn254-msm");
+}
+
+/// An affine point of G1, on `y^2 = x^3 + 3` over the BN254 base field.
+///
+/// The point at infinity has no representation.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254G1AffineVariable {
+    pub x: Bn254FpVariable,
+    pub y: Bn254FpVariable,
+}
+
+impl CircuitVariable for Bn254G1AffineVariable {
+    type ValueType<F: RichField> = G1Affine;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            x: Bn254FpVariable::init_unsafe(builder),
+            y: Bn254FpVariable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.x.variables();
+        variables.extend(self.y.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bn254FpVariable::nb_elements();
+        assert_eq!(variables.len(), 2 * n);
+        Self {
+            x: Bn254FpVariable::from_variables_unsafe(&variables[..n]),
+            y: Bn254FpVariable::from_variables_unsafe(&variables[n..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.x.assert_is_valid(builder);
+        self.y.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        2 * Bn254FpVariable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut elements = Bn254FpVariable::elements::<F>(value.x);
+        elements.extend(Bn254FpVariable::elements::<F>(value.y));
+        elements
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bn254FpVariable::nb_elements();
+        G1Affine {
+            x: Bn254FpVariable::from_elements::<F>(&elements[..n]),
+            y: Bn254FpVariable::from_elements::<F>(&elements[n..]),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Asserts that `point` is on the curve `y^2 = x^3 + 3`. As G1 has prime order, this also
+    /// asserts that it is in the subgroup of order `r`.
+    pub fn bn254_g1_assert_on_curve(&mut self, point: &Bn254G1AffineVariable) {
+        let three = self.constant::<Bn254FpVariable>(BigUint::from(3u32));
+        let one = self.constant::<Bn254FpVariable>(BigUint::from(1u32));
+        let x2 = self.bn254_fp_mul(&point.x, &point.x);
+        let zero = self.bn254_fp_sum_of_products(&[
+            Bn254FpProduct::new(x2, point.x, false),
+            Bn254FpProduct::new(three, one, false),
+            Bn254FpProduct::new(point.y, point.y, true),
+        ]);
+        let is_zero = self.bn254_fp_is_zero(&zero);
+        let true_val = self._true();
+        self.assert_is_equal(is_zero, true_val);
+    }
+
+    pub fn bn254_g1_neg(&mut self, a: &Bn254G1AffineVariable) -> Bn254G1AffineVariable {
+        Bn254G1AffineVariable {
+            x: a.x,
+            y: self.bn254_fp_neg(&a.y),
+        }
+    }
+
+    /// Returns the point `(slope^2 - a.x - b.x, slope (a.x - x) - a.y)` on the line through `a`.
+    fn bn254_g1_chord(
+        &mut self,
+        a: &Bn254G1AffineVariable,
+        b_x: &Bn254FpVariable,
+        slope: &Bn254FpVariable,
+    ) -> Bn254G1AffineVariable {
+        let one = self.constant::<Bn254FpVariable>(BigUint::from(1u32));
+        let x = self.bn254_fp_sum_of_products(&[
+            Bn254FpProduct::new(*slope, *slope, false),
+            Bn254FpProduct::new(a.x, one, true),
+            Bn254FpProduct::new(*b_x, one, true),
+        ]);
+        let dx = self.bn254_fp_sub(&a.x, &x);
+        let y = self.bn254_fp_sum_of_products(&[
+            Bn254FpProduct::new(*slope, dx, false),
+            Bn254FpProduct::new(a.y, one, true),
+        ]);
+        Bn254G1AffineVariable { x, y }
+    }
+
+    /// Adds two points with distinct x coordinates, which is constrained. The addition therefore
+    /// never silently returns a wrong result, but it cannot be proven for `a = +-b`.
+    pub fn bn254_g1_add(
+        &mut self,
+        a: &Bn254G1AffineVariable,
+        b: &Bn254G1AffineVariable,
+    ) -> Bn254G1AffineVariable {
+        let dy = self.bn254_fp_sub(&b.y, &a.y);
+        let dx = self.bn254_fp_sub(&b.x, &a.x);
+        let slope = self.bn254_fp_div(&dy, &dx);
+        self.bn254_g1_chord(a, &b.x, &slope)
+    }
+
+    /// Doubles a point, whose y coordinate is never zero as G1 has odd order.
+    pub fn bn254_g1_double(&mut self, a: &Bn254G1AffineVariable) -> Bn254G1AffineVariable {
+        let three = self.constant::<Bn254FpVariable>(BigUint::from(3u32));
+        let x2 = self.bn254_fp_mul(&a.x, &a.x);
+        let numerator = self.bn254_fp_mul(&three, &x2);
+        let denominator = self.bn254_fp_add(&a.y, &a.y);
+        let slope = self.bn254_fp_div(&numerator, &denominator);
+        self.bn254_g1_chord(a, &a.x, &slope)
+    }
+
+    /// Returns `base + sum(scalars[i] * points[i])`.
+    ///
+    /// The scalars are processed bit by bit with shared doublings. The accumulator starts from a
+    /// fixed offset point which is subtracted at the end, so that it is never the point at
+    /// infinity and zero scalars are handled. The result itself must not be the point at
+    /// infinity.
+    pub fn bn254_g1_msm<const N: usize>(
+        &mut self,
+        base: &Bn254G1AffineVariable,
+        points: &ArrayVariable<Bn254G1AffineVariable, N>,
+        scalars: &ArrayVariable<U256Variable, N>,
+    ) -> Bn254G1AffineVariable {
+        let offset = g1_mul(&BN254_G1_GENERATOR, &BN254_MSM_OFFSET)
+            .expect("the offset is not the point at infinity");
+        let shifted_offset = g1_mul(&offset, &(BigUint::from(1u32) << 256))
+            .expect("the offset is not the point at infinity");
+        let offset = self.constant::<Bn254G1AffineVariable>(offset);
+        let neg_shifted_offset = self.constant::<Bn254G1AffineVariable>(shifted_offset.neg());
+
+        let bits = scalars
+            .as_vec()
+            .iter()
+            .map(|scalar| scalar.to_be_bits(self))
+            .collect::<Vec<_>>();
+        let mut acc = offset;
+        for i in 0..256 {
+            acc = self.bn254_g1_double(&acc);
+            for (point, bits) in points.as_vec().iter().zip(bits.iter()) {
+                let sum = self.bn254_g1_add(&acc, point);
+                acc = self.select(bits[i], sum, acc);
+            }
+        }
+        let base = self.bn254_g1_add(base, &neg_shifted_offset);
+        self.bn254_g1_add(&acc, &base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+    use crate::utils;
+    use crate::utils::bn254::g1_add;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bn254_g1_msm() {
+        utils::setup_logger();
+
+        const N: usize = 3;
+        let mut builder = DefaultBuilder::new();
+        let base = builder.read::<Bn254G1AffineVariable>();
+        let points = builder.read::<ArrayVariable<Bn254G1AffineVariable, N>>();
+        let scalars = builder.read::<ArrayVariable<U256Variable, N>>();
+        let result = builder.bn254_g1_msm(&base, &points, &scalars);
+        builder.write(result);
+        let circuit = builder.build();
+
+        let base = g1_mul(&BN254_G1_GENERATOR, &BigUint::from(7u32)).unwrap();
+        let points = [2u32, 3, 5]
+            .map(|dlog| g1_mul(&BN254_G1_GENERATOR, &BigUint::from(dlog)).unwrap())
+            .to_vec();
+        let scalars = [U256::from(0xdeadbeefu64), U256::zero(), U256::MAX >> 3];
+        let expected = points
+            .iter()
+            .zip(scalars.iter())
+            .fold(Some(base.clone()), |acc, (point, scalar)| {
+                let mut bytes = [0u8; 32];
+                scalar.to_big_endian(&mut bytes);
+                g1_add(&acc, &g1_mul(point, &BigUint::from_bytes_be(&bytes)))
+            })
+            .unwrap();
+
+        let mut input = circuit.input();
+        input.write::<Bn254G1AffineVariable>(base);
+        input.write::<ArrayVariable<Bn254G1AffineVariable, N>>(points);
+        input.write::<ArrayVariable<U256Variable, N>>(scalars.to_vec());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<Bn254G1AffineVariable>(), expected);
+    }
+}
@@ -0,0 +1,190 @@
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+
+use super::fp2::{Bn254Fp2Product, Bn254Fp2Variable};
+use crate::prelude::{CircuitBuilder, CircuitVariable, PlonkParameters, Variable};
+use crate::utils::bn254::{G2Affine, BN254_ORDER, BN254_PI2_COEFF, BN254_PI_COEFFS, BN254_TWIST_B};
+
+/// An affine point of G2, on the twist `y^2 = x^3 + 3 / (u + 9)` over `Fp2`.
+///
+/// The point at infinity has no representation.
+#[derive(Debug, Clone, Copy)]
+pub struct Bn254G2AffineVariable {
+    pub x: Bn254Fp2Variable,
+    pub y: Bn254Fp2Variable,
+}
+
+impl CircuitVariable for Bn254G2AffineVariable {
+    type ValueType<F: RichField> = G2Affine;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            x: Bn254Fp2Variable::init_unsafe(builder),
+            y: Bn254Fp2Variable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.x.variables();
+        variables.extend(self.y.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        let n = Bn254Fp2Variable::nb_elements();
+        assert_eq!(variables.len(), 2 * n);
+        Self {
+            x: Bn254Fp2Variable::from_variables_unsafe(&variables[..n]),
+            y: Bn254Fp2Variable::from_variables_unsafe(&variables[n..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.x.assert_is_valid(builder);
+        self.y.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        2 * Bn254Fp2Variable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        let mut elements = Bn254Fp2Variable::elements::<F>(value.x);
+        elements.extend(Bn254Fp2Variable::elements::<F>(value.y));
+        elements
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        let n = Bn254Fp2Variable::nb_elements();
+        G2Affine {
+            x: Bn254Fp2Variable::from_elements::<F>(&elements[..n]),
+            y: Bn254Fp2Variable::from_elements::<F>(&elements[n..]),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Asserts that `point` is on the twist `y^2 = x^3 + 3 / (u + 9)`.
+    pub fn bn254_g2_assert_on_curve(&mut self, point: &Bn254G2AffineVariable) {
+        let b = self.constant::<Bn254Fp2Variable>(BN254_TWIST_B.clone());
+        let one = Bn254Fp2Variable::constant_u32(self, 1, 0);
+        let x2 = self.bn254_fp2_mul(&point.x, &point.x);
+        let rhs = self.bn254_fp2_sum_of_products(&[
+            Bn254Fp2Product::new(x2, point.x),
+            Bn254Fp2Product::new(b, one),
+        ]);
+        let y2 = self.bn254_fp2_mul(&point.y, &point.y);
+        self.assert_is_equal(rhs, y2);
+    }
+
+    pub fn bn254_g2_neg(&mut self, a: &Bn254G2AffineVariable) -> Bn254G2AffineVariable {
+        Bn254G2AffineVariable {
+            x: a.x,
+            y: self.bn254_fp2_neg(&a.y),
+        }
+    }
+
+    /// Returns the point `(slope^2 - a.x - b.x, slope (a.x - x) - a.y)` on the line through `a`.
+    fn bn254_g2_chord(
+        &mut self,
+        a: &Bn254G2AffineVariable,
+        b_x: &Bn254Fp2Variable,
+        slope: &Bn254Fp2Variable,
+    ) -> Bn254G2AffineVariable {
+        let one = Bn254Fp2Variable::constant_u32(self, 1, 0);
+        let mut a_x = Bn254Fp2Product::new(a.x, one);
+        a_x.negate = true;
+        let mut b_x = Bn254Fp2Product::new(*b_x, one);
+        b_x.negate = true;
+        let x = self.bn254_fp2_sum_of_products(&[Bn254Fp2Product::new(*slope, *slope), a_x, b_x]);
+        let dx = self.bn254_fp2_sub(&a.x, &x);
+        let mut a_y = Bn254Fp2Product::new(a.y, one);
+        a_y.negate = true;
+        let y = self.bn254_fp2_sum_of_products(&[Bn254Fp2Product::new(*slope, dx), a_y]);
+        Bn254G2AffineVariable { x, y }
+    }
+
+    /// Adds two points with distinct x coordinates, and returns the sum together with the slope
+    /// of the line through them.
+    pub(crate) fn bn254_g2_add_with_slope(
+        &mut self,
+        a: &Bn254G2AffineVariable,
+        b: &Bn254G2AffineVariable,
+    ) -> (Bn254G2AffineVariable, Bn254Fp2Variable) {
+        let dy = self.bn254_fp2_sub(&b.y, &a.y);
+        let dx = self.bn254_fp2_sub(&b.x, &a.x);
+        let slope = self.bn254_fp2_div(&dy, &dx);
+        (self.bn254_g2_chord(a, &b.x, &slope), slope)
+    }
+
+    /// Doubles a point whose y coordinate is non-zero, and returns the double together with the
+    /// slope of the tangent.
+    pub(crate) fn bn254_g2_double_with_slope(
+        &mut self,
+        a: &Bn254G2AffineVariable,
+    ) -> (Bn254G2AffineVariable, Bn254Fp2Variable) {
+        let three = Bn254Fp2Variable::constant_u32(self, 3, 0);
+        let x2 = self.bn254_fp2_mul(&a.x, &a.x);
+        let numerator = self.bn254_fp2_mul(&three, &x2);
+        let denominator = self.bn254_fp2_add(&a.y, &a.y);
+        let slope = self.bn254_fp2_div(&numerator, &denominator);
+        (self.bn254_g2_chord(a, &a.x, &slope), slope)
+    }
+
+    /// Adds two points with distinct x coordinates, which is constrained.
+    pub fn bn254_g2_add(
+        &mut self,
+        a: &Bn254G2AffineVariable,
+        b: &Bn254G2AffineVariable,
+    ) -> Bn254G2AffineVariable {
+        self.bn254_g2_add_with_slope(a, b).0
+    }
+
+    pub fn bn254_g2_double(&mut self, a: &Bn254G2AffineVariable) -> Bn254G2AffineVariable {
+        self.bn254_g2_double_with_slope(a).0
+    }
+
+    /// The Frobenius endomorphism of the twist, which acts on G2 as the multiplication by `p`.
+    pub fn bn254_g2_frobenius(&mut self, a: &Bn254G2AffineVariable) -> Bn254G2AffineVariable {
+        let (cx, cy) = &*BN254_PI_COEFFS;
+        let x = self.bn254_fp2_conjugate(&a.x);
+        let y = self.bn254_fp2_conjugate(&a.y);
+        Bn254G2AffineVariable {
+            x: self.bn254_fp2_mul_by_constant(&x, cx),
+            y: self.bn254_fp2_mul_by_constant(&y, cy),
+        }
+    }
+
+    /// Returns `-pi^2(a)`, where `pi^2(x, y) = (c x, -y)` is the squared Frobenius endomorphism.
+    pub fn bn254_g2_neg_frobenius2(&mut self, a: &Bn254G2AffineVariable) -> Bn254G2AffineVariable {
+        Bn254G2AffineVariable {
+            x: self.bn254_fp2_mul_by_constant(&a.x, &BN254_PI2_COEFF),
+            y: a.y,
+        }
+    }
+
+    /// Asserts that `point` is on the twist and in the subgroup of order `r`, by checking that
+    /// `[r - 1] P = -P`.
+    ///
+    /// For a point of order `r`, none of the additions of the double-and-add loop is degenerate,
+    /// as the prefixes of the even scalar `r - 1` are smaller than `r / 2`.
+    pub fn bn254_g2_assert_in_subgroup(&mut self, point: &Bn254G2AffineVariable) {
+        self.bn254_g2_assert_on_curve(point);
+
+        let scalar = &*BN254_ORDER - BigUint::from(1u32);
+        let mut multiple = *point;
+        for i in (0..scalar.bits() - 1).rev() {
+            multiple = self.bn254_g2_double(&multiple);
+            if scalar.bit(i) {
+                multiple = self.bn254_g2_add(&multiple, point);
+            }
+        }
+        let neg_point = self.bn254_g2_neg(point);
+        self.assert_is_equal(multiple, neg_point);
+    }
+}
@@ -0,0 +1,259 @@
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+use plonky2x_derive::CircuitVariable;
+
+use super::g1::Bn254G1AffineVariable;
+use super::g2::Bn254G2AffineVariable;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::{
+    ArrayVariable, BoolVariable, CircuitBuilder, CircuitVariable, PlonkParameters, U256Variable,
+    Variable,
+};
+use crate::utils::bn254::BN254_ORDER;
+
+/// The verifying key of a Groth16 proof over BN254 with `N` public inputs, in the layout used by
+/// snarkjs and gnark.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(Groth16VerifyingKey)]
+pub struct Groth16VerifyingKeyVariable<const N: usize> {
+    pub alpha: Bn254G1AffineVariable,
+    pub beta: Bn254G2AffineVariable,
+    pub gamma: Bn254G2AffineVariable,
+    pub delta: Bn254G2AffineVariable,
+    pub ic0: Bn254G1AffineVariable,
+    pub ic: ArrayVariable<Bn254G1AffineVariable, N>,
+}
+
+/// A Groth16 proof over BN254.
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(Groth16Proof)]
+pub struct Groth16ProofVariable {
+    pub a: Bn254G1AffineVariable,
+    pub b: Bn254G2AffineVariable,
+    pub c: Bn254G1AffineVariable,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Verifies a Groth16 proof over BN254, i.e. checks that
+    /// `e(A, B) = e(alpha, beta) e(ic0 + sum(inputs[i] ic[i]), gamma) e(C, delta)`.
+    ///
+    /// The points of the proof are checked to be in their subgroups and the public inputs to be
+    /// reduced modulo `r`. The verifying key is trusted: it is typically a constant of the
+    /// circuit, and its points are not checked. The linear combination of the public inputs is
+    /// proven by the BN254 accelerator.
+    pub fn verify_groth16<const N: usize>(
+        &mut self,
+        vk: &Groth16VerifyingKeyVariable<N>,
+        proof: &Groth16ProofVariable,
+        public_inputs: &ArrayVariable<U256Variable, N>,
+    ) {
+        self.bn254_g1_assert_on_curve(&proof.a);
+        self.bn254_g1_assert_on_curve(&proof.c);
+        self.bn254_g2_assert_in_subgroup(&proof.b);
+
+        let max_input = self
+            .api
+            .constant_biguint(&(&*BN254_ORDER - BigUint::from(1u32)));
+        let true_val = self._true();
+        for input in public_inputs.as_vec() {
+            let input = BigUintTarget {
+                limbs: input
+                    .limbs
+                    .iter()
+                    .map(|limb| U32Target::from(*limb))
+                    .collect(),
+            };
+            let is_reduced = self.api.cmp_biguint(&input, &max_input);
+            self.assert_is_equal(BoolVariable::from(is_reduced), true_val);
+        }
+
+        let l = self.curta_bn254_g1_msm(&vk.ic0, &vk.ic, public_inputs);
+        let neg_a = self.bn254_g1_neg(&proof.a);
+        self.bn254_assert_pairing_product_is_one(&[
+            (neg_a, proof.b),
+            (vk.alpha, vk.beta),
+            (l, vk.gamma),
+            (proof.c, vk.delta),
+        ]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Read;
+
+    use ethers::types::U256;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::prelude::DefaultBuilder;
+    use crate::utils;
+    use crate::utils::bn254::{
+        g1_mul, g2_mul, Fp2, G1Affine, G2Affine, BN254_G1_GENERATOR, BN254_G2_GENERATOR,
+    };
+
+    /// A verifying key, proof and public inputs exported by gnark, which are also used by the
+    /// Solidity verifier tests. Field elements are decimal strings in the verifying key, which
+    /// stores `-beta`, `-gamma` and `-delta`, and hex strings in the proof, which is in the
+    /// EIP-197 layout `A.x, A.y, B.x.c1, B.x.c0, B.y.c1, B.y.c0, C.x, C.y`.
+    fn read_gnark_fixture() -> (
+        Groth16VerifyingKey<3, GoldilocksField>,
+        Groth16Proof<GoldilocksField>,
+        Vec<U256>,
+    ) {
+        let mut file = File::open("./src/frontend/ecc/bn254/fixtures/groth16_gnark.json").unwrap();
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).unwrap();
+        let fixture: Value = serde_json::from_str(&contents).unwrap();
+
+        let fp = |value: &Value| {
+            let value = value.as_str().unwrap();
+            match value.strip_prefix("0x") {
+                Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16).unwrap(),
+                None => BigUint::parse_bytes(value.as_bytes(), 10).unwrap(),
+            }
+        };
+        let g1 = |value: &Value| G1Affine {
+            x: fp(&value[0]),
+            y: fp(&value[1]),
+        };
+        let neg_g2 = |value: &Value| {
+            G2Affine {
+                x: Fp2::new(fp(&value[0][0]), fp(&value[0][1])),
+                y: Fp2::new(fp(&value[1][0]), fp(&value[1][1])),
+            }
+            .neg()
+        };
+
+        let vk = &fixture["vk"];
+        let ic = vk["ic"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(g1)
+            .collect::<Vec<_>>();
+        let vk = Groth16VerifyingKey::<3, GoldilocksField> {
+            alpha: g1(&vk["alpha"]),
+            beta: neg_g2(&vk["beta_neg"]),
+            gamma: neg_g2(&vk["gamma_neg"]),
+            delta: neg_g2(&vk["delta_neg"]),
+            ic0: ic[0].clone(),
+            ic: ic[1..].to_vec(),
+        };
+
+        let proof = &fixture["proof"];
+        let proof = Groth16Proof::<GoldilocksField> {
+            a: G1Affine {
+                x: fp(&proof[0]),
+                y: fp(&proof[1]),
+            },
+            b: G2Affine {
+                x: Fp2::new(fp(&proof[3]), fp(&proof[2])),
+                y: Fp2::new(fp(&proof[5]), fp(&proof[4])),
+            },
+            c: G1Affine {
+                x: fp(&proof[6]),
+                y: fp(&proof[7]),
+            },
+        };
+
+        let public_inputs = fixture["inputs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|input| U256::from_big_endian(&fp(input).to_bytes_be()))
+            .collect();
+        (vk, proof, public_inputs)
+    }
+
+    fn verify_gnark_proof(public_inputs: Vec<U256>) {
+        utils::setup_logger();
+
+        const N: usize = 3;
+        let mut builder = DefaultBuilder::new();
+        let vk = builder.read::<Groth16VerifyingKeyVariable<N>>();
+        let proof = builder.read::<Groth16ProofVariable>();
+        let inputs = builder.read::<ArrayVariable<U256Variable, N>>();
+        builder.verify_groth16(&vk, &proof, &inputs);
+        let circuit = builder.build();
+
+        let (vk, proof, _) = read_gnark_fixture();
+        let mut input = circuit.input();
+        input.write::<Groth16VerifyingKeyVariable<N>>(vk);
+        input.write::<Groth16ProofVariable>(proof);
+        input.write::<ArrayVariable<U256Variable, N>>(public_inputs);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_groth16_gnark() {
+        let (_, _, public_inputs) = read_gnark_fixture();
+        verify_gnark_proof(public_inputs);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_groth16_gnark_wrong_input() {
+        let (_, _, mut public_inputs) = read_gnark_fixture();
+        public_inputs[2] += U256::one();
+        verify_gnark_proof(public_inputs);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_groth16() {
+        utils::setup_logger();
+
+        const N: usize = 2;
+        let mut builder = DefaultBuilder::new();
+        let vk = builder.read::<Groth16VerifyingKeyVariable<N>>();
+        let proof = builder.read::<Groth16ProofVariable>();
+        let public_inputs = builder.read::<ArrayVariable<U256Variable, N>>();
+        builder.verify_groth16(&vk, &proof, &public_inputs);
+        let circuit = builder.build();
+
+        // A synthetic key and proof, built from known discrete logarithms so that the pairing
+        // equation holds.
+        let r = &*BN254_ORDER;
+        let [alpha, beta, gamma, delta, ic0, ic1, ic2, a, b] =
+            [3u32, 5, 7, 11, 13, 17, 19, 23, 29].map(BigUint::from);
+        let inputs = [BigUint::from(1234u32), r - BigUint::from(1u32)];
+        let l = &ic0 + &ic1 * &inputs[0] + &ic2 * &inputs[1];
+        let ab = (&a * &b) % r;
+        let rhs = (&alpha * &beta + &l * &gamma) % r;
+        let c = ((ab + r - rhs) * delta.modpow(&(r - BigUint::from(2u32)), r)) % r;
+
+        let g1 = |k: &BigUint| g1_mul(&BN254_G1_GENERATOR, k).unwrap();
+        let g2 = |k: &BigUint| g2_mul(&BN254_G2_GENERATOR, k).unwrap();
+        let vk = Groth16VerifyingKey::<N, GoldilocksField> {
+            alpha: g1(&alpha),
+            beta: g2(&beta),
+            gamma: g2(&gamma),
+            delta: g2(&delta),
+            ic0: g1(&ic0),
+            ic: vec![g1(&ic1), g1(&ic2)],
+        };
+        let proof = Groth16Proof::<GoldilocksField> {
+            a: g1(&a),
+            b: g2(&b),
+            c: g1(&c),
+        };
+        let public_inputs = inputs
+            .iter()
+            .map(|input| U256::from_big_endian(&input.to_bytes_be()))
+            .collect::<Vec<_>>();
+
+        let mut input = circuit.input();
+        input.write::<Groth16VerifyingKeyVariable<N>>(vk);
+        input.write::<Groth16ProofVariable>(proof);
+        input.write::<ArrayVariable<U256Variable, N>>(public_inputs);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+}
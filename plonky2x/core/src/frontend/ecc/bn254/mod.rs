@@ -0,0 +1,9 @@
+pub mod curta;
+pub mod ec_ops;
+pub mod fp;
+pub mod fp12;
+pub mod fp2;
+pub mod g1;
+pub mod g2;
+pub mod groth16;
+pub mod pairing;
@@ -0,0 +1,141 @@
+use num::{BigUint, Zero};
+
+use super::fp::{Bn254FpProduct, Bn254FpVariable};
+use super::fp12::Bn254Fp12Variable;
+use super::fp2::{Bn254Fp2Product, Bn254Fp2Variable};
+use super::g1::Bn254G1AffineVariable;
+use super::g2::Bn254G2AffineVariable;
+use crate::prelude::{CircuitBuilder, CircuitVariable, PlonkParameters};
+use crate::utils::bn254::{Fp12, BN254_ATE_LOOP_COUNT};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Multiplies `f` by the line through `t` with slope `slope`, evaluated at `p`, i.e. by
+    /// `y_p - slope x_p w + (slope x_t - y_t) w^3`.
+    fn bn254_mul_by_line(
+        &mut self,
+        f: &Bn254Fp12Variable,
+        slope: &Bn254Fp2Variable,
+        t: &Bn254G2AffineVariable,
+        p: &Bn254G1AffineVariable,
+    ) -> Bn254Fp12Variable {
+        let l0 = Bn254Fp2Variable {
+            c0: p.y,
+            c1: self.constant::<Bn254FpVariable>(BigUint::zero()),
+        };
+        let l1 = Bn254Fp2Variable {
+            c0: self.bn254_fp_sum_of_products(&[Bn254FpProduct::new(slope.c0, p.x, true)]),
+            c1: self.bn254_fp_sum_of_products(&[Bn254FpProduct::new(slope.c1, p.x, true)]),
+        };
+        let one = Bn254Fp2Variable::constant_u32(self, 1, 0);
+        let mut y_t = Bn254Fp2Product::new(t.y, one);
+        y_t.negate = true;
+        let l3 = self.bn254_fp2_sum_of_products(&[Bn254Fp2Product::new(*slope, t.x), y_t]);
+        self.bn254_fp12_mul_sparse(f, &[Some(l0), Some(l1), None, Some(l3), None, None])
+    }
+
+    /// Adds `q` to each `t` and multiplies `f` by the lines through them.
+    fn bn254_add_step(
+        &mut self,
+        f: &Bn254Fp12Variable,
+        pairs: &[(Bn254G1AffineVariable, Bn254G2AffineVariable)],
+        ts: &mut [Bn254G2AffineVariable],
+    ) -> Bn254Fp12Variable {
+        let mut f = *f;
+        for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+            let (sum, slope) = self.bn254_g2_add_with_slope(t, q);
+            f = self.bn254_mul_by_line(&f, &slope, t, p);
+            *t = sum;
+        }
+        f
+    }
+
+    /// Computes the product of the Miller loops of the optimal ate pairing for the given pairs,
+    /// sharing the squarings of the accumulator.
+    ///
+    /// The points of G2 must be in the subgroup of order `r`, so that none of the intermediate
+    /// additions and doublings are degenerate.
+    pub fn bn254_miller_loop(
+        &mut self,
+        pairs: &[(Bn254G1AffineVariable, Bn254G2AffineVariable)],
+    ) -> Bn254Fp12Variable {
+        let mut f = self.constant::<Bn254Fp12Variable>(Fp12::one());
+        let mut ts = pairs.iter().map(|(_, q)| *q).collect::<Vec<_>>();
+        let nb_bits = 128 - BN254_ATE_LOOP_COUNT.leading_zeros() as usize;
+        for i in (0..nb_bits - 1).rev() {
+            // The accumulator is one before the first iteration.
+            if i != nb_bits - 2 {
+                f = self.bn254_fp12_square(&f);
+            }
+            for ((p, _), t) in pairs.iter().zip(ts.iter_mut()) {
+                let (double, slope) = self.bn254_g2_double_with_slope(t);
+                f = self.bn254_mul_by_line(&f, &slope, t, p);
+                *t = double;
+            }
+            if (BN254_ATE_LOOP_COUNT >> i) & 1 == 1 {
+                f = self.bn254_add_step(&f, pairs, &mut ts);
+            }
+        }
+
+        // The final steps with `pi(Q)` and `-pi^2(Q)`.
+        let frobenius_pairs = pairs
+            .iter()
+            .map(|(p, q)| (*p, self.bn254_g2_frobenius(q)))
+            .collect::<Vec<_>>();
+        f = self.bn254_add_step(&f, &frobenius_pairs, &mut ts);
+        let frobenius2_pairs = pairs
+            .iter()
+            .map(|(p, q)| (*p, self.bn254_g2_neg_frobenius2(q)))
+            .collect::<Vec<_>>();
+        self.bn254_add_step(&f, &frobenius2_pairs, &mut ts)
+    }
+
+    /// Raises the output of the Miller loop to the power `m (p^12 - 1) / r`, where
+    /// `m = 2x (6x^2 + 3x + 1)` is coprime to `r`, which is equivalent to the usual final
+    /// exponentiation for pairing checks.
+    ///
+    /// The hard part follows Fuentes-Castañeda, Knapp and Rodríguez-Henríquez,
+    /// "Faster hashing to G2".
+    pub fn bn254_final_exponentiation(&mut self, f: &Bn254Fp12Variable) -> Bn254Fp12Variable {
+        // The easy part, `(p^6 - 1)(p^2 + 1)`.
+        let inverse = self.bn254_fp12_inverse(f);
+        let conjugate = self.bn254_fp12_conjugate(f);
+        let f = self.bn254_fp12_mul(&conjugate, &inverse);
+        let frobenius = self.bn254_fp12_frobenius_map(&f, 2);
+        let f = self.bn254_fp12_mul(&frobenius, &f);
+
+        // The hard part.
+        let y0 = self.bn254_fp12_cyclotomic_exp_by_neg_x(&f);
+        let y1 = self.bn254_fp12_square(&y0);
+        let y2 = self.bn254_fp12_square(&y1);
+        let y3 = self.bn254_fp12_mul(&y2, &y1);
+        let y4 = self.bn254_fp12_cyclotomic_exp_by_neg_x(&y3);
+        let y5 = self.bn254_fp12_square(&y4);
+        let y6 = self.bn254_fp12_cyclotomic_exp_by_neg_x(&y5);
+        let y6 = self.bn254_fp12_conjugate(&y6);
+        let y3 = self.bn254_fp12_conjugate(&y3);
+        let y7 = self.bn254_fp12_mul(&y6, &y4);
+        let y8 = self.bn254_fp12_mul(&y7, &y3);
+        let y9 = self.bn254_fp12_mul(&y8, &y1);
+        let y10 = self.bn254_fp12_mul(&y8, &y4);
+        let y11 = self.bn254_fp12_mul(&y10, &f);
+        let y12 = self.bn254_fp12_frobenius_map(&y9, 1);
+        let y13 = self.bn254_fp12_mul(&y12, &y11);
+        let y8 = self.bn254_fp12_frobenius_map(&y8, 2);
+        let y14 = self.bn254_fp12_mul(&y8, &y13);
+        let f_conjugate = self.bn254_fp12_conjugate(&f);
+        let y15 = self.bn254_fp12_mul(&f_conjugate, &y9);
+        let y15 = self.bn254_fp12_frobenius_map(&y15, 3);
+        self.bn254_fp12_mul(&y15, &y14)
+    }
+
+    /// Asserts that the product of the pairings of the given pairs is one.
+    pub fn bn254_assert_pairing_product_is_one(
+        &mut self,
+        pairs: &[(Bn254G1AffineVariable, Bn254G2AffineVariable)],
+    ) {
+        let f = self.bn254_miller_loop(pairs);
+        let result = self.bn254_final_exponentiation(&f);
+        let one = self.constant::<Bn254Fp12Variable>(Fp12::one());
+        self.assert_is_equal(result, one);
+    }
+}
@@ -0,0 +1,17 @@
+use super::request::{EcOpRequest, EcOpResponse};
+use super::EcOpCurve;
+
+#[derive(Debug, Clone)]
+pub struct EcAccelerator<E: EcOpCurve> {
+    pub ec_op_requests: Vec<EcOpRequest<E>>,
+    pub ec_op_responses: Vec<EcOpResponse<E>>,
+}
+
+impl<E: EcOpCurve> Default for EcAccelerator<E> {
+    fn default() -> Self {
+        Self {
+            ec_op_requests: Vec::new(),
+            ec_op_responses: Vec::new(),
+        }
+    }
+}
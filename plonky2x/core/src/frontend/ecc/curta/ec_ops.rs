@@ -0,0 +1,82 @@
+use super::accelerator::EcAccelerator;
+use super::request::{EcOpRequest, EcOpRequestType, EcOpResponse};
+use super::CurtaCurve;
+use crate::frontend::curta::ec::point::{AffinePointVariable, CompressedEdwardsYVariable};
+use crate::frontend::curta::field::variable::FieldVariable;
+use crate::prelude::{CircuitBuilder, PlonkParameters, U256Variable};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Add two points on the curve.
+    pub fn curta_ec_add<C: CurtaCurve<L, D>>(
+        &mut self,
+        a: AffinePointVariable<C>,
+        b: AffinePointVariable<C>,
+    ) -> AffinePointVariable<C> {
+        let request = EcOpRequest::Add(Box::new(a), Box::new(b));
+        match self.add_ec_op_request(request) {
+            EcOpResponse::Add(result) => result,
+            _ => unreachable!("response should be of type Add"),
+        }
+    }
+
+    /// Multiply a point on the curve by a scalar.
+    pub fn curta_ec_scalar_mul<C: CurtaCurve<L, D>>(
+        &mut self,
+        scalar: U256Variable,
+        point: AffinePointVariable<C>,
+    ) -> AffinePointVariable<C> {
+        let request = EcOpRequest::ScalarMul(Box::new(scalar), Box::new(point));
+        match self.add_ec_op_request(request) {
+            EcOpResponse::ScalarMul(result) => result,
+            _ => unreachable!("response should be of type ScalarMul"),
+        }
+    }
+
+    /// Decompress a compressed point, on a curve which supports decompression.
+    pub fn curta_ec_decompress<C: CurtaCurve<L, D, DecompressOps = ()>>(
+        &mut self,
+        compressed_point: CompressedEdwardsYVariable,
+    ) -> AffinePointVariable<C> {
+        let request = EcOpRequest::Decompress((), Box::new(compressed_point));
+        match self.add_ec_op_request::<C>(request) {
+            EcOpResponse::Decompress(point, _) => point,
+            _ => unreachable!("response should be of type Decompress"),
+        }
+    }
+
+    /// Check if a point is valid, on a curve which supports validity checks.
+    pub fn curta_ec_is_valid<C: CurtaCurve<L, D, DecompressOps = ()>>(
+        &mut self,
+        point: AffinePointVariable<C>,
+    ) {
+        let request = EcOpRequest::IsValid((), Box::new(point));
+        match self.add_ec_op_request(request) {
+            EcOpResponse::IsValid => {}
+            _ => unreachable!("response should be of type IsValid"),
+        }
+    }
+
+    /// Add an EC operation request to the accelerator of the curve.
+    fn add_ec_op_request<C: CurtaCurve<L, D>>(
+        &mut self,
+        request: EcOpRequest<C>,
+    ) -> EcOpResponse<C> {
+        let response = match request.req_type() {
+            EcOpRequestType::Add => EcOpResponse::Add(self.init::<AffinePointVariable<C>>()),
+            EcOpRequestType::ScalarMul => {
+                EcOpResponse::ScalarMul(self.init::<AffinePointVariable<C>>())
+            }
+            EcOpRequestType::Decompress(_) => EcOpResponse::Decompress(
+                self.init::<AffinePointVariable<C>>(),
+                self.init::<FieldVariable<C::BaseField>>(),
+            ),
+            EcOpRequestType::IsValid(_) => EcOpResponse::IsValid,
+        };
+
+        let accelerator = C::accelerator(self).get_or_insert_with(EcAccelerator::default);
+        accelerator.ec_op_requests.push(request);
+        accelerator.ec_op_responses.push(response.clone());
+
+        response
+    }
+}
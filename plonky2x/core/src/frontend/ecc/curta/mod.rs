@@ -0,0 +1,99 @@
+use core::fmt::Debug;
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use num::BigUint;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::point::CompressedPointRegister;
+use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
+use starkyx::chip::ec::scalar::ECScalarRegister;
+use starkyx::chip::ec::EllipticCurve;
+use starkyx::chip::field::register::FieldRegister;
+use starkyx::chip::AirParameters;
+use starkyx::machine::emulated::builder::EmulatedBuilder;
+
+use self::accelerator::EcAccelerator;
+use crate::prelude::{CircuitBuilder, PlonkParameters};
+
+pub mod accelerator;
+pub mod builder;
+pub mod ec_ops;
+pub mod proof_hint;
+pub mod request;
+pub mod result_hint;
+pub mod stark;
+
+/// Marks the curve operations which only some curves support.
+///
+/// Decompressing a [`CompressedEdwardsY`] point and checking that a point is on the curve are only
+/// constrained for Ed25519. The requests for these operations carry a value of
+/// [`EcOpCurve::DecompressOps`], which is `()` for the curves that support them and
+/// [`Unsupported`] for the others, so that such requests cannot be built for the other curves.
+pub trait EcOpCurve: EllipticCurve + Debug + Clone + Send + Sync + 'static {
+    /// `()` if the curve supports decompression and validity checks, [`Unsupported`] otherwise.
+    type DecompressOps: Copy + Debug + Send + Sync + Serialize + DeserializeOwned + 'static;
+}
+
+/// An uninhabited type, used as [`EcOpCurve::DecompressOps`] by the curves which do not support
+/// decompression and validity checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Unsupported {}
+
+/// An interface for an elliptic curve whose operations are proven using Curta.
+///
+/// The operations requested on a curve are batched by an [`EcAccelerator`] and proven by a
+/// single [`stark::EcStark`]. A curve only needs to describe how its operations are constrained
+/// in the stark. Additions and scalar multiplications are supported by every curve. The methods
+/// for decompression and validity checks take a [`EcOpCurve::DecompressOps`] value, so curves
+/// which set it to [`Unsupported`] implement them with an empty `match`.
+pub trait CurtaCurve<L: PlonkParameters<D>, const D: usize>: EcOpCurve {
+    /// The air parameters of the corresponding Curta stark.
+    type AirParameters: AirParameters<Field = L::Field, CubicParams = L::CubicParams>;
+
+    /// The name of the curve, used in logs, error messages and hint ids.
+    const NAME: &'static str;
+
+    /// The accelerator of the builder which batches the operations on this curve.
+    fn accelerator(builder: &mut CircuitBuilder<L, D>) -> &mut Option<EcAccelerator<Self>>;
+
+    /// Constrains the sum of two points in the stark.
+    fn air_add(
+        builder: &mut AirBuilder<Self::AirParameters>,
+        a: AffinePointRegister<Self>,
+        b: AffinePointRegister<Self>,
+    ) -> AffinePointRegister<Self>;
+
+    /// Constrains a batch of scalar multiplications in the stark, each taking 256 rows.
+    fn air_scalar_mul_batch(
+        builder: &mut EmulatedBuilder<Self::AirParameters>,
+        points: &[AffinePointRegister<Self>],
+        scalars: &[ECScalarRegister<Self>],
+        results: &[AffinePointRegister<Self>],
+    );
+
+    /// Constrains a point to be on the curve.
+    fn air_assert_valid(
+        ops: Self::DecompressOps,
+        builder: &mut AirBuilder<Self::AirParameters>,
+        point: &AffinePointRegister<Self>,
+    );
+
+    /// Allocates a public compressed point and constrains its decompression, returning the point
+    /// and the square root used to recover its x coordinate.
+    fn air_decompress(
+        ops: Self::DecompressOps,
+        builder: &mut AirBuilder<Self::AirParameters>,
+    ) -> (
+        CompressedPointRegister,
+        AffinePointRegister<Self>,
+        FieldRegister<Self::BaseField>,
+    );
+
+    /// Decompresses a point, returning the point and the square root used to recover its x
+    /// coordinate.
+    fn decompress(
+        ops: Self::DecompressOps,
+        compressed_point: &CompressedEdwardsY,
+    ) -> (AffinePoint<Self>, BigUint);
+}
@@ -0,0 +1,113 @@
+use core::marker::PhantomData;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use super::request::EcOpRequestType;
+use super::stark::{EcCurtaOpValue, EcStark};
+use super::{CurtaCurve, EcOpCurve};
+use crate::frontend::curta::ec::point::{AffinePointVariable, CompressedEdwardsYVariable};
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::Uint;
+use crate::prelude::{PlonkParameters, U256Variable, ValueStream};
+
+/// Provides a STARK proof for a set of EC operations.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EcOpProofHint<C: EcOpCurve> {
+    requests: Vec<EcOpRequestType<C::DecompressOps>>,
+    _marker: PhantomData<C>,
+}
+
+impl<C: EcOpCurve> EcOpProofHint<C> {
+    /// Creates a new proof hint for a set of EC operations.
+    pub fn new(requests: &[EcOpRequestType<C::DecompressOps>]) -> Self {
+        Self {
+            requests: requests.to_vec(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, C: CurtaCurve<L, D>, const D: usize> Hint<L, D> for EcOpProofHint<C> {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        // Read inputs.
+        let input = self
+            .requests
+            .iter()
+            .map(|req| match req {
+                EcOpRequestType::Add => {
+                    let a = input_stream.read_value::<AffinePointVariable<C>>();
+                    let b = input_stream.read_value::<AffinePointVariable<C>>();
+                    let result = input_stream.read_value::<AffinePointVariable<C>>();
+                    EcCurtaOpValue::Add(a, b, result)
+                }
+                EcOpRequestType::ScalarMul => {
+                    let scalar = BigUint::new(
+                        input_stream
+                            .read_value::<U256Variable>()
+                            .to_u32_limbs()
+                            .to_vec(),
+                    );
+                    let point = input_stream.read_value::<AffinePointVariable<C>>();
+                    let result = input_stream.read_value::<AffinePointVariable<C>>();
+                    EcCurtaOpValue::ScalarMul(scalar, point, result)
+                }
+                EcOpRequestType::Decompress(_) => {
+                    let compressed = input_stream.read_value::<CompressedEdwardsYVariable>();
+                    let result = input_stream.read_value::<AffinePointVariable<C>>();
+                    EcCurtaOpValue::Decompress(compressed, result)
+                }
+                EcOpRequestType::IsValid(_) => {
+                    let point = input_stream.read_value::<AffinePointVariable<C>>();
+                    EcCurtaOpValue::IsValid(point)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Initialize the STARK.
+        let stark = EcStark::<L, C, D>::new(&self.requests);
+
+        // Create the proof.
+        let (proof, public_inputs) = stark.prove(&input);
+
+        // Write outputs.
+        output_stream.write_emulated_stark_proof(proof);
+        output_stream.write_slice(&public_inputs)
+    }
+
+    /// The id of the hint, which does not depend on the module path of the curve.
+    fn id() -> String {
+        format!("EcOpProofHint<{}>", C::NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+
+    use super::*;
+    use crate::backend::circuit::DefaultParameters;
+
+    #[test]
+    fn test_ec_op_proof_hint_encoding() {
+        // The Ed25519 hint is encoded as the non-generic hint it replaced, a vector of unit
+        // variants, so that serialized circuits remain readable.
+        let hint = EcOpProofHint::<Ed25519>::new(&[
+            EcOpRequestType::Add,
+            EcOpRequestType::ScalarMul,
+            EcOpRequestType::Decompress(()),
+            EcOpRequestType::IsValid(()),
+        ]);
+        let mut expected = 4u64.to_le_bytes().to_vec();
+        for variant in 0u32..4 {
+            expected.extend(variant.to_le_bytes());
+        }
+        assert_eq!(bincode::serialize(&hint).unwrap(), expected);
+
+        assert_eq!(
+            <EcOpProofHint<Ed25519> as Hint<DefaultParameters, 2>>::id(),
+            "EcOpProofHint<Ed25519>"
+        );
+    }
+}
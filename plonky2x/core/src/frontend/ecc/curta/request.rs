@@ -1,47 +1,50 @@
 use serde::{Deserialize, Serialize};
-use starkyx::chip::ec::EllipticCurve;
 
+use super::EcOpCurve;
 use crate::frontend::curta::ec::point::{AffinePointVariable, CompressedEdwardsYVariable};
 use crate::frontend::curta::field::variable::FieldVariable;
 use crate::prelude::U256Variable;
 
+/// The type of an EC operation.
+///
+/// Decompression and validity checks carry the [`EcOpCurve::DecompressOps`] value of the curve.
 #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
-pub enum EcOpRequestType {
+pub enum EcOpRequestType<T = ()> {
     Add,
     ScalarMul,
-    Decompress,
-    IsValid,
+    Decompress(T),
+    IsValid(T),
 }
 
 /// A request for a EC OP computation.
 #[derive(Debug, Clone)]
-pub enum EcOpRequest<E: EllipticCurve> {
+pub enum EcOpRequest<E: EcOpCurve> {
     /// Add
     Add(Box<AffinePointVariable<E>>, Box<AffinePointVariable<E>>),
     /// Scalar Mul
     ScalarMul(Box<U256Variable>, Box<AffinePointVariable<E>>),
     /// Decompress
-    Decompress(Box<CompressedEdwardsYVariable>),
+    Decompress(E::DecompressOps, Box<CompressedEdwardsYVariable>),
     /// IsValid
-    IsValid(Box<AffinePointVariable<E>>),
+    IsValid(E::DecompressOps, Box<AffinePointVariable<E>>),
 }
 
 #[derive(Debug, Clone)]
-pub enum EcOpResponse<E: EllipticCurve> {
+pub enum EcOpResponse<E: EcOpCurve> {
     Add(AffinePointVariable<E>),
     ScalarMul(AffinePointVariable<E>),
     Decompress(AffinePointVariable<E>, FieldVariable<E::BaseField>),
     IsValid,
 }
 
-impl<E: EllipticCurve> EcOpRequest<E> {
+impl<E: EcOpCurve> EcOpRequest<E> {
     /// Returns the type of the request.
-    pub const fn req_type(&self) -> EcOpRequestType {
+    pub const fn req_type(&self) -> EcOpRequestType<E::DecompressOps> {
         match self {
             EcOpRequest::Add(_, _) => EcOpRequestType::Add,
             EcOpRequest::ScalarMul(_, _) => EcOpRequestType::ScalarMul,
-            EcOpRequest::Decompress(_) => EcOpRequestType::Decompress,
-            EcOpRequest::IsValid(_) => EcOpRequestType::IsValid,
+            EcOpRequest::Decompress(ops, _) => EcOpRequestType::Decompress(*ops),
+            EcOpRequest::IsValid(ops, _) => EcOpRequestType::IsValid(*ops),
         }
     }
 }
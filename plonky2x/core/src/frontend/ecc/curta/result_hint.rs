@@ -1,10 +1,10 @@
+use core::marker::PhantomData;
+
 use num_bigint::BigUint;
 use serde::{Deserialize, Serialize};
-use starkyx::chip::ec::edwards::ed25519::decompress::decompress;
-use starkyx::chip::ec::EllipticCurveParameters;
 
 use super::request::EcOpRequestType;
-use super::Curve;
+use super::{CurtaCurve, EcOpCurve};
 use crate::frontend::curta::ec::point::{AffinePointVariable, CompressedEdwardsYVariable};
 use crate::frontend::curta::field::variable::FieldVariable;
 use crate::frontend::hint::simple::hint::Hint;
@@ -13,17 +13,19 @@ use crate::prelude::{PlonkParameters, U256Variable, ValueStream};
 
 /// Provides the result of a EC operation.
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct EcOpResultHint {
-    ec_op: EcOpRequestType,
+#[serde(bound = "")]
+pub struct EcOpResultHint<C: EcOpCurve> {
+    ec_op: EcOpRequestType<C::DecompressOps>,
+    _marker: PhantomData<C>,
 }
 
-impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for EcOpResultHint {
+impl<L: PlonkParameters<D>, C: CurtaCurve<L, D>, const D: usize> Hint<L, D> for EcOpResultHint<C> {
     fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
         match &self.ec_op {
             EcOpRequestType::Add => {
-                let a = input_stream.read_value::<AffinePointVariable<Curve>>();
-                let b = input_stream.read_value::<AffinePointVariable<Curve>>();
-                output_stream.write_value::<AffinePointVariable<Curve>>(a + b);
+                let a = input_stream.read_value::<AffinePointVariable<C>>();
+                let b = input_stream.read_value::<AffinePointVariable<C>>();
+                output_stream.write_value::<AffinePointVariable<C>>(a + b);
             }
             EcOpRequestType::ScalarMul => {
                 let scalar = BigUint::new(
@@ -32,25 +34,30 @@ impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for EcOpResultHint {
                         .to_u32_limbs()
                         .to_vec(),
                 );
-                let point = input_stream.read_value::<AffinePointVariable<Curve>>();
-                output_stream.write_value::<AffinePointVariable<Curve>>(point * scalar);
+                let point = input_stream.read_value::<AffinePointVariable<C>>();
+                output_stream.write_value::<AffinePointVariable<C>>(point * scalar);
             }
-            EcOpRequestType::Decompress => {
+            EcOpRequestType::Decompress(ops) => {
                 let compressed_point = input_stream.read_value::<CompressedEdwardsYVariable>();
-                let (point, root) = decompress(&compressed_point);
-                output_stream.write_value::<AffinePointVariable<Curve>>(point);
-                output_stream
-                    .write_value::<FieldVariable<<Curve as EllipticCurveParameters>::BaseField>>(
-                        root,
-                    );
+                let (point, root) = C::decompress(*ops, &compressed_point);
+                output_stream.write_value::<AffinePointVariable<C>>(point);
+                output_stream.write_value::<FieldVariable<C::BaseField>>(root);
             }
-            EcOpRequestType::IsValid => {}
+            EcOpRequestType::IsValid(_) => {}
         }
     }
+
+    /// The id of the hint, which does not depend on the module path of the curve.
+    fn id() -> String {
+        format!("EcOpResultHint<{}>", C::NAME)
+    }
 }
 
-impl EcOpResultHint {
-    pub fn new(ec_op: EcOpRequestType) -> Self {
-        Self { ec_op }
+impl<C: EcOpCurve> EcOpResultHint<C> {
+    pub fn new(ec_op: EcOpRequestType<C::DecompressOps>) -> Self {
+        Self {
+            ec_op,
+            _marker: PhantomData,
+        }
     }
 }
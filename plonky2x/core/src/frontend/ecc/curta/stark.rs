@@ -3,82 +3,73 @@ use itertools::Itertools;
 use log::debug;
 use num_bigint::BigUint;
 use plonky2::iop::target::BoolTarget;
-use plonky2::util::log2_ceil;
 use plonky2::util::timing::TimingTree;
 use starkyx::chip::builder::AirBuilder;
-use starkyx::chip::ec::edwards::ed25519::gadget::{
-    CompressedPointAirWriter, CompressedPointGadget,
-};
-use starkyx::chip::ec::edwards::ed25519::instruction::Ed25519FpInstruction;
-use starkyx::chip::ec::edwards::ed25519::params::Ed25519BaseField;
+use starkyx::chip::ec::edwards::ed25519::gadget::CompressedPointAirWriter;
 use starkyx::chip::ec::edwards::ed25519::point::CompressedPointRegister;
 use starkyx::chip::ec::gadget::EllipticCurveAirWriter;
 use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
 use starkyx::chip::ec::scalar::ECScalarRegister;
-use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::ec::EllipticCurve;
 use starkyx::chip::field::register::FieldRegister;
 use starkyx::chip::register::Register;
 use starkyx::chip::trace::writer::data::AirWriterData;
 use starkyx::chip::trace::writer::AirWriter;
-use starkyx::chip::AirParameters;
 use starkyx::machine::builder::Builder;
-use starkyx::machine::ec::builder::EllipticCurveBuilder;
 use starkyx::machine::emulated::builder::EmulatedBuilder;
 use starkyx::machine::emulated::proof::EmulatedStarkProof;
 use starkyx::machine::emulated::stark::EmulatedStark;
 use starkyx::maybe_rayon::*;
 
-use super::air_parameters::Ed25519AirParameters;
 use super::request::EcOpRequestType;
-use super::Curve;
+use super::CurtaCurve;
 use crate::frontend::curta::ec::point::{AffinePointVariable, CompressedEdwardsYVariable};
 use crate::frontend::curta::field::variable::FieldVariable;
 use crate::frontend::curta::proof::EmulatedStarkProofVariable;
 use crate::prelude::*;
 
-pub enum Ed25519CurtaOp {
+/// The number of rows of the trace taken by a scalar multiplication.
+const SCALAR_MUL_ROWS: usize = 256;
+
+pub enum EcCurtaOp<C: EllipticCurve> {
     Add(
-        AffinePointRegister<Curve>,
-        AffinePointRegister<Curve>,
-        AffinePointRegister<Curve>,
+        AffinePointRegister<C>,
+        AffinePointRegister<C>,
+        AffinePointRegister<C>,
     ),
     ScalarMul(
-        ECScalarRegister<Curve>,
-        AffinePointRegister<Curve>,
-        AffinePointRegister<Curve>,
+        ECScalarRegister<C>,
+        AffinePointRegister<C>,
+        AffinePointRegister<C>,
     ),
     Decompress(
         CompressedPointRegister,
-        AffinePointRegister<Curve>,
-        FieldRegister<Ed25519BaseField>,
+        AffinePointRegister<C>,
+        FieldRegister<C::BaseField>,
     ),
-    IsValid(AffinePointRegister<Curve>),
+    IsValid(AffinePointRegister<C>),
 }
 
-pub enum Ed25519OpVariable {
+pub enum EcOpVariable<C: EllipticCurve> {
     Add(
-        AffinePointVariable<Curve>,
-        AffinePointVariable<Curve>,
-        AffinePointVariable<Curve>,
-    ),
-    ScalarMul(
-        U256Variable,
-        AffinePointVariable<Curve>,
-        AffinePointVariable<Curve>,
+        AffinePointVariable<C>,
+        AffinePointVariable<C>,
+        AffinePointVariable<C>,
     ),
+    ScalarMul(U256Variable, AffinePointVariable<C>, AffinePointVariable<C>),
     Decompress(
         Box<CompressedEdwardsYVariable>,
-        AffinePointVariable<Curve>,
-        FieldVariable<Ed25519BaseField>,
+        AffinePointVariable<C>,
+        FieldVariable<C::BaseField>,
     ),
-    IsValid(AffinePointVariable<Curve>),
+    IsValid(AffinePointVariable<C>),
 }
 
-pub enum Ed25519CurtaOpValue {
-    Add(AffinePoint<Curve>, AffinePoint<Curve>, AffinePoint<Curve>),
-    ScalarMul(BigUint, AffinePoint<Curve>, AffinePoint<Curve>),
-    Decompress(CompressedEdwardsY, AffinePoint<Curve>),
-    IsValid(AffinePoint<Curve>),
+pub enum EcCurtaOpValue<C: EllipticCurve> {
+    Add(AffinePoint<C>, AffinePoint<C>, AffinePoint<C>),
+    ScalarMul(BigUint, AffinePoint<C>, AffinePoint<C>),
+    Decompress(CompressedEdwardsY, AffinePoint<C>),
+    IsValid(AffinePoint<C>),
 }
 
 /// A Curta stark for proving EC operations.
@@ -88,15 +79,34 @@ pub enum Ed25519CurtaOpValue {
 ///    - EC Add, decompress, and is_valid operations are done on public inputs and using the AIR
 ///      table only for range checks.
 ///    - Scalar mul operations are done in the AIR table, with each scalae mul taking 256 rows.
-pub struct Ed25519Stark<L: PlonkParameters<D>, const D: usize> {
-    stark: EmulatedStark<Ed25519AirParameters<L, D>, L::CurtaConfig, D>,
-    operations: Vec<Ed25519CurtaOp>,
+pub struct EcStark<L: PlonkParameters<D>, C: CurtaCurve<L, D>, const D: usize> {
+    stark: EmulatedStark<C::AirParameters, L::CurtaConfig, D>,
+    operations: Vec<EcCurtaOp<C>>,
     degree: usize,
 }
 
-impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
-    pub fn new(request_data: &[EcOpRequestType]) -> Self {
-        let mut builder = EmulatedBuilder::<Ed25519AirParameters<L, D>>::new();
+impl<L: PlonkParameters<D>, C: CurtaCurve<L, D>, const D: usize> EcStark<L, C, D> {
+    /// Returns the number of rows of the trace proving the given operations.
+    ///
+    /// Each scalar multiplication takes `SCALAR_MUL_ROWS` rows. Additions, decompressions and
+    /// validity checks are constrained on the public inputs and take no rows. The trace is
+    /// written in windows of `SCALAR_MUL_ROWS` rows, so it has at least one window even when
+    /// no scalar multiplication is requested, as for the MSM.
+    pub fn num_rows(request_data: &[EcOpRequestType<C::DecompressOps>]) -> usize {
+        let rows = request_data
+            .iter()
+            .map(|kind| match kind {
+                EcOpRequestType::ScalarMul => SCALAR_MUL_ROWS,
+                EcOpRequestType::Add
+                | EcOpRequestType::Decompress(_)
+                | EcOpRequestType::IsValid(_) => 0,
+            })
+            .sum::<usize>();
+        rows.max(SCALAR_MUL_ROWS).next_power_of_two()
+    }
+
+    pub fn new(request_data: &[EcOpRequestType<C::DecompressOps>]) -> Self {
+        let mut builder = EmulatedBuilder::<C::AirParameters>::new();
 
         let mut scalars = vec![];
         let mut scalar_mul_points = vec![];
@@ -104,8 +114,8 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
         let operations = request_data
             .iter()
             .map(|kind| {
-                let air_op = Ed25519CurtaOp::new(&mut builder.api, kind);
-                if let Ed25519CurtaOp::ScalarMul(scalar, point, result) = &air_op {
+                let air_op = Self::air_op(&mut builder.api, kind);
+                if let EcCurtaOp::ScalarMul(scalar, point, result) = &air_op {
                     scalars.push(*scalar);
                     scalar_mul_points.push(*point);
                     scalar_mul_results.push(*result);
@@ -114,15 +124,18 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
             })
             .collect::<Vec<_>>();
 
-        let num_scalar_muls = scalars.len();
-        let degree_log = log2_ceil(num_scalar_muls * 256);
-        let degree = 1 << degree_log;
+        let degree = Self::num_rows(request_data);
         // Constrain the scalar mul operations.
-        builder.scalar_mul_batch(&scalar_mul_points, &scalars, &scalar_mul_results);
+        C::air_scalar_mul_batch(
+            &mut builder,
+            &scalar_mul_points,
+            &scalars,
+            &scalar_mul_results,
+        );
 
         let stark = builder.build::<L::CurtaConfig, D>(degree);
 
-        Ed25519Stark {
+        EcStark {
             stark,
             operations,
             degree,
@@ -132,23 +145,22 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
     pub fn write_input(
         &self,
         writer: &mut impl AirWriter<Field = L::Field>,
-        input: &[Ed25519CurtaOpValue],
+        input: &[EcCurtaOpValue<C>],
     ) {
         self.operations
             .iter()
             .zip(input.iter())
             .for_each(|(op, op_value)| match &op {
-                Ed25519CurtaOp::Add(a, b, _) => {
-                    if let Ed25519CurtaOpValue::Add(a_val, b_val, _) = &op_value {
+                EcCurtaOp::Add(a, b, _) => {
+                    if let EcCurtaOpValue::Add(a_val, b_val, _) = &op_value {
                         writer.write_ec_point(a, a_val);
                         writer.write_ec_point(b, b_val);
                     } else {
                         panic!("invalid input");
                     }
                 }
-                Ed25519CurtaOp::ScalarMul(scalar, point, result) => {
-                    if let Ed25519CurtaOpValue::ScalarMul(scalar_val, point_val, result_val) =
-                        &op_value
+                EcCurtaOp::ScalarMul(scalar, point, result) => {
+                    if let EcCurtaOpValue::ScalarMul(scalar_val, point_val, result_val) = &op_value
                     {
                         let mut limb_values = scalar_val.to_u32_digits();
                         limb_values.resize(8, 0);
@@ -161,15 +173,15 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
                         panic!("invalid input");
                     }
                 }
-                Ed25519CurtaOp::Decompress(compressed_point, _, _) => {
-                    if let Ed25519CurtaOpValue::Decompress(compressed_point_val, _) = &op_value {
+                EcCurtaOp::Decompress(compressed_point, _, _) => {
+                    if let EcCurtaOpValue::Decompress(compressed_point_val, _) = &op_value {
                         writer.write_ec_compressed_point(compressed_point, compressed_point_val);
                     } else {
                         panic!("invalid input");
                     }
                 }
-                Ed25519CurtaOp::IsValid(point) => {
-                    if let Ed25519CurtaOpValue::IsValid(point_val) = &op_value {
+                EcCurtaOp::IsValid(point) => {
+                    if let EcCurtaOpValue::IsValid(point_val) = &op_value {
                         writer.write_ec_point(point, point_val);
                     } else {
                         panic!("invalid input");
@@ -181,7 +193,7 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
     #[allow(clippy::type_complexity)]
     pub fn prove(
         &self,
-        input: &[Ed25519CurtaOpValue],
+        input: &[EcCurtaOpValue<C>],
     ) -> (
         EmulatedStarkProof<L::Field, L::CurtaConfig, D>,
         Vec<L::Field>,
@@ -190,20 +202,22 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
 
         let mut writer_data = AirWriterData::new(&self.stark.air_data, num_rows);
 
-        debug!("Writing EC stark input");
+        debug!("Writing {} stark input", C::NAME);
         let mut writer = writer_data.public_writer();
         self.write_input(&mut writer, input);
 
-        debug!("Writing EC execusion trace");
+        debug!("Writing {} execution trace", C::NAME);
         self.stark.air_data.write_global_instructions(&mut writer);
-        writer_data.chunks_par(256).for_each(|mut chunk| {
-            for i in 0..256 {
-                let mut writer = chunk.window_writer(i);
-                self.stark.air_data.write_trace_instructions(&mut writer);
-            }
-        });
+        writer_data
+            .chunks_par(SCALAR_MUL_ROWS)
+            .for_each(|mut chunk| {
+                for i in 0..SCALAR_MUL_ROWS {
+                    let mut writer = chunk.window_writer(i);
+                    self.stark.air_data.write_trace_instructions(&mut writer);
+                }
+            });
 
-        debug!("EC stark proof generation");
+        debug!("{} stark proof generation", C::NAME);
         let (trace, public) = (writer_data.trace, writer_data.public);
         let proof = self
             .stark
@@ -213,7 +227,7 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
         // Verify the proof as a stark
         self.stark.verify(proof.clone(), &public).unwrap();
 
-        debug!("EC stark proof verified");
+        debug!("{} stark proof verified", C::NAME);
 
         (proof, public)
     }
@@ -223,7 +237,7 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
         builder: &mut CircuitBuilder<L, D>,
         proof: EmulatedStarkProofVariable<D>,
         public_inputs: &[Variable],
-        ec_ops: &[Ed25519OpVariable],
+        ec_ops: &[EcOpVariable<C>],
     ) {
         // Verify the stark proof in the circuit.
         builder.verify_emulated_stark_proof(&self.stark, proof, public_inputs);
@@ -232,25 +246,22 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
 
         for (curta_op, op) in self.operations.iter().zip_eq(ec_ops.iter()) {
             match (curta_op, op) {
-                (
-                    Ed25519CurtaOp::Add(a, b, result),
-                    Ed25519OpVariable::Add(a_var, b_var, result_var),
-                ) => {
+                (EcCurtaOp::Add(a, b, result), EcOpVariable::Add(a_var, b_var, result_var)) => {
                     Self::assert_point_equal(builder, a, a_var, public_inputs);
                     Self::assert_point_equal(builder, b, b_var, public_inputs);
                     Self::assert_point_equal(builder, result, result_var, public_inputs);
                 }
                 (
-                    Ed25519CurtaOp::ScalarMul(scalar, point, result),
-                    Ed25519OpVariable::ScalarMul(scalar_var, point_var, result_var),
+                    EcCurtaOp::ScalarMul(scalar, point, result),
+                    EcOpVariable::ScalarMul(scalar_var, point_var, result_var),
                 ) => {
                     Self::assert_scalar_equal(builder, scalar, scalar_var, public_inputs);
                     Self::assert_point_equal(builder, point, point_var, public_inputs);
                     Self::assert_point_equal(builder, result, result_var, public_inputs);
                 }
                 (
-                    Ed25519CurtaOp::Decompress(compressed_point, result, pos_sqrt),
-                    Ed25519OpVariable::Decompress(compressed_point_var, result_var, pos_sqrt_var),
+                    EcCurtaOp::Decompress(compressed_point, result, pos_sqrt),
+                    EcOpVariable::Decompress(compressed_point_var, result_var, pos_sqrt_var),
                 ) => {
                     Self::assert_compressed_point_equal(
                         builder,
@@ -266,7 +277,7 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
                         public_inputs,
                     );
                 }
-                (Ed25519CurtaOp::IsValid(point), Ed25519OpVariable::IsValid(point_var)) => {
+                (EcCurtaOp::IsValid(point), EcOpVariable::IsValid(point_var)) => {
                     Self::assert_point_equal(builder, point, point_var, public_inputs);
                 }
                 _ => panic!("invalid operation"),
@@ -314,7 +325,7 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
 
     fn assert_scalar_equal(
         builder: &mut CircuitBuilder<L, D>,
-        s: &ECScalarRegister<Curve>,
+        s: &ECScalarRegister<C>,
         s_var: &U256Variable,
         public_inputs: &[Variable],
     ) {
@@ -326,8 +337,8 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
 
     fn assert_field_element_equal(
         builder: &mut CircuitBuilder<L, D>,
-        element: &FieldRegister<<Curve as EllipticCurveParameters>::BaseField>,
-        element_var: &FieldVariable<<Curve as EllipticCurveParameters>::BaseField>,
+        element: &FieldRegister<C::BaseField>,
+        element_var: &FieldVariable<C::BaseField>,
         public_inputs: &[Variable],
     ) {
         let element = FieldVariable::from_variables_unsafe(
@@ -338,54 +349,104 @@ impl<L: PlonkParameters<D>, const D: usize> Ed25519Stark<L, D> {
 
     fn assert_point_equal(
         builder: &mut CircuitBuilder<L, D>,
-        a: &AffinePointRegister<Curve>,
-        a_var: &AffinePointVariable<Curve>,
+        a: &AffinePointRegister<C>,
+        a_var: &AffinePointVariable<C>,
         public_inputs: &[Variable],
     ) {
         Self::assert_field_element_equal(builder, &a.x, &a_var.x, public_inputs);
         Self::assert_field_element_equal(builder, &a.y, &a_var.y, public_inputs);
     }
 
-    pub fn read_proof_with_public_input(
-        &self,
-        builder: &mut CircuitBuilder<L, D>,
-        output_stream: &OutputVariableStream<L, D>,
-    ) -> (EmulatedStarkProofVariable<D>, Vec<Variable>) {
-        let proof = output_stream.read_emulated_stark_proof(builder, &self.stark);
-        let public_inputs = output_stream.read_vec(builder, self.stark.air_data.num_public_inputs);
-
-        (proof, public_inputs)
-    }
-}
-
-impl Ed25519CurtaOp {
-    pub fn new<L: AirParameters<Instruction = Ed25519FpInstruction>>(
-        builder: &mut AirBuilder<L>,
-        reuest_type: &EcOpRequestType,
-    ) -> Self {
-        match reuest_type {
+    /// Allocates the registers of an operation and constrains it in the stark.
+    fn air_op(
+        builder: &mut AirBuilder<C::AirParameters>,
+        request_type: &EcOpRequestType<C::DecompressOps>,
+    ) -> EcCurtaOp<C> {
+        match request_type {
             EcOpRequestType::Add => {
                 let a = builder.alloc_public_ec_point();
                 let b = builder.alloc_public_ec_point();
-                let result = builder.add(a, b);
-                Self::Add(a, b, result)
+                let result = C::air_add(builder, a, b);
+                EcCurtaOp::Add(a, b, result)
             }
             EcOpRequestType::ScalarMul => {
                 let point = builder.alloc_public_ec_point();
                 let scalar = ECScalarRegister::new(builder.alloc_array_public(8));
                 let result = builder.alloc_public_ec_point();
-                Self::ScalarMul(scalar, point, result)
+                EcCurtaOp::ScalarMul(scalar, point, result)
             }
-            EcOpRequestType::Decompress => {
-                let compressed_point = builder.alloc_public_ec_compressed_point();
-                let (result, pos_square_root) = builder.ed25519_decompress(&compressed_point);
-                Self::Decompress(compressed_point, result, pos_square_root)
+            EcOpRequestType::Decompress(ops) => {
+                let (compressed_point, result, pos_square_root) = C::air_decompress(*ops, builder);
+                EcCurtaOp::Decompress(compressed_point, result, pos_square_root)
             }
-            EcOpRequestType::IsValid => {
+            EcOpRequestType::IsValid(ops) => {
                 let point = builder.alloc_public_ec_point();
-                builder.ed_assert_valid(&point);
-                Self::IsValid(point)
+                C::air_assert_valid(*ops, builder, &point);
+                EcCurtaOp::IsValid(point)
             }
         }
     }
+
+    pub fn read_proof_with_public_input(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        output_stream: &OutputVariableStream<L, D>,
+    ) -> (EmulatedStarkProofVariable<D>, Vec<Variable>) {
+        let proof = output_stream.read_emulated_stark_proof(builder, &self.stark);
+        let public_inputs = output_stream.read_vec(builder, self.stark.air_data.num_public_inputs);
+
+        (proof, public_inputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+
+    use super::*;
+    use crate::backend::circuit::DefaultParameters;
+
+    type Ed25519Stark = EcStark<DefaultParameters, Ed25519, 2>;
+
+    #[test]
+    fn test_ec_stark_num_rows() {
+        const NUM_POINTS: usize = 3;
+
+        // Without scalar multiplications, the trace still has one window of rows.
+        assert_eq!(Ed25519Stark::num_rows(&[]), 256);
+        assert_eq!(Ed25519Stark::num_rows(&[EcOpRequestType::Add]), 256);
+
+        // The MSM of `n` points only requests `(2^4 + 256 / 4) n + 256` additions, while a
+        // scalar multiplication per point takes 256 rows each.
+        let msm = |n: usize| vec![EcOpRequestType::Add; (16 + 64) * n + 256];
+        let scalar_muls = vec![EcOpRequestType::ScalarMul; NUM_POINTS];
+        assert_eq!(Ed25519Stark::num_rows(&msm(NUM_POINTS)), 256);
+        assert_eq!(Ed25519Stark::num_rows(&scalar_muls), 1024);
+
+        // Verifying signatures one by one takes two scalar multiplications per signature, while
+        // the batch verification decompresses and checks the points and runs an MSM over the
+        // signature points, the public keys and the generator.
+        let mut single = Vec::new();
+        let mut batch = Vec::new();
+        for _ in 0..NUM_POINTS {
+            single.extend([
+                EcOpRequestType::ScalarMul,
+                EcOpRequestType::Decompress(()),
+                EcOpRequestType::IsValid(()),
+                EcOpRequestType::ScalarMul,
+                EcOpRequestType::Decompress(()),
+                EcOpRequestType::IsValid(()),
+                EcOpRequestType::Add,
+            ]);
+            batch.extend([
+                EcOpRequestType::Decompress(()),
+                EcOpRequestType::IsValid(()),
+                EcOpRequestType::Decompress(()),
+                EcOpRequestType::IsValid(()),
+            ]);
+        }
+        batch.extend(msm(2 * NUM_POINTS + 1));
+        assert_eq!(Ed25519Stark::num_rows(&single), 2048);
+        assert_eq!(Ed25519Stark::num_rows(&batch), 256);
+    }
 }
@@ -1,11 +1,120 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use num::BigUint;
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::decompress::decompress;
+use starkyx::chip::ec::edwards::ed25519::gadget::CompressedPointGadget;
 use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+use starkyx::chip::ec::edwards::ed25519::point::CompressedPointRegister;
+use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
+use starkyx::chip::ec::scalar::ECScalarRegister;
+use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::field::register::FieldRegister;
+use starkyx::machine::ec::builder::EllipticCurveBuilder;
+use starkyx::machine::emulated::builder::EmulatedBuilder;
+
+use self::air_parameters::Ed25519AirParameters;
+use crate::frontend::ecc::curta::accelerator::EcAccelerator;
+use crate::frontend::ecc::curta::{CurtaCurve, EcOpCurve};
+use crate::prelude::{CircuitBuilder, PlonkParameters};
 
-pub mod accelerator;
 pub mod air_parameters;
-pub mod builder;
-pub mod proof_hint;
-pub mod request;
-pub mod result_hint;
-pub mod stark;
 
-type Curve = Ed25519;
+/// The Ed25519 accelerator, now the generic [`EcAccelerator`] of the curve.
+#[deprecated(note = "use `EcAccelerator<Ed25519>` instead")]
+pub type EcOpAccelerator = EcAccelerator<Ed25519>;
+
+/// The Ed25519 requests, which moved to [`crate::frontend::ecc::curta::request`].
+pub mod request {
+    pub use crate::frontend::ecc::curta::request::{EcOpRequest, EcOpRequestType, EcOpResponse};
+}
+
+/// The Ed25519 proof hint, now the generic proof hint of the curve.
+pub mod proof_hint {
+    use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+
+    #[deprecated(note = "use `ecc::curta::proof_hint::EcOpProofHint<Ed25519>` instead")]
+    pub type EcOpProofHint = crate::frontend::ecc::curta::proof_hint::EcOpProofHint<Ed25519>;
+}
+
+/// The Ed25519 result hint, now the generic result hint of the curve.
+pub mod result_hint {
+    use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+
+    #[deprecated(note = "use `ecc::curta::result_hint::EcOpResultHint<Ed25519>` instead")]
+    pub type EcOpResultHint = crate::frontend::ecc::curta::result_hint::EcOpResultHint<Ed25519>;
+}
+
+/// The Ed25519 stark, now the generic stark of the curve.
+pub mod stark {
+    use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+
+    use crate::frontend::ecc::curta::stark::{EcCurtaOp, EcCurtaOpValue, EcOpVariable, EcStark};
+
+    #[deprecated(note = "use `EcStark<L, Ed25519, D>` instead")]
+    pub type Ed25519Stark<L, const D: usize> = EcStark<L, Ed25519, D>;
+
+    #[deprecated(note = "use `EcCurtaOp<Ed25519>` instead")]
+    pub type Ed25519CurtaOp = EcCurtaOp<Ed25519>;
+
+    #[deprecated(note = "use `EcOpVariable<Ed25519>` instead")]
+    pub type Ed25519OpVariable = EcOpVariable<Ed25519>;
+
+    #[deprecated(note = "use `EcCurtaOpValue<Ed25519>` instead")]
+    pub type Ed25519CurtaOpValue = EcCurtaOpValue<Ed25519>;
+}
+
+impl EcOpCurve for Ed25519 {
+    type DecompressOps = ();
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CurtaCurve<L, D> for Ed25519 {
+    type AirParameters = Ed25519AirParameters<L, D>;
+
+    const NAME: &'static str = "Ed25519";
+
+    fn accelerator(builder: &mut CircuitBuilder<L, D>) -> &mut Option<EcAccelerator<Self>> {
+        &mut builder.ec_25519_ops_accelerator
+    }
+
+    fn air_add(
+        builder: &mut AirBuilder<Self::AirParameters>,
+        a: AffinePointRegister<Self>,
+        b: AffinePointRegister<Self>,
+    ) -> AffinePointRegister<Self> {
+        builder.add(a, b)
+    }
+
+    fn air_scalar_mul_batch(
+        builder: &mut EmulatedBuilder<Self::AirParameters>,
+        points: &[AffinePointRegister<Self>],
+        scalars: &[ECScalarRegister<Self>],
+        results: &[AffinePointRegister<Self>],
+    ) {
+        builder.scalar_mul_batch(points, scalars, results);
+    }
+
+    fn air_assert_valid(
+        _: (),
+        builder: &mut AirBuilder<Self::AirParameters>,
+        point: &AffinePointRegister<Self>,
+    ) {
+        builder.ed_assert_valid(point);
+    }
+
+    fn air_decompress(
+        _: (),
+        builder: &mut AirBuilder<Self::AirParameters>,
+    ) -> (
+        CompressedPointRegister,
+        AffinePointRegister<Self>,
+        FieldRegister<<Self as EllipticCurveParameters>::BaseField>,
+    ) {
+        let compressed_point = builder.alloc_public_ec_compressed_point();
+        let (result, pos_square_root) = builder.ed25519_decompress(&compressed_point);
+        (compressed_point, result, pos_square_root)
+    }
+
+    fn decompress(_: (), compressed_point: &CompressedEdwardsY) -> (AffinePoint<Self>, BigUint) {
+        decompress(compressed_point)
+    }
+}
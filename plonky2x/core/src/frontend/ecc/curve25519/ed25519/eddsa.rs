@@ -14,8 +14,8 @@ use crate::frontend::curta::ec::point::{AffinePointVariable, CompressedEdwardsYV
 use crate::frontend::uint::num::biguint::biguint_from_bytes_variable;
 use crate::frontend::uint::uint512::U512Variable;
 use crate::prelude::{
-    ArrayVariable, BoolVariable, BytesVariable, CircuitBuilder, CircuitVariable, PlonkParameters,
-    U256Variable, U32Variable, Variable,
+    ArrayVariable, BoolVariable, ByteVariable, BytesVariable, CircuitBuilder, CircuitVariable,
+    PlonkParameters, U256Variable, U32Variable, Variable,
 };
 
 #[derive(Clone, Debug, CircuitVariable)]
@@ -65,20 +65,22 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         (pub_key, signature, message, dummy_msg_byte_length)
     }
 
-    /// This function will verify a set of eddsa signatures.  It also contains a BoolVariable array
-    /// bitmask ("is_active") that will specify which signatures should be verified.  If
-    /// message_byte_lengths is None, then all the messages should have the length of
-    /// MAX_MSG_LENGTH_BYTES.
-    pub fn curta_eddsa_verify_sigs_conditional<
-        const MAX_MSG_LENGTH_BYTES: usize,
-        const NUM_SIGS: usize,
-    >(
+    /// Replaces the inputs of the inactive signatures with a dummy signature, which is valid for
+    /// pure Ed25519. If message_byte_lengths is None, then the active messages should have the
+    /// length of MAX_MSG_LENGTH_BYTES.
+    #[allow(clippy::type_complexity)]
+    fn eddsa_select_active<const MAX_MSG_LENGTH_BYTES: usize, const NUM_SIGS: usize>(
         &mut self,
-        is_active: ArrayVariable<BoolVariable, NUM_SIGS>,
+        is_active: &ArrayVariable<BoolVariable, NUM_SIGS>,
         message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
         messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
         signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
         pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) -> (
+        ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        ArrayVariable<U32Variable, NUM_SIGS>,
+        ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
     ) {
         assert!(NUM_SIGS > 0);
         assert!(is_active.len() == NUM_SIGS);
@@ -114,16 +116,38 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             pub_key_vec.push(self.select(is_active[i], pubkeys[i].clone(), dummy_pub_key.clone()));
         }
 
-        let msg_len_vec = ArrayVariable::<U32Variable, NUM_SIGS>::from(
-            msg_len_vec.into_iter().collect::<Vec<_>>(),
+        (
+            ArrayVariable::<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>::from(msg_vec),
+            ArrayVariable::<U32Variable, NUM_SIGS>::from(msg_len_vec),
+            ArrayVariable::<EDDSASignatureVariable, NUM_SIGS>::from(sig_vec),
+            ArrayVariable::<CompressedEdwardsYVariable, NUM_SIGS>::from(pub_key_vec),
+        )
+    }
+
+    /// This function will verify a set of eddsa signatures.  It also contains a BoolVariable array
+    /// bitmask ("is_active") that will specify which signatures should be verified.  If
+    /// message_byte_lengths is None, then all the messages should have the length of
+    /// MAX_MSG_LENGTH_BYTES.
+    pub fn curta_eddsa_verify_sigs_conditional<
+        const MAX_MSG_LENGTH_BYTES: usize,
+        const NUM_SIGS: usize,
+    >(
+        &mut self,
+        is_active: ArrayVariable<BoolVariable, NUM_SIGS>,
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) {
+        let (msg_array, msg_len_array, sig_array, pub_key_array) = self.eddsa_select_active(
+            &is_active,
+            message_byte_lengths,
+            messages,
+            signatures,
+            pubkeys,
         );
-        let msg_array =
-            ArrayVariable::<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>::from(msg_vec);
-        let sig_array = ArrayVariable::<EDDSASignatureVariable, NUM_SIGS>::from(sig_vec);
-        let pub_key_array =
-            ArrayVariable::<CompressedEdwardsYVariable, NUM_SIGS>::from(pub_key_vec);
 
-        self.curta_eddsa_verify_sigs(msg_array, Some(msg_len_vec), sig_array, pub_key_array);
+        self.curta_eddsa_verify_sigs(msg_array, Some(msg_len_array), sig_array, pub_key_array);
     }
 
     /// This function will verify a set of eddsa signatures. If message_byte_lengths is None, then
@@ -138,6 +162,210 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
         signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
         pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) {
+        self.curta_eddsa_verify_sigs_with_dom(
+            &[],
+            None,
+            messages,
+            message_byte_lengths,
+            signatures,
+            pubkeys,
+        );
+    }
+
+    /// Verifies a set of Ed25519ctx signatures with the given context, which must be between 1
+    /// and 255 bytes long. If message_byte_lengths is None, then all the messages should have the
+    /// length of MAX_MSG_LENGTH_BYTES.
+    pub fn curta_eddsa_ctx_verify_sigs<const MAX_MSG_LENGTH_BYTES: usize, const NUM_SIGS: usize>(
+        &mut self,
+        context: &[u8],
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+        signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) {
+        assert!(
+            !context.is_empty(),
+            "Ed25519ctx requires a non-empty context"
+        );
+        self.curta_eddsa_verify_sigs_with_dom(
+            &dom2(0, context),
+            None,
+            messages,
+            message_byte_lengths,
+            signatures,
+            pubkeys,
+        );
+    }
+
+    /// Verifies a set of Ed25519ctx signatures, where the bitmask "is_active" specifies which
+    /// signatures should be verified. See `curta_eddsa_ctx_verify_sigs`.
+    pub fn curta_eddsa_ctx_verify_sigs_conditional<
+        const MAX_MSG_LENGTH_BYTES: usize,
+        const NUM_SIGS: usize,
+    >(
+        &mut self,
+        is_active: ArrayVariable<BoolVariable, NUM_SIGS>,
+        context: &[u8],
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) {
+        assert!(
+            !context.is_empty(),
+            "Ed25519ctx requires a non-empty context"
+        );
+        // The dummy signature is only valid for pure Ed25519, so the verification equation of
+        // the inactive signatures is skipped.
+        let (msg_array, msg_len_array, sig_array, pub_key_array) = self.eddsa_select_active(
+            &is_active,
+            message_byte_lengths,
+            messages,
+            signatures,
+            pubkeys,
+        );
+        self.curta_eddsa_verify_sigs_with_dom(
+            &dom2(0, context),
+            Some(is_active),
+            msg_array,
+            Some(msg_len_array),
+            sig_array,
+            pub_key_array,
+        );
+    }
+
+    /// Verifies a set of Ed25519ph signatures with the given context of at most 255 bytes, where
+    /// the messages are prehashed with SHA-512 in the circuit. If message_byte_lengths is None,
+    /// then all the messages should have the length of MAX_MSG_LENGTH_BYTES.
+    pub fn curta_eddsa_ph_verify_sigs<const MAX_MSG_LENGTH_BYTES: usize, const NUM_SIGS: usize>(
+        &mut self,
+        context: &[u8],
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+        signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) {
+        let prehashes = self.eddsa_prehash(messages, message_byte_lengths);
+        self.curta_eddsa_verify_sigs_with_dom(
+            &dom2(1, context),
+            None,
+            prehashes,
+            None,
+            signatures,
+            pubkeys,
+        );
+    }
+
+    /// Verifies a set of Ed25519ph signatures, where the bitmask "is_active" specifies which
+    /// signatures should be verified. See `curta_eddsa_ph_verify_sigs`.
+    pub fn curta_eddsa_ph_verify_sigs_conditional<
+        const MAX_MSG_LENGTH_BYTES: usize,
+        const NUM_SIGS: usize,
+    >(
+        &mut self,
+        is_active: ArrayVariable<BoolVariable, NUM_SIGS>,
+        context: &[u8],
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
+    ) {
+        // The dummy signature is only valid for pure Ed25519, so the verification equation of
+        // the inactive signatures is skipped.
+        let (msg_array, msg_len_array, sig_array, pub_key_array) = self.eddsa_select_active(
+            &is_active,
+            message_byte_lengths,
+            messages,
+            signatures,
+            pubkeys,
+        );
+        let prehashes = self.eddsa_prehash(msg_array, Some(msg_len_array));
+        self.curta_eddsa_verify_sigs_with_dom(
+            &dom2(1, context),
+            Some(is_active),
+            prehashes,
+            None,
+            sig_array,
+            pub_key_array,
+        );
+    }
+
+    /// Computes the SHA-512 prehashes of the messages for Ed25519ph.
+    fn eddsa_prehash<const MAX_MSG_LENGTH_BYTES: usize, const NUM_SIGS: usize>(
+        &mut self,
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+    ) -> ArrayVariable<BytesVariable<64>, NUM_SIGS> {
+        let prehashes = (0..NUM_SIGS)
+            .map(|i| match message_byte_lengths {
+                Some(ref msg_lens) => self.curta_sha512_variable(&messages[i].0, msg_lens[i]),
+                None => self.curta_sha512(&messages[i].0),
+            })
+            .collect::<Vec<_>>();
+        ArrayVariable::<BytesVariable<64>, NUM_SIGS>::from(prehashes)
+    }
+
+    /// Computes the challenge `SHA512(dom || R || A || M)` modulo the order of the base point. If
+    /// message_byte_length is None, then the message should have the length of
+    /// MAX_MSG_LENGTH_BYTES.
+    fn eddsa_challenge<const MAX_MSG_LENGTH_BYTES: usize>(
+        &mut self,
+        dom: &[u8],
+        message: BytesVariable<MAX_MSG_LENGTH_BYTES>,
+        message_byte_length: Option<U32Variable>,
+        signature: &EDDSASignatureVariable,
+        pubkey: &CompressedEdwardsYVariable,
+    ) -> U256Variable {
+        let scalar_modulus_value =
+            U512::from_little_endian(&Ed25519ScalarField::modulus().to_bytes_le());
+        let scalar_modulus = self.constant::<U512Variable>(scalar_modulus_value);
+
+        // Create a new BytesVariable that will contain the message to be hashed.
+        // The hashed message is a concatenation of dom, sigR, pk, and msg.
+        let mut message_bytes = dom
+            .iter()
+            .map(|b| self.constant::<ByteVariable>(*b))
+            .collect::<Vec<_>>();
+        message_bytes.extend(signature.r.0.as_bytes());
+        message_bytes.extend(pubkey.0.as_bytes());
+        message_bytes.extend(message.0);
+
+        let digest: BytesVariable<64>;
+        if let Some(msg_len) = message_byte_length {
+            let prefix_len = U32Variable::constant(self, 64 + dom.len() as u32);
+            let message_to_hash_len = self.add(msg_len, prefix_len);
+            digest = self.curta_sha512_variable(&message_bytes, message_to_hash_len);
+        } else {
+            digest = self.curta_sha512(&message_bytes);
+        }
+
+        let h_limbs = biguint_from_bytes_variable(self, digest)
+            .limbs
+            .into_iter()
+            .map(|x| x.target)
+            .collect::<Vec<_>>();
+        let h_int = U512Variable::from_targets(&h_limbs);
+        let h_scalar_512_limbs = self.rem(h_int, scalar_modulus).limbs;
+        U256Variable {
+            limbs: array![i => h_scalar_512_limbs[i]; 8],
+        }
+    }
+
+    /// Verifies a set of eddsa signatures, where the hashed message of each signature is prefixed
+    /// by `dom`. If is_active is Some, the verification equation is only checked for the active
+    /// signatures, while the inputs of the inactive ones must still be valid.
+    fn curta_eddsa_verify_sigs_with_dom<
+        const MAX_MSG_LENGTH_BYTES: usize,
+        const NUM_SIGS: usize,
+    >(
+        &mut self,
+        dom: &[u8],
+        is_active: Option<ArrayVariable<BoolVariable, NUM_SIGS>>,
+        messages: ArrayVariable<BytesVariable<MAX_MSG_LENGTH_BYTES>, NUM_SIGS>,
+        message_byte_lengths: Option<ArrayVariable<U32Variable, NUM_SIGS>>,
+        signatures: ArrayVariable<EDDSASignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>,
     ) {
         assert!(NUM_SIGS > 0);
         assert!(messages.len() == NUM_SIGS);
@@ -151,40 +379,18 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         let generator_affine = AffinePoint::new(generator_x, generator_y);
         let generator_var = AffinePointVariable::constant(self, generator_affine);
 
-        let scalar_modulus_value =
-            U512::from_little_endian(&Ed25519ScalarField::modulus().to_bytes_le());
-        let scalar_modulus = self.constant::<U512Variable>(scalar_modulus_value);
         let scalar_mod_256_value =
             U256::from_little_endian(&Ed25519ScalarField::modulus().to_bytes_le());
         let scalar_mod_256 = self.constant::<U256Variable>(scalar_mod_256_value);
 
         for i in 0..NUM_SIGS {
-            // Create a new BytesVariable that will contain the message to be hashed.
-            // The hashed message is a concatenation of sigR, pk, and msg.
-            let mut message_bytes = Vec::new();
-            message_bytes.extend(signatures[i].r.0.as_bytes());
-            message_bytes.extend(pubkeys[i].0.as_bytes());
-            message_bytes.extend(messages[i].0);
-
-            let digest: BytesVariable<64>;
-            if let Some(ref msg_lens) = message_byte_lengths {
-                let const_64 = U32Variable::constant(self, 64);
-                let message_to_hash_len = self.add(msg_lens[i], const_64);
-                digest = self.curta_sha512_variable(&message_bytes, message_to_hash_len);
-            } else {
-                digest = self.curta_sha512(&message_bytes);
-            }
-
-            let h_limbs = biguint_from_bytes_variable(self, digest)
-                .limbs
-                .into_iter()
-                .map(|x| x.target)
-                .collect::<Vec<_>>();
-            let h_int = U512Variable::from_targets(&h_limbs);
-            let h_scalar_512_limbs = self.rem(h_int, scalar_modulus).limbs;
-            let h_scalar = U256Variable {
-                limbs: array![i => h_scalar_512_limbs[i]; 8],
-            };
+            let h_scalar = self.eddsa_challenge(
+                dom,
+                messages[i],
+                message_byte_lengths.as_ref().map(|msg_lens| msg_lens[i]),
+                &signatures[i],
+                &pubkeys[i],
+            );
 
             let s = signatures[i].s;
             // Assert that s is less than the scalar modulus.
@@ -199,23 +405,46 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             self.curta_25519_is_valid(sigr_affine.clone());
             p2 = self.curta_25519_add(sigr_affine, p2);
 
-            self.assert_is_equal(p1, p2);
+            match is_active {
+                Some(ref is_active) => {
+                    // For an inactive signature, compare p2 with itself.
+                    let p1 = self.select(is_active[i], p1, p2.clone());
+                    self.assert_is_equal(p1, p2);
+                }
+                None => self.assert_is_equal(p1, p2),
+            }
         }
     }
 }
 
+/// The domain separation prefix of RFC 8032 for Ed25519ctx (`phflag = 0`) and Ed25519ph
+/// (`phflag = 1`).
+fn dom2(phflag: u8, context: &[u8]) -> Vec<u8> {
+    assert!(
+        context.len() <= 255,
+        "the context is at most 255 bytes long"
+    );
+    let mut dom = b"SigEd25519 no Ed25519 collisions".to_vec();
+    dom.push(phflag);
+    dom.push(context.len() as u8);
+    dom.extend(context);
+    dom
+}
+
 #[cfg(test)]
 mod tests {
-    use curve25519_dalek::edwards::CompressedEdwardsY;
-    use ed25519_dalek::{Signer, SigningKey};
+    use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+    use curve25519_dalek::scalar::{clamp_integer, Scalar};
+    use ed25519_dalek::{Signature, Signer, SigningKey};
     use ethers::types::U256;
     use plonky2::field::goldilocks_field::GoldilocksField;
     use rand::rngs::OsRng;
     use rand::Rng;
+    use sha2::{Digest, Sha512};
 
     use crate::frontend::curta::ec::point::CompressedEdwardsYVariable;
     use crate::frontend::ecc::curve25519::ed25519::eddsa::{
-        EDDSASignatureVariable, EDDSASignatureVariableValue,
+        dom2, EDDSASignatureVariable, EDDSASignatureVariableValue,
     };
     use crate::prelude::{ArrayVariable, BoolVariable, BytesVariable, DefaultBuilder, U32Variable};
     use crate::utils;
@@ -474,4 +703,149 @@ mod tests {
             false,
         );
     }
+
+    /// Signs a message as in RFC 8032, with the hashed messages prefixed by `dom`.
+    fn sign_with_dom(
+        signing_key: &SigningKey,
+        dom: &[u8],
+        message: &[u8],
+    ) -> EDDSASignatureVariableValue<GoldilocksField> {
+        let h = Sha512::digest(signing_key.to_bytes());
+        let a = Scalar::from_bytes_mod_order(clamp_integer(h[..32].try_into().unwrap()));
+        let r_digest = Sha512::new()
+            .chain_update(dom)
+            .chain_update(&h[32..])
+            .chain_update(message)
+            .finalize();
+        let r = Scalar::from_bytes_mod_order_wide(r_digest.as_slice().try_into().unwrap());
+        let r_point = EdwardsPoint::mul_base(&r).compress();
+        let k_digest = Sha512::new()
+            .chain_update(dom)
+            .chain_update(r_point.as_bytes())
+            .chain_update(signing_key.verifying_key().as_bytes())
+            .chain_update(message)
+            .finalize();
+        let k = Scalar::from_bytes_mod_order_wide(k_digest.as_slice().try_into().unwrap());
+        let s = r + k * a;
+        EDDSASignatureVariableValue {
+            r: r_point,
+            s: U256::from_little_endian(s.as_bytes()),
+        }
+    }
+
+    fn test_curta_eddsa_domain_verify_sigs_conditional(prehash: bool) {
+        utils::setup_logger();
+
+        const CONTEXT: &[u8] = b"plonky2x";
+        let mut builder = DefaultBuilder::new();
+
+        let is_active = builder.read::<ArrayVariable<BoolVariable, NUM_SIGS>>();
+        let pkeys = builder.read::<ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>>();
+        let signatures = builder.read::<ArrayVariable<EDDSASignatureVariable, NUM_SIGS>>();
+        let messages = builder.read::<ArrayVariable<BytesVariable<MAX_MSG_LEN_BYTES>, NUM_SIGS>>();
+        let message_lens = builder.read::<ArrayVariable<U32Variable, NUM_SIGS>>();
+        if prehash {
+            builder.curta_eddsa_ph_verify_sigs_conditional(
+                is_active,
+                CONTEXT,
+                Some(message_lens),
+                messages,
+                signatures,
+                pkeys,
+            );
+        } else {
+            builder.curta_eddsa_ctx_verify_sigs_conditional(
+                is_active,
+                CONTEXT,
+                Some(message_lens),
+                messages,
+                signatures,
+                pkeys,
+            );
+        }
+
+        let circuit = builder.build();
+
+        // The second signature is inactive and invalid.
+        let test_is_active = vec![true, false, true];
+        let mut test_messages: Vec<[u8; MAX_MSG_LEN_BYTES]> = Vec::new();
+        let mut test_message_lens = Vec::new();
+        let mut test_pub_keys = Vec::new();
+        let mut test_signatures = Vec::new();
+
+        let mut csprng = OsRng;
+        for i in 0..NUM_SIGS {
+            let msg_len = rand::thread_rng().gen_range(1..MAX_MSG_LEN_BYTES);
+            let mut test_message = (0..msg_len)
+                .map(|_| rand::thread_rng().gen_range(0..255))
+                .collect::<Vec<u8>>();
+
+            let test_signing_key = SigningKey::generate(&mut csprng);
+            let test_signature = if prehash {
+                let prehashed = Sha512::new().chain_update(&test_message);
+                let signature = sign_with_dom(
+                    &test_signing_key,
+                    &dom2(1, CONTEXT),
+                    &prehashed.clone().finalize(),
+                );
+                let mut s_bytes = [0u8; 32];
+                signature.s.to_little_endian(&mut s_bytes);
+                test_signing_key
+                    .verifying_key()
+                    .verify_prehashed(
+                        prehashed,
+                        Some(CONTEXT),
+                        &Signature::from_components(signature.r.to_bytes(), s_bytes),
+                    )
+                    .unwrap();
+                signature
+            } else {
+                sign_with_dom(&test_signing_key, &dom2(0, CONTEXT), &test_message)
+            };
+
+            if !test_is_active[i] {
+                test_message[0] = test_message[0].wrapping_add(1);
+            }
+            test_message.resize(MAX_MSG_LEN_BYTES, 0);
+            test_messages.push(test_message.try_into().unwrap());
+            test_message_lens.push(msg_len as u32);
+            test_pub_keys.push(CompressedEdwardsY(
+                test_signing_key.verifying_key().to_bytes(),
+            ));
+            test_signatures.push(test_signature);
+        }
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BoolVariable, NUM_SIGS>>(test_is_active);
+        input.write::<ArrayVariable<CompressedEdwardsYVariable, NUM_SIGS>>(test_pub_keys);
+        input.write::<ArrayVariable<EDDSASignatureVariable, NUM_SIGS>>(test_signatures);
+        input.write::<ArrayVariable<BytesVariable<MAX_MSG_LEN_BYTES>, NUM_SIGS>>(test_messages);
+        input.write::<ArrayVariable<U32Variable, NUM_SIGS>>(test_message_lens);
+
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    fn test_eddsa_sign_with_empty_dom() {
+        // Pure Ed25519 signatures have an empty domain separation prefix.
+        let test_signing_key = SigningKey::generate(&mut OsRng);
+        let test_message = b"hello world";
+        let signature = sign_with_dom(&test_signing_key, &[], test_message);
+        let expected = test_signing_key.sign(test_message);
+        assert_eq!(signature.r.to_bytes(), *expected.r_bytes());
+        assert_eq!(signature.s, U256::from_little_endian(expected.s_bytes()));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_curta_eddsa_ctx_verify_sigs_conditional() {
+        test_curta_eddsa_domain_verify_sigs_conditional(false);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_curta_eddsa_ph_verify_sigs_conditional() {
+        test_curta_eddsa_domain_verify_sigs_conditional(true);
+    }
 }
@@ -1,3 +1,4 @@
 pub mod curta;
 pub mod ec_ops;
 pub mod ed25519;
+pub mod msm;
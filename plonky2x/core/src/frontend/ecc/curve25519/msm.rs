@@ -0,0 +1,137 @@
+use num::BigUint;
+use plonky2::iop::target::BoolTarget;
+use starkyx::chip::ec::edwards::ed25519::params::Ed25519;
+use starkyx::chip::ec::point::AffinePoint;
+
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::prelude::{CircuitBuilder, PlonkParameters, U256Variable, Variable};
+
+/// The number of bits of the windows of the scalars in the MSM.
+const MSM_WINDOW_BITS: usize = 4;
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// The identity of Ed25519, i.e. the point `(0, 1)`.
+    pub fn curta_25519_identity(&mut self) -> AffinePointVariable<Ed25519> {
+        AffinePointVariable::constant(
+            self,
+            AffinePoint::new(BigUint::from(0u32), BigUint::from(1u32)),
+        )
+    }
+
+    /// Splits a scalar into its little-endian windows of `MSM_WINDOW_BITS` bits.
+    fn msm_windows(&mut self, scalar: U256Variable) -> Vec<Variable> {
+        scalar
+            .limbs
+            .iter()
+            .flat_map(|limb| self.api.split_le(limb.variable.0, 32))
+            .collect::<Vec<BoolTarget>>()
+            .chunks_exact(MSM_WINDOW_BITS)
+            .map(|bits| Variable(self.api.le_sum(bits.iter())))
+            .collect()
+    }
+
+    /// Computes the multi-scalar multiplication `sum(scalars[i] * points[i])` on Ed25519 with the
+    /// Straus method.
+    ///
+    /// For windows of `w` bits, each point has a table of its first `2^w` multiples, and the
+    /// accumulator is doubled `w` times per window before the table entry of each scalar is
+    /// added. The additions and doublings are proven by the Ed25519 accelerator, whose addition
+    /// is complete, so no scalar multiplication is queued: for `n` points, this takes about
+    /// `(2^w + 256 / w) n + 256` additions.
+    pub fn curta_25519_msm(
+        &mut self,
+        scalars: &[U256Variable],
+        points: &[AffinePointVariable<Ed25519>],
+    ) -> AffinePointVariable<Ed25519> {
+        assert_eq!(scalars.len(), points.len());
+        assert!(!points.is_empty());
+
+        let identity = self.curta_25519_identity();
+        let tables = points
+            .iter()
+            .map(|point| {
+                let mut table = vec![identity.clone(), point.clone()];
+                for _ in 2..(1 << MSM_WINDOW_BITS) {
+                    let last = table.last().unwrap().clone();
+                    table.push(self.curta_25519_add(last, point.clone()));
+                }
+                table
+            })
+            .collect::<Vec<_>>();
+        let windows = scalars
+            .iter()
+            .map(|scalar| self.msm_windows(*scalar))
+            .collect::<Vec<_>>();
+
+        let num_windows = windows[0].len();
+        let mut acc = identity;
+        for w in (0..num_windows).rev() {
+            if w != num_windows - 1 {
+                for _ in 0..MSM_WINDOW_BITS {
+                    acc = self.curta_25519_add(acc.clone(), acc);
+                }
+            }
+            for (table, scalar_windows) in tables.iter().zip(windows.iter()) {
+                let entry = self.select_array_random_gate(table, scalar_windows[w]);
+                acc = self.curta_25519_add(acc, entry);
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::edwards::EdwardsPoint;
+    use curve25519_dalek::scalar::Scalar;
+    use ethers::types::U256;
+    use rand::Rng;
+    use starkyx::chip::ec::edwards::ed25519::decompress::decompress;
+
+    use super::*;
+    use crate::prelude::*;
+    use crate::utils;
+
+    const NUM_POINTS: usize = 3;
+
+    fn affine(point: &EdwardsPoint) -> AffinePoint<Ed25519> {
+        decompress(&point.compress()).0
+    }
+
+    fn random_scalar() -> Scalar {
+        Scalar::from_bytes_mod_order(rand::thread_rng().gen())
+    }
+
+    #[test]
+    fn test_curta_25519_msm() {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        let scalars = builder.read::<ArrayVariable<U256Variable, NUM_POINTS>>();
+        let points = builder.read::<ArrayVariable<AffinePointVariable<Ed25519>, NUM_POINTS>>();
+        let result = builder.curta_25519_msm(scalars.as_slice(), points.as_slice());
+        builder.write(result);
+        let circuit = builder.mock_build();
+
+        let mut scalars = Vec::new();
+        let mut points = Vec::new();
+        let mut expected = EdwardsPoint::default();
+        for _ in 0..NUM_POINTS {
+            let scalar = random_scalar();
+            let point = EdwardsPoint::mul_base(&random_scalar());
+            expected += scalar * point;
+            scalars.push(U256::from_little_endian(scalar.as_bytes()));
+            points.push(affine(&point));
+        }
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<U256Variable, NUM_POINTS>>(scalars);
+        input.write::<ArrayVariable<AffinePointVariable<Ed25519>, NUM_POINTS>>(points);
+        let (_witness, mut output) = circuit.mock_prove(&input);
+
+        let result = output.read::<AffinePointVariable<Ed25519>>();
+        let expected = affine(&expected);
+        assert_eq!(result.x, expected.x);
+        assert_eq!(result.y, expected.y);
+    }
+}
@@ -1 +1,8 @@
+pub mod babyjubjub;
+pub mod bls12_381;
+pub mod bn254;
+pub mod curta;
 pub mod curve25519;
+pub mod nonnative;
+pub mod secp256k1;
+pub mod secp256r1;
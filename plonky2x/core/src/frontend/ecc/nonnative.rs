@@ -0,0 +1,39 @@
+use num::BigUint;
+
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::prelude::{CircuitBuilder, PlonkParameters};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns `(sum(positive) - sum(negative)) mod modulus`, where every negative term is assumed
+    /// to be less than `bound`.
+    ///
+    /// This is the lazy reduction shared by the emulated base fields of the pairing-friendly
+    /// curves: sums of unreduced products only pay for a single `rem_biguint`.
+    pub(crate) fn biguint_signed_sum_rem(
+        &mut self,
+        positive: &[BigUintTarget],
+        negative: &[BigUintTarget],
+        bound: &BigUint,
+        modulus: &BigUint,
+    ) -> BigUintTarget {
+        let mut sum = self.api.zero_biguint();
+        for term in positive.iter() {
+            sum = self.api.add_biguint(&sum, term);
+        }
+        if !negative.is_empty() {
+            // Add a multiple of the modulus which is larger than the sum of the negative terms, so
+            // that the subtraction does not underflow.
+            let offset = bound * negative.len();
+            let offset = ((offset + modulus - 1u32) / modulus) * modulus;
+            let offset = self.api.constant_biguint(&offset);
+            sum = self.api.add_biguint(&sum, &offset);
+            let mut negative_sum = self.api.zero_biguint();
+            for term in negative.iter() {
+                negative_sum = self.api.add_biguint(&negative_sum, term);
+            }
+            sum = self.api.sub_biguint(&sum, &negative_sum);
+        }
+        let modulus = self.api.constant_biguint(modulus);
+        self.api.rem_biguint(&sum, &modulus)
+    }
+}
@@ -0,0 +1,24 @@
+use starkyx::chip::ec::weierstrass::secp256k1::Secp256K1;
+
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::prelude::{CircuitBuilder, PlonkParameters, U256Variable};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Add two distinct points on secp256k1, neither of which is the negation of the other.
+    pub fn curta_secp256k1_add(
+        &mut self,
+        a: AffinePointVariable<Secp256K1>,
+        b: AffinePointVariable<Secp256K1>,
+    ) -> AffinePointVariable<Secp256K1> {
+        self.curta_ec_add(a, b)
+    }
+
+    /// Multiply a point on secp256k1 by a scalar.
+    pub fn curta_secp256k1_scalar_mul(
+        &mut self,
+        scalar: U256Variable,
+        point: AffinePointVariable<Secp256K1>,
+    ) -> AffinePointVariable<Secp256K1> {
+        self.curta_ec_scalar_mul(scalar, point)
+    }
+}
@@ -0,0 +1,586 @@
+use core::fmt::Debug;
+
+use ethers::core::k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use ethers::types::{Address, H256, U256};
+use lazy_static::lazy_static;
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::ec::point::AffinePoint;
+use starkyx::chip::ec::weierstrass::secp256k1::Secp256K1;
+use starkyx::chip::ec::EllipticCurve;
+use starkyx::chip::field::parameters::FieldParameters;
+
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::frontend::curta::field::variable::FieldVariable;
+use crate::frontend::eth::vars::AddressVariable;
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::vars::EvmVariable;
+use crate::prelude::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitBuilder, CircuitVariable,
+    Field, PlonkParameters, U256Variable, U32Variable, ValueStream, Variable, VariableStream,
+};
+
+lazy_static! {
+    /// The order of the secp256k1 group.
+    pub static ref SECP256K1_ORDER: BigUint = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16
+    )
+    .unwrap();
+    /// The modulus of the secp256k1 base field.
+    pub static ref SECP256K1_MODULUS: BigUint = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16
+    )
+    .unwrap();
+    pub(crate) static ref SECP256K1_GENERATOR_X: BigUint = BigUint::parse_bytes(
+        b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+        16
+    )
+    .unwrap();
+    pub(crate) static ref SECP256K1_GENERATOR_Y: BigUint = BigUint::parse_bytes(
+        b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+        16
+    )
+    .unwrap();
+}
+
+#[derive(Clone, Debug, CircuitVariable)]
+pub struct ECDSASignatureVariable {
+    pub r: U256Variable,
+    pub s: U256Variable,
+}
+
+/// Computes the inverse of a non-zero scalar modulo the order of secp256k1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256k1ScalarInverseHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Secp256k1ScalarInverseHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let mut bytes = [0u8; 32];
+        input_stream
+            .read_value::<U256Variable>()
+            .to_little_endian(&mut bytes);
+        let x = BigUint::from_bytes_le(&bytes);
+        let order = &*SECP256K1_ORDER;
+        let inverse = x.modpow(&(order - 2u32), order);
+        output_stream.write_value::<U256Variable>(U256::from_little_endian(&inverse.to_bytes_le()));
+    }
+}
+
+/// Recovers the public key which produced an Ethereum signature over a message hash.
+///
+/// Writes whether the signature is valid, the public key, which is the generator for an invalid
+/// signature, and a square root of `-(r^3 + 7)` modulo `p`, which exists when `r` is not the x
+/// coordinate of a point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256k1RecoverHint;
+
+impl Secp256k1RecoverHint {
+    /// Recovers the public key with the semantics of the `ecrecover` precompile, which accepts
+    /// signatures with a high `s`.
+    fn recover(msg_hash: H256, v: u8, r: U256, s: U256) -> Option<AffinePoint<Secp256K1>> {
+        if v != 27 && v != 28 {
+            return None;
+        }
+        let mut signature_bytes = [0u8; 64];
+        r.to_big_endian(&mut signature_bytes[..32]);
+        s.to_big_endian(&mut signature_bytes[32..]);
+        let mut signature = Signature::from_slice(&signature_bytes).ok()?;
+
+        // Negating `s` negates the nonce point, which flips the parity of its y coordinate.
+        let mut is_y_odd = v == 28;
+        if let Some(normalized) = signature.normalize_s() {
+            signature = normalized;
+            is_y_odd = !is_y_odd;
+        }
+        let recovery_id = RecoveryId::new(is_y_odd, false);
+        let pubkey =
+            VerifyingKey::recover_from_prehash(msg_hash.as_bytes(), &signature, recovery_id)
+                .ok()?
+                .to_encoded_point(false);
+
+        let x = BigUint::from_bytes_be(pubkey.x()?);
+        let y = BigUint::from_bytes_be(pubkey.y()?);
+        Some(AffinePoint::new(x, y))
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Secp256k1RecoverHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let msg_hash = input_stream.read_value::<Bytes32Variable>();
+        let v = input_stream.read_value::<ByteVariable>();
+        let r = input_stream.read_value::<U256Variable>();
+        let s = input_stream.read_value::<U256Variable>();
+
+        let pubkey = Self::recover(msg_hash, v, r, s);
+        let is_valid = pubkey.is_some();
+        let pubkey = pubkey.unwrap_or_else(|| {
+            AffinePoint::new(SECP256K1_GENERATOR_X.clone(), SECP256K1_GENERATOR_Y.clone())
+        });
+
+        // As p = 3 mod 4, a square root of a square `a` is `a^((p + 1) / 4)`.
+        let modulus = &*SECP256K1_MODULUS;
+        let mut r_bytes = [0u8; 32];
+        r.to_little_endian(&mut r_bytes);
+        let r = BigUint::from_bytes_le(&r_bytes);
+        let x3_plus_7 = (r.modpow(&BigUint::from(3u32), modulus) + 7u32) % modulus;
+        let minus_x3_plus_7 = (modulus - x3_plus_7) % modulus;
+        let root = minus_x3_plus_7.modpow(&((modulus + 1u32) >> 2), modulus);
+
+        output_stream.write_value::<BoolVariable>(is_valid);
+        output_stream.write_value::<AffinePointVariable<Secp256K1>>(pubkey);
+        output_stream.write_value::<U256Variable>(U256::from_little_endian(&root.to_bytes_le()));
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    pub(crate) fn u256_to_biguint_target(&mut self, x: U256Variable) -> BigUintTarget {
+        BigUintTarget {
+            limbs: x.limbs.iter().map(|limb| U32Target::from(*limb)).collect(),
+        }
+    }
+
+    /// Converts a `BigUintTarget` of at most 8 limbs to a `U256Variable`.
+    pub(crate) fn biguint_target_to_u256(&mut self, x: BigUintTarget) -> U256Variable {
+        assert!(x.limbs.len() <= 8);
+        let mut limbs = [self.constant::<U32Variable>(0); 8];
+        for (limb, x_limb) in limbs.iter_mut().zip(x.limbs) {
+            *limb = x_limb.into();
+        }
+        U256Variable { limbs }
+    }
+
+    /// Packs the 16-bit limbs of a 256-bit field element into a `U256Variable`. The limbs are
+    /// assumed to be range checked.
+    pub(crate) fn curta_field_to_u256<P: FieldParameters>(
+        &mut self,
+        x: &FieldVariable<P>,
+    ) -> U256Variable {
+        assert_eq!(P::NB_LIMBS * P::NB_BITS_PER_LIMB, 256);
+        let shift = self.constant::<Variable>(L::Field::from_canonical_u32(1 << 16));
+        let limbs = x.variables();
+        let mut u32_limbs = [self.constant::<U32Variable>(0); 8];
+        for (u32_limb, chunk) in u32_limbs.iter_mut().zip(limbs.chunks_exact(2)) {
+            let high = self.mul(chunk[1], shift);
+            let limb = self.add(chunk[0], high);
+            *u32_limb = U32Variable::from_variables_unsafe(&[limb]);
+        }
+        U256Variable { limbs: u32_limbs }
+    }
+
+    /// Returns `a * b` modulo the order of secp256k1.
+    fn secp256k1_scalar_mul_mod(&mut self, a: U256Variable, b: U256Variable) -> U256Variable {
+        let a = self.u256_to_biguint_target(a);
+        let b = self.u256_to_biguint_target(b);
+        let order = self.api.constant_biguint(&SECP256K1_ORDER);
+        let product = self.api.mul_biguint(&a, &b);
+        let product = self.api.rem_biguint(&product, &order);
+        self.biguint_target_to_u256(product)
+    }
+
+    /// Returns the inverse of `x` modulo the order of secp256k1, which constrains `x` to be
+    /// non-zero.
+    fn secp256k1_scalar_inverse(&mut self, x: U256Variable) -> U256Variable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&x);
+        let output_stream = self.hint(input_stream, Secp256k1ScalarInverseHint);
+        let inverse = output_stream.read::<U256Variable>(self);
+
+        let product = self.secp256k1_scalar_mul_mod(x, inverse);
+        let one = self.constant::<U256Variable>(U256::one());
+        self.assert_is_equal(product, one);
+        inverse
+    }
+
+    /// Returns whether `0 < x < n`, where `n` is the order of secp256k1.
+    fn secp256k1_is_valid_scalar(&mut self, x: U256Variable) -> BoolVariable {
+        let order = U256::from_little_endian(&SECP256K1_ORDER.to_bytes_le());
+        let order = self.constant::<U256Variable>(order);
+        let zero = self.constant::<U256Variable>(U256::zero());
+        let is_lt_order = self.lt(x, order);
+        let is_zero = self.is_equal(x, zero);
+        let is_nonzero = self.not(is_zero);
+        self.and(is_lt_order, is_nonzero)
+    }
+
+    /// Asserts that `0 < x < n`, where `n` is the order of secp256k1.
+    pub(crate) fn secp256k1_assert_valid_scalar(&mut self, x: U256Variable) {
+        let is_valid = self.secp256k1_is_valid_scalar(x);
+        let true_val = self._true();
+        self.assert_is_equal(is_valid, true_val);
+    }
+
+    /// Returns whether `x < p` is the x coordinate of no point on secp256k1, given a witness `w`
+    /// with `w^2 = -(x^3 + 7)` modulo `p`. As `-1` is not a square modulo `p`, such a witness
+    /// exists exactly when `x^3 + 7` is not a square.
+    fn secp256k1_is_not_x_coordinate(
+        &mut self,
+        x: U256Variable,
+        witness: U256Variable,
+    ) -> BoolVariable {
+        let x = self.u256_to_biguint_target(x);
+        let w = self.u256_to_biguint_target(witness);
+        let modulus = self.api.constant_biguint(&SECP256K1_MODULUS);
+        let seven = self.api.constant_biguint(&BigUint::from(7u32));
+
+        let x2 = self.api.mul_biguint(&x, &x);
+        let x2 = self.api.rem_biguint(&x2, &modulus);
+        let x3_plus_7 = self.api.mul_add_biguint(&x2, &x, &seven);
+        let x3_plus_7 = self.api.rem_biguint(&x3_plus_7, &modulus);
+        let w2 = self.api.mul_biguint(&w, &w);
+        let w2 = self.api.rem_biguint(&w2, &modulus);
+        let sum = self.api.add_biguint(&w2, &x3_plus_7);
+        let sum = self.api.rem_biguint(&sum, &modulus);
+
+        let x3_plus_7 = self.biguint_target_to_u256(x3_plus_7);
+        let sum = self.biguint_target_to_u256(sum);
+        let zero = self.constant::<U256Variable>(U256::zero());
+        let is_square_of_minus = self.is_equal(sum, zero);
+        let is_zero = self.is_equal(x3_plus_7, zero);
+        let is_nonzero = self.not(is_zero);
+        self.and(is_square_of_minus, is_nonzero)
+    }
+
+    /// Asserts that `point` is on secp256k1, i.e. that `y^2 = x^3 + 7`.
+    pub fn secp256k1_assert_on_curve(&mut self, point: &AffinePointVariable<Secp256K1>) {
+        point.assert_is_valid(self);
+        let x = self.curta_field_to_u256(&point.x);
+        let y = self.curta_field_to_u256(&point.y);
+        let x = self.u256_to_biguint_target(x);
+        let y = self.u256_to_biguint_target(y);
+        let modulus = self.api.constant_biguint(&SECP256K1_MODULUS);
+        let seven = self.api.constant_biguint(&BigUint::from(7u32));
+
+        let x2 = self.api.mul_biguint(&x, &x);
+        let x2 = self.api.rem_biguint(&x2, &modulus);
+        let x3_plus_7 = self.api.mul_add_biguint(&x2, &x, &seven);
+        let lhs = self.api.rem_biguint(&x3_plus_7, &modulus);
+        let y2 = self.api.mul_biguint(&y, &y);
+        let rhs = self.api.rem_biguint(&y2, &modulus);
+        self.api.connect_biguint(&lhs, &rhs);
+    }
+
+    /// Asserts that two points on a short Weierstrass curve have distinct x coordinates, i.e. that
+    /// they are neither equal nor opposite, as required by the incomplete addition law of the
+    /// accelerators. The coordinates are reduced modulo `p` first, so that two representations of
+    /// the same element are not taken as distinct.
+    pub(crate) fn curta_assert_distinct_x<E: EllipticCurve>(
+        &mut self,
+        p1: &AffinePointVariable<E>,
+        p2: &AffinePointVariable<E>,
+    ) {
+        let modulus = self
+            .api
+            .constant_biguint(&<E::BaseField as FieldParameters>::modulus());
+        let mut xs = Vec::with_capacity(2);
+        for point in [p1, p2] {
+            let x = self.curta_field_to_u256(&point.x);
+            let x = self.u256_to_biguint_target(x);
+            let x = self.api.rem_biguint(&x, &modulus);
+            xs.push(self.biguint_target_to_u256(x));
+        }
+        let is_same_x = self.is_equal(xs[0], xs[1]);
+        let false_val = self._false();
+        self.assert_is_equal(is_same_x, false_val);
+    }
+
+    /// Computes `R = (z / s) G + (r / s) Q`, whose x coordinate is `r` modulo the order when the
+    /// signature is valid.
+    ///
+    /// The accelerator adds the two terms with the incomplete addition law, whose slope is
+    /// unconstrained when they are equal or opposite, so their x coordinates are asserted to be
+    /// distinct. The public key may come from a hint, and would otherwise be chosen to forge `R`.
+    fn secp256k1_ecdsa_nonce_point(
+        &mut self,
+        z: U256Variable,
+        signature: &ECDSASignatureVariable,
+        pubkey: AffinePointVariable<Secp256K1>,
+    ) -> AffinePointVariable<Secp256K1> {
+        self.secp256k1_assert_valid_scalar(signature.r);
+        self.secp256k1_assert_valid_scalar(signature.s);
+
+        let generator = AffinePointVariable::constant(
+            self,
+            AffinePoint::new(SECP256K1_GENERATOR_X.clone(), SECP256K1_GENERATOR_Y.clone()),
+        );
+
+        let s_inverse = self.secp256k1_scalar_inverse(signature.s);
+        let u1 = self.secp256k1_scalar_mul_mod(z, s_inverse);
+        let u2 = self.secp256k1_scalar_mul_mod(signature.r, s_inverse);
+
+        let p1 = self.curta_secp256k1_scalar_mul(u1, generator);
+        let p2 = self.curta_secp256k1_scalar_mul(u2, pubkey);
+        self.curta_assert_distinct_x(&p1, &p2);
+        self.curta_secp256k1_add(p1, p2)
+    }
+
+    /// Verifies an ECDSA signature over secp256k1 of a 32 byte message hash.
+    ///
+    /// The scalar multiplications and the addition are proven by the secp256k1 accelerator. The
+    /// degenerate cases where `(z / s) G` and `(r / s) Q` are equal or opposite are rejected, as is
+    /// a message hash `z` which is zero modulo the order, since `(z / s) G` is then the point at
+    /// infinity. Neither happens for a hash of a message except with negligible probability.
+    pub fn ecdsa_verify(
+        &mut self,
+        msg_hash: Bytes32Variable,
+        signature: ECDSASignatureVariable,
+        pubkey: AffinePointVariable<Secp256K1>,
+    ) {
+        self.secp256k1_assert_on_curve(&pubkey);
+        let z = msg_hash.as_u256(self);
+        let nonce_point = self.secp256k1_ecdsa_nonce_point(z, &signature, pubkey);
+
+        let x = self.curta_field_to_u256(&nonce_point.x);
+        let x = self.u256_to_biguint_target(x);
+        let order = self.api.constant_biguint(&SECP256K1_ORDER);
+        let x = self.api.rem_biguint(&x, &order);
+        let x = self.biguint_target_to_u256(x);
+        self.assert_is_equal(x, signature.r);
+    }
+
+    /// Recovers the address which signed a 32 byte message hash, with the semantics of the
+    /// `ecrecover` precompile, i.e. with `v` in `{27, 28}` and the x coordinate of the nonce point
+    /// equal to `r`. Returns the zero address for an invalid signature.
+    ///
+    /// The public key is provided by a hint and checked by recomputing the nonce point from it.
+    /// When the hint reports the signature as invalid, the circuit checks that `v`, `r` or `s` is
+    /// out of range or that `r` is not the x coordinate of a point. The remaining invalid case,
+    /// where the recovered key is the point at infinity, cannot be proven. Neither can a valid
+    /// signature whose message hash is zero modulo the order, or for which `(z / s) G` and
+    /// `(r / s) Q` are equal or opposite, as in `ecdsa_verify`.
+    pub fn ecrecover(
+        &mut self,
+        msg_hash: Bytes32Variable,
+        v: ByteVariable,
+        r: U256Variable,
+        s: U256Variable,
+    ) -> AddressVariable {
+        let v_27 = self.constant::<ByteVariable>(27);
+        let v_28 = self.constant::<ByteVariable>(28);
+        let is_v_27 = self.is_equal(v, v_27);
+        let is_v_28 = self.is_equal(v, v_28);
+        let is_v_valid = self.or(is_v_27, is_v_28);
+        let is_r_valid = self.secp256k1_is_valid_scalar(r);
+        let is_s_valid = self.secp256k1_is_valid_scalar(s);
+        let is_rs_valid = self.and(is_r_valid, is_s_valid);
+        let is_well_formed = self.and(is_v_valid, is_rs_valid);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&msg_hash);
+        input_stream.write(&v);
+        input_stream.write(&r);
+        input_stream.write(&s);
+        let output_stream = self.hint(input_stream, Secp256k1RecoverHint);
+        let is_valid = output_stream.read::<BoolVariable>(self);
+        let pubkey = output_stream.read::<AffinePointVariable<Secp256K1>>(self);
+        let root = output_stream.read::<U256Variable>(self);
+        self.secp256k1_assert_on_curve(&pubkey);
+
+        // For an invalid signature, recompute `2G + G` instead so that the accelerated
+        // operations are well defined.
+        let z = msg_hash.as_u256(self);
+        let one = self.constant::<U256Variable>(U256::one());
+        let two = self.constant::<U256Variable>(U256::from(2));
+        let z = self.select(is_valid, z, two);
+        let signature = ECDSASignatureVariable {
+            r: self.select(is_valid, r, one),
+            s: self.select(is_valid, s, one),
+        };
+        let nonce_point = self.secp256k1_ecdsa_nonce_point(z, &signature, pubkey.clone());
+
+        // The nonce point is `(r, y)` where the parity of `y` is given by `v`.
+        let x = self.curta_field_to_u256(&nonce_point.x);
+        let is_x_r = self.is_equal(x, r);
+        let y_is_odd = BoolVariable::from(self.api.split_le(nonce_point.y.variables()[0].0, 16)[0]);
+        let is_parity_v = self.is_equal(y_is_odd, is_v_28);
+        let is_nonce_point_valid = self.and(is_x_r, is_parity_v);
+        let is_recovered = self.and(is_well_formed, is_nonce_point_valid);
+
+        // A well-formed signature may only be reported invalid if `r` is not an x coordinate.
+        let is_not_x_coordinate = self.secp256k1_is_not_x_coordinate(r, root);
+        let is_malformed = self.not(is_well_formed);
+        let is_invalid_proven = self.or(is_malformed, is_not_x_coordinate);
+
+        let is_consistent = self.select(is_valid, is_recovered, is_invalid_proven);
+        let true_val = self._true();
+        self.assert_is_equal(is_consistent, true_val);
+
+        let mut pubkey_bytes = self.curta_field_to_u256(&pubkey.x).encode(self);
+        let pubkey_y = self.curta_field_to_u256(&pubkey.y);
+        pubkey_bytes.extend(pubkey_y.encode(self));
+        let hash = self.keccak256(&pubkey_bytes);
+        let address = AddressVariable(BytesVariable(hash.as_bytes()[12..].try_into().unwrap()));
+        let zero_address = self.constant::<AddressVariable>(Address::zero());
+        self.select(is_valid, address, zero_address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::H256;
+    use ethers::utils::hash_message;
+    use num::BigUint;
+    use starkyx::chip::ec::point::AffinePoint;
+    use starkyx::chip::ec::weierstrass::secp256k1::Secp256K1;
+
+    use super::*;
+    use crate::prelude::*;
+    use crate::utils;
+
+    const NUM_SIGS: usize = 2;
+
+    fn sign(
+        wallet: &LocalWallet,
+        message: &[u8],
+    ) -> (H256, ECDSASignatureVariableValue<GoldilocksField>, u8) {
+        let msg_hash = hash_message(message);
+        let signature = wallet.sign_hash(msg_hash).unwrap();
+        (
+            msg_hash,
+            ECDSASignatureVariableValue {
+                r: signature.r,
+                s: signature.s,
+            },
+            signature.v as u8,
+        )
+    }
+
+    fn pubkey(wallet: &LocalWallet) -> AffinePoint<Secp256K1> {
+        let point = wallet.signer().verifying_key().to_encoded_point(false);
+        AffinePoint::new(
+            BigUint::from_bytes_be(point.x().unwrap()),
+            BigUint::from_bytes_be(point.y().unwrap()),
+        )
+    }
+
+    fn test_ecdsa_verify(messages: Vec<Vec<u8>>, tamper: bool) {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        for _ in 0..NUM_SIGS {
+            let msg_hash = builder.read::<Bytes32Variable>();
+            let signature = builder.read::<ECDSASignatureVariable>();
+            let pubkey = builder.read::<AffinePointVariable<Secp256K1>>();
+            builder.ecdsa_verify(msg_hash, signature, pubkey);
+        }
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        for (i, message) in messages.iter().enumerate() {
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let (mut msg_hash, signature, _) = sign(&wallet, message);
+            if tamper && i == 0 {
+                msg_hash.0[0] ^= 1;
+            }
+            input.write::<Bytes32Variable>(msg_hash);
+            input.write::<ECDSASignatureVariable>(signature);
+            input.write::<AffinePointVariable<Secp256K1>>(pubkey(&wallet));
+        }
+
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ecdsa_verify_sigs() {
+        test_ecdsa_verify(vec![b"hello world".to_vec(), vec![0u8; 100]], false);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ecdsa_verify_sigs_failure() {
+        test_ecdsa_verify(vec![b"hello world".to_vec(), vec![0u8; 100]], true);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ecrecover() {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        for _ in 0..NUM_SIGS {
+            let msg_hash = builder.read::<Bytes32Variable>();
+            let v = builder.read::<ByteVariable>();
+            let signature = builder.read::<ECDSASignatureVariable>();
+            let address = builder.ecrecover(msg_hash, v, signature.r, signature.s);
+            builder.write(address);
+        }
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        let mut addresses = Vec::new();
+        for i in 0..NUM_SIGS {
+            let wallet = LocalWallet::new(&mut rand::thread_rng());
+            let (msg_hash, signature, v) = sign(&wallet, &[i as u8; 32]);
+            input.write::<Bytes32Variable>(msg_hash);
+            input.write::<ByteVariable>(v);
+            input.write::<ECDSASignatureVariable>(signature);
+            addresses.push(wallet.address());
+        }
+
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        for address in addresses {
+            assert_eq!(output.read::<AddressVariable>(), address);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_ecrecover_invalid() {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        for _ in 0..4 {
+            let msg_hash = builder.read::<Bytes32Variable>();
+            let v = builder.read::<ByteVariable>();
+            let signature = builder.read::<ECDSASignatureVariable>();
+            let address = builder.ecrecover(msg_hash, v, signature.r, signature.s);
+            builder.write(address);
+        }
+        let circuit = builder.build();
+
+        // The smallest `r` which is not the x coordinate of a point.
+        let modulus = &*SECP256K1_MODULUS;
+        let legendre_exponent = (modulus - 1u32) >> 1;
+        let non_x = (1u32..)
+            .find(|x| {
+                let x3_plus_7 = BigUint::from(*x).pow(3) + 7u32;
+                x3_plus_7.modpow(&legendre_exponent, modulus) != BigUint::from(1u32)
+            })
+            .unwrap();
+
+        let wallet = LocalWallet::new(&mut rand::thread_rng());
+        let (msg_hash, signature, v) = sign(&wallet, b"hello world");
+        let order = U256::from_little_endian(&SECP256K1_ORDER.to_bytes_le());
+        let inputs = [
+            (v, signature.r, signature.s),
+            (29, signature.r, signature.s),
+            (v, signature.r, U256::zero()),
+            (27, U256::from(non_x), order - 1),
+        ];
+
+        let mut input = circuit.input();
+        for (v, r, s) in inputs {
+            input.write::<Bytes32Variable>(msg_hash);
+            input.write::<ByteVariable>(v);
+            input.write::<ECDSASignatureVariable>(ECDSASignatureVariableValue { r, s });
+        }
+
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<AddressVariable>(), wallet.address());
+        for _ in 1..inputs.len() {
+            assert_eq!(output.read::<AddressVariable>(), Address::zero());
+        }
+    }
+}
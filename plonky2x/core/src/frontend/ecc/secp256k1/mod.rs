@@ -0,0 +1,4 @@
+pub mod curta;
+pub mod ec_ops;
+pub mod ecdsa;
+pub mod schnorr;
@@ -0,0 +1,262 @@
+use ethers::types::{H256, U256};
+use num::BigUint;
+use plonky2::hash::hash_types::RichField;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starkyx::chip::ec::point::AffinePoint;
+use starkyx::chip::ec::weierstrass::secp256k1::Secp256K1;
+
+use super::ecdsa::{
+    SECP256K1_GENERATOR_X, SECP256K1_GENERATOR_Y, SECP256K1_MODULUS, SECP256K1_ORDER,
+};
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::CircuitBuilderBiguint;
+use crate::prelude::{
+    ArrayVariable, BoolVariable, ByteVariable, Bytes32Variable, CircuitBuilder, CircuitVariable,
+    PlonkParameters, U256Variable, ValueStream, VariableStream,
+};
+
+/// The tag of the challenge hash of BIP-340.
+const BIP340_CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+
+/// A BIP-340 Schnorr signature, i.e. the x coordinate of the nonce point followed by a scalar.
+#[derive(Clone, Debug, CircuitVariable)]
+pub struct SchnorrSignatureVariable {
+    pub r: Bytes32Variable,
+    pub s: Bytes32Variable,
+}
+
+/// Computes the point of secp256k1 with the given x coordinate and an even y coordinate, as
+/// `lift_x` in BIP-340.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256k1LiftXHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Secp256k1LiftXHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let mut bytes = [0u8; 32];
+        input_stream
+            .read_value::<U256Variable>()
+            .to_little_endian(&mut bytes);
+        let x = BigUint::from_bytes_le(&bytes);
+
+        // Since p = 3 mod 4, a square root of c is c^((p + 1) / 4), if c is a square. Otherwise,
+        // the point is not on the curve and the circuit is unsatisfiable.
+        let p = &*SECP256K1_MODULUS;
+        let y_squared = (x.modpow(&BigUint::from(3u32), p) + 7u32) % p;
+        let mut y = y_squared.modpow(&((p + 1u32) / 4u32), p);
+        if y.bit(0) {
+            y = p - y;
+        }
+        output_stream.write_value::<AffinePointVariable<Secp256K1>>(AffinePoint::new(x, y));
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns whether the coordinate of a point of secp256k1 is even, after asserting that it is
+    /// reduced.
+    fn secp256k1_is_even_coordinate(&mut self, y: U256Variable) -> BoolVariable {
+        let modulus = U256::from_little_endian(&SECP256K1_MODULUS.to_bytes_le());
+        let modulus = self.constant::<U256Variable>(modulus);
+        let is_reduced = self.lt(y, modulus);
+        let true_val = self._true();
+        self.assert_is_equal(is_reduced, true_val);
+
+        let y_is_odd = BoolVariable::from(self.api.split_le(y.limbs[0].variable.0, 32)[0]);
+        self.not(y_is_odd)
+    }
+
+    /// Verifies a BIP-340 Schnorr signature over secp256k1 of a 32 byte message with an x-only
+    /// public key, if `is_active` is true.
+    ///
+    /// The inputs of an inactive signature are replaced by a dummy signature, for which only the
+    /// final check on the nonce point is skipped. As for ECDSA, `s G` and `-e P` are asserted to
+    /// have distinct x coordinates before they are added with the incomplete addition law.
+    fn bip340_verify_if(
+        &mut self,
+        is_active: BoolVariable,
+        msg: Bytes32Variable,
+        signature: &SchnorrSignatureVariable,
+        pubkey: Bytes32Variable,
+    ) {
+        let generator_x_bytes = H256::from_slice(&SECP256K1_GENERATOR_X.to_bytes_be());
+        let generator_x_bytes = self.constant::<Bytes32Variable>(generator_x_bytes);
+        let one = self.constant::<Bytes32Variable>(H256::from_low_u64_be(1));
+        let zero = self.constant::<Bytes32Variable>(H256::zero());
+
+        // The dummy signature has the generator as public key, and `r = x(G)` and `s = 1`.
+        let msg = self.select(is_active, msg, zero);
+        let pubkey = self.select(is_active, pubkey, generator_x_bytes);
+        let r_bytes = self.select(is_active, signature.r, generator_x_bytes);
+        let s_bytes = self.select(is_active, signature.s, one);
+
+        let true_val = self._true();
+        let modulus = U256::from_little_endian(&SECP256K1_MODULUS.to_bytes_le());
+        let modulus = self.constant::<U256Variable>(modulus);
+        let px = pubkey.as_u256(self);
+        let r = r_bytes.as_u256(self);
+        let s = s_bytes.as_u256(self);
+        let is_px_reduced = self.lt(px, modulus);
+        self.assert_is_equal(is_px_reduced, true_val);
+        let is_r_reduced = self.lt(r, modulus);
+        self.assert_is_equal(is_r_reduced, true_val);
+        self.secp256k1_assert_valid_scalar(s);
+
+        // Lift the public key to the point with an even y coordinate.
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&px);
+        let output_stream = self.hint(input_stream, Secp256k1LiftXHint);
+        let pubkey_point = output_stream.read::<AffinePointVariable<Secp256K1>>(self);
+        self.secp256k1_assert_on_curve(&pubkey_point);
+        let pubkey_x = self.curta_field_to_u256(&pubkey_point.x);
+        self.assert_is_equal(pubkey_x, px);
+        let pubkey_y = self.curta_field_to_u256(&pubkey_point.y);
+        let is_pubkey_y_even = self.secp256k1_is_even_coordinate(pubkey_y);
+        self.assert_is_equal(is_pubkey_y_even, true_val);
+
+        // The challenge is `sha256(tag_hash || tag_hash || r || px || msg)` modulo the order.
+        let tag_hash = Sha256::digest(BIP340_CHALLENGE_TAG);
+        let mut challenge_input = tag_hash
+            .iter()
+            .chain(tag_hash.iter())
+            .map(|b| self.constant::<ByteVariable>(*b))
+            .collect::<Vec<_>>();
+        challenge_input.extend(r_bytes.as_bytes());
+        challenge_input.extend(pubkey.as_bytes());
+        challenge_input.extend(msg.as_bytes());
+        let e = self.curta_sha256(&challenge_input).as_u256(self);
+        let e = self.u256_to_biguint_target(e);
+        let order = self.api.constant_biguint(&SECP256K1_ORDER);
+        let e = self.api.rem_biguint(&e, &order);
+        let neg_e = self.api.sub_biguint(&order, &e);
+        let neg_e = self.biguint_target_to_u256(neg_e);
+
+        // R = s G - e P must have an even y coordinate and the x coordinate r.
+        let generator = AffinePointVariable::constant(
+            self,
+            AffinePoint::new(SECP256K1_GENERATOR_X.clone(), SECP256K1_GENERATOR_Y.clone()),
+        );
+        let s_g = self.curta_secp256k1_scalar_mul(s, generator);
+        let neg_e_p = self.curta_secp256k1_scalar_mul(neg_e, pubkey_point);
+        self.curta_assert_distinct_x(&s_g, &neg_e_p);
+        let nonce_point = self.curta_secp256k1_add(s_g, neg_e_p);
+
+        let nonce_x = self.curta_field_to_u256(&nonce_point.x);
+        let is_nonce_x_r = self.is_equal(nonce_x, r);
+        let nonce_y = self.curta_field_to_u256(&nonce_point.y);
+        let is_nonce_y_even = self.secp256k1_is_even_coordinate(nonce_y);
+        let is_valid = self.and(is_nonce_x_r, is_nonce_y_even);
+        let is_inactive = self.not(is_active);
+        let is_valid_or_inactive = self.or(is_valid, is_inactive);
+        self.assert_is_equal(is_valid_or_inactive, true_val);
+    }
+
+    /// Verifies a BIP-340 Schnorr signature over secp256k1 of a 32 byte message with an x-only
+    /// public key, e.g. a Taproot key path spend over its signature hash.
+    ///
+    /// The scalar multiplications and the addition are proven by the secp256k1 accelerator.
+    ///
+    /// This deviates from BIP-340 on a few valid signatures, for which the circuit cannot be
+    /// satisfied:
+    /// - signatures with `s = 0`, since the accelerator cannot represent `0 G`,
+    /// - signatures for which `s G` and `-e P` are equal or opposite, since they are added with the
+    ///   incomplete addition law,
+    /// - signatures whose challenge `e` is zero modulo the order.
+    ///
+    /// An honest signer produces none of these except with negligible probability, and none of
+    /// them lets a signature that BIP-340 rejects be accepted.
+    pub fn bip340_verify(
+        &mut self,
+        msg: Bytes32Variable,
+        signature: SchnorrSignatureVariable,
+        pubkey: Bytes32Variable,
+    ) {
+        let true_val = self._true();
+        self.bip340_verify_if(true_val, msg, &signature, pubkey);
+    }
+
+    /// This function will verify a set of BIP-340 signatures.  It also contains a BoolVariable
+    /// array bitmask ("is_active") that will specify which signatures should be verified. The active
+    /// signatures are subject to the same restrictions as in `bip340_verify`.
+    pub fn bip340_verify_sigs_conditional<const NUM_SIGS: usize>(
+        &mut self,
+        is_active: ArrayVariable<BoolVariable, NUM_SIGS>,
+        messages: ArrayVariable<Bytes32Variable, NUM_SIGS>,
+        signatures: ArrayVariable<SchnorrSignatureVariable, NUM_SIGS>,
+        pubkeys: ArrayVariable<Bytes32Variable, NUM_SIGS>,
+    ) {
+        assert!(NUM_SIGS > 0);
+        for i in 0..NUM_SIGS {
+            self.bip340_verify_if(is_active[i], messages[i], &signatures[i], pubkeys[i]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::schnorr::SigningKey;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::prelude::*;
+    use crate::utils;
+
+    const NUM_SIGS: usize = 3;
+
+    fn test_bip340_verify_sigs_conditional(tamper_active: bool) {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        let is_active = builder.read::<ArrayVariable<BoolVariable, NUM_SIGS>>();
+        let messages = builder.read::<ArrayVariable<Bytes32Variable, NUM_SIGS>>();
+        let signatures = builder.read::<ArrayVariable<SchnorrSignatureVariable, NUM_SIGS>>();
+        let pubkeys = builder.read::<ArrayVariable<Bytes32Variable, NUM_SIGS>>();
+        builder.bip340_verify_sigs_conditional(is_active, messages, signatures, pubkeys);
+        let circuit = builder.build();
+
+        // The second signature is inactive and invalid.
+        let test_is_active = vec![true, false, true];
+        let mut test_messages = Vec::new();
+        let mut test_signatures = Vec::new();
+        let mut test_pubkeys = Vec::new();
+        for i in 0..NUM_SIGS {
+            let signing_key = SigningKey::random(&mut OsRng);
+            let mut msg: [u8; 32] = rand::thread_rng().gen();
+            let aux_rand: [u8; 32] = rand::thread_rng().gen();
+            let signature = signing_key.sign_raw(&msg, &aux_rand).unwrap().to_bytes();
+            if !test_is_active[i] || (tamper_active && i == 0) {
+                msg[0] ^= 1;
+            }
+
+            test_messages.push(H256(msg));
+            test_signatures.push(SchnorrSignatureVariableValue {
+                r: H256::from_slice(&signature[..32]),
+                s: H256::from_slice(&signature[32..]),
+            });
+            test_pubkeys.push(H256::from_slice(&signing_key.verifying_key().to_bytes()));
+        }
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<BoolVariable, NUM_SIGS>>(test_is_active);
+        input.write::<ArrayVariable<Bytes32Variable, NUM_SIGS>>(test_messages);
+        input.write::<ArrayVariable<SchnorrSignatureVariable, NUM_SIGS>>(test_signatures);
+        input.write::<ArrayVariable<Bytes32Variable, NUM_SIGS>>(test_pubkeys);
+
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bip340_verify_sigs() {
+        test_bip340_verify_sigs_conditional(false);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_bip340_verify_sigs_failure() {
+        test_bip340_verify_sigs_conditional(true);
+    }
+}
@@ -0,0 +1,22 @@
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use starkyx::chip::field::instruction::FpInstruction;
+use starkyx::chip::AirParameters;
+
+use crate::frontend::ecc::secp256r1::params::Secp256R1BaseField;
+use crate::prelude::PlonkParameters;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256r1AirParameters<L, const D: usize>(PhantomData<L>);
+
+impl<L: PlonkParameters<D>, const D: usize> AirParameters for Secp256r1AirParameters<L, D> {
+    type Field = L::Field;
+    type CubicParams = L::CubicParams;
+
+    type Instruction = FpInstruction<Secp256R1BaseField>;
+
+    const NUM_ARITHMETIC_COLUMNS: usize = 1632;
+    const NUM_FREE_COLUMNS: usize = 20;
+    const EXTENDED_COLUMNS: usize = 2502;
+}
@@ -0,0 +1,72 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use num::BigUint;
+use starkyx::chip::builder::AirBuilder;
+use starkyx::chip::ec::edwards::ed25519::point::CompressedPointRegister;
+use starkyx::chip::ec::point::{AffinePoint, AffinePointRegister};
+use starkyx::chip::ec::scalar::ECScalarRegister;
+use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::field::register::FieldRegister;
+use starkyx::machine::ec::builder::EllipticCurveBuilder;
+use starkyx::machine::emulated::builder::EmulatedBuilder;
+
+use self::air_parameters::Secp256r1AirParameters;
+use super::params::Secp256R1;
+use crate::frontend::ecc::curta::accelerator::EcAccelerator;
+use crate::frontend::ecc::curta::{CurtaCurve, EcOpCurve, Unsupported};
+use crate::prelude::{CircuitBuilder, PlonkParameters};
+
+pub mod air_parameters;
+
+impl EcOpCurve for Secp256R1 {
+    type DecompressOps = Unsupported;
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CurtaCurve<L, D> for Secp256R1 {
+    type AirParameters = Secp256r1AirParameters<L, D>;
+
+    const NAME: &'static str = "secp256r1";
+
+    fn accelerator(builder: &mut CircuitBuilder<L, D>) -> &mut Option<EcAccelerator<Self>> {
+        &mut builder.ec_secp256r1_ops_accelerator
+    }
+
+    fn air_add(
+        builder: &mut AirBuilder<Self::AirParameters>,
+        a: AffinePointRegister<Self>,
+        b: AffinePointRegister<Self>,
+    ) -> AffinePointRegister<Self> {
+        builder.add(a, b)
+    }
+
+    fn air_scalar_mul_batch(
+        builder: &mut EmulatedBuilder<Self::AirParameters>,
+        points: &[AffinePointRegister<Self>],
+        scalars: &[ECScalarRegister<Self>],
+        results: &[AffinePointRegister<Self>],
+    ) {
+        builder.scalar_mul_batch(points, scalars, results);
+    }
+
+    fn air_assert_valid(
+        ops: Unsupported,
+        _: &mut AirBuilder<Self::AirParameters>,
+        _: &AffinePointRegister<Self>,
+    ) {
+        match ops {}
+    }
+
+    fn air_decompress(
+        ops: Unsupported,
+        _: &mut AirBuilder<Self::AirParameters>,
+    ) -> (
+        CompressedPointRegister,
+        AffinePointRegister<Self>,
+        FieldRegister<<Self as EllipticCurveParameters>::BaseField>,
+    ) {
+        match ops {}
+    }
+
+    fn decompress(ops: Unsupported, _: &CompressedEdwardsY) -> (AffinePoint<Self>, BigUint) {
+        match ops {}
+    }
+}
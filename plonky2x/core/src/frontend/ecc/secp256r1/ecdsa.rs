@@ -0,0 +1,208 @@
+use ethers::types::U256;
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::ec::point::AffinePoint;
+use starkyx::chip::ec::weierstrass::WeierstrassParameters;
+use starkyx::chip::field::parameters::FieldParameters;
+
+use super::params::{Secp256R1, Secp256R1BaseField, Secp256R1Parameters};
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::frontend::ecc::secp256k1::ecdsa::ECDSASignatureVariable;
+use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::uint::num::biguint::CircuitBuilderBiguint;
+use crate::prelude::{
+    Bytes32Variable, CircuitBuilder, PlonkParameters, U256Variable, ValueStream, VariableStream,
+};
+
+/// Computes the inverse of a non-zero scalar modulo the order of secp256r1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256r1ScalarInverseHint;
+
+impl<L: PlonkParameters<D>, const D: usize> Hint<L, D> for Secp256r1ScalarInverseHint {
+    fn hint(&self, input_stream: &mut ValueStream<L, D>, output_stream: &mut ValueStream<L, D>) {
+        let mut bytes = [0u8; 32];
+        input_stream
+            .read_value::<U256Variable>()
+            .to_little_endian(&mut bytes);
+        let x = BigUint::from_bytes_le(&bytes);
+        let order = Secp256R1Parameters::prime_group_order();
+        let inverse = x.modpow(&(&order - 2u32), &order);
+        output_stream.write_value::<U256Variable>(U256::from_little_endian(&inverse.to_bytes_le()));
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Returns `a * b` modulo the order of secp256r1.
+    fn secp256r1_scalar_mul_mod(&mut self, a: U256Variable, b: U256Variable) -> U256Variable {
+        let a = self.u256_to_biguint_target(a);
+        let b = self.u256_to_biguint_target(b);
+        let order = self
+            .api
+            .constant_biguint(&Secp256R1Parameters::prime_group_order());
+        let product = self.api.mul_biguint(&a, &b);
+        let product = self.api.rem_biguint(&product, &order);
+        self.biguint_target_to_u256(product)
+    }
+
+    /// Returns the inverse of `x` modulo the order of secp256r1, which constrains `x` to be
+    /// non-zero.
+    fn secp256r1_scalar_inverse(&mut self, x: U256Variable) -> U256Variable {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&x);
+        let output_stream = self.hint(input_stream, Secp256r1ScalarInverseHint);
+        let inverse = output_stream.read::<U256Variable>(self);
+
+        let product = self.secp256r1_scalar_mul_mod(x, inverse);
+        let one = self.constant::<U256Variable>(U256::one());
+        self.assert_is_equal(product, one);
+        inverse
+    }
+
+    /// Asserts that `0 < x < n`, where `n` is the order of secp256r1.
+    fn secp256r1_assert_valid_scalar(&mut self, x: U256Variable) {
+        let order = Secp256R1Parameters::prime_group_order();
+        let order = self.constant::<U256Variable>(U256::from_little_endian(&order.to_bytes_le()));
+        let zero = self.constant::<U256Variable>(U256::zero());
+        let is_lt_order = self.lt(x, order);
+        let is_zero = self.is_equal(x, zero);
+        let true_val = self._true();
+        let false_val = self._false();
+        self.assert_is_equal(is_lt_order, true_val);
+        self.assert_is_equal(is_zero, false_val);
+    }
+
+    /// Asserts that `point` is on secp256r1, i.e. that `y^2 = x^3 - 3x + b`.
+    pub fn secp256r1_assert_on_curve(&mut self, point: &AffinePointVariable<Secp256R1>) {
+        point.assert_is_valid(self);
+        let x = self.curta_field_to_u256(&point.x);
+        let y = self.curta_field_to_u256(&point.y);
+        let x = self.u256_to_biguint_target(x);
+        let y = self.u256_to_biguint_target(y);
+        let modulus = self.api.constant_biguint(&Secp256R1BaseField::modulus());
+        let b = self.api.constant_biguint(&Secp256R1Parameters::b_int());
+        let three = self.api.constant_biguint(&BigUint::from(3u32));
+
+        // Check `x^3 + b = y^2 + 3x`, so that both sides are sums of non-negative terms.
+        let x2 = self.api.mul_biguint(&x, &x);
+        let x2 = self.api.rem_biguint(&x2, &modulus);
+        let x3_plus_b = self.api.mul_add_biguint(&x2, &x, &b);
+        let lhs = self.api.rem_biguint(&x3_plus_b, &modulus);
+        let three_x = self.api.mul_biguint(&x, &three);
+        let y2_plus_three_x = self.api.mul_add_biguint(&y, &y, &three_x);
+        let rhs = self.api.rem_biguint(&y2_plus_three_x, &modulus);
+        self.api.connect_biguint(&lhs, &rhs);
+    }
+
+    /// Verifies an ECDSA signature over secp256r1 (NIST P-256) of a 32 byte message hash.
+    ///
+    /// The scalar multiplications and the addition are proven by the secp256r1 accelerator. As
+    /// for secp256k1, the addition law is incomplete, so `(z / s) G` and `(r / s) Q` are asserted
+    /// to have distinct x coordinates, and signatures for which they are equal or opposite are
+    /// rejected.
+    pub fn secp256r1_ecdsa_verify(
+        &mut self,
+        msg_hash: Bytes32Variable,
+        signature: ECDSASignatureVariable,
+        pubkey: AffinePointVariable<Secp256R1>,
+    ) {
+        self.secp256r1_assert_on_curve(&pubkey);
+        self.secp256r1_assert_valid_scalar(signature.r);
+        self.secp256r1_assert_valid_scalar(signature.s);
+
+        let (generator_x, generator_y) = Secp256R1Parameters::generator();
+        let generator =
+            AffinePointVariable::constant(self, AffinePoint::new(generator_x, generator_y));
+
+        let z = msg_hash.as_u256(self);
+        let s_inverse = self.secp256r1_scalar_inverse(signature.s);
+        let u1 = self.secp256r1_scalar_mul_mod(z, s_inverse);
+        let u2 = self.secp256r1_scalar_mul_mod(signature.r, s_inverse);
+
+        let p1 = self.curta_secp256r1_scalar_mul(u1, generator);
+        let p2 = self.curta_secp256r1_scalar_mul(u2, pubkey);
+        self.curta_assert_distinct_x(&p1, &p2);
+        let nonce_point = self.curta_secp256r1_add(p1, p2);
+
+        let x = self.curta_field_to_u256(&nonce_point.x);
+        let x = self.u256_to_biguint_target(x);
+        let order = self
+            .api
+            .constant_biguint(&Secp256R1Parameters::prime_group_order());
+        let x = self.api.rem_biguint(&x, &order);
+        let x = self.biguint_target_to_u256(x);
+        self.assert_is_equal(x, signature.r);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+    use p256::ecdsa::signature::hazmat::PrehashSigner;
+    use p256::ecdsa::{Signature, SigningKey};
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::frontend::ecc::secp256k1::ecdsa::ECDSASignatureVariableValue;
+    use crate::prelude::*;
+    use crate::utils;
+
+    const NUM_SIGS: usize = 2;
+
+    fn pubkey(signing_key: &SigningKey) -> AffinePoint<Secp256R1> {
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        AffinePoint::new(
+            BigUint::from_bytes_be(point.x().unwrap()),
+            BigUint::from_bytes_be(point.y().unwrap()),
+        )
+    }
+
+    fn signature_value(signature: &Signature) -> ECDSASignatureVariableValue<GoldilocksField> {
+        let bytes = signature.to_bytes();
+        ECDSASignatureVariableValue {
+            r: U256::from_big_endian(&bytes[..32]),
+            s: U256::from_big_endian(&bytes[32..]),
+        }
+    }
+
+    fn test_secp256r1_ecdsa_verify(tamper: bool) {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        for _ in 0..NUM_SIGS {
+            let msg_hash = builder.read::<Bytes32Variable>();
+            let signature = builder.read::<ECDSASignatureVariable>();
+            let pubkey = builder.read::<AffinePointVariable<Secp256R1>>();
+            builder.secp256r1_ecdsa_verify(msg_hash, signature, pubkey);
+        }
+        let circuit = builder.build();
+
+        let mut input = circuit.input();
+        for i in 0..NUM_SIGS {
+            let signing_key = SigningKey::random(&mut rand::thread_rng());
+            let mut msg_hash: [u8; 32] = Sha256::digest([i as u8; 100]).into();
+            let signature: Signature = signing_key.sign_prehash(&msg_hash).unwrap();
+            if tamper && i == 0 {
+                msg_hash[0] ^= 1;
+            }
+            input.write::<Bytes32Variable>(H256(msg_hash));
+            input.write::<ECDSASignatureVariable>(signature_value(&signature));
+            input.write::<AffinePointVariable<Secp256R1>>(pubkey(&signing_key));
+        }
+
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_secp256r1_ecdsa_verify_sigs() {
+        test_secp256r1_ecdsa_verify(false);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_secp256r1_ecdsa_verify_sigs_failure() {
+        test_secp256r1_ecdsa_verify(true);
+    }
+}
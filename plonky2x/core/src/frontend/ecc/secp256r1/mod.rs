@@ -0,0 +1,5 @@
+pub mod curta;
+pub mod ec_ops;
+pub mod ecdsa;
+pub mod params;
+pub mod webauthn;
@@ -0,0 +1,122 @@
+use num::{BigUint, Num};
+use serde::{Deserialize, Serialize};
+use starkyx::chip::ec::weierstrass::{SWCurve, WeierstrassParameters};
+use starkyx::chip::ec::EllipticCurveParameters;
+use starkyx::chip::field::parameters::{FieldParameters, MAX_NB_LIMBS};
+
+/// The NIST P-256 curve, also known as secp256r1 or prime256v1.
+pub type Secp256R1 = SWCurve<Secp256R1Parameters>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Secp256R1Parameters;
+
+/// The base field of secp256r1, of modulus `2^256 - 2^224 + 2^192 + 2^96 - 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Secp256R1BaseField;
+
+impl FieldParameters for Secp256R1BaseField {
+    const NB_BITS_PER_LIMB: usize = 16;
+    const NB_LIMBS: usize = 16;
+    const NB_WITNESS_LIMBS: usize = 2 * Self::NB_LIMBS - 2;
+    const MODULUS: [u16; MAX_NB_LIMBS] = [
+        0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+        0x0000, 0x0001, 0x0000, 0xffff, 0xffff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    const WITNESS_OFFSET: usize = 1usize << 20;
+
+    fn modulus() -> BigUint {
+        BigUint::from_str_radix(
+            "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+            16,
+        )
+        .unwrap()
+    }
+}
+
+impl EllipticCurveParameters for Secp256R1Parameters {
+    type BaseField = Secp256R1BaseField;
+}
+
+impl WeierstrassParameters for Secp256R1Parameters {
+    /// The coefficient `a = -3`, reduced modulo the base field.
+    const A: [u16; MAX_NB_LIMBS] = [
+        0xfffc, 0xffff, 0xffff, 0xffff, 0xffff, 0xffff, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+        0x0000, 0x0001, 0x0000, 0xffff, 0xffff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    const B: [u16; MAX_NB_LIMBS] = [
+        0x604b, 0x27d2, 0x3c3e, 0x3bce, 0xb0f6, 0xcc53, 0x06b0, 0x651d, 0x86bc, 0x7698, 0xbd55,
+        0xb3eb, 0x93e7, 0xaa3a, 0x35d8, 0x5ac6, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    fn generator() -> (BigUint, BigUint) {
+        let x = BigUint::from_str_radix(
+            "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            16,
+        )
+        .unwrap();
+        let y = BigUint::from_str_radix(
+            "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+            16,
+        )
+        .unwrap();
+        (x, y)
+    }
+
+    fn prime_group_order() -> BigUint {
+        BigUint::from_str_radix(
+            "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+            16,
+        )
+        .unwrap()
+    }
+
+    fn a_int() -> BigUint {
+        Secp256R1BaseField::modulus() - 3u32
+    }
+
+    fn b_int() -> BigUint {
+        BigUint::from_str_radix(
+            "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+            16,
+        )
+        .unwrap()
+    }
+
+    fn nb_scalar_bits() -> usize {
+        256
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_limbs(limbs: &[u16]) -> BigUint {
+        limbs
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, limb| (acc << 16) + *limb)
+    }
+
+    #[test]
+    fn test_secp256r1_parameters() {
+        let p = Secp256R1BaseField::modulus();
+        assert_eq!(from_limbs(&Secp256R1BaseField::MODULUS), p);
+        assert_eq!(
+            from_limbs(&Secp256R1Parameters::A),
+            Secp256R1Parameters::a_int()
+        );
+        assert_eq!(
+            from_limbs(&Secp256R1Parameters::B),
+            Secp256R1Parameters::b_int()
+        );
+
+        // The generator is on the curve.
+        let (x, y) = Secp256R1Parameters::generator();
+        let lhs = (&y * &y) % &p;
+        let rhs =
+            (&x * &x * &x + Secp256R1Parameters::a_int() * &x + Secp256R1Parameters::b_int()) % &p;
+        assert_eq!(lhs, rhs);
+    }
+}
@@ -0,0 +1,320 @@
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::target::BoolTarget;
+use plonky2x_derive::CircuitVariable;
+
+use super::params::Secp256R1;
+use crate::frontend::curta::ec::point::AffinePointVariable;
+use crate::frontend::ecc::secp256k1::ecdsa::ECDSASignatureVariable;
+use crate::prelude::{
+    ArrayVariable, BoolVariable, ByteVariable, Bytes32Variable, CircuitBuilder, CircuitVariable,
+    Field, PlonkParameters, U32Variable,
+};
+
+/// The `type` member of the client data of an assertion.
+const TYPE_FIELD: &[u8] = b"\"type\":\"webauthn.get\"";
+
+/// The length of the `type` member of the client data.
+const TYPE_FIELD_LEN: usize = TYPE_FIELD.len();
+
+/// The key of the `challenge` member of the client data, up to the opening quote of its value.
+const CHALLENGE_FIELD_PREFIX: &[u8] = b"\"challenge\":\"";
+
+/// The length of the base64url encoding, without padding, of a 32 byte challenge.
+const ENCODED_CHALLENGE_LEN: usize = 43;
+
+/// The length of the `challenge` member of the client data, including both quotes of its value.
+const CHALLENGE_FIELD_LEN: usize = CHALLENGE_FIELD_PREFIX.len() + ENCODED_CHALLENGE_LEN + 1;
+
+/// The minimum length of the authenticator data: the RP ID hash, the flags and the signature
+/// counter.
+const MIN_AUTHENTICATOR_DATA_LEN: usize = 37;
+
+/// The user present bit of the authenticator data flags.
+const USER_PRESENT_BIT: usize = 0;
+
+/// A WebAuthn (passkey) assertion, i.e. the response of an authenticator to
+/// `navigator.credentials.get`.
+///
+/// The client data JSON is padded to `CLIENT_DATA_LEN` bytes. The offsets of its `type` and
+/// `challenge` members are supplied by the prover and checked in the circuit.
+#[derive(Debug, Clone, CircuitVariable)]
+#[value_name(WebAuthnAssertion)]
+pub struct WebAuthnAssertionVariable<const AUTH_DATA_LEN: usize, const CLIENT_DATA_LEN: usize> {
+    pub authenticator_data: ArrayVariable<ByteVariable, AUTH_DATA_LEN>,
+    pub client_data_json: ArrayVariable<ByteVariable, CLIENT_DATA_LEN>,
+    pub client_data_json_len: U32Variable,
+    pub type_offset: U32Variable,
+    pub challenge_offset: U32Variable,
+    pub signature: ECDSASignatureVariable,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Encodes bytes in base64url, without padding, as specified in RFC 4648.
+    pub fn base64url_encode(&mut self, input: &[ByteVariable]) -> Vec<ByteVariable> {
+        let mut bits = input
+            .iter()
+            .flat_map(|byte| byte.as_be_bits())
+            .collect::<Vec<_>>();
+        let num_chars = (bits.len() + 5) / 6;
+        bits.resize(num_chars * 6, self._false());
+
+        bits.chunks_exact(6)
+            .map(|sextet| self.base64url_char(sextet))
+            .collect()
+    }
+
+    /// Returns the base64url character of a sextet, given by its big-endian bits.
+    ///
+    /// The character is `v + 65` for `v < 26`, `v + 71` for `v < 52`, `v - 4` for `v < 62`, and
+    /// `-` or `_` for 62 and 63, which is computed from the bits with a few comparisons.
+    fn base64url_char(&mut self, sextet: &[BoolVariable]) -> ByteVariable {
+        let [b5, b4, b3, b2, b1, b0]: [BoolVariable; 6] = sextet.try_into().unwrap();
+
+        let b5_b4 = self.and(b5, b4);
+        let b4_b3 = self.and(b4, b3);
+        let b2_or_b1 = self.or(b2, b1);
+        let b3_or_b2 = self.or(b3, b2);
+        let b2_b1 = self.and(b2, b1);
+
+        let lt_32_ge_26 = self.and(b4_b3, b2_or_b1);
+        let ge_26 = self.or(b5, lt_32_ge_26);
+        let ge_52 = self.and(b5_b4, b3_or_b2);
+        let b5_b4_b3 = self.and(b5_b4, b3);
+        let ge_62 = self.and(b5_b4_b3, b2_b1);
+        let eq_63 = self.and(ge_62, b0);
+
+        let mut char = self.api.le_sum(
+            [b0, b1, b2, b3, b4, b5]
+                .iter()
+                .map(|b| BoolTarget::new_unsafe(b.variable.0)),
+        );
+        char = self
+            .api
+            .add_const(char, L::Field::from_canonical_u32(b'A' as u32));
+        for (flag, offset) in [
+            (ge_26, L::Field::from_canonical_u32(6)),
+            (ge_52, -L::Field::from_canonical_u32(75)),
+            (ge_62, -L::Field::from_canonical_u32(13)),
+            (eq_63, L::Field::from_canonical_u32(49)),
+        ] {
+            char = self.api.mul_const_add(offset, flag.variable.0, char);
+        }
+        ByteVariable::from_target(self, char)
+    }
+
+    /// Asserts that `offset + len <= end`, where `end` is at most `MAX_END`.
+    fn assert_field_in_bounds<const MAX_END: usize>(
+        &mut self,
+        offset: U32Variable,
+        len: usize,
+        end: U32Variable,
+    ) {
+        let true_val = self._true();
+        let max_end = self.constant::<U32Variable>(MAX_END as u32);
+        let is_offset_valid = self.lte(offset, max_end);
+        self.assert_is_equal(is_offset_valid, true_val);
+
+        let len = self.constant::<U32Variable>(len as u32);
+        let field_end = self.add(offset, len);
+        let is_field_end_valid = self.lte(field_end, end);
+        self.assert_is_equal(is_field_end_valid, true_val);
+    }
+
+    /// Verifies a WebAuthn assertion signed by a passkey with a P-256 public key, for the relying
+    /// party of `rp_id_hash` and the 32 byte `challenge`.
+    ///
+    /// The following is checked:
+    ///     1) The authenticator data starts with `rp_id_hash` and has the user present flag set.
+    ///     2) The client data JSON has the member `"type":"webauthn.get"`.
+    ///     3) The client data JSON has the member `"challenge":"<base64url(challenge)>"`.
+    ///     4) The signature is valid over `sha256(authenticator_data || sha256(client_data_json))`.
+    ///
+    /// The members are found at prover supplied offsets. Since quotes are escaped inside JSON
+    /// strings, these byte sequences cannot appear inside another string of the client data. The
+    /// origin and the signature counter are left to the caller.
+    pub fn verify_webauthn_assertion<const AUTH_DATA_LEN: usize, const CLIENT_DATA_LEN: usize>(
+        &mut self,
+        assertion: &WebAuthnAssertionVariable<AUTH_DATA_LEN, CLIENT_DATA_LEN>,
+        rp_id_hash: Bytes32Variable,
+        challenge: Bytes32Variable,
+        pubkey: AffinePointVariable<Secp256R1>,
+    ) {
+        assert!(AUTH_DATA_LEN >= MIN_AUTHENTICATOR_DATA_LEN);
+        let authenticator_data = assertion.authenticator_data.as_slice();
+        for (byte, expected) in authenticator_data[..32]
+            .iter()
+            .zip(rp_id_hash.as_bytes().iter())
+        {
+            self.assert_is_equal(*byte, *expected);
+        }
+        let flags = authenticator_data[32].as_le_bits();
+        let true_val = self._true();
+        self.assert_is_equal(flags[USER_PRESENT_BIT], true_val);
+
+        let client_data_hash = self.curta_sha256_variable(
+            assertion.client_data_json.as_slice(),
+            assertion.client_data_json_len,
+        );
+        let seed = client_data_hash.as_bytes();
+
+        self.assert_field_in_bounds::<CLIENT_DATA_LEN>(
+            assertion.type_offset,
+            TYPE_FIELD_LEN,
+            assertion.client_data_json_len,
+        );
+        let type_field = self.get_fixed_subarray::<CLIENT_DATA_LEN, TYPE_FIELD_LEN>(
+            &assertion.client_data_json,
+            assertion.type_offset.variable,
+            &seed,
+        );
+        for (byte, expected) in type_field.as_slice().iter().zip(TYPE_FIELD) {
+            let expected = self.constant::<ByteVariable>(*expected);
+            self.assert_is_equal(*byte, expected);
+        }
+
+        self.assert_field_in_bounds::<CLIENT_DATA_LEN>(
+            assertion.challenge_offset,
+            CHALLENGE_FIELD_LEN,
+            assertion.client_data_json_len,
+        );
+        let challenge_field = self.get_fixed_subarray::<CLIENT_DATA_LEN, CHALLENGE_FIELD_LEN>(
+            &assertion.client_data_json,
+            assertion.challenge_offset.variable,
+            &seed,
+        );
+        let encoded_challenge = self.base64url_encode(&challenge.as_bytes());
+        let mut expected_field = CHALLENGE_FIELD_PREFIX
+            .iter()
+            .map(|b| self.constant::<ByteVariable>(*b))
+            .collect::<Vec<_>>();
+        expected_field.extend(encoded_challenge);
+        expected_field.push(self.constant::<ByteVariable>(b'"'));
+        for (byte, expected) in challenge_field.as_slice().iter().zip(expected_field) {
+            self.assert_is_equal(*byte, expected);
+        }
+
+        let mut signed_data = authenticator_data.to_vec();
+        signed_data.extend(client_data_hash.as_bytes());
+        let msg_hash = self.curta_sha256(&signed_data);
+        self.secp256r1_ecdsa_verify(msg_hash, assertion.signature.clone(), pubkey);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::{H256, U256};
+    use num::BigUint;
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::{Signature, SigningKey};
+    use rand::Rng;
+    use sha2::{Digest, Sha256};
+    use starkyx::chip::ec::point::AffinePoint;
+
+    use super::*;
+    use crate::frontend::ecc::secp256k1::ecdsa::ECDSASignatureVariableValue;
+    use crate::prelude::*;
+    use crate::utils;
+
+    const AUTH_DATA_LEN: usize = 37;
+    const CLIENT_DATA_LEN: usize = 192;
+
+    #[test]
+    fn test_base64url_encode() {
+        let mut builder = DefaultBuilder::new();
+        let input = builder.read::<ArrayVariable<ByteVariable, 49>>();
+        let encoded = builder.base64url_encode(input.as_slice());
+        builder.write(ArrayVariable::<ByteVariable, 66>::from(encoded));
+        let circuit = builder.build();
+
+        // Include every sextet value in the input, followed by a byte which is not a multiple of
+        // three bytes long.
+        let mut bytes = Vec::new();
+        for chunk in (0u8..64).collect::<Vec<_>>().chunks(4) {
+            let bits = chunk.iter().fold(0u32, |acc, x| (acc << 6) | *x as u32);
+            bytes.extend(&bits.to_be_bytes()[1..]);
+        }
+        bytes.push(0xff);
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, 49>>(bytes.clone());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let encoded = output.read::<ArrayVariable<ByteVariable, 66>>();
+        assert_eq!(
+            encoded,
+            base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD).into_bytes()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_webauthn_assertion() {
+        utils::setup_logger();
+
+        let mut builder = DefaultBuilder::new();
+        let assertion = builder.read::<WebAuthnAssertionVariable<AUTH_DATA_LEN, CLIENT_DATA_LEN>>();
+        let rp_id_hash = builder.read::<Bytes32Variable>();
+        let challenge = builder.read::<Bytes32Variable>();
+        let pubkey = builder.read::<AffinePointVariable<Secp256R1>>();
+        builder.verify_webauthn_assertion(&assertion, rp_id_hash, challenge, pubkey);
+        let circuit = builder.build();
+
+        let signing_key = SigningKey::random(&mut rand::thread_rng());
+        let challenge: [u8; 32] = rand::thread_rng().gen();
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com","crossOrigin":false}}"#,
+            base64::encode_config(challenge, base64::URL_SAFE_NO_PAD)
+        )
+        .into_bytes();
+        let rp_id_hash: [u8; 32] = Sha256::digest(b"example.com").into();
+        let mut authenticator_data = rp_id_hash.to_vec();
+        authenticator_data.push(0x05);
+        authenticator_data.extend(1u32.to_be_bytes());
+
+        let mut signed_data = authenticator_data.clone();
+        signed_data.extend(Sha256::digest(&client_data_json));
+        let signature: Signature = signing_key.sign(&signed_data);
+        let signature_bytes = signature.to_bytes();
+
+        let type_offset = find(&client_data_json, TYPE_FIELD);
+        let challenge_offset = find(&client_data_json, CHALLENGE_FIELD_PREFIX);
+        let client_data_json_len = client_data_json.len() as u32;
+        let mut padded_client_data_json = client_data_json;
+        padded_client_data_json.resize(CLIENT_DATA_LEN, 0);
+
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        let pubkey = AffinePoint::new(
+            BigUint::from_bytes_be(point.x().unwrap()),
+            BigUint::from_bytes_be(point.y().unwrap()),
+        );
+
+        let mut input = circuit.input();
+        input.write::<WebAuthnAssertionVariable<AUTH_DATA_LEN, CLIENT_DATA_LEN>>(
+            WebAuthnAssertion::<AUTH_DATA_LEN, CLIENT_DATA_LEN, GoldilocksField> {
+                authenticator_data,
+                client_data_json: padded_client_data_json,
+                client_data_json_len,
+                type_offset,
+                challenge_offset,
+                signature: ECDSASignatureVariableValue {
+                    r: U256::from_big_endian(&signature_bytes[..32]),
+                    s: U256::from_big_endian(&signature_bytes[32..]),
+                },
+            },
+        );
+        input.write::<Bytes32Variable>(H256(rp_id_hash));
+        input.write::<Bytes32Variable>(H256(challenge));
+        input.write::<AffinePointVariable<Secp256R1>>(pubkey);
+
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    fn find(haystack: &[u8], needle: &[u8]) -> u32 {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .unwrap() as u32
+    }
+}
@@ -44,8 +44,11 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     const PREFIX_EXTENSION_ODD: u8 = 1;
     const PREFIX_LEAF_EVEN: u8 = 2;
     const PREFIX_LEAF_ODD: u8 = 3;
-    /// Get the validators for a given block root.
-    /// P is the number of proof elements to be considered
+    /// Verifies that `proof` is a path from `root` to a leaf at `keccak256(key)` which holds
+    /// `value`, such as a slot of a storage trie.
+    ///
+    /// The value is decoded from the leaf by `decode_mpt_leaf_value`. Exclusion proofs are not
+    /// supported, so `value` must be non-zero.
     pub fn verify_mpt_proof<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
         &mut self,
         key: Bytes32Variable,
@@ -54,6 +57,112 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         root: Bytes32Variable,
         value: Bytes32Variable,
     ) {
+        let (leaf, leaf_len) = self.verify_mpt_proof_leaf(&key.as_bytes(), proof, len_nodes, root);
+        let leaf_value = self.decode_mpt_leaf_value(&leaf, leaf_len);
+        self.assert_is_equal(leaf_value, value);
+    }
+
+    /// Decodes the value of a leaf holding an integer of at most 32 bytes, such as a leaf of a
+    /// storage trie, and returns its big-endian bytes.
+    ///
+    /// The leaf is the list of its path and of a string holding the RLP encoding of the integer.
+    /// That string is its own encoding for a value below 0x80, and otherwise is prefixed by its
+    /// length, which is 33 bytes for a value of 32 bytes.
+    pub fn decode_mpt_leaf_value<const ENCODING_LEN: usize>(
+        &mut self,
+        leaf: &ArrayVariable<ByteVariable, ENCODING_LEN>,
+        leaf_len: U32Variable,
+    ) -> Bytes32Variable {
+        let one = self.one::<Variable>();
+        let const_0x7f = self.constant::<Variable>(L::Field::from_canonical_u8(0x7f));
+        let const_0x80 = self.constant::<Variable>(L::Field::from_canonical_u8(0x80));
+        let const_0xc0 = self.constant::<Variable>(L::Field::from_canonical_u8(0xc0));
+        let const_0x80_u32 = self.constant::<U32Variable>(0x80);
+        let const_0xc0_u32 = self.constant::<U32Variable>(0xc0);
+        let const_0xf8_u32 = self.constant::<U32Variable>(0xf8);
+        let long_list_prefix = self.constant::<ByteVariable>(0xf8);
+        let t = self._true();
+
+        // The payload of the leaf is at most 68 bytes, so the list prefix is 0xc0 plus the
+        // payload length, or 0xf8 followed by the payload length.
+        let list_prefix = leaf[0].to_variable(self);
+        let list_prefix_u32 = U32Variable::from_variables_unsafe(&[list_prefix]);
+        let is_list = self.lte(const_0xc0_u32, list_prefix_u32);
+        let is_short_list = self.lt(list_prefix_u32, const_0xf8_u32);
+        let is_long_list = self.is_equal(leaf[0], long_list_prefix);
+        let is_valid_prefix = self.or(is_short_list, is_long_list);
+        let is_valid_prefix = self.and(is_list, is_valid_prefix);
+        self.assert_is_equal(is_valid_prefix, t);
+        let short_payload_len = self.sub(list_prefix, const_0xc0);
+        let long_payload_len = leaf[1].to_variable(self);
+        let payload_len = self.select(is_long_list, long_payload_len, short_payload_len);
+        let header_len = self.add(one, is_long_list.variable);
+        let expected_leaf_len = self.add(header_len, payload_len);
+        self.assert_is_equal(expected_leaf_len, leaf_len.variable);
+
+        // The path is a single byte below 0x80, or a string of at most 33 bytes.
+        let path_prefix = self.select_array(&leaf.as_slice()[1..3], is_long_list.variable);
+        let path_prefix = path_prefix.to_variable(self);
+        let path_prefix_u32 = U32Variable::from_variables_unsafe(&[path_prefix]);
+        let path_is_single_byte = self.lt(path_prefix_u32, const_0x80_u32);
+        let path_string_len = self.sub(path_prefix, const_0x7f);
+        let path_len = self.select(path_is_single_byte, one, path_string_len);
+        let value_start = self.add(header_len, path_len);
+
+        // The string holding the value ends the leaf, and is at most 34 bytes long.
+        let item = self.get_fixed_subarray::<ENCODING_LEN, 34>(leaf, value_start, leaf.as_slice());
+        let item_prefix = item[0].to_variable(self);
+        let item_prefix_u32 = U32Variable::from_variables_unsafe(&[item_prefix]);
+        let is_self_encoded = self.lt(item_prefix_u32, const_0x80_u32);
+        let encoding = self.select(
+            is_self_encoded,
+            ArrayVariable::<ByteVariable, 33>::from(item[0..33].to_vec()),
+            ArrayVariable::<ByteVariable, 33>::from(item[1..34].to_vec()),
+        );
+        let (value, encoding_len) =
+            self.decode_rlp_integer::<Bytes32Variable, 32>(encoding.as_slice());
+
+        // A string which is not its own encoding is prefixed by 0x80 plus its length.
+        let item_string_len = self.sub(item_prefix, const_0x80);
+        let expected_encoding_len = self.select(is_self_encoded, encoding_len, item_string_len);
+        self.assert_is_equal(expected_encoding_len, encoding_len);
+        let is_prefixed = self.not(is_self_encoded);
+        let item_len = self.add(encoding_len, is_prefixed.variable);
+        let value_end = self.add(value_start, item_len);
+        self.assert_is_equal(value_end, leaf_len.variable);
+
+        value
+    }
+
+    /// Verifies that `proof` is a path from `root` to a leaf at `keccak256(key)`, and returns the
+    /// leaf node together with its length.
+    ///
+    /// The value of the leaf may be longer than 32 bytes, such as the RLP encoding of an account in
+    /// the state trie, and is left to the caller to decode.
+    pub fn verify_mpt_proof_leaf<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
+        &mut self,
+        key: &[ByteVariable],
+        proof: ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: ArrayVariable<U32Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> (ArrayVariable<ByteVariable, ENCODING_LEN>, U32Variable) {
+        let hash_key = self.keccak256(key);
+        let key_path: ArrayVariable<ByteVariable, 64> =
+            hash_key.as_bytes().to_vec().to_nibbles(self).into();
+        self.verify_mpt_path(key_path, &proof, &len_nodes, root)
+    }
+
+    /// Private helper method to walk `proof` from `root` along `key_path`.
+    ///
+    /// The path must terminate at a node which consumes the whole key, since exclusion proofs are
+    /// not supported. Returns the node at which the path terminated and its length.
+    fn verify_mpt_path<const ENCODING_LEN: usize, const PROOF_LEN: usize>(
+        &mut self,
+        key_path: ArrayVariable<ByteVariable, 64>,
+        proof: &ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>,
+        len_nodes: &ArrayVariable<U32Variable, PROOF_LEN>,
+        root: Bytes32Variable,
+    ) -> (ArrayVariable<ByteVariable, ENCODING_LEN>, U32Variable) {
         const ELEMENT_LEN: usize = 32; // Maximum size of list element
         const LIST_LEN: usize = 17; // Maximum length of the list for each proof element
 
@@ -69,7 +178,9 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
         let const_64 = self.constant::<Variable>(L::Field::from_canonical_u8(64));
         let const_32 = self.constant::<U32Variable>(32u32);
-        let const_128 = self.constant::<U32Variable>(128u32);
+        let zero_nibble = self.constant::<ByteVariable>(0);
+        let t = self._true();
+        let false_val = self._false();
 
         let mut current_key_idx = self.zero::<Variable>();
         let mut finished = self._false();
@@ -79,14 +190,13 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             padded_root.push(self.constant::<ByteVariable>(0));
         }
         let mut current_node_id = ArrayVariable::<ByteVariable, ELEMENT_LEN>::new(padded_root);
-        let hash_key = self.keccak256_witness(&key.as_bytes());
-        let key_path: ArrayVariable<ByteVariable, 64> =
-            hash_key.as_bytes().to_vec().to_nibbles(self).into();
+        let mut leaf = proof[0].clone();
+        let mut leaf_len = len_nodes[0];
 
         for i in 0..PROOF_LEN {
             let current_node = proof[i].clone();
             let current_node_hash =
-                self.keccak256_variable_witness(current_node.as_slice(), len_nodes[i].variable);
+                self.keccak256_variable(current_node.as_slice(), len_nodes[i].variable);
 
             if i == 0 {
                 self.assert_is_equal(current_node_hash, root);
@@ -105,7 +215,6 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
                 let case_len_gt_32 = self.and(inter, hash_eq);
                 let equality_fulfilled = self.or(case_len_le_32, case_len_gt_32);
                 let checked_equality = self.or(equality_fulfilled, finished);
-                let t = self._true();
                 self.assert_is_equal(checked_equality, t);
             }
 
@@ -126,8 +235,11 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             let prefix_extension_even = self.is_equal(prefix, prefix_extension_even);
             let prefix_extension_odd = self.is_equal(prefix, prefix_extension_odd);
 
-            let offset_even = self.mul(prefix_extension_even.variable, two);
-            let offset_odd = self.mul(prefix_extension_odd.variable, one);
+            // The hex prefix is followed by a zero nibble if the path has an even length.
+            let prefix_even = self.or(prefix_extension_even, prefix_leaf_even);
+            let prefix_odd = self.or(prefix_extension_odd, prefix_leaf_odd);
+            let offset_even = self.mul(prefix_even.variable, two);
+            let offset_odd = self.mul(prefix_odd.variable, one);
             let offset = self.add(offset_even, offset_odd);
             let branch_key = self.select_array(key_path.clone().as_slice(), current_key_idx);
             let branch_key_variable = branch_key.to_variable(self);
@@ -156,8 +268,16 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
             let mut do_path_remainder_check = self.not(finished);
             do_path_remainder_check = self.and(do_path_remainder_check, is_leaf);
-            let d = self.or(prefix_extension_even, prefix_extension_odd);
-            do_path_remainder_check = self.and(do_path_remainder_check, d);
+            let d = self.or(prefix_even, prefix_odd);
+            // Leaves and extensions must have a valid hex prefix.
+            let has_invalid_prefix = self.not(d);
+            let has_invalid_prefix = self.and(do_path_remainder_check, has_invalid_prefix);
+            self.assert_is_equal(has_invalid_prefix, false_val);
+            let is_padding_zero = self.is_equal(path[1], zero_nibble);
+            let has_invalid_padding = self.and(do_path_remainder_check, prefix_even);
+            let is_padding_nonzero = self.not(is_padding_zero);
+            let has_invalid_padding = self.and(has_invalid_padding, is_padding_nonzero);
+            self.assert_is_equal(has_invalid_padding, false_val);
 
             let e = self.mul(decoded_element_lens[0], two);
             let f = self.mul(offset, do_path_remainder_check.variable);
@@ -180,31 +300,37 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             let prefix_leaf_odd_and_leaf = self.and(prefix_leaf_odd, is_leaf);
             let l = self.or(is_branch_and_key_terminated, prefix_leaf_even_and_leaf);
             let m = self.or(l, prefix_leaf_odd_and_leaf);
+            // The leaf is the node at which the path terminates for the first time.
+            let not_finished = self.not(finished);
+            let is_leaf_node = self.and(m, not_finished);
+            leaf = self.select(is_leaf_node, proof[i].clone(), leaf);
+            leaf_len = self.select(is_leaf_node, len_nodes[i], leaf_len);
             finished = self.or(finished, m);
+
+            // The path must consume the whole key where it terminates.
+            let key_consumed = self.is_equal(current_key_idx, const_64);
+            let not_leaf_node = self.not(is_leaf_node);
+            let key_check = self.or(not_leaf_node, key_consumed);
+            self.assert_is_equal(key_check, t);
         }
 
-        // Can be unsafe because `current_node_id` comes from a ByteVariable.
-        let current_node_id_u32 =
-            U32Variable::from_variables_unsafe(&[current_node_id[0].to_variable(self)]);
-        let current_node_len = self.sub(current_node_id_u32, const_128);
-        let lhs_offset = self.sub(const_32, current_node_len);
-
-        self.assert_subarray_equal(
-            &value.as_bytes(),
-            lhs_offset.variable,
-            current_node_id.as_slice(),
-            one,
-            current_node_len.variable,
-        );
+        // The proof must end in a leaf, exclusion proofs are not supported.
+        self.assert_is_equal(finished, t);
+
+        (leaf, leaf_len)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use ethers::types::H256;
+    use ethers::utils::keccak256;
     use log::debug;
 
     use super::super::utils::{read_fixture, EIP1186ProofResponse};
     use super::*;
+    use crate::frontend::eth::rlp::decoder::RLPItem;
+    use crate::frontend::eth::rlp::encoder::encode;
     use crate::frontend::eth::utils::u256_to_h256_be;
     use crate::prelude::DefaultBuilder;
     use crate::utils;
@@ -272,4 +398,107 @@ mod tests {
 
         let (_witness, mut _output) = circuit.mock_prove(&input);
     }
+
+    /// Returns the nodes of a trie made of a branch at the root and a single leaf, which holds
+    /// `value` at `leaf_key`.
+    fn single_leaf_trie_proof(leaf_key: [u8; 32], value: &[u8]) -> Vec<Vec<u8>> {
+        let nibbles = keccak256(leaf_key)
+            .iter()
+            .flat_map(|b| [b >> 4, b & 0xf])
+            .collect::<Vec<_>>();
+        // The leaf is below the first nibble, so its path has the 63 other ones and an odd prefix.
+        let mut leaf_path = vec![0x30 | nibbles[1]];
+        leaf_path.extend(nibbles[2..].chunks(2).map(|n| (n[0] << 4) | n[1]));
+        let leaf = encode(&RLPItem::List(vec![
+            RLPItem::String(leaf_path),
+            RLPItem::String(encode(&RLPItem::String(value.to_vec()))),
+        ]));
+        let mut children = (0..17).map(|_| RLPItem::String(vec![])).collect::<Vec<_>>();
+        children[nibbles[0] as usize] = RLPItem::String(keccak256(&leaf).to_vec());
+        let branch = encode(&RLPItem::List(children));
+        vec![branch, leaf]
+    }
+
+    fn test_verify_mpt_proof_single_leaf(wrong_leaf: bool, value: &[u8]) {
+        utils::setup_logger();
+        const ENCODING_LEN: usize = 600;
+        const PROOF_LEN: usize = 4;
+
+        let leaf_key = [0u8; 32];
+        let proof = single_leaf_trie_proof(leaf_key, value);
+        let root = H256(keccak256(&proof[0]));
+
+        // A key whose path starts with the same nibble reaches the leaf of `leaf_key`.
+        let key = if wrong_leaf {
+            (1u64..)
+                .map(|i| H256::from_low_u64_be(i).0)
+                .find(|key| keccak256(key)[0] >> 4 == keccak256(leaf_key)[0] >> 4)
+                .unwrap()
+        } else {
+            leaf_key
+        };
+        let mut value_bytes = [0u8; 32];
+        value_bytes[32 - value.len()..].copy_from_slice(value);
+
+        let (proof_as_fixed, lengths_as_fixed) =
+            transform_proof_to_padded::<ENCODING_LEN, PROOF_LEN>(proof);
+        let len_nodes_value = lengths_as_fixed
+            .iter()
+            .map(|x| *x as u32)
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let key_variable = builder.read::<Bytes32Variable>();
+        let proof_variable =
+            builder.read::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>();
+        let len_nodes = builder.read::<ArrayVariable<U32Variable, PROOF_LEN>>();
+        let root_variable = builder.read::<Bytes32Variable>();
+        let value_variable = builder.read::<Bytes32Variable>();
+        builder.verify_mpt_proof::<ENCODING_LEN, PROOF_LEN>(
+            key_variable,
+            proof_variable,
+            len_nodes,
+            root_variable,
+            value_variable,
+        );
+        let circuit = builder.mock_build();
+
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(H256(key));
+        input.write::<ArrayVariable<ArrayVariable<ByteVariable, ENCODING_LEN>, PROOF_LEN>>(
+            proof_as_fixed,
+        );
+        input.write::<ArrayVariable<U32Variable, PROOF_LEN>>(len_nodes_value);
+        input.write::<Bytes32Variable>(root);
+        input.write::<Bytes32Variable>(H256(value_bytes));
+
+        let (_witness, _output) = circuit.mock_prove(&input);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_mpt_proof_single_leaf_success() {
+        test_verify_mpt_proof_single_leaf(false, &[0x12, 0x34]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_mpt_proof_self_encoded_value() {
+        // A value below 0x80 is its own encoding, and the leaf ends with that single byte.
+        test_verify_mpt_proof_single_leaf(false, &[0x01]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_mpt_proof_full_value() {
+        // A value of 32 bytes is encoded as a 33 byte string, held in a 34 byte string.
+        test_verify_mpt_proof_single_leaf(false, &[0xff; 32]);
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_verify_mpt_proof_wrong_leaf() {
+        test_verify_mpt_proof_single_leaf(true, &[0x12, 0x34]);
+    }
 }
@@ -1,7 +1,8 @@
-use std::marker::PhantomData;
+use plonky2::field::types::Field;
 
-use super::generators::SubarrayEqualGenerator;
-use crate::prelude::{BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters, Variable};
+use crate::prelude::{
+    BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters, Target, Variable,
+};
 
 // Checks that a[a_offset:a_offset+len] = b[b_offset:b_offset+len]
 pub fn subarray_equal(a: &[u8], a_offset: usize, b: &[u8], b_offset: usize, len: usize) -> u8 {
@@ -14,7 +15,11 @@ pub fn subarray_equal(a: &[u8], a_offset: usize, b: &[u8], b_offset: usize, len:
 }
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
-    #[allow(unused_variables, dead_code)]
+    /// Returns whether `a[a_offset..a_offset + len] == b[b_offset..b_offset + len]`.
+    ///
+    /// Both arrays are looked up with random access gates, so they can have at most 64 elements.
+    /// A subarray which is out of bounds is never equal to the other one, and `len` must be at
+    /// most the length of the shortest array, which is constrained.
     pub fn subarray_equal(
         &mut self,
         a: &[ByteVariable],
@@ -23,10 +28,46 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         b_offset: Variable,
         len: Variable,
     ) -> BoolVariable {
-        todo!();
+        // The arrays are padded with distinct values which are not bytes, so that an out of
+        // bounds element never matches.
+        let n = a.len().min(b.len());
+        let a = self.subarray_lookup_table(a, 256);
+        let b = self.subarray_lookup_table(b, 257);
+
+        let zero = self.zero::<Variable>();
+        let mut is_equal = self._true();
+        let mut within = self._true();
+        let mut is_len_valid = self._false();
+        for i in 0..n {
+            let i_variable = self.constant::<Variable>(L::Field::from_canonical_usize(i));
+            let is_len_i = self.is_equal(len, i_variable);
+            is_len_valid = self.or(is_len_valid, is_len_i);
+            let not_len_i = self.not(is_len_i);
+            within = self.and(within, not_len_i);
+
+            // The indices are only used within the subarray, and are zero otherwise.
+            let a_idx = self.add(a_offset, i_variable);
+            let a_idx = self.select(within, a_idx, zero);
+            let b_idx = self.add(b_offset, i_variable);
+            let b_idx = self.select(within, b_idx, zero);
+            let a_i = Variable(self.api.random_access(a_idx.0, a.clone()));
+            let b_i = Variable(self.api.random_access(b_idx.0, b.clone()));
+            let is_equal_i = self.is_equal(a_i, b_i);
+            let outside = self.not(within);
+            let is_equal_i = self.or(is_equal_i, outside);
+            is_equal = self.and(is_equal, is_equal_i);
+        }
+        let n_variable = self.constant::<Variable>(L::Field::from_canonical_usize(n));
+        let is_len_n = self.is_equal(len, n_variable);
+        is_len_valid = self.or(is_len_valid, is_len_n);
+        let t = self._true();
+        self.assert_is_equal(is_len_valid, t);
+
+        is_equal
     }
 
-    #[allow(unused_variables, dead_code)]
+    /// Asserts that `a[a_offset..a_offset + len] == b[b_offset..b_offset + len]`, with the same
+    /// restrictions as `subarray_equal`.
     pub fn assert_subarray_equal(
         &mut self,
         a: &[ByteVariable],
@@ -35,19 +76,64 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         b_offset: Variable,
         len: Variable,
     ) {
-        // TODO: Need to constrain generator result.
-        let generator: SubarrayEqualGenerator<L, D> = SubarrayEqualGenerator {
-            a: a.to_vec(),
-            a_offset,
-            b: b.to_vec(),
-            b_offset,
-            len,
-            _phantom: PhantomData::<L>,
-        };
-        self.add_simple_generator(generator);
+        let is_equal = self.subarray_equal(a, a_offset, b, b_offset, len);
+        let t = self._true();
+        self.assert_is_equal(is_equal, t);
+    }
+
+    /// Private helper method to convert `array` to targets for a random access gate, padded to a
+    /// power of two with `padding`.
+    fn subarray_lookup_table(&mut self, array: &[ByteVariable], padding: u32) -> Vec<Target> {
+        assert!(
+            array.len() <= 64,
+            "arrays of at most 64 elements are supported"
+        );
+        let padding = self.constant::<Variable>(L::Field::from_canonical_u32(padding));
+        let mut table = array
+            .iter()
+            .map(|byte| byte.to_variable(self).0)
+            .collect::<Vec<_>>();
+        table.resize(array.len().next_power_of_two(), padding.0);
+        table
     }
 }
 
+#[cfg(test)]
 pub(crate) mod tests {
-    // TODO add a test for subarray_equal
+    use super::*;
+    use crate::prelude::{DefaultBuilder, GoldilocksField};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_subarray_equal() {
+        let mut builder = DefaultBuilder::new();
+        let a = (0..12u8)
+            .map(|x| builder.constant::<ByteVariable>(x))
+            .collect::<Vec<_>>();
+        let b = (4..24u8)
+            .map(|x| builder.constant::<ByteVariable>(x))
+            .collect::<Vec<_>>();
+        let cases = [
+            (4, 0, 8, true),
+            (6, 2, 6, true),
+            (0, 0, 0, true),
+            (4, 1, 8, false),
+            // b[6..10] is in bounds, but a[10..14] is not.
+            (10, 6, 4, false),
+        ];
+        for (a_offset, b_offset, len, expected) in cases {
+            let a_offset = builder.constant::<Variable>(F::from_canonical_usize(a_offset));
+            let b_offset = builder.constant::<Variable>(F::from_canonical_usize(b_offset));
+            let len = builder.constant::<Variable>(F::from_canonical_usize(len));
+            let is_equal = builder.subarray_equal(&a, a_offset, &b, b_offset, len);
+            let expected = builder.constant::<BoolVariable>(expected);
+            builder.assert_is_equal(is_equal, expected);
+        }
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
@@ -1,3 +1,6 @@
+use itertools::Itertools;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::iop::challenger::RecursiveChallenger;
 use serde::{Deserialize, Serialize};
 use starkyx::math::field::Field;
 use starkyx::math::prelude::PrimeField64;
@@ -5,9 +8,10 @@ use starkyx::math::prelude::PrimeField64;
 use super::utils::decode_padded_mpt_node;
 use crate::frontend::eth::rlp::utils::MAX_RLP_ITEM_SIZE;
 use crate::frontend::hint::simple::hint::Hint;
+use crate::frontend::vars::EvmVariable;
 use crate::prelude::{
-    ArrayVariable, BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters, ValueStream,
-    Variable, VariableStream,
+    ArrayVariable, BoolVariable, ByteVariable, CircuitBuilder, PlonkParameters, U32Variable,
+    ValueStream, Variable, VariableStream,
 };
 
 /// A Hint structure to decode an RLP-encoded string.
@@ -79,25 +83,396 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         let decoded_element_lens = output_stream.read::<ArrayVariable<Variable, LIST_LEN>>(self);
         let len_decoded_list = output_stream.read::<Variable>(self);
 
-        // TODO: here add verification logic constraints using `builder` to check that the decoded list is correct
+        self.verify_decoded_list(
+            &encoded,
+            len,
+            &decoded_list,
+            &decoded_element_lens,
+            len_decoded_list,
+            skip_computation,
+        );
 
         (decoded_list, decoded_element_lens, len_decoded_list)
     }
+
+    /// Asserts that the output of `DecodeHint` is the decoding of the MPT node `encoded[..len]`,
+    /// unless `skip_computation` is set.
+    ///
+    /// This is the circuit version of `verify_decoded_list` in utils.rs: the encoding is compared
+    /// to the re-encoding of the decoded list as polynomials evaluated at random challenges,
+    /// which are derived from both of them. The node must be a list of 2 or 17 strings of at most
+    /// `ELEMENT_LEN` bytes, whose unused bytes are zero. The only exception is the value of a leaf,
+    /// which may be longer, in which case it is not decoded and is the rest of the encoding.
+    fn verify_decoded_list<
+        const ENCODING_LEN: usize,
+        const LIST_LEN: usize,
+        const ELEMENT_LEN: usize,
+    >(
+        &mut self,
+        encoded: &ArrayVariable<ByteVariable, ENCODING_LEN>,
+        len: Variable,
+        decoded_list: &ArrayVariable<ArrayVariable<ByteVariable, ELEMENT_LEN>, LIST_LEN>,
+        decoded_element_lens: &ArrayVariable<Variable, LIST_LEN>,
+        len_decoded_list: Variable,
+        skip_computation: BoolVariable,
+    ) {
+        assert!(LIST_LEN >= 2 && ELEMENT_LEN < 55);
+        const NUM_LOOPS: usize = 3;
+        let t = self._true();
+        let f = self._false();
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+        let constant = |builder: &mut Self, x: usize| {
+            builder.constant::<Variable>(L::Field::from_canonical_usize(x))
+        };
+        let mut is_valid = t;
+
+        let is_branch = {
+            let branch_len = constant(self, 17);
+            self.is_equal(len_decoded_list, branch_len)
+        };
+        let is_leaf = {
+            let leaf_len = constant(self, 2);
+            self.is_equal(len_decoded_list, leaf_len)
+        };
+        let is_valid_list_len = self.or(is_branch, is_leaf);
+        is_valid = self.and(is_valid, is_valid_list_len);
+
+        // The prefix and the length of the encoding of every item, and the polynomial of its
+        // data, which is zero after its length.
+        let mut items = Vec::new();
+        let mut payload_len = zero;
+        let mut covered_len = zero;
+        let mut long_value_len = zero;
+        for i in 0..LIST_LEN {
+            let item_len = decoded_element_lens[i];
+            let data = decoded_list[i]
+                .as_slice()
+                .iter()
+                .map(|byte| byte.to_variable(self))
+                .collect::<Vec<_>>();
+
+            // within is whether j < item_len, and is_short whether item_len <= ELEMENT_LEN.
+            let mut within = t;
+            let mut padding_sum = zero;
+            for (j, byte) in data.iter().enumerate() {
+                let j_variable = constant(self, j);
+                let is_len_j = self.is_equal(item_len, j_variable);
+                let not_len_j = self.not(is_len_j);
+                within = self.and(within, not_len_j);
+                let padding = self.select(within, zero, *byte);
+                padding_sum = self.add(padding_sum, padding);
+            }
+            let is_padding_zero = self.is_equal(padding_sum, zero);
+            is_valid = self.and(is_valid, is_padding_zero);
+            let element_len = constant(self, ELEMENT_LEN);
+            let is_len_max = self.is_equal(item_len, element_len);
+            let not_within = self.not(within);
+            let is_short = self.or(not_within, is_len_max);
+
+            // Only the value of a leaf may be longer, in which case its data is zero. Its prefix is
+            // a single byte if it is at most 55 bytes long, and two bytes otherwise.
+            let (is_long, is_very_long) = if i == 1 {
+                let data_sum = data.iter().fold(zero, |acc, byte| self.add(acc, *byte));
+                let is_data_zero = self.is_equal(data_sum, zero);
+                let is_data_valid = self.or(is_short, is_data_zero);
+                is_valid = self.and(is_valid, is_data_valid);
+                let mut is_at_most_55 = is_short;
+                for j in ELEMENT_LEN + 1..=55 {
+                    let j_variable = constant(self, j);
+                    let is_len_j = self.is_equal(item_len, j_variable);
+                    is_at_most_55 = self.or(is_at_most_55, is_len_j);
+                }
+                let is_long = self.not(is_short);
+                let is_long_allowed = self.or(is_short, is_leaf);
+                is_valid = self.and(is_valid, is_long_allowed);
+                (is_long, self.not(is_at_most_55))
+            } else {
+                is_valid = self.and(is_valid, is_short);
+                (f, f)
+            };
+
+            // The items after the length of the list are empty.
+            let is_included = if i < 2 { t } else { is_branch };
+            let is_empty = self.is_equal(item_len, zero);
+            let is_included_or_empty = self.or(is_included, is_empty);
+            is_valid = self.and(is_valid, is_included_or_empty);
+
+            // A single byte below 0x80 is its own encoding. Otherwise, the prefix is 0x80 plus the
+            // length of the string, or 0xb8 followed by the length for long strings.
+            let is_len_one = self.is_equal(item_len, one);
+            let is_small_byte = self.not(decoded_list[i][0].as_be_bits()[0]);
+            let is_self_encoded = self.and(is_len_one, is_small_byte);
+            let const_0x80 = constant(self, 0x80);
+            let const_0xb8 = constant(self, 0xb8);
+            let short_prefix = self.add(const_0x80, item_len);
+            let prefix = self.select(is_very_long, const_0xb8, short_prefix);
+            let prefix_len = self.add(one, is_very_long.variable);
+            let string_encoding_len = self.add(prefix_len, item_len);
+            let encoding_len = self.select(is_self_encoded, one, string_encoding_len);
+            let included_encoding_len = self.mul(is_included.variable, encoding_len);
+            payload_len = self.add(payload_len, included_encoding_len);
+
+            // The encoding is known up to the data of a long value, which ends the encoding.
+            let known_len = self.select(is_long, prefix_len, encoding_len);
+            let included_known_len = self.mul(is_included.variable, known_len);
+            covered_len = self.add(covered_len, included_known_len);
+            let long_len = self.mul(is_long.variable, item_len);
+            long_value_len = self.add(long_value_len, long_len);
+
+            items.push((
+                data,
+                item_len,
+                prefix,
+                encoding_len,
+                is_self_encoded,
+                is_very_long,
+                is_included,
+            ));
+        }
+
+        // The list prefix is 0xc0 plus the payload length, 0xf8 followed by the payload length or
+        // 0xf9 followed by the two bytes of the payload length.
+        let header = (0..3)
+            .map(|k| encoded[k].to_variable(self))
+            .collect::<Vec<_>>();
+        let const_0xf8 = self.constant::<ByteVariable>(0xf8);
+        let const_0xf9 = self.constant::<ByteVariable>(0xf9);
+        let is_one_byte_len = self.is_equal(encoded[0], const_0xf8);
+        let is_two_byte_len = self.is_equal(encoded[0], const_0xf9);
+        let prefix_u32 = U32Variable::from_variables_unsafe(&[header[0]]);
+        let const_0xf8_u32 = self.constant::<U32Variable>(0xf8);
+        let is_short_list = self.lt(prefix_u32, const_0xf8_u32);
+        let is_valid_header = self.or(is_short_list, is_one_byte_len);
+        let is_valid_header = self.or(is_valid_header, is_two_byte_len);
+        is_valid = self.and(is_valid, is_valid_header);
+
+        let const_0xc0 = constant(self, 0xc0);
+        let const_256 = constant(self, 256);
+        let short_payload_len = self.sub(header[0], const_0xc0);
+        let two_byte_payload_len = self.mul(header[1], const_256);
+        let two_byte_payload_len = self.add(two_byte_payload_len, header[2]);
+        let expected_payload_len = self.select(is_one_byte_len, header[1], short_payload_len);
+        let expected_payload_len =
+            self.select(is_two_byte_len, two_byte_payload_len, expected_payload_len);
+        let is_payload_len_valid = self.is_equal(payload_len, expected_payload_len);
+        is_valid = self.and(is_valid, is_payload_len_valid);
+
+        let header_len = self.add(one, is_one_byte_len.variable);
+        let header_len = self.add(header_len, is_two_byte_len.variable);
+        let header_len = self.add(header_len, is_two_byte_len.variable);
+        let expected_len = self.add(header_len, payload_len);
+        let is_len_valid = self.is_equal(expected_len, len);
+        is_valid = self.and(is_valid, is_len_valid);
+
+        // Only the first `covered_len` bytes of the encoding are compared, the rest is the data of
+        // a long value.
+        let covered_len = self.add(header_len, covered_len);
+        let expected_covered_len = self.sub(len, long_value_len);
+        let is_covered_len_valid = self.is_equal(covered_len, expected_covered_len);
+        is_valid = self.and(is_valid, is_covered_len_valid);
+        let mut within = t;
+        let mut covered_encoding = Vec::new();
+        for k in 0..ENCODING_LEN {
+            let k_variable = constant(self, k);
+            let is_len_k = self.is_equal(covered_len, k_variable);
+            let not_len_k = self.not(is_len_k);
+            within = self.and(within, not_len_k);
+            let byte = encoded[k].to_variable(self);
+            covered_encoding.push(self.select(within, byte, zero));
+        }
+
+        // The challenges are derived from the encoding and the decoded list.
+        let mut seed = encoded.as_slice().to_vec();
+        for item in decoded_list.as_slice() {
+            seed.extend_from_slice(item.as_slice());
+        }
+        let mut seed_targets = seed
+            .chunks(7)
+            .map(|chunk| {
+                let bits = chunk.iter().flat_map(|x| x.as_bool_targets()).collect_vec();
+                self.api.le_sum(bits.iter())
+            })
+            .collect_vec();
+        seed_targets.push(len.0);
+        seed_targets.extend(decoded_element_lens.as_slice().iter().map(|x| x.0));
+        seed_targets.push(len_decoded_list.0);
+        let mut challenger = RecursiveChallenger::<L::Field, PoseidonHash, D>::new(&mut self.api);
+        challenger.observe_elements(&seed_targets);
+        let challenges = challenger
+            .get_n_challenges(&mut self.api, NUM_LOOPS)
+            .into_iter()
+            .map(Variable::from)
+            .collect_vec();
+
+        for r in challenges {
+            let mut r_pows = vec![one];
+            for _ in 0..ELEMENT_LEN + 2 {
+                let r_pow = self.mul(*r_pows.last().unwrap(), r);
+                r_pows.push(r_pow);
+            }
+
+            let mut encoding_poly = zero;
+            for byte in covered_encoding.iter().rev() {
+                encoding_poly = self.mul(encoding_poly, r);
+                encoding_poly = self.add(encoding_poly, *byte);
+            }
+
+            // The re-encoding of the items, shifted by the encoding length of the previous ones.
+            let mut body_poly = zero;
+            let mut shift = one;
+            for (
+                data,
+                item_len,
+                prefix,
+                encoding_len,
+                is_self_encoded,
+                is_very_long,
+                is_included,
+            ) in items.iter()
+            {
+                let mut data_poly = zero;
+                for byte in data.iter().rev() {
+                    data_poly = self.mul(data_poly, r);
+                    data_poly = self.add(data_poly, *byte);
+                }
+                let long_len = self.mul(is_very_long.variable, *item_len);
+                let string_poly = self.add(data_poly, long_len);
+                let string_poly = self.mul(string_poly, r);
+                let string_poly = self.add(string_poly, *prefix);
+                let item_poly = self.select(*is_self_encoded, data_poly, string_poly);
+                let item_poly = self.mul(item_poly, shift);
+                let item_poly = self.mul(item_poly, is_included.variable);
+                body_poly = self.add(body_poly, item_poly);
+
+                let item_shift = self.select_array(&r_pows, *encoding_len);
+                shift = self.mul(shift, item_shift);
+            }
+
+            let header_poly = self.mul(header[2], r_pows[2]);
+            let header_poly = self.mul(header_poly, is_two_byte_len.variable);
+            let header_poly_1 = self.mul(header[1], r);
+            let has_length_byte = self.or(is_one_byte_len, is_two_byte_len);
+            let header_poly_1 = self.mul(header_poly_1, has_length_byte.variable);
+            let header_poly = self.add(header_poly, header_poly_1);
+            let header_poly = self.add(header_poly, header[0]);
+            let header_shift = self.select_array(&r_pows[..4], header_len);
+            let claim_poly = self.mul(body_poly, header_shift);
+            let claim_poly = self.add(claim_poly, header_poly);
+
+            let is_poly_equal = self.is_equal(claim_poly, encoding_poly);
+            is_valid = self.and(is_valid, is_poly_equal);
+        }
+
+        let is_valid = self.or(is_valid, skip_computation);
+        self.assert_is_equal(is_valid, t);
+    }
+
+    /// Decodes the RLP encoding of an integer of at most `N` bytes at the start of `data`, which
+    /// must hold at least `N + 1` bytes. Returns the integer as a `V`, which must be at least `N`
+    /// bytes long, and the length of its encoding.
+    pub fn decode_rlp_integer<V: EvmVariable, const N: usize>(
+        &mut self,
+        data: &[ByteVariable],
+    ) -> (V, Variable) {
+        let nb_bytes = V::nb_bytes::<L, D>();
+        assert!(N <= nb_bytes && data.len() > N);
+        let zero = self.zero::<Variable>();
+        let one = self.one::<Variable>();
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let const_0x80 = self.constant::<Variable>(L::Field::from_canonical_u8(0x80));
+
+        // A single byte below 0x80 is its own encoding, otherwise the encoding is 0x80 plus the
+        // length of the integer, followed by its big-endian bytes.
+        let prefix = data[0].to_variable(self);
+        let prefix_u32 = U32Variable::from_variables_unsafe(&[prefix]);
+        let const_0x80_u32 = self.constant::<U32Variable>(0x80);
+        let is_single_byte = self.lt(prefix_u32, const_0x80_u32);
+        let string_len = self.sub(prefix, const_0x80);
+        let len = self.select(is_single_byte, one, string_len);
+        let start = self.select(is_single_byte, zero, one);
+        let encoding_len = self.add(start, len);
+
+        // has_byte[k] is whether the integer has more than k bytes, which also checks len <= N.
+        let mut has_byte = vec![self._false(); N];
+        let mut is_len_valid = self._false();
+        for i in 0..=N {
+            let i_variable = self.constant::<Variable>(L::Field::from_canonical_usize(i));
+            let is_len_i = self.is_equal(len, i_variable);
+            is_len_valid = self.or(is_len_valid, is_len_i);
+            for h in has_byte.iter_mut().take(i) {
+                *h = self.or(*h, is_len_i);
+            }
+        }
+        let t = self._true();
+        self.assert_is_equal(is_len_valid, t);
+
+        // The k-th least significant byte of the integer is data[start + len - 1 - k].
+        let last = self.add(start, len);
+        let last = self.sub(last, one);
+        let mut bytes = vec![zero_byte; nb_bytes];
+        for (k, h) in has_byte.into_iter().enumerate() {
+            let k_variable = self.constant::<Variable>(L::Field::from_canonical_usize(k));
+            let idx = self.sub(last, k_variable);
+            let byte = self.select_array(&data[..=N], idx);
+            bytes[nb_bytes - 1 - k] = self.select(h, byte, zero_byte);
+        }
+
+        (V::decode(self, &bytes), encoding_len)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::backend::circuit::DefaultParameters;
+    use crate::frontend::uint::uint64::U64Variable;
     use crate::prelude::{DefaultBuilder, GoldilocksField};
     use crate::utils::bytes;
 
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_decode_rlp_integer() {
+        let mut builder: CircuitBuilder<DefaultParameters, 2> = DefaultBuilder::new();
+
+        let encodings: [&[u8]; 4] = [
+            &[0x80],
+            &[0x0f],
+            &[0x82, 0x04, 0x00],
+            &[0x88, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+        ];
+        let expected = [
+            (0u64, 1u64),
+            (0x0f, 1),
+            (0x0400, 3),
+            (0x0102030405060708, 9),
+        ];
+        for (encoding, (value, encoding_len)) in encodings.iter().zip(expected) {
+            let mut data = encoding.to_vec();
+            data.resize(9, 0xff);
+            let data = data
+                .into_iter()
+                .map(|b| builder.constant::<ByteVariable>(b))
+                .collect::<Vec<_>>();
+            let (decoded, decoded_len) = builder.decode_rlp_integer::<U64Variable, 8>(&data);
+            let value = builder.constant::<U64Variable>(value);
+            builder.assert_is_equal(decoded, value);
+            let encoding_len = builder.constant::<Variable>(F::from_canonical_u64(encoding_len));
+            builder.assert_is_equal(decoded_len, encoding_len);
+        }
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
     #[test]
 
     fn test_rlp_decode_hint() {
         let mut builder: CircuitBuilder<DefaultParameters, 2> = DefaultBuilder::new();
 
-        type F = GoldilocksField;
         const ENCODING_LEN: usize = 600;
         const LIST_LEN: usize = 17;
 
@@ -163,7 +538,6 @@ mod tests {
     fn test_rlp_decode_hint_short_encoding() {
         let mut builder: CircuitBuilder<DefaultParameters, 2> = DefaultBuilder::new();
 
-        type F = GoldilocksField;
         const ENCODING_LEN: usize = 600;
         const LIST_LEN: usize = 2;
 
@@ -228,4 +602,72 @@ mod tests {
             }
         }
     }
+
+    fn test_verify_decoded_list(rlp_encoding: &[u8], tamper: bool) {
+        const ENCODING_LEN: usize = 600;
+        const LIST_LEN: usize = 17;
+
+        let mut builder: CircuitBuilder<DefaultParameters, 2> = DefaultBuilder::new();
+        let encoded = builder.read::<ArrayVariable<ByteVariable, ENCODING_LEN>>();
+        let len = builder.read::<Variable>();
+        let decoded_list = builder
+            .read::<ArrayVariable<ArrayVariable<ByteVariable, MAX_RLP_ITEM_SIZE>, LIST_LEN>>();
+        let decoded_element_lens = builder.read::<ArrayVariable<Variable, LIST_LEN>>();
+        let len_decoded_list = builder.read::<Variable>();
+        let skip_computation = builder._false();
+        builder.verify_decoded_list(
+            &encoded,
+            len,
+            &decoded_list,
+            &decoded_element_lens,
+            len_decoded_list,
+            skip_computation,
+        );
+        let circuit = builder.mock_build();
+
+        let mut encoding_fixed_size = [0u8; ENCODING_LEN];
+        encoding_fixed_size[..rlp_encoding.len()].copy_from_slice(rlp_encoding);
+        let mut mpt_node = decode_padded_mpt_node(&encoding_fixed_size, rlp_encoding.len(), false);
+        if tamper {
+            mpt_node.data[0].data[0] ^= 1;
+        }
+
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, ENCODING_LEN>>(encoding_fixed_size.to_vec());
+        input.write::<Variable>(F::from_canonical_usize(rlp_encoding.len()));
+        input.write::<ArrayVariable<ArrayVariable<ByteVariable, MAX_RLP_ITEM_SIZE>, LIST_LEN>>(
+            mpt_node.data.iter().map(|x| x.data.to_vec()).collect(),
+        );
+        input.write::<ArrayVariable<Variable, LIST_LEN>>(
+            mpt_node
+                .data
+                .iter()
+                .map(|x| F::from_canonical_usize(x.len))
+                .collect(),
+        );
+        input.write::<Variable>(F::from_canonical_usize(mpt_node.len));
+        let (_witness, _output) = circuit.mock_prove(&input);
+    }
+
+    #[test]
+    fn test_verify_decoded_list_success() {
+        // A branch node, an extension node and the leaf of an account, whose value is longer than
+        // an item.
+        let encodings: [Vec<u8>; 3] = [
+            bytes!("0xf851808080808080808080808080a035d937961d73f8a0eea9ae41b2f4cbb73c1d2c0666ea35f1ae05c43b5896b1098080a0b286218777cc1883b08227a900f3b4b876e52de06e342560852a263838d4c8a280"),
+            bytes!("0xe482006fa0188d1100731419827900267bf4e6ea6d428fa5a67656e021485d1f6c89e69be6"),
+            bytes!("0xf8669d205a0b5b32d2bce36ccb3dc0894bd4c31e8e577d7e3a1d51150128e6deb846f8440180a0073d71569b4b986bc20b6921dbbc1b74145588f765627dd5e566d65a6b7b33cca0b9c1c929064cd21734c102a698e68bf617feefcfa5a9f62407c45401546736bf"),
+        ];
+        for encoding in encodings.iter() {
+            test_verify_decoded_list(encoding, false);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_verify_decoded_list_tampered() {
+        let encoding: Vec<u8> =
+            bytes!("0xe482006fa0188d1100731419827900267bf4e6ea6d428fa5a67656e021485d1f6c89e69be6");
+        test_verify_decoded_list(&encoding, true);
+    }
 }
@@ -0,0 +1,79 @@
+//! This file implements RLP encoder, the inverse of the decoder in decoder.rs.
+//!
+//! Reference: https://ethereum.org/en/developers/docs/data-structures-and-encoding/rlp/
+
+use super::decoder::RLPItem;
+
+/// Private helper method to encode the prefix of a string or a list whose payload has `len` bytes.
+/// `offset` is 0x80 for strings and 0xC0 for lists.
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len <= 55 {
+        vec![offset + len as u8]
+    } else {
+        let length_data = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|x| *x == 0)
+            .collect::<Vec<_>>();
+        let mut prefix = vec![offset + 55 + length_data.len() as u8];
+        prefix.extend(length_data);
+        prefix
+    }
+}
+
+/// Takes an item and returns its RLP encoding.
+pub fn encode(item: &RLPItem) -> Vec<u8> {
+    match item {
+        RLPItem::String(data) => {
+            if data.len() == 1 && data[0] < 0x80 {
+                // A single byte less than 0x80 is its own RLP encoding.
+                data.clone()
+            } else {
+                let mut encoding = encode_length(data.len(), 0x80);
+                encoding.extend(data);
+                encoding
+            }
+        }
+        RLPItem::List(items) => {
+            let payload = items.iter().flat_map(encode).collect::<Vec<_>>();
+            let mut encoding = encode_length(payload.len(), 0xC0);
+            encoding.extend(payload);
+            encoding
+        }
+    }
+}
+
+/// Returns the item of an integer, i.e. its big-endian bytes without leading zeros.
+pub fn integer_item(bytes: &[u8]) -> RLPItem {
+    RLPItem::String(bytes.iter().copied().skip_while(|x| *x == 0).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::eth::rlp::decoder::decode;
+
+    #[test]
+    fn test_encoder() {
+        assert_eq!(encode(&RLPItem::String(vec![])), vec![0x80]);
+        assert_eq!(encode(&RLPItem::List(vec![])), vec![0xc0]);
+        assert_eq!(encode(&integer_item(&[0, 0, 0x0f])), vec![0x0f]);
+        assert_eq!(encode(&integer_item(&[0x04, 0x00])), vec![0x82, 0x04, 0x00]);
+        assert_eq!(
+            encode(&RLPItem::List(vec![
+                RLPItem::String(b"cat".to_vec()),
+                RLPItem::String(b"dog".to_vec())
+            ])),
+            b"\xc8\x83cat\x83dog".to_vec()
+        );
+
+        let long_item = RLPItem::List(vec![
+            RLPItem::String(vec![0xaa; 56]),
+            RLPItem::List(vec![RLPItem::String(vec![0x01]), RLPItem::String(vec![])]),
+            RLPItem::String(vec![0x55; 300]),
+        ]);
+        let encoding = encode(&long_item);
+        assert_eq!(encoding[..3], [0xf9, 0x01, 0x6c]);
+        assert_eq!(decode(&encoding), long_item);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod builder;
 pub mod decoder;
+pub mod encoder;
 pub mod utils;
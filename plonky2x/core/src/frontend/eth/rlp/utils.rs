@@ -37,8 +37,14 @@ impl From<&RLPItem> for RLPItemFixedSize {
                 // Copy data into self.data.
                 // If data.len() > MAX_RLP_ITEM_SIZE, panic.
                 let len = data.len();
+                assert!(
+                    len <= MAX_RLP_ITEM_SIZE,
+                    "item of {} bytes does not fit in {} bytes",
+                    len,
+                    MAX_RLP_ITEM_SIZE
+                );
                 let mut array = [0; MAX_RLP_ITEM_SIZE];
-                array[..data.len()].copy_from_slice(data);
+                array[..len].copy_from_slice(data);
 
                 RLPItemFixedSize { data: array, len }
             }
@@ -72,7 +78,20 @@ impl From<RLPItem> for MPTNodeFixedSize {
                 assert!(ls.len() == 2 || ls.len() == 17);
                 let mut res = MPTNodeFixedSize::default();
                 for (i, item) in ls.iter().enumerate() {
-                    res.data[i] = RLPItemFixedSize::from(item);
+                    res.data[i] = match item {
+                        // The value of a leaf may not fit in an item, such as an account in the
+                        // state trie. Only its length is kept, and the value is read from the
+                        // encoding of the node instead.
+                        RLPItem::String(data)
+                            if ls.len() == 2 && i == 1 && data.len() > MAX_RLP_ITEM_SIZE =>
+                        {
+                            RLPItemFixedSize {
+                                data: [0; MAX_RLP_ITEM_SIZE],
+                                len: data.len(),
+                            }
+                        }
+                        _ => RLPItemFixedSize::from(item),
+                    };
                 }
                 res.len = ls.len();
                 res
@@ -279,4 +298,23 @@ mod tests {
         });
         assert!(result.is_err());
     }
+
+    #[test]
+    #[should_panic]
+    fn test_rlp_item_fixed_size_too_long() {
+        let _ = RLPItemFixedSize::from(&RLPItem::String(vec![0xaa; MAX_RLP_ITEM_SIZE + 1]));
+    }
+
+    #[test]
+    fn test_rlp_decode_mpt_node_long_leaf_value() {
+        // The leaf of an account, whose value is the 70 byte encoding of the account.
+        let rlp_encoding: Vec<u8> = bytes!("0xf8669d205a0b5b32d2bce36ccb3dc0894bd4c31e8e577d7e3a1d51150128e6deb846f8440180a0073d71569b4b986bc20b6921dbbc1b74145588f765627dd5e566d65a6b7b33cca0b9c1c929064cd21734c102a698e68bf617feefcfa5a9f62407c45401546736bf");
+
+        let node = decode_padded_mpt_node(&rlp_encoding, rlp_encoding.len(), false);
+        assert_eq!(node.len, 2);
+        assert_eq!(node.data[0].len, 29);
+        assert_eq!(node.data[0].data[..29], rlp_encoding[3..32]);
+        assert_eq!(node.data[1].len, 70);
+        assert_eq!(node.data[1].data, [0; MAX_RLP_ITEM_SIZE]);
+    }
 }
@@ -1,16 +1,34 @@
-use ethers::types::Address;
+use plonky2::field::types::Field;
 
 use super::generators::{
-    EthBlockGenerator, EthLogGenerator, EthStorageKeyGenerator, EthStorageProofHint,
+    EthAccountProofHint, EthBlockGenerator, EthFullStorageProofHint, EthHeaderRlpHint,
+    EthLogGenerator, EthStorageKeyGenerator, EthStorageProofHint, MAX_HEADER_LEN, MAX_PROOF_LEN,
+    MAX_PROOF_NODE_LEN,
 };
-use super::vars::{EthAccountVariable, EthHeaderVariable, EthLogVariable};
+use super::vars::{EthAccountVariable, EthFullHeaderVariable, EthHeaderVariable, EthLogVariable};
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::uint::uint256::U256Variable;
-use crate::frontend::vars::{Bytes32Variable, VariableStream};
+use crate::frontend::uint::uint64::U64Variable;
+use crate::frontend::vars::{
+    ArrayVariable, ByteVariable, Bytes32Variable, BytesVariable, EvmVariable, OptionVariable,
+    U32Variable, Variable, VariableStream,
+};
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes the storage slot of `map_key` in the mapping stored at `mapping_location`, i.e.
+    /// `keccak256(map_key ++ mapping_location)`.
+    pub fn get_storage_key_at(
+        &mut self,
+        mapping_location: U256Variable,
+        map_key: Bytes32Variable,
+    ) -> Bytes32Variable {
+        let mut input = map_key.as_bytes().to_vec();
+        input.extend(mapping_location.encode(self));
+        self.keccak256(&input)
+    }
+
     pub fn get_storage_key_at_witness(
         &mut self,
         mapping_location: U256Variable,
@@ -22,6 +40,291 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         value
     }
 
+    /// Returns the value of `storage_key` in the storage of `address` at the block `block_hash`.
+    ///
+    /// The header of the block is checked against `block_hash`, the account proof against the
+    /// state root of the header and the storage proof against the storage root of the account,
+    /// and the value is decoded from the leaf of the storage proof. Some valid slots cannot be
+    /// proven:
+    /// - Exclusion proofs are not supported, so the account must exist and the value of the slot
+    ///   must be non-zero.
+    /// - Each proof must have at most `MAX_PROOF_LEN` nodes of at most `MAX_PROOF_NODE_LEN` bytes.
+    pub fn eth_get_storage_at(
+        &mut self,
+        block_hash: Bytes32Variable,
+        address: AddressVariable,
+        storage_key: Bytes32Variable,
+    ) -> Bytes32Variable {
+        let state_root = self.eth_get_state_root(block_hash);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+        input_stream.write(&address);
+        input_stream.write(&storage_key);
+
+        let hint = EthFullStorageProofHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let account_proof = output_stream
+            .read::<ArrayVariable<ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>, MAX_PROOF_LEN>>(
+                self,
+            );
+        let account_proof_lens =
+            output_stream.read::<ArrayVariable<U32Variable, MAX_PROOF_LEN>>(self);
+        let storage_proof = output_stream
+            .read::<ArrayVariable<ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>, MAX_PROOF_LEN>>(
+                self,
+            );
+        let storage_proof_lens =
+            output_stream.read::<ArrayVariable<U32Variable, MAX_PROOF_LEN>>(self);
+
+        let (leaf, leaf_len) = self.verify_mpt_proof_leaf(
+            &address.0 .0,
+            account_proof,
+            account_proof_lens,
+            state_root,
+        );
+
+        let (storage_hash, _) = self.eth_decode_account_hashes(&leaf, leaf_len);
+
+        let (leaf, leaf_len) = self.verify_mpt_proof_leaf(
+            &storage_key.as_bytes(),
+            storage_proof,
+            storage_proof_lens,
+            storage_hash,
+        );
+        self.decode_mpt_leaf_value(&leaf, leaf_len)
+    }
+
+    /// Returns the header of the block `block_hash`, decoded from its RLP encoding, which is
+    /// checked against the block hash.
+    pub fn eth_get_block_by_hash(&mut self, block_hash: Bytes32Variable) -> EthFullHeaderVariable {
+        let (header, header_len) = self.eth_get_header_rlp(block_hash);
+        self.decode_eth_header(&header, header_len)
+    }
+
+    /// Decodes the RLP encoding of a block header of `header_len` bytes, padded to
+    /// `MAX_HEADER_LEN`.
+    pub fn decode_eth_header(
+        &mut self,
+        header: &ArrayVariable<ByteVariable, MAX_HEADER_LEN>,
+        header_len: U32Variable,
+    ) -> EthFullHeaderVariable {
+        // The fields up to the bloom have a fixed size, and the others are encoded in the tail,
+        // which is at most 294 bytes long.
+        const TAIL_START: usize = 448;
+        const TAIL_LEN: usize = 294;
+
+        let hash_prefix = self.constant::<ByteVariable>(0xa0);
+        let address_prefix = self.constant::<ByteVariable>(0x94);
+        let nonce_prefix = self.constant::<ByteVariable>(0x88);
+        let const_256 = self.constant::<Variable>(L::Field::from_canonical_u16(256));
+        let const_3 = self.constant::<Variable>(L::Field::from_canonical_u8(3));
+        let const_9 = self.constant::<Variable>(L::Field::from_canonical_u8(9));
+        let const_33 = self.constant::<Variable>(L::Field::from_canonical_u8(33));
+        let t = self._true();
+
+        // The header is a list with a two byte length.
+        let list_prefix = self.constant::<ByteVariable>(0xf9);
+        self.assert_is_equal(header[0], list_prefix);
+        let payload_len_high = header[1].to_variable(self);
+        let payload_len_low = header[2].to_variable(self);
+        let payload_len = self.mul(payload_len_high, const_256);
+        let payload_len = self.add(payload_len, payload_len_low);
+        let expected_header_len = self.add(payload_len, const_3);
+        self.assert_is_equal(expected_header_len, header_len.variable);
+
+        for i in [3, 36, 90, 123, 156] {
+            self.assert_is_equal(header[i], hash_prefix);
+        }
+        self.assert_is_equal(header[69], address_prefix);
+        let bloom_prefix = [0xb9, 0x01, 0x00].map(|b| self.constant::<ByteVariable>(b));
+        for (i, prefix) in bloom_prefix.into_iter().enumerate() {
+            self.assert_is_equal(header[189 + i], prefix);
+        }
+
+        let mut coinbase = [header[70]; 20];
+        coinbase.copy_from_slice(&header[70..90]);
+        let mut bloom = [header[192]; 256];
+        bloom.copy_from_slice(&header[192..448]);
+
+        let tail = ArrayVariable::<ByteVariable, TAIL_LEN>::new(
+            header[TAIL_START..TAIL_START + TAIL_LEN].to_vec(),
+        );
+        let const_tail_start = self.constant::<U32Variable>(TAIL_START as u32);
+        let tail_len = self.sub(header_len, const_tail_start);
+        let mut offset = self.zero::<Variable>();
+
+        let encoding = self.get_fixed_subarray::<TAIL_LEN, 33>(&tail, offset, tail.as_slice());
+        let (difficulty, len) = self.decode_rlp_integer::<U256Variable, 32>(encoding.as_slice());
+        offset = self.add(offset, len);
+        let mut u64_fields = Vec::new();
+        for _ in 0..4 {
+            let encoding = self.get_fixed_subarray::<TAIL_LEN, 9>(&tail, offset, tail.as_slice());
+            let (value, len) = self.decode_rlp_integer::<U64Variable, 8>(encoding.as_slice());
+            u64_fields.push(value);
+            offset = self.add(offset, len);
+        }
+
+        // The extra data is a single byte below 0x80, or a string of at most 32 bytes.
+        let encoding = self.get_fixed_subarray::<TAIL_LEN, 33>(&tail, offset, tail.as_slice());
+        let extra_prefix = encoding[0].to_variable(self);
+        let extra_prefix_u32 = U32Variable::from_variables_unsafe(&[extra_prefix]);
+        let const_0x80 = self.constant::<U32Variable>(0x80);
+        let const_0xa0 = self.constant::<U32Variable>(0xa0);
+        let extra_is_single_byte = self.lt(extra_prefix_u32, const_0x80);
+        let extra_is_valid = self.lte(extra_prefix_u32, const_0xa0);
+        self.assert_is_equal(extra_is_valid, t);
+        let one_u32 = self.constant::<U32Variable>(1);
+        let extra_string_len =
+            U32Variable::from_variables_unsafe(&[self.sub(extra_prefix, const_0x80.variable)]);
+        let extra_len = self.select(extra_is_single_byte, one_u32, extra_string_len);
+        let zero_byte = self.constant::<ByteVariable>(0);
+        let not_single_byte = self.not(extra_is_single_byte);
+        let mut extra = [zero_byte; 32];
+        for (k, byte) in extra.iter_mut().enumerate() {
+            let k_u32 = self.constant::<U32Variable>(k as u32);
+            let in_string = self.lt(k_u32, extra_len);
+            let string_byte = self.select(in_string, encoding[k + 1], zero_byte);
+            *byte = if k == 0 {
+                self.select(extra_is_single_byte, encoding[0], string_byte)
+            } else {
+                self.select(not_single_byte, string_byte, zero_byte)
+            };
+        }
+        let one = self.one::<Variable>();
+        let extra_string_encoding_len = self.add(extra_len.variable, one);
+        let extra_encoding_len = self.select(extra_is_single_byte, one, extra_string_encoding_len);
+        offset = self.add(offset, extra_encoding_len);
+
+        let encoding = self.get_fixed_subarray::<TAIL_LEN, 33>(&tail, offset, tail.as_slice());
+        self.assert_is_equal(encoding[0], hash_prefix);
+        let mix_digest: Bytes32Variable = encoding[1..33].into();
+        offset = self.add(offset, const_33);
+
+        let encoding = self.get_fixed_subarray::<TAIL_LEN, 9>(&tail, offset, tail.as_slice());
+        self.assert_is_equal(encoding[0], nonce_prefix);
+        let mut nonce = [zero_byte; 8];
+        nonce.copy_from_slice(&encoding[1..9]);
+        offset = self.add(offset, const_9);
+
+        // The fields of later forks are present if the header does not end before them.
+        let offset_u32 = U32Variable::from_variables_unsafe(&[offset]);
+        let has_base_fee = self.lt(offset_u32, tail_len);
+        let encoding = self.get_fixed_subarray::<TAIL_LEN, 33>(&tail, offset, tail.as_slice());
+        let (base_fee, len) = self.decode_rlp_integer::<U256Variable, 32>(encoding.as_slice());
+        let base_fee = OptionVariable::new(self, has_base_fee, base_fee);
+        let len = self.mul(has_base_fee.variable, len);
+        offset = self.add(offset, len);
+
+        let withdrawals_hash = self.decode_optional_header_hash(&tail, &mut offset, tail_len);
+
+        let mut blob_gas_fields = Vec::new();
+        for _ in 0..2 {
+            let offset_u32 = U32Variable::from_variables_unsafe(&[offset]);
+            let is_some = self.lt(offset_u32, tail_len);
+            let encoding = self.get_fixed_subarray::<TAIL_LEN, 9>(&tail, offset, tail.as_slice());
+            let (value, len) = self.decode_rlp_integer::<U64Variable, 8>(encoding.as_slice());
+            blob_gas_fields.push(OptionVariable::new(self, is_some, value));
+            let len = self.mul(is_some.variable, len);
+            offset = self.add(offset, len);
+        }
+
+        let parent_beacon_root = self.decode_optional_header_hash(&tail, &mut offset, tail_len);
+        let requests_hash = self.decode_optional_header_hash(&tail, &mut offset, tail_len);
+
+        // The fields must fill the header exactly.
+        self.assert_is_equal(offset, tail_len.variable);
+
+        EthFullHeaderVariable {
+            parent_hash: header[4..36].into(),
+            uncle_hash: header[37..69].into(),
+            coinbase: AddressVariable(BytesVariable(coinbase)),
+            root: header[91..123].into(),
+            tx_hash: header[124..156].into(),
+            receipt_hash: header[157..189].into(),
+            bloom: BytesVariable(bloom),
+            difficulty,
+            number: u64_fields[0],
+            gas_limit: u64_fields[1],
+            gas_used: u64_fields[2],
+            time: u64_fields[3],
+            extra: BytesVariable(extra),
+            extra_len,
+            mix_digest,
+            nonce: BytesVariable(nonce),
+            base_fee,
+            withdrawals_hash,
+            blob_gas_used: blob_gas_fields[0],
+            excess_blob_gas: blob_gas_fields[1],
+            parent_beacon_root,
+            requests_hash,
+        }
+    }
+
+    /// Private helper method to decode an optional hash at `offset` in the tail of a header, and
+    /// advance `offset` past it if it is present.
+    fn decode_optional_header_hash<const TAIL_LEN: usize>(
+        &mut self,
+        tail: &ArrayVariable<ByteVariable, TAIL_LEN>,
+        offset: &mut Variable,
+        tail_len: U32Variable,
+    ) -> OptionVariable<Bytes32Variable> {
+        let hash_prefix = self.constant::<ByteVariable>(0xa0);
+        let const_33 = self.constant::<Variable>(L::Field::from_canonical_u8(33));
+
+        let offset_u32 = U32Variable::from_variables_unsafe(&[*offset]);
+        let is_some = self.lt(offset_u32, tail_len);
+        let encoding = self.get_fixed_subarray::<TAIL_LEN, 33>(tail, *offset, tail.as_slice());
+        let is_hash = self.is_equal(encoding[0], hash_prefix);
+        let is_none = self.not(is_some);
+        let is_valid = self.or(is_hash, is_none);
+        let t = self._true();
+        self.assert_is_equal(is_valid, t);
+
+        let len = self.mul(is_some.variable, const_33);
+        *offset = self.add(*offset, len);
+        OptionVariable::new(self, is_some, encoding[1..33].into())
+    }
+
+    /// Private helper method to fetch the RLP encoding of the header of the block `block_hash`,
+    /// padded to `MAX_HEADER_LEN`, and check it against the block hash.
+    fn eth_get_header_rlp(
+        &mut self,
+        block_hash: Bytes32Variable,
+    ) -> (ArrayVariable<ByteVariable, MAX_HEADER_LEN>, U32Variable) {
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+
+        let hint = EthHeaderRlpHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let header = output_stream.read::<ArrayVariable<ByteVariable, MAX_HEADER_LEN>>(self);
+        let header_len = output_stream.read::<U32Variable>(self);
+
+        let header_hash = self.keccak256_variable(header.as_slice(), header_len.variable);
+        self.assert_is_equal(header_hash, block_hash);
+
+        (header, header_len)
+    }
+
+    /// Private helper method to fetch the RLP encoding of the header of the block `block_hash`,
+    /// check it against the block hash, and return its state root.
+    fn eth_get_state_root(&mut self, block_hash: Bytes32Variable) -> Bytes32Variable {
+        let (header, _) = self.eth_get_header_rlp(block_hash);
+
+        // The header is a list with a two byte length, starting with the parent hash, the uncle
+        // hash, the coinbase and the state root.
+        let list_prefix = self.constant::<ByteVariable>(0xf9);
+        let hash_prefix = self.constant::<ByteVariable>(0xa0);
+        let address_prefix = self.constant::<ByteVariable>(0x94);
+        self.assert_is_equal(header[0], list_prefix);
+        self.assert_is_equal(header[3], hash_prefix);
+        self.assert_is_equal(header[36], hash_prefix);
+        self.assert_is_equal(header[69], address_prefix);
+        self.assert_is_equal(header[90], hash_prefix);
+
+        header[91..123].into()
+    }
+
     pub fn eth_get_storage_at_witness(
         &mut self,
         block_hash: Bytes32Variable,
@@ -49,12 +352,147 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         value
     }
 
+    /// Returns the account of `address` at the block `block_hash`.
+    ///
+    /// The header of the block is checked against `block_hash` and the account proof against the
+    /// state root of the header. Exclusion proofs are not supported, so the account must exist.
     pub fn eth_get_account(
         &mut self,
-        _address: Address,
-        _block_hash: Bytes32Variable,
+        address: AddressVariable,
+        block_hash: Bytes32Variable,
+    ) -> EthAccountVariable {
+        let state_root = self.eth_get_state_root(block_hash);
+
+        let mut input_stream = VariableStream::new();
+        input_stream.write(&block_hash);
+        input_stream.write(&address);
+
+        let hint = EthAccountProofHint::new(self);
+        let output_stream = self.async_hint(input_stream, hint);
+        let account_proof = output_stream
+            .read::<ArrayVariable<ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>, MAX_PROOF_LEN>>(
+                self,
+            );
+        let account_proof_lens =
+            output_stream.read::<ArrayVariable<U32Variable, MAX_PROOF_LEN>>(self);
+
+        self.eth_verify_account_proof(address, account_proof, account_proof_lens, state_root)
+    }
+
+    /// Private helper method to verify the proof of the account at `address` against
+    /// `state_root`, and to decode the account in its leaf.
+    fn eth_verify_account_proof(
+        &mut self,
+        address: AddressVariable,
+        account_proof: ArrayVariable<
+            ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>,
+            MAX_PROOF_LEN,
+        >,
+        account_proof_lens: ArrayVariable<U32Variable, MAX_PROOF_LEN>,
+        state_root: Bytes32Variable,
     ) -> EthAccountVariable {
-        todo!()
+        let (leaf, leaf_len) = self.verify_mpt_proof_leaf(
+            &address.0 .0,
+            account_proof,
+            account_proof_lens,
+            state_root,
+        );
+        self.eth_decode_account(&leaf, leaf_len)
+    }
+
+    /// Private helper method to decode the account in the leaf of an account proof.
+    ///
+    /// The leaf is the list `[path, account]`, where `account` is the RLP encoding of the list
+    /// `[nonce, balance, storage_hash, code_hash]`. Both lists are longer than 55 bytes and shorter
+    /// than 256 bytes, so their prefixes are `0xf8` followed by a single length byte, and the
+    /// encoding of the account is a string prefixed by `0xb8` and a single length byte.
+    fn eth_decode_account(
+        &mut self,
+        leaf: &ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>,
+        leaf_len: U32Variable,
+    ) -> EthAccountVariable {
+        let short_list_prefix = self.constant::<ByteVariable>(0xf8);
+        let short_string_prefix = self.constant::<ByteVariable>(0xb8);
+        let one = self.one::<Variable>();
+        let two = self.constant::<Variable>(L::Field::from_canonical_u8(2));
+        let four = self.constant::<Variable>(L::Field::from_canonical_u8(4));
+        let const_66 = self.constant::<Variable>(L::Field::from_canonical_u8(66));
+        let const_0x7f = self.constant::<Variable>(L::Field::from_canonical_u8(0x7f));
+
+        // The leaf list.
+        self.assert_is_equal(leaf[0], short_list_prefix);
+        let leaf_payload_len = leaf[1].to_variable(self);
+        let expected_leaf_len = self.add(leaf_payload_len, two);
+        self.assert_is_equal(expected_leaf_len, leaf_len.variable);
+
+        // The path is a single byte below 0x80, or a string of at most 33 bytes.
+        let path_prefix = leaf[2].to_variable(self);
+        let path_prefix_u32 = U32Variable::from_variables_unsafe(&[path_prefix]);
+        let const_0x80 = self.constant::<U32Variable>(0x80);
+        let path_is_single_byte = self.lt(path_prefix_u32, const_0x80);
+        let path_string_len = self.sub(path_prefix, const_0x7f);
+        let path_len = self.select(path_is_single_byte, one, path_string_len);
+        let account_start = self.add(two, path_len);
+
+        // The account string, the account list, the nonce (at most 9 bytes) and the balance.
+        let account_header =
+            self.get_fixed_subarray::<MAX_PROOF_NODE_LEN, 13>(leaf, account_start, leaf.as_slice());
+        self.assert_is_equal(account_header[0], short_string_prefix);
+        self.assert_is_equal(account_header[2], short_list_prefix);
+        let account_len = account_header[1].to_variable(self);
+        let account_payload_len = account_header[3].to_variable(self);
+
+        let account_end = self.add(account_start, two);
+        let account_end = self.add(account_end, account_len);
+        self.assert_is_equal(account_end, leaf_len.variable);
+        let expected_account_len = self.add(account_payload_len, two);
+        self.assert_is_equal(expected_account_len, account_len);
+
+        let (nonce, nonce_len) = self.decode_rlp_integer::<U256Variable, 8>(&account_header[4..13]);
+        let balance_start = self.add(account_start, four);
+        let balance_start = self.add(balance_start, nonce_len);
+        let balance_encoding =
+            self.get_fixed_subarray::<MAX_PROOF_NODE_LEN, 33>(leaf, balance_start, leaf.as_slice());
+        let (balance, balance_len) =
+            self.decode_rlp_integer::<U256Variable, 32>(balance_encoding.as_slice());
+
+        // The nonce, the balance and the two hashes fill the account list.
+        let fields_len = self.add(nonce_len, balance_len);
+        let fields_len = self.add(fields_len, const_66);
+        self.assert_is_equal(fields_len, account_payload_len);
+
+        let (storage_hash, code_hash) = self.eth_decode_account_hashes(leaf, leaf_len);
+
+        EthAccountVariable {
+            balance,
+            code_hash,
+            nonce,
+            storage_hash,
+        }
+    }
+
+    /// Private helper method to read the storage hash and the code hash of the account in the
+    /// leaf of an account proof.
+    ///
+    /// The account is the last item of the leaf, and ends with the 32 byte storage hash and the 32
+    /// byte code hash, each prefixed by 0xa0.
+    fn eth_decode_account_hashes(
+        &mut self,
+        leaf: &ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>,
+        leaf_len: U32Variable,
+    ) -> (Bytes32Variable, Bytes32Variable) {
+        let const_66 = self.constant::<U32Variable>(66);
+        let suffix_start = self.sub(leaf_len, const_66);
+        let suffix = self.get_fixed_subarray::<MAX_PROOF_NODE_LEN, 66>(
+            leaf,
+            suffix_start.variable,
+            leaf.as_slice(),
+        );
+        let hash_prefix = self.constant::<ByteVariable>(0xa0);
+        self.assert_is_equal(suffix[0], hash_prefix);
+        self.assert_is_equal(suffix[33], hash_prefix);
+
+        (suffix[1..33].into(), suffix[34..66].into())
     }
 
     pub fn eth_get_transaction_log_witness(
@@ -74,15 +512,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 mod tests {
     use std::env;
 
-    use ethers::providers::{Http, Provider};
-    use ethers::types::{U256, U64};
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::types::{Address, H256, U256, U64};
+    use ethers::utils::keccak256;
     use log::debug;
+    use tokio::runtime::Runtime;
 
     use super::*;
     use crate::backend::circuit::{CircuitBuild, DefaultParameters, GateRegistry, HintRegistry};
+    use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
+    use crate::frontend::eth::mpt::utils::read_fixture;
     use crate::frontend::eth::storage::utils::get_map_storage_location;
-    use crate::frontend::eth::storage::vars::{EthHeader, EthLog};
-    use crate::prelude::DefaultBuilder;
+    use crate::frontend::eth::storage::vars::{EthAccount, EthFullHeader, EthHeader, EthLog};
+    use crate::prelude::{DefaultBuilder, GoldilocksField};
     use crate::utils::{self, address, bytes32};
 
     type L = DefaultParameters;
@@ -156,6 +598,203 @@ mod tests {
         circuit.verify(&proof, &input, &output);
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[allow(non_snake_case)]
+    fn test_eth_get_storage_at() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        // This is the circuit definition
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider);
+        let block_hash = builder.evm_read::<Bytes32Variable>();
+        let address = builder.evm_read::<AddressVariable>();
+        let location = builder.evm_read::<Bytes32Variable>();
+        let value = builder.eth_get_storage_at(block_hash, address, location);
+        builder.evm_write(value);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Write to the circuit input.
+        // These values are taken from Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        // block hash
+        input.evm_write::<Bytes32Variable>(bytes32!(
+            "0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe"
+        ));
+        // address
+        input.evm_write::<AddressVariable>(address!("0x55032650b14df07b85bF18A3a3eC8E0Af2e028d5"));
+        // location
+        input.evm_write::<Bytes32Variable>(bytes32!(
+            "0xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5"
+        ));
+
+        // Generate a proof.
+        let (proof, mut output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // Read output.
+        let circuit_value = output.evm_read::<Bytes32Variable>();
+        debug!("{:?}", circuit_value);
+        assert_eq!(
+            circuit_value,
+            bytes32!("0x0000000000000000000000dd4bc51496dc93a0c47008e820e0d80745476f2201"),
+        );
+
+        // initialize serializers
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let hint_serializer = HintRegistry::<L, D>::new();
+
+        // test serialization
+        let _ = circuit
+            .serialize(&gate_serializer, &hint_serializer)
+            .unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[allow(non_snake_case)]
+    fn test_eth_get_account() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        // This is the circuit definition
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider.clone());
+        let address = builder.read::<AddressVariable>();
+        let block_hash = builder.read::<Bytes32Variable>();
+        let value = builder.eth_get_account(address, block_hash);
+        builder.write(value);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Write to the circuit input.
+        // These values are taken from Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        let address_value = address!("0x55032650b14df07b85bF18A3a3eC8E0Af2e028d5");
+        let block_hash_value =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        input.write::<AddressVariable>(address_value);
+        input.write::<Bytes32Variable>(block_hash_value);
+
+        // Generate a proof.
+        let (proof, mut output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // Read output and compare it to the account returned by the RPC.
+        let circuit_value = output.read::<EthAccountVariable>();
+        debug!("{:?}", circuit_value);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let result = rt
+            .block_on(provider.get_proof(address_value, vec![], Some(block_hash_value.into())))
+            .unwrap();
+        assert_eq!(circuit_value.nonce, U256::from(result.nonce.as_u64()));
+        assert_eq!(circuit_value.balance, result.balance);
+        assert_eq!(circuit_value.storage_hash, result.storage_hash);
+        assert_eq!(circuit_value.code_hash, result.code_hash);
+
+        // initialize serializers
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let hint_serializer = HintRegistry::<L, D>::new();
+
+        // test serialization
+        let _ = circuit
+            .serialize(&gate_serializer, &hint_serializer)
+            .unwrap();
+    }
+
+    /// Verifies the account proof of `fixtures/example.json`, taken from Ethereum block
+    /// https://etherscan.io/block/17880427, for `address` and returns the decoded account.
+    fn verify_account_proof_fixture(address: Address, tamper: bool) -> EthAccount<GoldilocksField> {
+        let result = read_fixture("./src/frontend/eth/mpt/fixtures/example.json");
+        let mut account_proof = result
+            .account_proof
+            .iter()
+            .map(|b| b.to_vec())
+            .collect::<Vec<Vec<u8>>>();
+        let state_root = H256(keccak256(&account_proof[0]));
+        if tamper {
+            // Set the nonce of the account in the leaf to 2.
+            let leaf = account_proof.last_mut().unwrap();
+            let nonce_idx = leaf.len() - 68;
+            assert_eq!(leaf[nonce_idx], 0x01);
+            leaf[nonce_idx] = 0x02;
+        }
+        let (proof_as_fixed, lengths_as_fixed) =
+            transform_proof_to_padded::<MAX_PROOF_NODE_LEN, MAX_PROOF_LEN>(account_proof);
+
+        let mut builder = DefaultBuilder::new();
+        let address_variable = builder.read::<AddressVariable>();
+        let account_proof_variable = builder
+            .read::<ArrayVariable<ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>, MAX_PROOF_LEN>>(
+            );
+        let account_proof_lens = builder.read::<ArrayVariable<U32Variable, MAX_PROOF_LEN>>();
+        let state_root_variable = builder.read::<Bytes32Variable>();
+        let value = builder.eth_verify_account_proof(
+            address_variable,
+            account_proof_variable,
+            account_proof_lens,
+            state_root_variable,
+        );
+        builder.write(value);
+        let circuit = builder.mock_build();
+
+        let mut input = circuit.input();
+        input.write::<AddressVariable>(address);
+        input
+            .write::<ArrayVariable<ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>, MAX_PROOF_LEN>>(
+                proof_as_fixed,
+            );
+        input.write::<ArrayVariable<U32Variable, MAX_PROOF_LEN>>(
+            lengths_as_fixed.iter().map(|x| *x as u32).collect(),
+        );
+        input.write::<Bytes32Variable>(state_root);
+        let (_witness, mut output) = circuit.mock_prove(&input);
+        output.read::<EthAccountVariable>()
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_eth_verify_account_proof() {
+        utils::setup_logger();
+        let result = read_fixture("./src/frontend/eth/mpt/fixtures/example.json");
+        let account = verify_account_proof_fixture(result.address, false);
+        assert_eq!(account.nonce, U256::from(result.nonce.as_u64()));
+        assert_eq!(account.balance, result.balance);
+        assert_eq!(account.storage_hash, result.storage_hash);
+        assert_eq!(account.code_hash, result.code_hash);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[should_panic]
+    fn test_eth_verify_account_proof_absent_address() {
+        utils::setup_logger();
+        // This address is not in the proof, and exclusion proofs are not supported.
+        let address = address!("0x0000000000000000000000000000000000000001");
+        verify_account_proof_fixture(address, false);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[should_panic]
+    fn test_eth_verify_account_proof_tampered() {
+        utils::setup_logger();
+        let result = read_fixture("./src/frontend/eth/mpt/fixtures/example.json");
+        verify_account_proof_fixture(result.address, true);
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     #[allow(non_snake_case)]
@@ -274,6 +913,35 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_get_storage_key_at_constrained() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+        let mapping_location = builder.read::<U256Variable>();
+        let map_key = builder.read::<Bytes32Variable>();
+
+        let value = builder.get_storage_key_at(mapping_location, map_key);
+        builder.write(value);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        let mapping_location = U256::from("0x0");
+        input.write::<U256Variable>(mapping_location);
+        let map_key =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        input.write::<Bytes32Variable>(map_key);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let expected = get_map_storage_location(mapping_location.as_u128(), map_key);
+        assert_eq!(output.read::<Bytes32Variable>(), expected);
+        assert_eq!(
+            expected,
+            bytes32!("0xca77d4e79102603cb6842afffd8846a3123877159ed214aeadfc4333d595fd50"),
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     #[allow(non_snake_case)]
@@ -348,6 +1016,97 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    #[allow(non_snake_case)]
+    fn test_eth_get_block_by_hash() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+
+        // This is the circuit definition
+        let mut builder = DefaultBuilder::new();
+        builder.set_execution_client(provider.clone());
+        let block_hash = builder.read::<Bytes32Variable>();
+
+        let value = builder.eth_get_block_by_hash(block_hash);
+        builder.write(value);
+
+        // Build your circuit.
+        let circuit = builder.build();
+
+        // Write to the circuit input.
+        // These values are taken from Ethereum block https://etherscan.io/block/17880427
+        let mut input = circuit.input();
+        let block_hash_value =
+            bytes32!("0x281dc31bb78779a1ede7bf0f4d2bc5f07ddebc9f9d1155e413d8804384604bbe");
+        input.write::<Bytes32Variable>(block_hash_value);
+
+        // Generate a proof.
+        let (proof, mut output) = circuit.prove(&input);
+
+        // Verify proof.
+        circuit.verify(&proof, &input, &output);
+
+        // Read output and compare it to the block returned by the RPC.
+        let circuit_value = output.read::<EthFullHeaderVariable>();
+        debug!("{:?}", circuit_value);
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+        let block = rt
+            .block_on(provider.get_block(block_hash_value))
+            .unwrap()
+            .unwrap();
+        let mut extra = [0u8; 32];
+        extra[..block.extra_data.len()].copy_from_slice(&block.extra_data);
+        assert_eq!(
+            circuit_value,
+            EthFullHeader {
+                parent_hash: bytes32!(
+                    "0x7b012bf12a831368d7278edad91eb968df7912902aeb45bce0948f1ec8b411df"
+                ),
+                uncle_hash: bytes32!(
+                    "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+                ),
+                coinbase: address!("0xa8c62111e4652b07110a0fc81816303c42632f64"),
+                root: bytes32!(
+                    "0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e"
+                ),
+                tx_hash: bytes32!(
+                    "0x8d0a3c10b76930ebda83551649856882b51455de61689184c9db535ef5c29e93"
+                ),
+                receipt_hash: bytes32!(
+                    "0x8fa46ad6b448faefbfc010736a3d39595ca68eb8bdd4e6b4ab30513bab688068"
+                ),
+                bloom: block.logs_bloom.unwrap().0,
+                difficulty: U256::from("0x0"),
+                number: 0x110d56b,
+                gas_limit: 0x1c9c380,
+                gas_used: 0x16041f6,
+                time: 0x64d41817,
+                extra,
+                extra_len: block.extra_data.len() as u32,
+                mix_digest: block.mix_hash.unwrap(),
+                nonce: block.nonce.unwrap().0,
+                base_fee: block.base_fee_per_gas,
+                withdrawals_hash: block.withdrawals_root,
+                blob_gas_used: None,
+                excess_blob_gas: None,
+                parent_beacon_root: None,
+                requests_hash: None,
+            }
+        );
+
+        // initialize serializers
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let hint_serializer = HintRegistry::<L, D>::new();
+
+        // test serialization
+        let _ = circuit
+            .serialize(&gate_serializer, &hint_serializer)
+            .unwrap();
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     #[allow(non_snake_case)]
@@ -1,21 +1,84 @@
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+use async_trait::async_trait;
 use ethers::providers::Middleware;
 use ethers::types::{Block, H256};
+use ethers::utils::keccak256;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::PartitionWitness;
 use plonky2::plonk::circuit_data::CommonCircuitData;
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use serde::{Deserialize, Serialize};
 use tokio::runtime::Runtime;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::storage::utils::rlp_encode_header;
 use crate::frontend::eth::storage::vars::{EthHeader, EthHeaderVariable};
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable};
+use crate::frontend::hint::asynchronous::hint::AsyncHint;
+use crate::frontend::uint::uint32::U32Variable;
+use crate::frontend::vars::{
+    ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable, ValueStream,
+};
 use crate::utils::eth::get_provider;
 
+/// The maximum length of the RLP encoding of a block header.
+pub const MAX_HEADER_LEN: usize = 1024;
+
+/// Fetches the RLP encoding of the header of a block, padded to `MAX_HEADER_LEN`, and its length.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthHeaderRlpHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthHeaderRlpHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthHeaderRlpHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthHeaderRlpHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthHeaderRlpHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+
+        let provider = get_provider(self.chain_id);
+        let block = provider
+            .request::<_, serde_json::Value>("eth_getBlockByHash", (block_hash, false))
+            .await
+            .expect("Failed to get block");
+        let mut header = rlp_encode_header(&block);
+        assert_eq!(
+            H256::from(keccak256(&header)),
+            block_hash,
+            "The encoded header does not match the block hash"
+        );
+        if header.len() > MAX_HEADER_LEN {
+            panic!(
+                "Header is too long, has {} bytes, but MAX_HEADER_LEN is {}",
+                header.len(),
+                MAX_HEADER_LEN
+            );
+        }
+
+        let header_len = header.len() as u32;
+        header.resize(MAX_HEADER_LEN, 0);
+        output_stream.write_value::<ArrayVariable<ByteVariable, MAX_HEADER_LEN>>(header);
+        output_stream.write_value::<U32Variable>(header_len);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EthBlockGenerator<L: PlonkParameters<D>, const D: usize> {
     block_hash: Bytes32Variable,
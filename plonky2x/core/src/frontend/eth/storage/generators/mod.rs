@@ -1,7 +1,8 @@
 mod block;
 mod storage;
 
-pub use block::EthBlockGenerator;
+pub use block::{EthBlockGenerator, EthHeaderRlpHint, MAX_HEADER_LEN};
 pub use storage::{
-    EthLogGenerator, EthStorageKeyGenerator, EthStorageProofGenerator, EthStorageProofHint,
+    EthAccountProofHint, EthFullStorageProofHint, EthLogGenerator, EthStorageKeyGenerator,
+    EthStorageProofGenerator, EthStorageProofHint, MAX_PROOF_LEN, MAX_PROOF_NODE_LEN,
 };
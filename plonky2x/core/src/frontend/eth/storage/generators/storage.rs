@@ -17,13 +17,17 @@ use tokio::runtime::Runtime;
 
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::eth::mpt::builder::transform_proof_to_padded;
 use crate::frontend::eth::storage::utils::get_map_storage_location;
 use crate::frontend::eth::storage::vars::{EthLog, EthLogVariable};
 use crate::frontend::eth::utils::u256_to_h256_be;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::hint::asynchronous::hint::AsyncHint;
 use crate::frontend::uint::uint256::U256Variable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable, ValueStream};
+use crate::frontend::uint::uint32::U32Variable;
+use crate::frontend::vars::{
+    ArrayVariable, ByteVariable, Bytes32Variable, CircuitVariable, ValueStream,
+};
 use crate::utils::eth::get_provider;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +67,117 @@ impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthStorageProofH
     }
 }
 
+/// The maximum length of a node in an account or storage proof.
+pub const MAX_PROOF_NODE_LEN: usize = 600;
+
+/// The maximum number of nodes in an account or storage proof.
+pub const MAX_PROOF_LEN: usize = 16;
+
+/// Fetches the account proof and the storage proof of a storage slot.
+///
+/// The proofs are padded to `MAX_PROOF_LEN` nodes of `MAX_PROOF_NODE_LEN` bytes each.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthFullStorageProofHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthFullStorageProofHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthFullStorageProofHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthFullStorageProofHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+/// Writes a proof padded to `MAX_PROOF_LEN` nodes of `MAX_PROOF_NODE_LEN` bytes, and the lengths
+/// of its nodes, to `output_stream`.
+pub(crate) fn write_padded_proof<L: PlonkParameters<D>, const D: usize>(
+    output_stream: &mut ValueStream<L, D>,
+    proof: Vec<Vec<u8>>,
+) {
+    let (proof, lengths) = transform_proof_to_padded::<MAX_PROOF_NODE_LEN, MAX_PROOF_LEN>(proof);
+    output_stream
+        .write_value::<ArrayVariable<ArrayVariable<ByteVariable, MAX_PROOF_NODE_LEN>, MAX_PROOF_LEN>>(
+            proof,
+        );
+    output_stream.write_value::<ArrayVariable<U32Variable, MAX_PROOF_LEN>>(
+        lengths.into_iter().map(|x| x as u32).collect(),
+    );
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthFullStorageProofHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+        let address = input_stream.read_value::<AddressVariable>();
+        let location = input_stream.read_value::<Bytes32Variable>();
+
+        let provider = get_provider(self.chain_id);
+        let result = provider
+            .get_proof(address, vec![location], Some(block_hash.into()))
+            .await
+            .expect("Failed to get proof");
+        let storage_proof = &result.storage_proof[0];
+
+        write_padded_proof(
+            output_stream,
+            result.account_proof.iter().map(|b| b.to_vec()).collect(),
+        );
+        write_padded_proof(
+            output_stream,
+            storage_proof.proof.iter().map(|b| b.to_vec()).collect(),
+        );
+    }
+}
+
+/// Fetches the account proof of an address, padded to `MAX_PROOF_LEN` nodes of
+/// `MAX_PROOF_NODE_LEN` bytes each.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EthAccountProofHint<L: PlonkParameters<D>, const D: usize> {
+    chain_id: u64,
+    _phantom: PhantomData<L>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize> EthAccountProofHint<L, D> {
+    pub fn new(builder: &CircuitBuilder<L, D>) -> EthAccountProofHint<L, D> {
+        let chain_id = builder.get_chain_id();
+        EthAccountProofHint {
+            chain_id,
+            _phantom: PhantomData::<L>,
+        }
+    }
+}
+
+#[async_trait]
+impl<L: PlonkParameters<D>, const D: usize> AsyncHint<L, D> for EthAccountProofHint<L, D> {
+    async fn hint(
+        &self,
+        input_stream: &mut ValueStream<L, D>,
+        output_stream: &mut ValueStream<L, D>,
+    ) {
+        let block_hash = input_stream.read_value::<Bytes32Variable>();
+        let address = input_stream.read_value::<AddressVariable>();
+
+        let provider = get_provider(self.chain_id);
+        let result = provider
+            .get_proof(address, vec![], Some(block_hash.into()))
+            .await
+            .expect("Failed to get proof");
+
+        write_padded_proof(
+            output_stream,
+            result.account_proof.iter().map(|b| b.to_vec()).collect(),
+        );
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EthStorageProofGenerator<L: PlonkParameters<D>, const D: usize> {
     block_hash: Bytes32Variable,
@@ -1,6 +1,40 @@
 use ethers::abi::{AbiEncode, Token};
 use ethers::types::{H256, U256};
 use ethers::utils::keccak256;
+use serde_json::Value;
+
+use crate::frontend::eth::rlp::decoder::RLPItem;
+use crate::frontend::eth::rlp::encoder::{encode, integer_item};
+
+/// The fields of a block header in the order of its RLP encoding, as named by `eth_getBlockByHash`,
+/// together with whether they are integers. The fields from `baseFeePerGas` on were introduced by
+/// later forks (London, Shanghai, Cancun and Prague), and are only present from their fork on.
+pub const HEADER_FIELDS: [(&str, bool); 21] = [
+    ("parentHash", false),
+    ("sha3Uncles", false),
+    ("miner", false),
+    ("stateRoot", false),
+    ("transactionsRoot", false),
+    ("receiptsRoot", false),
+    ("logsBloom", false),
+    ("difficulty", true),
+    ("number", true),
+    ("gasLimit", true),
+    ("gasUsed", true),
+    ("timestamp", true),
+    ("extraData", false),
+    ("mixHash", false),
+    ("nonce", false),
+    ("baseFeePerGas", true),
+    ("withdrawalsRoot", false),
+    ("blobGasUsed", true),
+    ("excessBlobGas", true),
+    ("parentBeaconBlockRoot", false),
+    ("requestsHash", false),
+];
+
+/// The number of fields of the headers before London.
+pub const NB_LEGACY_HEADER_FIELDS: usize = 15;
 
 pub fn get_map_storage_location(mapping_location: u128, map_key: H256) -> H256 {
     let encoded = [
@@ -11,3 +45,121 @@ pub fn get_map_storage_location(mapping_location: u128, map_key: H256) -> H256 {
     let hash = keccak256(encoded);
     H256::from(hash)
 }
+
+/// Returns the RLP encoding of the header of a block returned by `eth_getBlockByHash`, whose
+/// keccak256 hash is the block hash.
+pub fn rlp_encode_header(block: &Value) -> Vec<u8> {
+    let mut items = Vec::new();
+    for (i, (name, is_integer)) in HEADER_FIELDS.iter().enumerate() {
+        let value = match block.get(name).and_then(|value| value.as_str()) {
+            Some(value) => value,
+            None if i >= NB_LEGACY_HEADER_FIELDS => break,
+            None => panic!("Missing header field {}", name),
+        };
+        let value = value.strip_prefix("0x").unwrap_or(value);
+        let value = if value.len() % 2 == 1 {
+            format!("0{}", value)
+        } else {
+            value.to_string()
+        };
+        let bytes = hex::decode(value).expect("Invalid hex header field");
+        if *is_integer {
+            items.push(integer_item(&bytes));
+        } else {
+            items.push(RLPItem::String(bytes));
+        }
+    }
+    encode(&RLPItem::List(items))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ethers::providers::{Http, Middleware, Provider};
+    use ethers::types::U64;
+    use serde_json::json;
+    use tokio::runtime::Runtime;
+
+    use super::*;
+    use crate::frontend::eth::rlp::decoder::decode;
+    use crate::utils::{self, bytes32};
+
+    #[test]
+    fn test_rlp_encode_header() {
+        // A Shanghai header with some fields of the mainnet block 17880427 and placeholders for the
+        // others (e.g., the bloom and the mix hash), so it does not hash to the block hash.
+        let block = json!({
+            "parentHash": "0x7b012bf12a831368d7278edad91eb968df7912902aeb45bce0948f1ec8b411df",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0xa8c62111e4652b07110a0fc81816303c42632f64",
+            "stateRoot": "0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e",
+            "transactionsRoot": "0x8d0a3c10b76930ebda83551649856882b51455de61689184c9db535ef5c29e93",
+            "receiptsRoot": "0x8fa46ad6b448faefbfc010736a3d39595ca68eb8bdd4e6b4ab30513bab688068",
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "difficulty": "0x0",
+            "number": "0x110d56b",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x16041f6",
+            "timestamp": "0x64d41817",
+            "extraData": "0x",
+            "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+            "nonce": "0x0000000000000000",
+            "baseFeePerGas": "0x5",
+            "withdrawalsRoot": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        });
+        let encoding = rlp_encode_header(&block);
+
+        // The list has a 3 byte prefix, and the state root is the fourth field.
+        assert_eq!(encoding[0], 0xf9);
+        assert_eq!(
+            u16::from_be_bytes([encoding[1], encoding[2]]) as usize,
+            encoding.len() - 3
+        );
+        assert_eq!(encoding[90], 0xa0);
+        assert_eq!(
+            H256::from_slice(&encoding[91..123]),
+            bytes32!("0xff90251f501c864f21d696c811af4c3aa987006916bd0e31a6c06cc612e7632e")
+        );
+        // The empty extra data, the zero difficulty and the small base fee.
+        let decoded = decode(&encoding);
+        match decoded {
+            RLPItem::List(items) => {
+                assert_eq!(items.len(), 17);
+                assert_eq!(items[7], RLPItem::String(vec![]));
+                assert_eq!(items[12], RLPItem::String(vec![]));
+                assert_eq!(items[15], RLPItem::String(vec![5]));
+            }
+            RLPItem::String(_) => panic!("the header is a list"),
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_rlp_encode_header_block_hash() {
+        utils::setup_logger();
+        dotenv::dotenv().ok();
+        let rpc_url = env::var("RPC_1").unwrap();
+        let provider = Provider::<Http>::try_from(rpc_url).unwrap();
+        let rt = Runtime::new().expect("failed to create tokio runtime");
+
+        // Mainnet blocks after the Shanghai, Cancun and Prague forks, with their number of header
+        // fields.
+        let blocks = [(17035870u64, 17), (19427587, 20), (22432084, 21)];
+        for (number, nb_fields) in blocks {
+            let block = rt
+                .block_on(
+                    provider
+                        .request::<_, Value>("eth_getBlockByNumber", (U64::from(number), false)),
+                )
+                .unwrap();
+            let encoding = rlp_encode_header(&block);
+            let block_hash: H256 = serde_json::from_value(block["hash"].clone()).unwrap();
+            assert_eq!(H256(keccak256(&encoding)), block_hash);
+            match decode(&encoding) {
+                RLPItem::List(items) => assert_eq!(items.len(), nb_fields),
+                RLPItem::String(_) => panic!("the header is a list"),
+            }
+        }
+    }
+}
@@ -7,7 +7,9 @@ use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::eth::vars::AddressVariable;
 use crate::frontend::uint::uint64::U64Variable;
-use crate::frontend::vars::{Bytes32Variable, CircuitVariable, U256Variable};
+use crate::frontend::vars::{
+    Bytes32Variable, BytesVariable, CircuitVariable, OptionVariable, U256Variable, U32Variable,
+};
 use crate::prelude::Variable;
 
 /// A variable representing the Ethereum Block Header
@@ -34,3 +36,37 @@ pub struct EthHeaderVariable {
     pub time: U256Variable,
     // pub extra: Bytes32Variable, // TODO: add back once we have arbitrary bytes variables
 }
+
+/// Includes every field of a block header, up to the Prague hard fork.
+///
+/// The fields introduced by London (`base_fee`), Shanghai (`withdrawals_hash`), Cancun
+/// (`blob_gas_used`, `excess_blob_gas` and `parent_beacon_root`) and Prague (`requests_hash`) are
+/// empty for the blocks before their fork. `extra` holds the first `extra_len` bytes of the extra
+/// data, padded with zeros.
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(EthFullHeader)]
+#[value_derive(PartialEq, Eq)]
+pub struct EthFullHeaderVariable {
+    pub parent_hash: Bytes32Variable,
+    pub uncle_hash: Bytes32Variable,
+    pub coinbase: AddressVariable,
+    pub root: Bytes32Variable,
+    pub tx_hash: Bytes32Variable,
+    pub receipt_hash: Bytes32Variable,
+    pub bloom: BytesVariable<256>,
+    pub difficulty: U256Variable,
+    pub number: U64Variable,
+    pub gas_limit: U64Variable,
+    pub gas_used: U64Variable,
+    pub time: U64Variable,
+    pub extra: BytesVariable<32>,
+    pub extra_len: U32Variable,
+    pub mix_digest: Bytes32Variable,
+    pub nonce: BytesVariable<8>,
+    pub base_fee: OptionVariable<U256Variable>,
+    pub withdrawals_hash: OptionVariable<Bytes32Variable>,
+    pub blob_gas_used: OptionVariable<U64Variable>,
+    pub excess_blob_gas: OptionVariable<U64Variable>,
+    pub parent_beacon_root: OptionVariable<Bytes32Variable>,
+    pub requests_hash: OptionVariable<Bytes32Variable>,
+}
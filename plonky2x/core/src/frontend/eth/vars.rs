@@ -50,6 +50,47 @@ impl CircuitVariable for BLSPubkeyVariable {
     }
 }
 
+/// A compressed BLS signature, i.e. a point of G2 in the 96 byte ZCash encoding.
+#[derive(Debug, Clone, Copy)]
+pub struct BLSSignatureVariable(pub BytesVariable<96>);
+
+impl CircuitVariable for BLSSignatureVariable {
+    type ValueType<F: RichField> = [u8; 96];
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self(BytesVariable::init_unsafe(builder))
+    }
+
+    fn nb_elements() -> usize {
+        BytesVariable::<96>::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        BytesVariable::<96>::elements(value)
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        BytesVariable::<96>::from_elements(elements)
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.0.variables()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        Self(BytesVariable::from_variables_unsafe(variables))
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.0.assert_is_valid(builder);
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct AddressVariable(pub BytesVariable<20>);
 
@@ -1,4 +1,8 @@
+use plonky2::util::log2_ceil;
+
+use crate::frontend::builder::bits::BitView;
 use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target};
 use crate::prelude::{BoolVariable, CircuitVariable, PlonkParameters, U32Variable, Variable};
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
@@ -66,18 +70,68 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         a: [BoolVariable; S],
         b: [BoolVariable; S],
     ) -> [BoolVariable; S] {
-        if S == 32 {
-            let a_u32 = U32Variable::from_be_bits(&a, self);
-            let b_u32 = U32Variable::from_be_bits(&b, self);
+        self.add_many_arr(&[a, b])
+    }
+
+    /// Adds big-endian words modulo 2^S. Words whose size is a multiple of 32 are added limb by
+    /// limb with one addition gate per limb, and the packed values of the operands and the bits of
+    /// the result go through the builder's bits cache, so words that were already packed or
+    /// decomposed are not converted again. Other words are packed into a single field element.
+    pub fn add_many_arr<const S: usize>(
+        &mut self,
+        values: &[[BoolVariable; S]],
+    ) -> [BoolVariable; S] {
+        if values.is_empty() {
+            return [self._false(); S];
+        }
 
-            let a_u64 = a_u32.to_u64(self);
-            let b_u64 = b_u32.to_u64(self);
-            let c_u64 = self.add(a_u64, b_u64);
-            let c_u32 = c_u64.limbs[0];
+        if S % 32 == 0 {
+            let limbs = values
+                .iter()
+                .map(|value| BitView::from_be_bits(value).limbs(self))
+                .collect::<Vec<_>>();
+
+            let mut sum = Vec::with_capacity(S / 32);
+            let mut carry = self.api.zero_u32();
+            for i in 0..S / 32 {
+                let to_add = limbs
+                    .iter()
+                    .map(|limbs| U32Target::from(limbs[i]))
+                    .collect::<Vec<_>>();
+                let (limb, next_carry) = if i == 0 {
+                    self.api.add_many_u32(&to_add)
+                } else {
+                    self.api.add_u32s_with_carry(&to_add, carry)
+                };
+                sum.push(U32Variable::from(limb));
+                carry = next_carry;
+            }
 
-            c_u32.to_be_bits(self).to_vec().try_into().unwrap()
+            BitView::from_limbs(&sum).be_bits(self).try_into().unwrap()
         } else {
-            todo!();
+            // The sum is less than `values.len() * 2^S`, and its decomposition is only unique if
+            // it fits in 63 bits.
+            let nb_bits = S + log2_ceil(values.len());
+            assert!(
+                nb_bits <= 63,
+                "sum of {}-bit words does not fit in a field element",
+                S
+            );
+
+            let mut sum = self.zero::<Variable>();
+            for value in values.iter() {
+                let le_bits = value.iter().rev().copied().collect::<Vec<_>>();
+                let value = self.le_sum_cached(&le_bits);
+                sum = self.add(sum, value);
+            }
+            let bits = self.split_le_cached(sum, nb_bits);
+            bits[..S]
+                .iter()
+                .rev()
+                .copied()
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
         }
     }
 
@@ -145,3 +199,125 @@ pub fn and_arr<const S: usize, L: PlonkParameters<D>, const D: usize>(
         .try_into()
         .unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::frontend::vars::EvmVariable;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_add_arr_gate_count() {
+        // The previous implementation of `add_arr`, which packs the operands and decomposes the
+        // result without the bits cache and adds them as u64s.
+        let mut builder = DefaultBuilder::new();
+        let x = builder.read::<U32Variable>();
+        let mut acc = x.to_be_bits(&mut builder);
+        let num_gates = builder.api.num_gates();
+        for _ in 0..16 {
+            let a = U32Variable::from_be_bits(&acc, &mut builder).to_u64(&mut builder);
+            let b = U32Variable::from_be_bits(&acc, &mut builder).to_u64(&mut builder);
+            let c = builder.add(a, b);
+            acc = c.limbs[0].to_be_bits(&mut builder);
+        }
+        let old_cost = builder.api.num_gates() - num_gates;
+
+        let mut builder = DefaultBuilder::new();
+        let x = builder.read::<U32Variable>();
+        let mut acc: [BoolVariable; 32] = x.to_be_bits(&mut builder);
+        let num_gates = builder.api.num_gates();
+        for _ in 0..16 {
+            acc = builder.add_arr(acc, acc);
+        }
+        let new_cost = builder.api.num_gates() - num_gates;
+
+        assert!(
+            new_cost < old_cost,
+            "add_arr costs {} gates, previously {}",
+            new_cost,
+            old_cost
+        );
+    }
+
+    #[test]
+    fn test_add_many_arr_gate_count() {
+        // Adding five words as in the SHA-256 round function, once with `add_many_arr` and once
+        // with a chain of `add_arr`, which decomposes every intermediate sum into bits.
+        fn operands(builder: &mut DefaultBuilder) -> Vec<[BoolVariable; 32]> {
+            (0..5)
+                .map(|_| {
+                    let x = builder.read::<U32Variable>();
+                    x.to_be_bits(builder)
+                })
+                .collect()
+        }
+
+        let mut builder = DefaultBuilder::new();
+        let words = operands(&mut builder);
+        let num_gates = builder.api.num_gates();
+        let mut acc = words[0];
+        for word in words[1..].iter() {
+            acc = builder.add_arr(acc, *word);
+        }
+        let chained_cost = builder.api.num_gates() - num_gates;
+
+        let mut builder = DefaultBuilder::new();
+        let words = operands(&mut builder);
+        let num_gates = builder.api.num_gates();
+        builder.add_many_arr(&words);
+        let many_cost = builder.api.num_gates() - num_gates;
+
+        assert!(
+            many_cost < chained_cost,
+            "add_many_arr costs {} gates, chained add_arr {}",
+            many_cost,
+            chained_cost
+        );
+    }
+
+    #[test]
+    fn test_add_many_arr() {
+        let mut builder = DefaultBuilder::new();
+        let x = builder.read::<U64Variable>();
+        let y = builder.read::<U64Variable>();
+        let z = builder.read::<U64Variable>();
+
+        let bits = [x, y, z].map(|v| {
+            let bits = v.to_be_bits(&mut builder);
+            let bits: [BoolVariable; 64] = bits.try_into().unwrap();
+            bits
+        });
+        let sum_64 = builder.add_many_arr(&bits);
+        let sum_64_bytes = sum_64
+            .chunks(8)
+            .map(|byte| ByteVariable(byte.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let sum_64 = U64Variable::decode(&mut builder, &sum_64_bytes);
+        builder.write(sum_64);
+
+        let low_bytes = bits.map(|bits| {
+            let low: [BoolVariable; 8] = bits[56..].try_into().unwrap();
+            low
+        });
+        let sum_8 = builder.add_many_arr(&low_bytes);
+        builder.write(ByteVariable(sum_8));
+
+        let circuit = builder.build();
+
+        let (x, y, z) = (u64::MAX - 1, 0x0123456789abcdefu64, 0xfedcba9876543210u64);
+        let mut input = circuit.input();
+        input.write::<U64Variable>(x);
+        input.write::<U64Variable>(y);
+        input.write::<U64Variable>(z);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(
+            output.read::<U64Variable>(),
+            x.wrapping_add(y).wrapping_add(z)
+        );
+        assert_eq!(
+            output.read::<ByteVariable>(),
+            (x as u8).wrapping_add(y as u8).wrapping_add(z as u8)
+        );
+    }
+}
@@ -0,0 +1,363 @@
+//! A harness which checks the hash gadgets against their reference implementations.
+//!
+//! Every gadget hashes random messages whose lengths sit around its padding boundaries, e.g. 55,
+//! 56, 63, 64 and 65 bytes for SHA-256, both as fixed-length inputs and as variable-length inputs
+//! followed by garbage. The messages are drawn from a seeded RNG so that failures can be
+//! reproduced. The lengths of the variable-length inputs are circuit inputs, not constants. The
+//! circuits are mock built, and for the gadgets which hash with gates only, the test checks that
+//! messages spanning more blocks or permutations add more gates.
+
+use crypto::digest::Digest;
+use plonky2::field::types::Sample;
+use plonky2::hash::hashing::PlonkyPermutation;
+use plonky2::hash::poseidon::PoseidonHash;
+use plonky2::plonk::config::{AlgebraicHasher, Hasher};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::frontend::hash::poseidon::poseidon2::Poseidon2Hash;
+use crate::frontend::hash::poseidon::poseidon256::PoseidonHashOutVariable;
+use crate::prelude::*;
+use crate::utils::hash::{sha256, sha512};
+
+type L = DefaultParameters;
+const D: usize = 2;
+
+/// The seed of the RNG which draws the messages.
+const SEED: u64 = 0x5eed;
+
+type FixedGadget = fn(&mut CircuitBuilder<L, D>, &[ByteVariable]) -> Vec<ByteVariable>;
+type VariableGadget =
+    fn(&mut CircuitBuilder<L, D>, &[ByteVariable], U32Variable) -> Vec<ByteVariable>;
+
+/// A byte-oriented hash gadget and its reference implementation.
+struct HashGadget {
+    name: &'static str,
+    /// The size of the blocks which the message is padded to.
+    block_size: usize,
+    /// The minimum number of bytes the padding appends to the message.
+    min_padding: usize,
+    reference: fn(&[u8]) -> Vec<u8>,
+    fixed: FixedGadget,
+    variable: Option<VariableGadget>,
+}
+
+impl HashGadget {
+    /// The message lengths around the first two padding boundaries, and a random one.
+    fn lengths(&self, rng: &mut StdRng) -> Vec<usize> {
+        let (b, p) = (self.block_size, self.min_padding);
+        let mut lengths = vec![0, 1, b - p - 1, b - 1, b, b + 1, 2 * b - p, 2 * b];
+        if p > 0 {
+            lengths.push(b - p);
+        }
+        lengths.push(rng.gen_range(0..3 * b));
+        lengths.sort();
+        lengths.dedup();
+        lengths
+    }
+}
+
+fn random_bytes(rng: &mut StdRng, len: usize) -> Vec<u8> {
+    (0..len).map(|_| rng.gen()).collect()
+}
+
+/// The number of gates added by the call on a message of `length` bytes or elements.
+fn cost(costs: &[(usize, usize)], length: usize) -> usize {
+    costs.iter().find(|(l, _)| *l == length).unwrap().1
+}
+
+fn read_bytes(builder: &mut CircuitBuilder<L, D>, len: usize) -> Vec<ByteVariable> {
+    (0..len).map(|_| builder.read::<ByteVariable>()).collect()
+}
+
+/// Checks `gadget` against its reference on messages of every length, and returns the number of
+/// gates added by each fixed-length call. For the Curta gadgets, this leaves out the STARK and its
+/// verifier, which are added when the circuit is built.
+fn check_hash_gadget(gadget: &HashGadget) -> Vec<(usize, usize)> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let lengths = gadget.lengths(&mut rng);
+    let max_length = *lengths.iter().max().unwrap();
+
+    let mut builder = CircuitBuilder::<L, D>::new();
+    let mut messages = Vec::new();
+    let mut costs = Vec::new();
+    for &length in lengths.iter() {
+        let message = read_bytes(&mut builder, length);
+        let nb_gates = builder.api.num_gates();
+        let digest = (gadget.fixed)(&mut builder, &message);
+        costs.push((length, builder.api.num_gates() - nb_gates));
+        digest.into_iter().for_each(|byte| builder.write(byte));
+        messages.push(random_bytes(&mut rng, length));
+    }
+    if let Some(variable) = gadget.variable {
+        let buffer = read_bytes(&mut builder, max_length + gadget.block_size);
+        for _ in lengths.iter() {
+            let length_variable = builder.read::<U32Variable>();
+            let digest = variable(&mut builder, &buffer, length_variable);
+            digest.into_iter().for_each(|byte| builder.write(byte));
+        }
+    }
+
+    let circuit = builder.mock_build();
+    let mut input = circuit.input();
+    for message in messages.iter() {
+        message
+            .iter()
+            .for_each(|byte| input.write::<ByteVariable>(*byte));
+    }
+    let buffer = random_bytes(&mut rng, max_length + gadget.block_size);
+    if gadget.variable.is_some() {
+        buffer
+            .iter()
+            .for_each(|byte| input.write::<ByteVariable>(*byte));
+        lengths
+            .iter()
+            .for_each(|length| input.write::<U32Variable>(*length as u32));
+    }
+    let (_, mut output) = circuit.mock_prove(&input);
+
+    for (length, message) in lengths.iter().zip(messages.iter()) {
+        let expected = (gadget.reference)(message);
+        let digest = (0..expected.len())
+            .map(|_| output.read::<ByteVariable>())
+            .collect::<Vec<_>>();
+        assert_eq!(digest, expected, "{} of {} bytes", gadget.name, length);
+    }
+    if gadget.variable.is_some() {
+        for length in lengths.iter() {
+            let expected = (gadget.reference)(&buffer[..*length]);
+            let digest = (0..expected.len())
+                .map(|_| output.read::<ByteVariable>())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                digest, expected,
+                "{} of the first {} bytes",
+                gadget.name, length
+            );
+        }
+    }
+
+    costs
+}
+
+/// Checks that the fixed-length calls of a gadget which hashes in the circuit cost more on
+/// messages spanning more blocks.
+fn assert_cost_grows_with_blocks(gadget: &HashGadget, costs: &[(usize, usize)]) {
+    let (b, p) = (gadget.block_size, gadget.min_padding);
+    // A single block, and three blocks.
+    let (short, long) = (b - p - 1, 2 * b);
+    assert!(
+        cost(costs, long) > cost(costs, short),
+        "{}: {} bytes cost {} gates, {} bytes cost {} gates",
+        gadget.name,
+        short,
+        cost(costs, short),
+        long,
+        cost(costs, long)
+    );
+}
+
+/// Checks a field-element hash gadget against its reference on inputs of every length up to a
+/// few permutations, and that inputs which need more permutations cost more gates.
+fn check_field_hash_gadget<H: AlgebraicHasher<GoldilocksField>>(
+    name: &str,
+    gadget: fn(&mut CircuitBuilder<L, D>, &[Variable]) -> PoseidonHashOutVariable,
+) {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let rate = <H::Permutation as PlonkyPermutation<GoldilocksField>>::RATE;
+    let lengths = (0..=2 * rate + 1).collect::<Vec<_>>();
+
+    let mut builder = CircuitBuilder::<L, D>::new();
+    let mut costs = Vec::new();
+    for &length in lengths.iter() {
+        let input = (0..length)
+            .map(|_| builder.read::<Variable>())
+            .collect::<Vec<_>>();
+        let nb_gates = builder.api.num_gates();
+        let hash = gadget(&mut builder, &input);
+        costs.push((length, builder.api.num_gates() - nb_gates));
+        builder.write(hash);
+    }
+
+    // One permutation, and three permutations.
+    assert!(
+        cost(&costs, 2 * rate + 1) > cost(&costs, rate),
+        "{}: {} elements cost {} gates, {} elements cost {} gates",
+        name,
+        rate,
+        cost(&costs, rate),
+        2 * rate + 1,
+        cost(&costs, 2 * rate + 1)
+    );
+
+    let circuit = builder.mock_build();
+    let mut input = circuit.input();
+    let inputs = lengths
+        .iter()
+        .map(|length| {
+            (0..*length)
+                .map(|_| GoldilocksField::sample(&mut rng))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    for elements in inputs.iter() {
+        elements
+            .iter()
+            .for_each(|element| input.write::<Variable>(*element));
+    }
+    let (_, mut output) = circuit.mock_prove(&input);
+
+    for elements in inputs.iter() {
+        assert_eq!(
+            output.read::<PoseidonHashOutVariable>(),
+            <H as Hasher<GoldilocksField>>::hash_no_pad(elements),
+            "{} of {} elements",
+            name,
+            elements.len()
+        );
+    }
+}
+
+fn blake2b(message: &[u8]) -> Vec<u8> {
+    let mut hasher = crypto::blake2b::Blake2b::new(32);
+    hasher.input(message);
+    let mut digest = vec![0u8; 32];
+    hasher.result(&mut digest);
+    digest
+}
+
+fn ripemd160(message: &[u8]) -> Vec<u8> {
+    let mut hasher = crypto::ripemd160::Ripemd160::new();
+    hasher.input(message);
+    let mut digest = vec![0u8; 20];
+    hasher.result(&mut digest);
+    digest
+}
+
+#[test]
+#[cfg_attr(feature = "ci", ignore)]
+fn test_sha256_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    let gadget = HashGadget {
+        name: "sha256",
+        block_size: 64,
+        min_padding: 9,
+        reference: |message| sha256(message).to_vec(),
+        fixed: |builder, message| builder.sha256(message).as_bytes().to_vec(),
+        variable: Some(|builder, message, length| {
+            let mut hasher = builder.sha256_init();
+            hasher.update(message);
+            hasher
+                .finalize_variable(builder, length)
+                .as_bytes()
+                .to_vec()
+        }),
+    };
+    let costs = check_hash_gadget(&gadget);
+    assert_cost_grows_with_blocks(&gadget, &costs);
+
+    // The length of a 56 byte message spills into a second block.
+    assert!(cost(&costs, 56) > cost(&costs, 55));
+}
+
+#[test]
+#[cfg_attr(feature = "ci", ignore)]
+fn test_curta_sha256_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    check_hash_gadget(&HashGadget {
+        name: "curta_sha256",
+        block_size: 64,
+        min_padding: 9,
+        reference: |message| sha256(message).to_vec(),
+        fixed: |builder, message| builder.curta_sha256(message).as_bytes().to_vec(),
+        variable: Some(|builder, message, length| {
+            builder
+                .curta_sha256_variable(message, length)
+                .as_bytes()
+                .to_vec()
+        }),
+    });
+}
+
+#[test]
+#[cfg_attr(feature = "ci", ignore)]
+fn test_curta_sha512_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    check_hash_gadget(&HashGadget {
+        name: "curta_sha512",
+        block_size: 128,
+        min_padding: 17,
+        reference: |message| sha512(message).to_vec(),
+        fixed: |builder, message| builder.curta_sha512(message).0.to_vec(),
+        variable: Some(|builder, message, length| {
+            builder.curta_sha512_variable(message, length).0.to_vec()
+        }),
+    });
+}
+
+#[test]
+#[cfg_attr(feature = "ci", ignore)]
+fn test_curta_blake2b_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    check_hash_gadget(&HashGadget {
+        name: "curta_blake2b",
+        block_size: 128,
+        min_padding: 0,
+        reference: blake2b,
+        fixed: |builder, message| builder.curta_blake2b(message).as_bytes().to_vec(),
+        variable: Some(|builder, message, length| {
+            builder
+                .curta_blake2b_variable(message, length)
+                .as_bytes()
+                .to_vec()
+        }),
+    });
+}
+
+#[test]
+#[cfg_attr(feature = "ci", ignore)]
+fn test_keccak256_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    let gadget = HashGadget {
+        name: "keccak256",
+        block_size: 136,
+        min_padding: 1,
+        reference: |message| ethers::utils::keccak256(message).to_vec(),
+        fixed: |builder, message| builder.keccak256(message).as_bytes().to_vec(),
+        variable: Some(|builder, message, length| {
+            builder
+                .keccak256_variable(message, length.variable)
+                .as_bytes()
+                .to_vec()
+        }),
+    };
+    let costs = check_hash_gadget(&gadget);
+    assert_cost_grows_with_blocks(&gadget, &costs);
+}
+
+#[test]
+#[cfg_attr(feature = "ci", ignore)]
+fn test_ripemd160_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    let gadget = HashGadget {
+        name: "ripemd160",
+        block_size: 64,
+        min_padding: 9,
+        reference: ripemd160,
+        fixed: |builder, message| builder.ripemd160(message).0.to_vec(),
+        variable: None,
+    };
+    let costs = check_hash_gadget(&gadget);
+    assert_cost_grows_with_blocks(&gadget, &costs);
+}
+
+#[test]
+fn test_poseidon_equivalence() {
+    env_logger::try_init().unwrap_or_default();
+    check_field_hash_gadget::<PoseidonHash>("poseidon", |builder, input| {
+        builder.poseidon_hash_with::<PoseidonHash>(input)
+    });
+    check_field_hash_gadget::<Poseidon2Hash>("poseidon2", |builder, input| {
+        builder.poseidon2_hash(input)
+    });
+}
@@ -0,0 +1,131 @@
+//! HKDF (RFC 5869) over HMAC-SHA256 and HMAC-SHA512.
+
+use plonky2::util::ceil_div_usize;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::vars::{ByteVariable, Bytes32Variable, BytesVariable};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes `T(1) | T(2) | ...` truncated to `length` bytes, where
+    /// `T(i) = HMAC(prk, T(i - 1) | info | i)`.
+    fn hkdf_expand(
+        &mut self,
+        prk: &[ByteVariable],
+        info: &[ByteVariable],
+        length: usize,
+        hash_len: usize,
+        hmac: fn(&mut Self, &[ByteVariable], &[ByteVariable]) -> Vec<ByteVariable>,
+    ) -> Vec<ByteVariable> {
+        assert!(
+            length <= 255 * hash_len,
+            "hkdf cannot expand to more than 255 blocks"
+        );
+        let mut okm = Vec::with_capacity(length);
+        let mut t = Vec::new();
+        for i in 1..=ceil_div_usize(length, hash_len) {
+            let counter = self.constant::<ByteVariable>(i as u8);
+            let message = [t, info.to_vec(), vec![counter]].concat();
+            t = hmac(self, prk, &message);
+            okm.extend_from_slice(&t);
+        }
+        okm.truncate(length);
+        okm
+    }
+
+    /// The HKDF-Extract step over HMAC-SHA256, which returns the pseudorandom key.
+    ///
+    /// An empty salt is equivalent to the default salt of RFC 5869, i.e. 32 zero bytes.
+    pub fn hkdf_sha256_extract(
+        &mut self,
+        salt: &[ByteVariable],
+        ikm: &[ByteVariable],
+    ) -> Bytes32Variable {
+        self.hmac_sha256(salt, ikm)
+    }
+
+    /// The HKDF-Expand step over HMAC-SHA256, which returns `length` bytes of output keying
+    /// material.
+    pub fn hkdf_sha256_expand(
+        &mut self,
+        prk: &[ByteVariable],
+        info: &[ByteVariable],
+        length: usize,
+    ) -> Vec<ByteVariable> {
+        self.hkdf_expand(prk, info, length, 32, |builder, key, message| {
+            builder.hmac_sha256(key, message).as_bytes().to_vec()
+        })
+    }
+
+    /// The HKDF-Extract step over HMAC-SHA512, which returns the pseudorandom key.
+    ///
+    /// An empty salt is equivalent to the default salt of RFC 5869, i.e. 64 zero bytes.
+    pub fn hkdf_sha512_extract(
+        &mut self,
+        salt: &[ByteVariable],
+        ikm: &[ByteVariable],
+    ) -> BytesVariable<64> {
+        self.hmac_sha512(salt, ikm)
+    }
+
+    /// The HKDF-Expand step over HMAC-SHA512, which returns `length` bytes of output keying
+    /// material.
+    pub fn hkdf_sha512_expand(
+        &mut self,
+        prk: &[ByteVariable],
+        info: &[ByteVariable],
+        length: usize,
+    ) -> Vec<ByteVariable> {
+        self.hkdf_expand(prk, info, length, 64, |builder, key, message| {
+            builder.hmac_sha512(key, message).0.to_vec()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::prelude::*;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_hkdf_rfc5869() {
+        env::set_var("RUST_LOG", "debug");
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        // Test case 1 of RFC 5869, and the same inputs over SHA-512.
+        let mut builder = DefaultBuilder::new();
+        let ikm = builder.constant::<BytesVariable<22>>([0x0b; 22]);
+        let salt = builder.constant::<BytesVariable<13>>(bytes!("000102030405060708090a0b0c"));
+        let info = builder.constant::<BytesVariable<10>>(bytes!("f0f1f2f3f4f5f6f7f8f9"));
+
+        let prk = builder.hkdf_sha256_extract(&salt.0, &ikm.0);
+        let okm = builder.hkdf_sha256_expand(&prk.as_bytes(), &info.0, 42);
+        let expected_prk = builder.constant::<Bytes32Variable>(bytes32!(
+            "0x077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        ));
+        builder.assert_is_equal(prk, expected_prk);
+        let expected_okm = builder.constant::<BytesVariable<42>>(bytes!(
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        ));
+        builder.assert_is_equal(BytesVariable::<42>(okm.try_into().unwrap()), expected_okm);
+
+        let prk = builder.hkdf_sha512_extract(&salt.0, &ikm.0);
+        let okm = builder.hkdf_sha512_expand(&prk.0, &info.0, 42);
+        let expected_prk = builder.constant::<BytesVariable<64>>(bytes!(
+            "665799823737ded04a88e47e54a5890bb2c3d247c7a4254a8e61350723590a26c36238127d8661b88cf80ef802d57e2f7cebcf1e00e083848be19929c61b4237"
+        ));
+        builder.assert_is_equal(prk, expected_prk);
+        let expected_okm = builder.constant::<BytesVariable<42>>(bytes!(
+            "832390086cda71fb47625bb5ceb168e4c8e26a1a16ed34d9fc7fe92c1481579338da362cb8d9f925d7cb"
+        ));
+        builder.assert_is_equal(BytesVariable::<42>(okm.try_into().unwrap()), expected_okm);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+}
@@ -0,0 +1,218 @@
+//! HMAC (RFC 2104) and HKDF (RFC 5869) over SHA-256 and SHA-512, built on the Curta SHA
+//! accelerators.
+
+pub mod hkdf;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::vars::{ByteVariable, Bytes32Variable, BytesVariable, U32Variable};
+
+const SHA256_BLOCK_SIZE_BYTES: usize = 64;
+const SHA512_BLOCK_SIZE_BYTES: usize = 128;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes `byte ^ c` by negating the bits which are set in `c`.
+    fn xor_const_byte(&mut self, byte: ByteVariable, c: u8) -> ByteVariable {
+        let mut bits = byte.as_be_bits();
+        for (i, bit) in bits.iter_mut().enumerate() {
+            if (c >> (7 - i)) & 1 == 1 {
+                *bit = self.not(*bit);
+            }
+        }
+        ByteVariable(bits)
+    }
+
+    /// Pads the key with zeros to the block size, after hashing it if it is longer than a block,
+    /// and returns it xored with `IPAD` and with `OPAD`.
+    fn hmac_pads(
+        &mut self,
+        key: &[ByteVariable],
+        block_size: usize,
+        hash: fn(&mut Self, &[ByteVariable]) -> Vec<ByteVariable>,
+    ) -> (Vec<ByteVariable>, Vec<ByteVariable>) {
+        let mut key = if key.len() > block_size {
+            hash(self, key)
+        } else {
+            key.to_vec()
+        };
+        key.resize(block_size, self.constant::<ByteVariable>(0));
+
+        let inner_pad = key.iter().map(|b| self.xor_const_byte(*b, IPAD)).collect();
+        let outer_pad = key.iter().map(|b| self.xor_const_byte(*b, OPAD)).collect();
+        (inner_pad, outer_pad)
+    }
+
+    /// Asserts that `length` is at most the length of the message, before it is offset by the
+    /// block size of the inner pad, where it could wrap around.
+    fn hmac_assert_length(&mut self, message: &[ByteVariable], length: U32Variable) {
+        let max_length = self.constant::<U32Variable>(message.len() as u32);
+        let in_range = self.lte(length, max_length);
+        let t = self._true();
+        self.assert_is_equal(in_range, t);
+    }
+
+    fn curta_sha256_bytes(&mut self, input: &[ByteVariable]) -> Vec<ByteVariable> {
+        self.curta_sha256(input).as_bytes().to_vec()
+    }
+
+    fn curta_sha512_bytes(&mut self, input: &[ByteVariable]) -> Vec<ByteVariable> {
+        self.curta_sha512(input).0.to_vec()
+    }
+
+    /// Computes the HMAC-SHA256 of a message of fixed length.
+    pub fn hmac_sha256(
+        &mut self,
+        key: &[ByteVariable],
+        message: &[ByteVariable],
+    ) -> Bytes32Variable {
+        let (inner_pad, outer_pad) =
+            self.hmac_pads(key, SHA256_BLOCK_SIZE_BYTES, Self::curta_sha256_bytes);
+        let inner_hash = self.curta_sha256(&[inner_pad, message.to_vec()].concat());
+        self.curta_sha256(&[outer_pad, inner_hash.as_bytes().to_vec()].concat())
+    }
+
+    /// Computes the HMAC-SHA256 of the first `length` bytes of `message`.
+    pub fn hmac_sha256_variable(
+        &mut self,
+        key: &[ByteVariable],
+        message: &[ByteVariable],
+        length: U32Variable,
+    ) -> Bytes32Variable {
+        let (inner_pad, outer_pad) =
+            self.hmac_pads(key, SHA256_BLOCK_SIZE_BYTES, Self::curta_sha256_bytes);
+        self.hmac_assert_length(message, length);
+        let block_size = self.constant::<U32Variable>(SHA256_BLOCK_SIZE_BYTES as u32);
+        let inner_length = self.add(block_size, length);
+        let inner_hash =
+            self.curta_sha256_variable(&[inner_pad, message.to_vec()].concat(), inner_length);
+        self.curta_sha256(&[outer_pad, inner_hash.as_bytes().to_vec()].concat())
+    }
+
+    /// Computes the HMAC-SHA512 of a message of fixed length.
+    pub fn hmac_sha512(
+        &mut self,
+        key: &[ByteVariable],
+        message: &[ByteVariable],
+    ) -> BytesVariable<64> {
+        let (inner_pad, outer_pad) =
+            self.hmac_pads(key, SHA512_BLOCK_SIZE_BYTES, Self::curta_sha512_bytes);
+        let inner_hash = self.curta_sha512(&[inner_pad, message.to_vec()].concat());
+        self.curta_sha512(&[outer_pad, inner_hash.0.to_vec()].concat())
+    }
+
+    /// Computes the HMAC-SHA512 of the first `length` bytes of `message`.
+    pub fn hmac_sha512_variable(
+        &mut self,
+        key: &[ByteVariable],
+        message: &[ByteVariable],
+        length: U32Variable,
+    ) -> BytesVariable<64> {
+        let (inner_pad, outer_pad) =
+            self.hmac_pads(key, SHA512_BLOCK_SIZE_BYTES, Self::curta_sha512_bytes);
+        self.hmac_assert_length(message, length);
+        let block_size = self.constant::<U32Variable>(SHA512_BLOCK_SIZE_BYTES as u32);
+        let inner_length = self.add(block_size, length);
+        let inner_hash =
+            self.curta_sha512_variable(&[inner_pad, message.to_vec()].concat(), inner_length);
+        self.curta_sha512(&[outer_pad, inner_hash.0.to_vec()].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::prelude::*;
+
+    /// Test cases 1, 2 and 6 of RFC 4231, the last one with a key longer than a block.
+    fn rfc4231_test_cases() -> Vec<(Vec<u8>, Vec<u8>, [u8; 32], [u8; 64])> {
+        vec![
+            (
+                vec![0x0b; 20],
+                b"Hi There".to_vec(),
+                bytes!("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"),
+                bytes!("87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"),
+            ),
+            (
+                b"Jefe".to_vec(),
+                b"what do ya want for nothing?".to_vec(),
+                bytes!("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"),
+                bytes!("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"),
+            ),
+            (
+                vec![0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First".to_vec(),
+                bytes!("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"),
+                bytes!("80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"),
+            ),
+        ]
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_hmac_rfc4231() {
+        env::set_var("RUST_LOG", "debug");
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let mut builder = DefaultBuilder::new();
+        for (key, message, expected_sha256, expected_sha512) in rfc4231_test_cases() {
+            let key = key
+                .iter()
+                .map(|b| builder.constant::<ByteVariable>(*b))
+                .collect::<Vec<_>>();
+            let message_len = message.len();
+            let mut message = message
+                .iter()
+                .map(|b| builder.constant::<ByteVariable>(*b))
+                .collect::<Vec<_>>();
+
+            let mac = builder.hmac_sha256(&key, &message);
+            let expected = builder.constant::<Bytes32Variable>(expected_sha256.into());
+            builder.assert_is_equal(mac, expected);
+            let mac = builder.hmac_sha512(&key, &message);
+            let expected = builder.constant::<BytesVariable<64>>(expected_sha512);
+            builder.assert_is_equal(mac, expected);
+
+            // The same messages, followed by garbage which is not part of them.
+            message.resize(message_len + 70, builder.constant::<ByteVariable>(0xff));
+            let length = builder.constant::<U32Variable>(message_len as u32);
+            let mac = builder.hmac_sha256_variable(&key, &message, length);
+            let expected = builder.constant::<Bytes32Variable>(expected_sha256.into());
+            builder.assert_is_equal(mac, expected);
+            let mac = builder.hmac_sha512_variable(&key, &message, length);
+            let expected = builder.constant::<BytesVariable<64>>(expected_sha512);
+            builder.assert_is_equal(mac, expected);
+        }
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_hmac_sha256_variable_length_too_long() {
+        let mut builder = DefaultBuilder::new();
+        let key = builder.read::<ArrayVariable<ByteVariable, 20>>();
+        let message = builder.read::<ArrayVariable<ByteVariable, 8>>();
+        let length = builder.read::<U32Variable>();
+        let mac = builder.hmac_sha256_variable(key.as_slice(), message.as_slice(), length);
+        builder.write(mac);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, 20>>(vec![0x0b; 20]);
+        input.write::<ArrayVariable<ByteVariable, 8>>(b"Hi There".to_vec());
+        // `64 + length` wraps around to 1.
+        input.write::<U32Variable>(u32::MAX - 62);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+}
@@ -1,122 +1,274 @@
-use core::marker::PhantomData;
+//! A constrained implementation of the keccak-f[1600] permutation and the keccak256 sponge using
+//! plain plonky2 gates.
+//!
+//! The state is kept as 25 lanes of 64 little-endian bits, so that the rotations and the lane
+//! permutation of each round are free rewirings and only `theta`, `chi` and `iota` cost gates.
 
-use ethers::types::H256;
-use ethers::utils::keccak256;
-use plonky2::field::types::PrimeField64;
-use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
-use plonky2::iop::target::Target;
-use plonky2::iop::witness::PartitionWitness;
-use plonky2::plonk::circuit_data::CommonCircuitData;
-use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+use plonky2::field::types::Field;
 
 use crate::backend::circuit::PlonkParameters;
-use crate::frontend::vars::{ByteVariable, Bytes32Variable, CircuitVariable, Variable};
-
-#[derive(Debug, Clone)]
-pub struct Keccak256Generator<L: PlonkParameters<D>, const D: usize> {
-    pub input: Vec<ByteVariable>,
-    pub output: Bytes32Variable,
-    pub length: Option<Variable>,
-    pub _phantom: PhantomData<L>,
-}
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitVariable, Variable,
+};
+
+/// The rate of keccak256 in bytes.
+pub const KECCAK256_RATE: usize = 136;
+
+/// A lane of the keccak state, as little-endian bits.
+type Lane = [BoolVariable; 64];
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the `rho` step, indexed by `x + 5 * y`.
+const ROTATION_OFFSETS: [usize; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
 
-impl<L: PlonkParameters<D>, const D: usize> Keccak256Generator<L, D> {
-    pub fn id() -> String {
-        "Keccak256Generator".to_string()
+fn rotate_left(lane: &Lane, n: usize) -> Lane {
+    let mut rotated = *lane;
+    for (i, bit) in lane.iter().enumerate() {
+        rotated[(i + n) % 64] = *bit;
     }
+    rotated
 }
 
-impl<L: PlonkParameters<D>, const D: usize> SimpleGenerator<L::Field, D>
-    for Keccak256Generator<L, D>
-{
-    fn id(&self) -> String {
-        Self::id()
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes `a ^ b` with a single arithmetic operation on top of `a + b`.
+    fn keccak_xor(&mut self, a: BoolVariable, b: BoolVariable) -> BoolVariable {
+        let sum = self.api.add(a.variable.0, b.variable.0);
+        let xor = self.api.arithmetic(
+            -L::Field::TWO,
+            L::Field::ONE,
+            a.variable.0,
+            b.variable.0,
+            sum,
+        );
+        BoolVariable::from_variables_unsafe(&[Variable(xor)])
     }
 
-    fn dependencies(&self) -> Vec<Target> {
-        let mut targets: Vec<Target> = Vec::new();
-        targets.extend(
-            self.input
-                .iter()
-                .flat_map(|x| x.targets())
-                .collect::<Vec<Target>>(),
+    /// Computes `!a & b` as `b - a * b`.
+    fn keccak_andn(&mut self, a: BoolVariable, b: BoolVariable) -> BoolVariable {
+        let andn = self.api.arithmetic(
+            -L::Field::ONE,
+            L::Field::ONE,
+            a.variable.0,
+            b.variable.0,
+            b.variable.0,
         );
-        if let Some(length) = self.length {
-            targets.extend(length.targets());
+        BoolVariable::from_variables_unsafe(&[Variable(andn)])
+    }
+
+    fn keccak_xor_lanes(&mut self, a: &Lane, b: &Lane) -> Lane {
+        let mut xor = *a;
+        for (xor_bit, (a_bit, b_bit)) in xor.iter_mut().zip(a.iter().zip(b.iter())) {
+            *xor_bit = self.keccak_xor(*a_bit, *b_bit);
         }
-        targets
+        xor
     }
 
-    fn run_once(
-        &self,
-        witness: &PartitionWitness<L::Field>,
-        out_buffer: &mut GeneratedValues<L::Field>,
-    ) {
-        let mut length = self.input.len();
-        if let Some(length_variable) = self.length {
-            length = length_variable.get(witness).to_canonical_u64() as usize;
+    /// Applies the keccak-f[1600] permutation to `state`.
+    pub(crate) fn keccak_f(&mut self, state: &mut [Lane; 25]) {
+        for round_constant in ROUND_CONSTANTS.iter() {
+            // theta
+            let mut c = Vec::with_capacity(5);
+            for x in 0..5 {
+                let mut column = state[x];
+                for y in 1..5 {
+                    column = self.keccak_xor_lanes(&column, &state[x + 5 * y]);
+                }
+                c.push(column);
+            }
+            for x in 0..5 {
+                let rotated = rotate_left(&c[(x + 1) % 5], 1);
+                let d = self.keccak_xor_lanes(&c[(x + 4) % 5], &rotated);
+                for y in 0..5 {
+                    state[x + 5 * y] = self.keccak_xor_lanes(&state[x + 5 * y], &d);
+                }
+            }
+
+            // rho and pi
+            let mut b = *state;
+            for x in 0..5 {
+                for y in 0..5 {
+                    b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                        rotate_left(&state[x + 5 * y], ROTATION_OFFSETS[x + 5 * y]);
+                }
+            }
+
+            // chi
+            for x in 0..5 {
+                for y in 0..5 {
+                    let (b0, b1, b2) =
+                        (b[x + 5 * y], b[(x + 1) % 5 + 5 * y], b[(x + 2) % 5 + 5 * y]);
+                    for (i, bit) in state[x + 5 * y].iter_mut().enumerate() {
+                        let andn = self.keccak_andn(b1[i], b2[i]);
+                        *bit = self.keccak_xor(b0[i], andn);
+                    }
+                }
+            }
+
+            // iota
+            for i in 0..64 {
+                if (round_constant >> i) & 1 == 1 {
+                    state[0][i] = self.not(state[0][i]);
+                }
+            }
         }
-        let input: Vec<u8> = self.input.iter().map(|x| x.get(witness)).collect();
-        let result = keccak256(&input[..length]);
-        self.output.set(out_buffer, H256::from_slice(&result[..]));
     }
 
-    #[allow(unused_variables)]
-    fn serialize(
-        &self,
-        dst: &mut Vec<u8>,
-        common_data: &CommonCircuitData<L::Field, D>,
-    ) -> IoResult<()> {
-        // Write each input as a target
-        let input_bytes = self
-            .input
-            .iter()
-            .flat_map(|x| x.targets())
-            .collect::<Vec<Target>>();
-        dst.write_target_vec(&input_bytes)?;
+    /// Absorbs the padded little-endian bits of each block and returns the state after each one.
+    fn keccak256_absorb(&mut self, blocks: &[Vec<BoolVariable>]) -> Vec<[Lane; 25]> {
+        let f = self._false();
+        let mut state = [[f; 64]; 25];
+        let mut states = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            assert_eq!(block.len(), KECCAK256_RATE * 8);
+            for (i, lane) in block.chunks_exact(64).enumerate() {
+                state[i] = self.keccak_xor_lanes(&state[i], &lane.try_into().unwrap());
+            }
+            self.keccak_f(&mut state);
+            states.push(state);
+        }
+        states
+    }
 
-        dst.write_target_vec(&self.output.targets())?;
+    /// Reads the first 32 bytes of the state, i.e. its first 4 lanes, as a digest.
+    fn keccak256_squeeze(&mut self, lanes: &[Lane]) -> Bytes32Variable {
+        let bytes = lanes[..4]
+            .iter()
+            .flat_map(|lane| lane.chunks_exact(8))
+            .map(|le_bits| {
+                let mut be_bits: [BoolVariable; 8] = le_bits.try_into().unwrap();
+                be_bits.reverse();
+                ByteVariable(be_bits)
+            })
+            .collect::<Vec<_>>();
+        Bytes32Variable(BytesVariable(bytes.try_into().unwrap()))
+    }
 
-        dst.write_bool(self.length.is_some())?;
-        if self.length.is_some() {
-            dst.write_target_vec(&self.length.unwrap().targets())
-        } else {
-            Ok(())
+    /// Computes the keccak256 hash of a message of fixed length.
+    pub fn keccak256(&mut self, bytes: &[ByteVariable]) -> Bytes32Variable {
+        let mut padded = bytes.to_vec();
+        padded.push(self.constant::<ByteVariable>(0x01));
+        while padded.len() % KECCAK256_RATE != 0 {
+            padded.push(self.constant::<ByteVariable>(0));
         }
+        let last = padded.len() - 1;
+        padded[last] = self.constant::<ByteVariable>(if last == bytes.len() { 0x81 } else { 0x80 });
+
+        let blocks = padded
+            .chunks_exact(KECCAK256_RATE)
+            .map(|block| block.iter().flat_map(|byte| byte.as_le_bits()).collect())
+            .collect::<Vec<_>>();
+        let states = self.keccak256_absorb(&blocks);
+        self.keccak256_squeeze(states.last().unwrap())
     }
 
-    #[allow(unused_variables)]
-    fn deserialize(
-        src: &mut Buffer,
-        common_data: &CommonCircuitData<L::Field, D>,
-    ) -> IoResult<Self> {
-        let input_targets = src.read_target_vec()?;
-        // Convert input_targets progressively into ByteVariables by chunks of 8
-        let mut input = Vec::new();
-        for i in 0..input_targets.len() / 8 {
-            let mut byte_targets = Vec::new();
-            for j in 0..8 {
-                byte_targets.push(input_targets[i * 8 + j]);
+    /// Computes the keccak256 hash of the first `length` bytes of `bytes`.
+    ///
+    /// The cost is that of hashing a message of `bytes.len()` bytes, and `length` is constrained to
+    /// be at most `bytes.len()`.
+    pub fn keccak256_variable(
+        &mut self,
+        bytes: &[ByteVariable],
+        length: Variable,
+    ) -> Bytes32Variable {
+        let nb_blocks = bytes.len() / KECCAK256_RATE + 1;
+        let padded_len = nb_blocks * KECCAK256_RATE;
+
+        // `is_end[i]` is true iff `i == length`, and `length <= bytes.len()` ensures that exactly
+        // one of them is true.
+        let zero = self.zero::<Variable>();
+        let mut is_end = Vec::with_capacity(padded_len);
+        let mut nb_ends = zero;
+        for i in 0..padded_len {
+            if i <= bytes.len() {
+                let index = self.constant::<Variable>(L::Field::from_canonical_usize(i));
+                let end = self.is_equal(length, index);
+                nb_ends = self.add(nb_ends, end.variable);
+                is_end.push(end.variable);
+            } else {
+                is_end.push(zero);
             }
-            let byte_variable = ByteVariable::from_targets(&byte_targets);
-            input.push(byte_variable);
+        }
+        let one = self.one::<Variable>();
+        self.assert_is_equal(nb_ends, one);
+
+        // `is_message[i]` is true iff `i < length`.
+        let mut is_message = vec![zero; padded_len];
+        for i in (0..padded_len - 1).rev() {
+            is_message[i] = self.add(is_message[i + 1], is_end[i + 1]);
         }
 
-        let output_targets = src.read_target_vec()?;
-        let output = Bytes32Variable::from_targets(&output_targets);
-
-        let length_exists = src.read_bool()?;
-        let length = if length_exists {
-            Some(Variable::from_targets(&src.read_target_vec()?))
-        } else {
-            None
-        };
-
-        Ok(Self {
-            input,
-            output,
-            length,
-            _phantom: PhantomData::<L>,
-        })
+        // `is_last_block[b]` is true iff `length` falls in block `b`.
+        let is_last_block = is_end
+            .chunks_exact(KECCAK256_RATE)
+            .map(|ends| self.add_many(ends))
+            .collect::<Vec<_>>();
+
+        let mut padded_bits = Vec::with_capacity(padded_len * 8);
+        for i in 0..padded_len {
+            let mut bits = [zero; 8];
+            if i < bytes.len() {
+                for (bit, message_bit) in bits.iter_mut().zip(bytes[i].as_le_bits()) {
+                    *bit = self.mul(is_message[i], message_bit.variable);
+                }
+            }
+            // The bits of the message and of the padding never overlap, so they can be added.
+            bits[0] = self.add(bits[0], is_end[i]);
+            if i % KECCAK256_RATE == KECCAK256_RATE - 1 {
+                bits[7] = self.add(bits[7], is_last_block[i / KECCAK256_RATE]);
+            }
+            padded_bits.extend(
+                bits.iter()
+                    .map(|bit| BoolVariable::from_variables_unsafe(&[*bit])),
+            );
+        }
+
+        let blocks = padded_bits
+            .chunks_exact(KECCAK256_RATE * 8)
+            .map(|block| block.to_vec())
+            .collect::<Vec<_>>();
+        let states = self.keccak256_absorb(&blocks);
+
+        // Select the state after the last block of the message.
+        let mut digest_state = [[zero; 64]; 4];
+        for (state, is_last) in states.iter().zip(is_last_block) {
+            for (digest_lane, lane) in digest_state.iter_mut().zip(state.iter()) {
+                for (digest_bit, bit) in digest_lane.iter_mut().zip(lane.iter()) {
+                    *digest_bit =
+                        Variable(self.api.mul_add(is_last.0, bit.variable.0, digest_bit.0));
+                }
+            }
+        }
+        let digest_lanes =
+            digest_state.map(|lane| lane.map(|bit| BoolVariable::from_variables_unsafe(&[bit])));
+        self.keccak256_squeeze(&digest_lanes)
     }
 }
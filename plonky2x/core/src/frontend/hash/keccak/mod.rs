@@ -1,8 +1,5 @@
 //! An implementation of the keccak256 hash functions in a plonky2 circuit
 
-use core::marker::PhantomData;
-
-use self::keccak256::Keccak256Generator;
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::vars::Bytes32Variable;
 use crate::prelude::{ByteVariable, CircuitBuilder, Variable};
@@ -10,61 +7,141 @@ use crate::prelude::{ByteVariable, CircuitBuilder, Variable};
 pub mod keccak256;
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
-    /// WARNING: DO NOT USE IN PRODUCTION, this is unconstrained!
+    /// Computes the keccak256 hash of `bytes`. The hash used to be an unconstrained witness, it
+    /// is now constrained by `keccak256`.
+    #[deprecated(note = "use `keccak256` instead")]
     pub fn keccak256_witness(&mut self, bytes: &[ByteVariable]) -> Bytes32Variable {
-        // TODO: Need to constrain generator result
-        let generator: Keccak256Generator<L, D> = Keccak256Generator {
-            input: bytes.to_vec(),
-            output: self.init(),
-            length: None,
-            _phantom: PhantomData::<L>,
-        };
-        let output = generator.output;
-        self.add_simple_generator(generator.clone());
-        output
+        self.keccak256(bytes)
     }
 
-    /// WARNING: DO NOT USE IN PRODUCTION, this is unconstrained!
+    /// Computes the keccak256 hash of the first `length` bytes of `bytes`. The hash used to be
+    /// an unconstrained witness, it is now constrained by `keccak256_variable`.
+    #[deprecated(note = "use `keccak256_variable` instead")]
     pub fn keccak256_variable_witness(
         &mut self,
         bytes: &[ByteVariable],
         length: Variable,
     ) -> Bytes32Variable {
-        // TODO: Need to constrain generator result
-        let generator = Keccak256Generator {
-            input: bytes.to_vec(),
-            output: self.init(),
-            length: Some(length),
-            _phantom: PhantomData::<L>,
-        };
-        self.add_simple_generator(generator.clone());
-        generator.output
+        self.keccak256_variable(bytes, length)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use ethers::types::H256;
+    use ethers::utils::keccak256;
+    use rand::{thread_rng, Rng};
 
-    use super::*;
-    use crate::backend::circuit::DefaultParameters;
+    use crate::prelude::*;
     use crate::utils::bytes32;
 
-    type L = DefaultParameters;
-    const D: usize = 2;
-
     #[test]
     fn test_keccak256() {
         env_logger::try_init().unwrap_or_default();
 
-        let mut builder = CircuitBuilder::<L, D>::new();
+        let mut builder = DefaultBuilder::new();
         let word = builder.constant::<Bytes32Variable>(bytes32!(
             "0x0000000000000000000000000000000000000000000000000000000000000000"
         ));
-        let hash = builder.keccak256_witness(&word.0 .0);
-        builder.watch(&hash, "hi");
+        let hash = builder.keccak256(&word.0 .0);
+        builder.write(hash);
 
         let circuit = builder.build();
         let input = circuit.input();
-        let (_, _) = circuit.prove(&input);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(
+            output.read::<Bytes32Variable>(),
+            bytes32!("0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563")
+        );
+    }
+
+    #[test]
+    fn test_keccak256_padding_boundaries() {
+        env_logger::try_init().unwrap_or_default();
+        let mut rng = thread_rng();
+
+        // Lengths around the rate of 136 bytes, where the padding changes shape.
+        let lengths = [0, 1, 134, 135, 136, 137, 271, 272];
+        let messages = lengths
+            .iter()
+            .map(|len| (0..*len).map(|_| rng.gen()).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        for message in messages.iter() {
+            let bytes = (0..message.len())
+                .map(|_| builder.read::<ByteVariable>())
+                .collect::<Vec<_>>();
+            let hash = builder.keccak256(&bytes);
+            builder.write(hash);
+        }
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        for byte in messages.iter().flatten() {
+            input.write::<ByteVariable>(*byte);
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        for message in messages.iter() {
+            let expected = H256::from(keccak256(message));
+            assert_eq!(output.read::<Bytes32Variable>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_keccak256_variable() {
+        env_logger::try_init().unwrap_or_default();
+        let mut rng = thread_rng();
+
+        const MAX_LEN: usize = 300;
+        let lengths = [0usize, 55, 135, 136, 200, 271, 272, 300];
+        let message = (0..MAX_LEN).map(|_| rng.gen()).collect::<Vec<u8>>();
+
+        let mut builder = DefaultBuilder::new();
+        let bytes = (0..MAX_LEN)
+            .map(|_| builder.read::<ByteVariable>())
+            .collect::<Vec<_>>();
+        for _ in lengths.iter() {
+            let length = builder.read::<Variable>();
+            let hash = builder.keccak256_variable(&bytes, length);
+            builder.write(hash);
+        }
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        for byte in message.iter() {
+            input.write::<ByteVariable>(*byte);
+        }
+        for length in lengths.iter() {
+            input.write::<Variable>(GoldilocksField::from_canonical_usize(*length));
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        for length in lengths.iter() {
+            let expected = H256::from(keccak256(&message[..*length]));
+            assert_eq!(output.read::<Bytes32Variable>(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_keccak256_variable_length_too_long() {
+        let mut builder = DefaultBuilder::new();
+        let bytes = builder.read::<ArrayVariable<ByteVariable, 10>>();
+        let length = builder.read::<Variable>();
+        let hash = builder.keccak256_variable(bytes.as_slice(), length);
+        builder.write(hash);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<ByteVariable, 10>>(vec![0u8; 10]);
+        input.write::<Variable>(GoldilocksField::from_canonical_usize(11));
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
     }
 }
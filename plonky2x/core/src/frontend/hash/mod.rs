@@ -1,6 +1,10 @@
 pub mod blake2;
 pub mod common;
 pub mod curta;
+#[cfg(test)]
+mod equivalence;
+pub mod hmac;
 pub mod keccak;
 pub mod poseidon;
+pub mod ripemd160;
 pub mod sha;
@@ -0,0 +1,538 @@
+use alloc::{format, vec};
+use core::marker::PhantomData;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::gates::gate::Gate;
+use plonky2::gates::util::StridedConstraintConsumer;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use plonky2::iop::target::Target;
+use plonky2::iop::wire::Wire;
+use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+
+use crate::utils::poseidon::poseidon2::{
+    poseidon2_add_round_constants, poseidon2_external_linear_layer,
+    poseidon2_internal_linear_layer, poseidon2_sbox, POSEIDON2_HALF_N_FULL_ROUNDS,
+    POSEIDON2_INTERNAL_DIAG, POSEIDON2_M4, POSEIDON2_N_PARTIAL_ROUNDS, POSEIDON2_ROUND_CONSTANTS,
+    POSEIDON2_WIDTH,
+};
+
+/// A gate which evaluates a Poseidon2 permutation, optionally swapping the first two quarters of
+/// its input first.
+///
+/// The layout mirrors plonky2's `PoseidonGate`: the inputs, outputs and swap flag are routed
+/// wires, and the input of every S-box except those of the first round is stored in an advice
+/// wire so that each constraint has degree 7.
+#[derive(Debug, Default)]
+pub struct Poseidon2Gate<F: RichField + Extendable<D>, const D: usize>(PhantomData<F>);
+
+impl<F: RichField + Extendable<D>, const D: usize> Poseidon2Gate<F, D> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+
+    /// The wire index for the `i`th input to the permutation.
+    pub fn wire_input(i: usize) -> usize {
+        i
+    }
+
+    /// The wire index for the `i`th output to the permutation.
+    pub fn wire_output(i: usize) -> usize {
+        POSEIDON2_WIDTH + i
+    }
+
+    /// If this is set to 1, the first four inputs will be swapped with the next four inputs.
+    pub const WIRE_SWAP: usize = 2 * POSEIDON2_WIDTH;
+
+    const START_DELTA: usize = 2 * POSEIDON2_WIDTH + 1;
+
+    /// A wire which stores `swap * (input[i + 4] - input[i])`; used to compute the swapped inputs.
+    fn wire_delta(i: usize) -> usize {
+        assert!(i < 4);
+        Self::START_DELTA + i
+    }
+
+    const START_FULL_0: usize = Self::START_DELTA + 4;
+
+    /// A wire which stores the input of the `i`th S-box of the `round`th round of the first set
+    /// of full rounds.
+    fn wire_full_sbox_0(round: usize, i: usize) -> usize {
+        debug_assert!(
+            round != 0,
+            "First round S-box inputs are not stored as wires"
+        );
+        debug_assert!(round < POSEIDON2_HALF_N_FULL_ROUNDS);
+        debug_assert!(i < POSEIDON2_WIDTH);
+        Self::START_FULL_0 + POSEIDON2_WIDTH * (round - 1) + i
+    }
+
+    const START_PARTIAL: usize =
+        Self::START_FULL_0 + POSEIDON2_WIDTH * (POSEIDON2_HALF_N_FULL_ROUNDS - 1);
+
+    /// A wire which stores the input of the S-box of the `round`th round of the partial rounds.
+    fn wire_partial_sbox(round: usize) -> usize {
+        debug_assert!(round < POSEIDON2_N_PARTIAL_ROUNDS);
+        Self::START_PARTIAL + round
+    }
+
+    const START_FULL_1: usize = Self::START_PARTIAL + POSEIDON2_N_PARTIAL_ROUNDS;
+
+    /// A wire which stores the input of the `i`th S-box of the `round`th round of the second set
+    /// of full rounds.
+    fn wire_full_sbox_1(round: usize, i: usize) -> usize {
+        debug_assert!(round < POSEIDON2_HALF_N_FULL_ROUNDS);
+        debug_assert!(i < POSEIDON2_WIDTH);
+        Self::START_FULL_1 + POSEIDON2_WIDTH * round + i
+    }
+
+    /// End of wire indices, exclusive.
+    fn end() -> usize {
+        Self::START_FULL_1 + POSEIDON2_WIDTH * POSEIDON2_HALF_N_FULL_ROUNDS
+    }
+
+    /// The constraints of the gate, shared by the base and extension field evaluations.
+    fn eval<T: Field>(wires: &[T]) -> Vec<T> {
+        let mut constraints = Vec::new();
+
+        // Assert that `swap` is binary.
+        let swap = wires[Self::WIRE_SWAP];
+        constraints.push(swap * (swap - T::ONE));
+
+        // Assert that each delta wire is set properly: `delta_i = swap * (rhs - lhs)`.
+        for i in 0..4 {
+            let input_lhs = wires[Self::wire_input(i)];
+            let input_rhs = wires[Self::wire_input(i + 4)];
+            let delta_i = wires[Self::wire_delta(i)];
+            constraints.push(swap * (input_rhs - input_lhs) - delta_i);
+        }
+
+        // Compute the possibly-swapped input layer.
+        let mut state = [T::ZERO; POSEIDON2_WIDTH];
+        for i in 0..4 {
+            let delta_i = wires[Self::wire_delta(i)];
+            state[i] = wires[Self::wire_input(i)] + delta_i;
+            state[i + 4] = wires[Self::wire_input(i + 4)] - delta_i;
+        }
+        for (i, x) in state.iter_mut().enumerate().skip(8) {
+            *x = wires[Self::wire_input(i)];
+        }
+
+        poseidon2_external_linear_layer(&mut state);
+
+        // First set of full rounds.
+        for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+            poseidon2_add_round_constants(&mut state, round);
+            if round != 0 {
+                for (i, x) in state.iter_mut().enumerate() {
+                    let sbox_in = wires[Self::wire_full_sbox_0(round, i)];
+                    constraints.push(*x - sbox_in);
+                    *x = sbox_in;
+                }
+            }
+            state = state.map(poseidon2_sbox);
+            poseidon2_external_linear_layer(&mut state);
+        }
+
+        // Partial rounds.
+        for (round, c) in POSEIDON2_ROUND_CONSTANTS.internal.iter().enumerate() {
+            let sbox_in = wires[Self::wire_partial_sbox(round)];
+            constraints.push(state[0] + T::from_canonical_u64(*c) - sbox_in);
+            state[0] = poseidon2_sbox(sbox_in);
+            poseidon2_internal_linear_layer(&mut state);
+        }
+
+        // Second set of full rounds.
+        for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+            poseidon2_add_round_constants(&mut state, POSEIDON2_HALF_N_FULL_ROUNDS + round);
+            for (i, x) in state.iter_mut().enumerate() {
+                let sbox_in = wires[Self::wire_full_sbox_1(round, i)];
+                constraints.push(*x - sbox_in);
+                *x = sbox_in;
+            }
+            state = state.map(poseidon2_sbox);
+            poseidon2_external_linear_layer(&mut state);
+        }
+
+        for (i, x) in state.iter().enumerate() {
+            constraints.push(*x - wires[Self::wire_output(i)]);
+        }
+
+        constraints
+    }
+}
+
+/// Applies the external linear layer of Poseidon2 in a recursive circuit.
+fn external_linear_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; POSEIDON2_WIDTH],
+) {
+    let mut chunks = Vec::with_capacity(POSEIDON2_WIDTH);
+    for input in state.chunks_exact(4) {
+        for row in POSEIDON2_M4.iter() {
+            let mut acc = builder.zero_extension();
+            for (m, x) in row.iter().zip(input.iter()) {
+                acc = builder.mul_const_add_extension(F::from_canonical_u64(*m), *x, acc);
+            }
+            chunks.push(acc);
+        }
+    }
+    for (i, x) in state.iter_mut().enumerate() {
+        let sum = builder.add_many_extension((0..3).map(|j| chunks[4 * j + i % 4]));
+        *x = builder.add_extension(chunks[i], sum);
+    }
+}
+
+/// Applies the internal linear layer of Poseidon2 in a recursive circuit.
+fn internal_linear_layer_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; POSEIDON2_WIDTH],
+) {
+    let sum = builder.add_many_extension(state.iter());
+    for (x, diag) in state.iter_mut().zip(POSEIDON2_INTERNAL_DIAG.iter()) {
+        *x = builder.mul_const_add_extension(F::from_canonical_u64(*diag), *x, sum);
+    }
+}
+
+/// Adds the constants of a full round to the state in a recursive circuit.
+fn add_round_constants_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &mut [ExtensionTarget<D>; POSEIDON2_WIDTH],
+    round: usize,
+) {
+    for (x, c) in state
+        .iter_mut()
+        .zip(POSEIDON2_ROUND_CONSTANTS.external[round].iter())
+    {
+        *x = builder.add_const_extension(*x, F::from_canonical_u64(*c));
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for Poseidon2Gate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}<WIDTH={POSEIDON2_WIDTH}>")
+    }
+
+    fn serialize(
+        &self,
+        _dst: &mut Vec<u8>,
+        _common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        Ok(Self::new())
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        Self::eval(vars.local_wires)
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        vars: EvaluationVarsBase<F>,
+        mut yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        let wires = (0..Self::end())
+            .map(|i| vars.local_wires[i])
+            .collect::<Vec<_>>();
+        yield_constr.many(Self::eval(&wires));
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let wires = vars.local_wires;
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+
+        // Assert that `swap` is binary.
+        let swap = wires[Self::WIRE_SWAP];
+        constraints.push(builder.mul_sub_extension(swap, swap, swap));
+
+        // Assert that each delta wire is set properly: `delta_i = swap * (rhs - lhs)`.
+        for i in 0..4 {
+            let input_lhs = wires[Self::wire_input(i)];
+            let input_rhs = wires[Self::wire_input(i + 4)];
+            let delta_i = wires[Self::wire_delta(i)];
+            let diff = builder.sub_extension(input_rhs, input_lhs);
+            constraints.push(builder.mul_sub_extension(swap, diff, delta_i));
+        }
+
+        // Compute the possibly-swapped input layer.
+        let mut state = [builder.zero_extension(); POSEIDON2_WIDTH];
+        for i in 0..4 {
+            let delta_i = wires[Self::wire_delta(i)];
+            state[i] = builder.add_extension(wires[Self::wire_input(i)], delta_i);
+            state[i + 4] = builder.sub_extension(wires[Self::wire_input(i + 4)], delta_i);
+        }
+        for (i, x) in state.iter_mut().enumerate().skip(8) {
+            *x = wires[Self::wire_input(i)];
+        }
+
+        external_linear_layer_circuit(builder, &mut state);
+
+        // First set of full rounds.
+        for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+            add_round_constants_circuit(builder, &mut state, round);
+            if round != 0 {
+                for (i, x) in state.iter_mut().enumerate() {
+                    let sbox_in = wires[Self::wire_full_sbox_0(round, i)];
+                    constraints.push(builder.sub_extension(*x, sbox_in));
+                    *x = sbox_in;
+                }
+            }
+            for x in state.iter_mut() {
+                *x = builder.exp_u64_extension(*x, 7);
+            }
+            external_linear_layer_circuit(builder, &mut state);
+        }
+
+        // Partial rounds.
+        for (round, c) in POSEIDON2_ROUND_CONSTANTS.internal.iter().enumerate() {
+            let sbox_in = wires[Self::wire_partial_sbox(round)];
+            let x = builder.add_const_extension(state[0], F::from_canonical_u64(*c));
+            constraints.push(builder.sub_extension(x, sbox_in));
+            state[0] = builder.exp_u64_extension(sbox_in, 7);
+            internal_linear_layer_circuit(builder, &mut state);
+        }
+
+        // Second set of full rounds.
+        for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+            add_round_constants_circuit(builder, &mut state, POSEIDON2_HALF_N_FULL_ROUNDS + round);
+            for (i, x) in state.iter_mut().enumerate() {
+                let sbox_in = wires[Self::wire_full_sbox_1(round, i)];
+                constraints.push(builder.sub_extension(*x, sbox_in));
+                *x = builder.exp_u64_extension(sbox_in, 7);
+            }
+            external_linear_layer_circuit(builder, &mut state);
+        }
+
+        for (i, x) in state.iter().enumerate() {
+            constraints.push(builder.sub_extension(*x, wires[Self::wire_output(i)]));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        let gen = Poseidon2Generator::<F, D> {
+            row,
+            _phantom: PhantomData,
+        };
+        vec![WitnessGeneratorRef::new(gen.adapter())]
+    }
+
+    fn num_wires(&self) -> usize {
+        Self::end()
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        7
+    }
+
+    fn num_constraints(&self) -> usize {
+        POSEIDON2_WIDTH * (2 * POSEIDON2_HALF_N_FULL_ROUNDS - 1)
+            + POSEIDON2_N_PARTIAL_ROUNDS
+            + POSEIDON2_WIDTH
+            + 1
+            + 4
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Poseidon2Generator<F: RichField + Extendable<D>, const D: usize> {
+    row: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Poseidon2Generator<F, D> {
+    pub fn id() -> String {
+        "Poseidon2Generator".to_string()
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for Poseidon2Generator<F, D>
+{
+    fn id(&self) -> String {
+        Self::id()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        (0..POSEIDON2_WIDTH)
+            .map(|i| Poseidon2Gate::<F, D>::wire_input(i))
+            .chain(Some(Poseidon2Gate::<F, D>::WIRE_SWAP))
+            .map(|column| Target::wire(self.row, column))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire {
+            row: self.row,
+            column,
+        };
+
+        let mut state = [F::ZERO; POSEIDON2_WIDTH];
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = witness.get_wire(local_wire(Poseidon2Gate::<F, D>::wire_input(i)));
+        }
+
+        let swap_value = witness.get_wire(local_wire(Poseidon2Gate::<F, D>::WIRE_SWAP));
+        debug_assert!(swap_value == F::ZERO || swap_value == F::ONE);
+
+        for i in 0..4 {
+            let delta_i = swap_value * (state[i + 4] - state[i]);
+            out_buffer.set_wire(local_wire(Poseidon2Gate::<F, D>::wire_delta(i)), delta_i);
+            state[i] += delta_i;
+            state[i + 4] -= delta_i;
+        }
+
+        poseidon2_external_linear_layer(&mut state);
+
+        for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+            poseidon2_add_round_constants(&mut state, round);
+            if round != 0 {
+                for (i, x) in state.iter().enumerate() {
+                    let wire = local_wire(Poseidon2Gate::<F, D>::wire_full_sbox_0(round, i));
+                    out_buffer.set_wire(wire, *x);
+                }
+            }
+            state = state.map(poseidon2_sbox);
+            poseidon2_external_linear_layer(&mut state);
+        }
+
+        for (round, c) in POSEIDON2_ROUND_CONSTANTS.internal.iter().enumerate() {
+            state[0] += F::from_canonical_u64(*c);
+            out_buffer.set_wire(
+                local_wire(Poseidon2Gate::<F, D>::wire_partial_sbox(round)),
+                state[0],
+            );
+            state[0] = poseidon2_sbox(state[0]);
+            poseidon2_internal_linear_layer(&mut state);
+        }
+
+        for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+            poseidon2_add_round_constants(&mut state, POSEIDON2_HALF_N_FULL_ROUNDS + round);
+            for (i, x) in state.iter().enumerate() {
+                let wire = local_wire(Poseidon2Gate::<F, D>::wire_full_sbox_1(round, i));
+                out_buffer.set_wire(wire, *x);
+            }
+            state = state.map(poseidon2_sbox);
+            poseidon2_external_linear_layer(&mut state);
+        }
+
+        for (i, x) in state.iter().enumerate() {
+            out_buffer.set_wire(local_wire(Poseidon2Gate::<F, D>::wire_output(i)), *x);
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let row = src.read_usize()?;
+        Ok(Self {
+            row,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Sample;
+    use plonky2::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use super::*;
+    use crate::utils::poseidon::poseidon2::poseidon2_permute;
+    use crate::utils::poseidon::poseidon2::tests::POSEIDON2_REFERENCE_OUTPUT;
+
+    #[test]
+    fn low_degree() {
+        test_low_degree::<GoldilocksField, _, 4>(Poseidon2Gate::<GoldilocksField, 4>::new())
+    }
+
+    #[test]
+    fn eval_fns() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        test_eval_fns::<F, C, _, D>(Poseidon2Gate::<F, D>::new()).unwrap();
+    }
+
+    #[test]
+    fn test_poseidon2_gate_matches_native() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let gate = builder.add_gate(Poseidon2Gate::<F, D>::new(), vec![]);
+        let swap = builder.add_virtual_bool_target_safe();
+        builder.connect(
+            swap.target,
+            Target::wire(gate, Poseidon2Gate::<F, D>::WIRE_SWAP),
+        );
+        let inputs = builder.add_virtual_targets(POSEIDON2_WIDTH);
+        for (i, input) in inputs.iter().enumerate() {
+            builder.connect(
+                *input,
+                Target::wire(gate, Poseidon2Gate::<F, D>::wire_input(i)),
+            );
+        }
+        let outputs = (0..POSEIDON2_WIDTH)
+            .map(|i| Target::wire(gate, Poseidon2Gate::<F, D>::wire_output(i)))
+            .collect::<Vec<_>>();
+        builder.register_public_inputs(&outputs);
+        let data = builder.build::<C>();
+
+        // The known-answer test of the reference implementation.
+        let input_values = core::array::from_fn::<_, POSEIDON2_WIDTH, _>(F::from_canonical_usize);
+        let mut pw = PartialWitness::new();
+        pw.set_bool_target(swap, false);
+        pw.set_target_arr(&inputs, &input_values);
+        let proof = data.prove(pw).unwrap();
+        assert_eq!(
+            proof.public_inputs,
+            POSEIDON2_REFERENCE_OUTPUT
+                .map(F::from_canonical_u64)
+                .to_vec()
+        );
+        data.verify(proof).unwrap();
+
+        for swap_value in [false, true] {
+            let input_values = F::rand_array::<POSEIDON2_WIDTH>();
+            let mut pw = PartialWitness::new();
+            pw.set_bool_target(swap, swap_value);
+            pw.set_target_arr(&inputs, &input_values);
+            let proof = data.prove(pw).unwrap();
+
+            let mut swapped = input_values;
+            if swap_value {
+                for i in 0..4 {
+                    swapped.swap(i, i + 4);
+                }
+            }
+            assert_eq!(proof.public_inputs, poseidon2_permute(swapped).to_vec());
+            data.verify(proof).unwrap();
+        }
+    }
+}
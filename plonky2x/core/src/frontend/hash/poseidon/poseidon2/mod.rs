@@ -0,0 +1,244 @@
+//! The Poseidon2 hash over Goldilocks, as a plonky2 `AlgebraicHasher` and as builder methods.
+//!
+//! `Poseidon2Hash` can be used anywhere plonky2 accepts a hasher, e.g. for native and in-circuit
+//! Merkle trees or as the accumulator hash of `mapreduce_with_hasher`.
+
+pub mod gate;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::hash::hash_types::{HashOut, RichField};
+use plonky2::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
+use plonky2::iop::target::{BoolTarget, Target};
+use plonky2::plonk::config::{AlgebraicHasher, Hasher};
+
+use self::gate::Poseidon2Gate;
+use super::poseidon256::PoseidonHashOutVariable;
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::vars::Variable;
+use crate::utils::poseidon::poseidon2::{poseidon2_permute, POSEIDON2_RATE, POSEIDON2_WIDTH};
+
+/// Types which can be permuted by `Poseidon2Permutation`.
+pub trait Poseidon2Permuter: Sized {
+    fn permute(input: [Self; POSEIDON2_WIDTH]) -> [Self; POSEIDON2_WIDTH];
+}
+
+impl Poseidon2Permuter for GoldilocksField {
+    fn permute(input: [Self; POSEIDON2_WIDTH]) -> [Self; POSEIDON2_WIDTH] {
+        poseidon2_permute(input)
+    }
+}
+
+impl Poseidon2Permuter for Target {
+    fn permute(_input: [Self; POSEIDON2_WIDTH]) -> [Self; POSEIDON2_WIDTH] {
+        panic!("Call `permute_swapped()` instead of `permute()`");
+    }
+}
+
+/// The sponge state of Poseidon2.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct Poseidon2Permutation<T> {
+    state: [T; POSEIDON2_WIDTH],
+}
+
+impl<T> AsRef<[T]> for Poseidon2Permutation<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.state
+    }
+}
+
+impl<T> PlonkyPermutation<T> for Poseidon2Permutation<T>
+where
+    T: Copy + core::fmt::Debug + Default + Eq + Poseidon2Permuter + Send + Sync,
+{
+    const RATE: usize = POSEIDON2_RATE;
+    const WIDTH: usize = POSEIDON2_WIDTH;
+
+    fn new<I: IntoIterator<Item = T>>(elts: I) -> Self {
+        let mut perm = Self {
+            state: [T::default(); POSEIDON2_WIDTH],
+        };
+        perm.set_from_iter(elts, 0);
+        perm
+    }
+
+    fn set_elt(&mut self, elt: T, idx: usize) {
+        self.state[idx] = elt;
+    }
+
+    fn set_from_slice(&mut self, elts: &[T], start_idx: usize) {
+        let begin = start_idx;
+        let end = start_idx + elts.len();
+        self.state[begin..end].copy_from_slice(elts);
+    }
+
+    fn set_from_iter<I: IntoIterator<Item = T>>(&mut self, elts: I, start_idx: usize) {
+        for (s, e) in self.state[start_idx..].iter_mut().zip(elts) {
+            *s = e;
+        }
+    }
+
+    fn permute(&mut self) {
+        self.state = T::permute(self.state);
+    }
+
+    fn squeeze(&self) -> &[T] {
+        &self.state[..Self::RATE]
+    }
+}
+
+/// The Poseidon2 hash over Goldilocks.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Poseidon2Hash;
+
+impl Hasher<GoldilocksField> for Poseidon2Hash {
+    const HASH_SIZE: usize = 4 * 8;
+    type Hash = HashOut<GoldilocksField>;
+    type Permutation = Poseidon2Permutation<GoldilocksField>;
+
+    fn hash_no_pad(input: &[GoldilocksField]) -> Self::Hash {
+        hash_n_to_hash_no_pad::<GoldilocksField, Self::Permutation>(input)
+    }
+
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
+        compress::<GoldilocksField, Self::Permutation>(left, right)
+    }
+}
+
+impl AlgebraicHasher<GoldilocksField> for Poseidon2Hash {
+    type AlgebraicPermutation = Poseidon2Permutation<Target>;
+
+    fn permute_swapped<const D: usize>(
+        inputs: Self::AlgebraicPermutation,
+        swap: BoolTarget,
+        builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<GoldilocksField, D>,
+    ) -> Self::AlgebraicPermutation
+    where
+        GoldilocksField: RichField + Extendable<D>,
+    {
+        let gate = builder.add_gate(Poseidon2Gate::<GoldilocksField, D>::new(), vec![]);
+
+        let swap_wire = Target::wire(gate, Poseidon2Gate::<GoldilocksField, D>::WIRE_SWAP);
+        builder.connect(swap.target, swap_wire);
+
+        for (i, input) in inputs.as_ref().iter().enumerate() {
+            let in_wire = Target::wire(gate, Poseidon2Gate::<GoldilocksField, D>::wire_input(i));
+            builder.connect(*input, in_wire);
+        }
+
+        Self::AlgebraicPermutation::new(
+            (0..POSEIDON2_WIDTH)
+                .map(|i| Target::wire(gate, Poseidon2Gate::<GoldilocksField, D>::wire_output(i))),
+        )
+    }
+}
+
+/// Implements the Poseidon2 hash for CircuitBuilder.
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes the Poseidon2 hash of the given variables with no padding.
+    pub fn poseidon2_hash(&mut self, variables: &[Variable]) -> PoseidonHashOutVariable
+    where
+        Poseidon2Hash: AlgebraicHasher<L::Field>,
+    {
+        self.poseidon_hash_with::<Poseidon2Hash>(variables)
+    }
+
+    /// Computes the Poseidon2 hash of the concatenation of two hashes with no padding.
+    pub fn poseidon2_hash_pair(
+        &mut self,
+        left: PoseidonHashOutVariable,
+        right: PoseidonHashOutVariable,
+    ) -> PoseidonHashOutVariable
+    where
+        Poseidon2Hash: AlgebraicHasher<L::Field>,
+    {
+        self.poseidon_hash_pair_with::<Poseidon2Hash>(left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::types::Sample;
+    use plonky2::hash::merkle_proofs::MerkleProofTarget;
+    use plonky2::hash::merkle_tree::MerkleTree;
+
+    use super::*;
+    use crate::prelude::*;
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn test_poseidon2_hash() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<ArrayVariable<Variable, 10>>();
+        let hash = builder.poseidon2_hash(a.as_slice());
+        builder.write(hash.clone());
+        let b = builder.read::<PoseidonHashOutVariable>();
+        let pair = builder.poseidon2_hash_pair(hash, b);
+        builder.write(pair);
+
+        let circuit = builder.build();
+        circuit.test_default_serializers();
+
+        let a_value = F::rand_vec(10);
+        let b_value = HashOut::<F>::rand();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<Variable, 10>>(a_value.clone());
+        input.write::<PoseidonHashOutVariable>(b_value);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        let hash_value = Poseidon2Hash::hash_no_pad(&a_value);
+        assert_eq!(output.read::<PoseidonHashOutVariable>(), hash_value);
+        assert_eq!(
+            output.read::<PoseidonHashOutVariable>(),
+            Poseidon2Hash::hash_no_pad(&[hash_value.elements, b_value.elements].concat())
+        );
+    }
+
+    #[test]
+    fn test_poseidon2_merkle_proof() {
+        env_logger::try_init().unwrap_or_default();
+
+        const LOG_NB_LEAVES: usize = 4;
+        let leaves = (0..1 << LOG_NB_LEAVES)
+            .map(|_| F::rand_vec(3))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::<F, Poseidon2Hash>::new(leaves.clone(), 0);
+        let index = 5;
+        let merkle_proof = tree.prove(index);
+
+        let mut builder = DefaultBuilder::new();
+        let leaf = builder.read::<ArrayVariable<Variable, 3>>();
+        let index_variable = builder.read::<Variable>();
+        let siblings = (0..LOG_NB_LEAVES)
+            .map(|_| builder.read::<PoseidonHashOutVariable>())
+            .collect::<Vec<_>>();
+        let root = builder.read::<PoseidonHashOutVariable>();
+
+        let index_bits = builder.api.split_le(index_variable.0, LOG_NB_LEAVES);
+        let proof_target = MerkleProofTarget {
+            siblings: siblings.into_iter().map(|s| s.into()).collect(),
+        };
+        builder.api.verify_merkle_proof::<Poseidon2Hash>(
+            leaf.as_vec().iter().map(|v| v.0).collect(),
+            &index_bits,
+            root.into(),
+            &proof_target,
+        );
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<ArrayVariable<Variable, 3>>(leaves[index].clone());
+        input.write::<Variable>(F::from_canonical_usize(index));
+        for sibling in merkle_proof.siblings {
+            input.write::<PoseidonHashOutVariable>(sibling);
+        }
+        input.write::<PoseidonHashOutVariable>(tree.cap.0[0]);
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+}
@@ -63,9 +63,19 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
             AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
     {
+        self.poseidon_hash_with::<<<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher>(
+            variables,
+        )
+    }
+
+    /// Computes the hash of the given variables with no padding, using the algebraic hasher `H`.
+    pub fn poseidon_hash_with<H: AlgebraicHasher<L::Field>>(
+        &mut self,
+        variables: &[Variable],
+    ) -> PoseidonHashOutVariable {
         let targets = variables.iter().map(|v| v.0).collect::<Vec<_>>();
         PoseidonHashOutVariable::from_targets(
-            &self.api.hash_n_to_hash_no_pad::<<<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher>(targets).elements,
+            &self.api.hash_n_to_hash_no_pad::<H>(targets).elements,
         )
     }
 
@@ -79,10 +89,22 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
             AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
     {
+        self.poseidon_hash_pair_with::<<<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher>(
+            left, right,
+        )
+    }
+
+    /// Computes the hash of the concatenation of two hashes with no padding, using the algebraic
+    /// hasher `H`.
+    pub fn poseidon_hash_pair_with<H: AlgebraicHasher<L::Field>>(
+        &mut self,
+        left: PoseidonHashOutVariable,
+        right: PoseidonHashOutVariable,
+    ) -> PoseidonHashOutVariable {
         let mut input = Vec::new();
         input.extend(left.variables());
         input.extend(right.variables());
-        self.poseidon_hash(&input)
+        self.poseidon_hash_with::<H>(&input)
     }
 
     /// Note: This Poseidon implementation operates on bytes, not field elements. The input bytes to
@@ -0,0 +1,367 @@
+//! circomlib's Poseidon over the BN254 scalar field, as an in-circuit gadget.
+//!
+//! Field elements are `U256Variable`s and the arithmetic modulo the BN254 scalar field is done
+//! with non-native `BigUintTarget` operations, so the gadget matches `utils::poseidon::poseidon_bn254`
+//! and the on-chain Poseidon implementations at the cost of a few reductions per S-box.
+
+use num::BigUint;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::backend::wrapper::poseidon_bn128::{FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH};
+use crate::backend::wrapper::poseidon_bn128_constants::{
+    C_CONSTANTS, M_MATRIX, P_MATRIX, S_CONSTANTS,
+};
+use crate::backend::wrapper::utils::Fr;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::ops::Zero;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::frontend::uint::uint256::U256Variable;
+use crate::frontend::vars::BoolVariable;
+use crate::utils::poseidon::poseidon_bn254::{
+    poseidon_bn254_fr_to_biguint, POSEIDON_BN254_MODULUS, POSEIDON_BN254_T3_PARAMS,
+};
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Reduces `x` modulo the BN254 scalar field.
+    fn poseidon_bn254_reduce(&mut self, x: &BigUintTarget) -> BigUintTarget {
+        let modulus = self.api.constant_biguint(&POSEIDON_BN254_MODULUS);
+        self.api.rem_biguint(x, &modulus)
+    }
+
+    /// Computes `x^5` modulo the BN254 scalar field.
+    fn poseidon_bn254_sbox(&mut self, x: &BigUintTarget) -> BigUintTarget {
+        let x2 = self.api.mul_biguint(x, x);
+        let x2 = self.poseidon_bn254_reduce(&x2);
+        let x4 = self.api.mul_biguint(&x2, &x2);
+        let x4 = self.poseidon_bn254_reduce(&x4);
+        let x5 = self.api.mul_biguint(&x4, x);
+        self.poseidon_bn254_reduce(&x5)
+    }
+
+    /// Adds the round constants of `backend::wrapper::poseidon_bn128` starting at `offset`.
+    ///
+    /// The sums are reduced by the S-boxes and the matrices, so they can stay unreduced.
+    fn poseidon_bn254_ark(&mut self, state: &mut [BigUintTarget], offset: usize) {
+        for (i, x) in state.iter_mut().enumerate() {
+            let c = self.poseidon_bn254_constant(&C_CONSTANTS[offset + i]);
+            *x = self.api.add_biguint(x, &c);
+        }
+    }
+
+    fn poseidon_bn254_constant(&mut self, c: &Fr) -> BigUintTarget {
+        self.api.constant_biguint(&poseidon_bn254_fr_to_biguint(c))
+    }
+
+    /// Multiplies the state by `matrix`, laid out as in `backend::wrapper::poseidon_bn128`, so that
+    /// `matrix[j][i]` is the coefficient of the `j`-th element in the `i`-th output.
+    fn poseidon_bn254_mix(
+        &mut self,
+        state: &[BigUintTarget],
+        matrix: &[Vec<Fr>],
+    ) -> Vec<BigUintTarget> {
+        let mut mixed = Vec::with_capacity(state.len());
+        for i in 0..state.len() {
+            let mut acc = self.api.zero_biguint();
+            for (row, x) in matrix.iter().zip(state.iter()) {
+                let m = self.poseidon_bn254_constant(&row[i]);
+                acc = self.api.mul_add_biguint(&m, x, &acc);
+            }
+            mixed.push(self.poseidon_bn254_reduce(&acc));
+        }
+        mixed
+    }
+
+    /// Applies the width 4 permutation in the optimized form of
+    /// `backend::wrapper::poseidon_bn128::permution`, with its round constants and matrices.
+    fn poseidon_bn254_permute_t4(&mut self, mut state: Vec<BigUintTarget>) -> Vec<BigUintTarget> {
+        self.poseidon_bn254_ark(&mut state, 0);
+
+        for round in 0..FULL_ROUNDS / 2 {
+            for x in state.iter_mut() {
+                *x = self.poseidon_bn254_sbox(x);
+            }
+            self.poseidon_bn254_ark(&mut state, (round + 1) * WIDTH);
+            let matrix = if round < FULL_ROUNDS / 2 - 1 {
+                &*M_MATRIX
+            } else {
+                &*P_MATRIX
+            };
+            state = self.poseidon_bn254_mix(&state, matrix);
+        }
+
+        for round in 0..PARTIAL_ROUNDS {
+            state[0] = self.poseidon_bn254_sbox(&state[0]);
+            let c =
+                self.poseidon_bn254_constant(&C_CONSTANTS[(FULL_ROUNDS / 2 + 1) * WIDTH + round]);
+            state[0] = self.api.add_biguint(&state[0], &c);
+
+            // The sparse matrix of the round, given by its first row and the rest of its first
+            // column.
+            let sparse = &S_CONSTANTS[(WIDTH * 2 - 1) * round..(WIDTH * 2 - 1) * (round + 1)];
+            let mut acc = self.api.zero_biguint();
+            for (s, x) in sparse[..WIDTH].iter().zip(state.iter()) {
+                let s = self.poseidon_bn254_constant(s);
+                acc = self.api.mul_add_biguint(&s, x, &acc);
+            }
+            for k in 1..WIDTH {
+                let s = self.poseidon_bn254_constant(&sparse[WIDTH + k - 1]);
+                let x = self.api.mul_add_biguint(&s, &state[0], &state[k]);
+                state[k] = self.poseidon_bn254_reduce(&x);
+            }
+            state[0] = self.poseidon_bn254_reduce(&acc);
+        }
+
+        for round in 0..FULL_ROUNDS / 2 {
+            for x in state.iter_mut() {
+                *x = self.poseidon_bn254_sbox(x);
+            }
+            if round < FULL_ROUNDS / 2 - 1 {
+                let offset = (FULL_ROUNDS / 2 + 1) * WIDTH + PARTIAL_ROUNDS + round * WIDTH;
+                self.poseidon_bn254_ark(&mut state, offset);
+            }
+            state = self.poseidon_bn254_mix(&state, &M_MATRIX);
+        }
+
+        state
+    }
+
+    /// Applies the width 3 permutation with the unoptimized parameters.
+    fn poseidon_bn254_permute_t3(&mut self, mut state: Vec<BigUintTarget>) -> Vec<BigUintTarget> {
+        let params = &*POSEIDON_BN254_T3_PARAMS;
+        for (round, constants) in params
+            .round_constants
+            .chunks_exact(params.width)
+            .enumerate()
+        {
+            // The sums are reduced by the S-boxes and the MDS matrix, so they can stay unreduced.
+            for (x, c) in state.iter_mut().zip(constants.iter()) {
+                let c = self.api.constant_biguint(c);
+                *x = self.api.add_biguint(x, &c);
+            }
+            if params.is_full_round(round) {
+                for x in state.iter_mut() {
+                    *x = self.poseidon_bn254_sbox(x);
+                }
+            } else {
+                state[0] = self.poseidon_bn254_sbox(&state[0]);
+            }
+
+            let mut mixed = Vec::with_capacity(params.width);
+            for row in params.mds.iter() {
+                let mut acc = self.api.zero_biguint();
+                for (m, x) in row.iter().zip(state.iter()) {
+                    let m = self.api.constant_biguint(m);
+                    acc = self.api.mul_add_biguint(&m, x, &acc);
+                }
+                mixed.push(self.poseidon_bn254_reduce(&acc));
+            }
+            state = mixed;
+        }
+        state
+    }
+
+    /// Applies circomlib's Poseidon permutation to a state of width 3 or 4.
+    ///
+    /// The elements of `state` are interpreted modulo the BN254 scalar field and the elements of
+    /// the output are reduced.
+    pub fn poseidon_bn254_permute(&mut self, state: &[U256Variable]) -> Vec<U256Variable> {
+        let state = state
+            .iter()
+            .map(|x| BigUintTarget {
+                limbs: x.limbs.iter().map(|limb| U32Target::from(*limb)).collect(),
+            })
+            .collect::<Vec<_>>();
+        let state = match state.len() {
+            3 => self.poseidon_bn254_permute_t3(state),
+            WIDTH => self.poseidon_bn254_permute_t4(state),
+            _ => panic!("poseidon over bn254 is only supported for widths 3 and 4"),
+        };
+
+        state
+            .into_iter()
+            .map(|x| {
+                let mut limbs = U256Variable::zero(self).limbs;
+                for (limb, x_limb) in limbs.iter_mut().zip(x.limbs) {
+                    *limb = x_limb.into();
+                }
+                U256Variable { limbs }
+            })
+            .collect()
+    }
+
+    /// Computes the Poseidon hash of 2 or 3 BN254 field elements.
+    ///
+    /// This matches circomlib's `Poseidon(inputs.len())` and the `PoseidonT3`/`PoseidonT4`
+    /// Solidity contracts.
+    pub fn poseidon_bn254(&mut self, inputs: &[U256Variable]) -> U256Variable {
+        let mut state = vec![U256Variable::zero(self)];
+        state.extend_from_slice(inputs);
+        self.poseidon_bn254_permute(&state)[0]
+    }
+
+    /// Computes the root of a binary Poseidon Merkle tree from a leaf and its Merkle proof.
+    ///
+    /// The siblings are ordered from the leaf to the root, and `path_indices[i]` is set if the
+    /// node at height `i` is a right child, as in Tornado Cash and Semaphore trees.
+    pub fn poseidon_bn254_merkle_root(
+        &mut self,
+        leaf: U256Variable,
+        siblings: &[U256Variable],
+        path_indices: &[BoolVariable],
+    ) -> U256Variable {
+        assert_eq!(siblings.len(), path_indices.len());
+        let mut node = leaf;
+        for (sibling, is_right) in siblings.iter().zip(path_indices.iter()) {
+            let left = self.select(*is_right, *sibling, node);
+            let right = self.select(*is_right, node, *sibling);
+            node = self.poseidon_bn254(&[left, right]);
+        }
+        node
+    }
+}
+
+/// Converts a BN254 field element to the value of a `U256Variable`.
+pub fn poseidon_bn254_to_u256(x: &BigUint) -> ethers::types::U256 {
+    ethers::types::U256::from_little_endian(&x.to_bytes_le())
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::U256;
+    use ff::Field;
+    use num::BigUint;
+
+    use super::poseidon_bn254_to_u256;
+    use crate::backend::wrapper::poseidon_bn128::permution;
+    use crate::backend::wrapper::utils::Fr;
+    use crate::prelude::*;
+    use crate::utils::poseidon::poseidon_bn254::{
+        poseidon_bn254_fr_to_biguint, poseidon_bn254_hash,
+    };
+
+    fn to_biguint(x: U256) -> BigUint {
+        let mut bytes = [0u8; 32];
+        x.to_little_endian(&mut bytes);
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    #[test]
+    fn test_poseidon_bn254() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<U256Variable>();
+        let b = builder.read::<U256Variable>();
+        let c = builder.read::<U256Variable>();
+        let hash2 = builder.poseidon_bn254(&[a, b]);
+        builder.write(hash2);
+        let hash3 = builder.poseidon_bn254(&[a, b, c]);
+        builder.write(hash3);
+
+        let circuit = builder.build();
+        let inputs = [U256::from(1), U256::from(2), U256::MAX];
+        let mut input = circuit.input();
+        for x in inputs {
+            input.write::<U256Variable>(x);
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        // The test vector of circomlib's `Poseidon(2)`.
+        assert_eq!(
+            output.read::<U256Variable>(),
+            U256::from_dec_str(
+                "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+            )
+            .unwrap()
+        );
+        let expected = poseidon_bn254_hash(&inputs.map(to_biguint));
+        assert_eq!(
+            output.read::<U256Variable>(),
+            poseidon_bn254_to_u256(&expected)
+        );
+    }
+
+    #[test]
+    fn test_poseidon_bn254_permute_matches_wrapper() {
+        env_logger::try_init().unwrap_or_default();
+
+        let inputs = [
+            [Fr::ZERO; 4],
+            [Fr::ZERO, Fr::ONE, Fr::from(2), Fr::from(3)],
+            [Fr::from(7), -Fr::ONE, Fr::from(1 << 40), Fr::from(11)],
+        ];
+
+        let mut builder = DefaultBuilder::new();
+        for _ in 0..inputs.len() {
+            let state = builder.read::<ArrayVariable<U256Variable, 4>>();
+            let permuted = builder.poseidon_bn254_permute(state.as_slice());
+            builder.write(ArrayVariable::<U256Variable, 4>::new(permuted));
+        }
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        for state in inputs.iter() {
+            input.write::<ArrayVariable<U256Variable, 4>>(
+                state
+                    .iter()
+                    .map(|x| poseidon_bn254_to_u256(&poseidon_bn254_fr_to_biguint(x)))
+                    .collect(),
+            );
+        }
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        for mut state in inputs {
+            permution(&mut state);
+            let expected = state
+                .iter()
+                .map(|x| poseidon_bn254_to_u256(&poseidon_bn254_fr_to_biguint(x)))
+                .collect::<Vec<_>>();
+            assert_eq!(output.read::<ArrayVariable<U256Variable, 4>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_poseidon_bn254_merkle_root() {
+        env_logger::try_init().unwrap_or_default();
+
+        const DEPTH: usize = 2;
+        let leaves = (0..1 << DEPTH)
+            .map(|i| BigUint::from(100u32 + i))
+            .collect::<Vec<_>>();
+        let layer = leaves
+            .chunks(2)
+            .map(poseidon_bn254_hash)
+            .collect::<Vec<_>>();
+        let root = poseidon_bn254_hash(&layer);
+
+        // The proof of the leaf at index 2.
+        let siblings = [leaves[3].clone(), layer[0].clone()];
+        let path_indices = [false, true];
+
+        let mut builder = DefaultBuilder::new();
+        let leaf = builder.read::<U256Variable>();
+        let siblings_variable = builder.read::<ArrayVariable<U256Variable, DEPTH>>();
+        let path_indices_variable = builder.read::<ArrayVariable<BoolVariable, DEPTH>>();
+        let computed_root = builder.poseidon_bn254_merkle_root(
+            leaf,
+            siblings_variable.as_slice(),
+            path_indices_variable.as_slice(),
+        );
+        builder.write(computed_root);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<U256Variable>(poseidon_bn254_to_u256(&leaves[2]));
+        input.write::<ArrayVariable<U256Variable, DEPTH>>(
+            siblings.iter().map(poseidon_bn254_to_u256).collect(),
+        );
+        input.write::<ArrayVariable<BoolVariable, DEPTH>>(path_indices.to_vec());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<U256Variable>(), poseidon_bn254_to_u256(&root));
+    }
+}
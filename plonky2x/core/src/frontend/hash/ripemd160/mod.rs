@@ -0,0 +1,283 @@
+//! An implementation of the RIPEMD-160 hash function in a plonky2 circuit, together with Bitcoin's
+//! `hash160`.
+//!
+//! Words are kept as big-endian bits, as in the SHA-256 gadget, so that the rotations are free
+//! rewirings and the additions go through `add_many_arr`.
+//! reference: https://homes.esat.kuleuven.be/~bosselae/ripemd160/pdf/AB-9601/AB-9601.pdf
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::hash::common::{and_arr, not_arr, xor2_arr, xor3_arr};
+use crate::frontend::vars::{BoolVariable, ByteVariable, BytesVariable};
+
+/// The size of a RIPEMD-160 block in bytes.
+pub const RIPEMD160_CHUNK_SIZE_BYTES: usize = 64;
+
+const INITIAL_HASH: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+const K_LEFT: [u32; 5] = [0x00000000, 0x5A827999, 0x6ED9EBA1, 0x8F1BBCDC, 0xA953FD4E];
+const K_RIGHT: [u32; 5] = [0x50A28BE6, 0x5C4DD124, 0x6D703EF3, 0x7A6D76E9, 0x00000000];
+
+/// The message word selected at each step of the left line.
+const R_LEFT: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, //
+    7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5, 2, 14, 11, 8, //
+    3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, //
+    1, 9, 11, 10, 0, 8, 12, 4, 13, 3, 7, 15, 14, 5, 6, 2, //
+    4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+/// The message word selected at each step of the right line.
+const R_RIGHT: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, //
+    6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12, 4, 9, 1, 2, //
+    15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, //
+    8, 6, 4, 1, 3, 11, 15, 0, 5, 12, 2, 13, 9, 7, 10, 14, //
+    12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// The left rotation applied at each step of the left line.
+const S_LEFT: [usize; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, //
+    7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15, 9, 11, 7, 13, 12, //
+    11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, //
+    11, 12, 14, 15, 14, 15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, //
+    9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+/// The left rotation applied at each step of the right line.
+const S_RIGHT: [usize; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, //
+    9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12, 7, 6, 15, 13, 11, //
+    9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, //
+    15, 5, 8, 11, 14, 14, 6, 14, 6, 9, 12, 9, 12, 5, 15, 8, //
+    8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+type Word = [BoolVariable; 32];
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    fn ripemd160_const_word(&mut self, u: u32) -> Word {
+        u.to_be_bytes()
+            .iter()
+            .flat_map(|b| self.constant::<ByteVariable>(*b).as_be_bits())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
+    }
+
+    fn ripemd160_rotate_left(&self, x: Word, s: usize) -> Word {
+        self._right_rotate(x, 32 - s)
+    }
+
+    /// The boolean function of the given round, where `x | !y` is computed as `!(!x & y)`.
+    fn ripemd160_f(&mut self, round: usize, x: Word, y: Word, z: Word) -> Word {
+        match round {
+            0 => xor3_arr(x, y, z, self),
+            1 => {
+                let x_and_y = and_arr(x, y, self);
+                let not_x = not_arr(x, self);
+                let not_x_and_z = and_arr(not_x, z, self);
+                xor2_arr(x_and_y, not_x_and_z, self)
+            }
+            2 => {
+                let not_x = not_arr(x, self);
+                let not_x_and_y = and_arr(not_x, y, self);
+                let x_or_not_y = not_arr(not_x_and_y, self);
+                xor2_arr(x_or_not_y, z, self)
+            }
+            3 => {
+                let x_and_z = and_arr(x, z, self);
+                let not_z = not_arr(z, self);
+                let y_and_not_z = and_arr(y, not_z, self);
+                xor2_arr(x_and_z, y_and_not_z, self)
+            }
+            4 => {
+                let not_y = not_arr(y, self);
+                let not_y_and_z = and_arr(not_y, z, self);
+                let y_or_not_z = not_arr(not_y_and_z, self);
+                xor2_arr(x, y_or_not_z, self)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Pads the message with a one bit, zeros and its length in bits as a little-endian u64.
+    fn pad_message_ripemd160(&mut self, input: &[ByteVariable]) -> Vec<ByteVariable> {
+        let mut padded = input.to_vec();
+        padded.push(self.constant::<ByteVariable>(0x80));
+        while padded.len() % RIPEMD160_CHUNK_SIZE_BYTES != RIPEMD160_CHUNK_SIZE_BYTES - 8 {
+            padded.push(self.constant::<ByteVariable>(0));
+        }
+        for byte in ((input.len() as u64) * 8).to_le_bytes() {
+            padded.push(self.constant::<ByteVariable>(byte));
+        }
+        padded
+    }
+
+    /// Applies the compression function to each block of `padded`, starting from the initial hash.
+    fn ripemd160_process_padded_message(&mut self, padded: &[ByteVariable]) -> [Word; 5] {
+        let k_left = K_LEFT.map(|k| self.ripemd160_const_word(k));
+        let k_right = K_RIGHT.map(|k| self.ripemd160_const_word(k));
+        let mut state = INITIAL_HASH.map(|h| self.ripemd160_const_word(h));
+
+        for chunk in padded.chunks_exact(RIPEMD160_CHUNK_SIZE_BYTES) {
+            // The message words are little-endian.
+            let x = chunk
+                .chunks_exact(4)
+                .map(|word| {
+                    word.iter()
+                        .rev()
+                        .flat_map(|byte| byte.as_be_bits())
+                        .collect::<Vec<_>>()
+                        .try_into()
+                        .unwrap()
+                })
+                .collect::<Vec<Word>>();
+
+            let [mut al, mut bl, mut cl, mut dl, mut el] = state;
+            let [mut ar, mut br, mut cr, mut dr, mut er] = state;
+            for j in 0..80 {
+                let round = j / 16;
+
+                let f = self.ripemd160_f(round, bl, cl, dl);
+                let sum = self.add_many_arr(&[al, f, x[R_LEFT[j]], k_left[round]]);
+                let rotated = self.ripemd160_rotate_left(sum, S_LEFT[j]);
+                let t = self.add_arr(rotated, el);
+                al = el;
+                el = dl;
+                dl = self.ripemd160_rotate_left(cl, 10);
+                cl = bl;
+                bl = t;
+
+                let f = self.ripemd160_f(4 - round, br, cr, dr);
+                let sum = self.add_many_arr(&[ar, f, x[R_RIGHT[j]], k_right[round]]);
+                let rotated = self.ripemd160_rotate_left(sum, S_RIGHT[j]);
+                let t = self.add_arr(rotated, er);
+                ar = er;
+                er = dr;
+                dr = self.ripemd160_rotate_left(cr, 10);
+                cr = br;
+                br = t;
+            }
+
+            state = [
+                self.add_many_arr(&[state[1], cl, dr]),
+                self.add_many_arr(&[state[2], dl, er]),
+                self.add_many_arr(&[state[3], el, ar]),
+                self.add_many_arr(&[state[4], al, br]),
+                self.add_many_arr(&[state[0], bl, cr]),
+            ];
+        }
+
+        state
+    }
+
+    /// Computes the RIPEMD-160 hash of a message of fixed length.
+    pub fn ripemd160(&mut self, input: &[ByteVariable]) -> BytesVariable<20> {
+        let padded = self.pad_message_ripemd160(input);
+        let state = self.ripemd160_process_padded_message(&padded);
+
+        // The digest is the little-endian encoding of the state.
+        let bytes = state
+            .iter()
+            .flat_map(|word| {
+                word.chunks_exact(8)
+                    .rev()
+                    .map(|be_bits| ByteVariable(be_bits.try_into().unwrap()))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        BytesVariable(bytes.try_into().unwrap())
+    }
+
+    /// Computes Bitcoin's `hash160` of a message of fixed length, i.e. `ripemd160(sha256(input))`.
+    ///
+    /// The SHA-256 hash is computed with `curta_sha256`.
+    pub fn hash160(&mut self, input: &[ByteVariable]) -> BytesVariable<20> {
+        let sha256_digest = self.curta_sha256(input);
+        self.ripemd160(&sha256_digest.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::prelude::*;
+    use crate::utils::bytes;
+
+    fn ripemd160_test_cases() -> Vec<(Vec<u8>, [u8; 20])> {
+        let message = |len: usize| (0..len as u8).collect::<Vec<_>>();
+        vec![
+            (vec![], bytes!("9c1185a5c5e9fc54612808977ee8f548b2258d31")),
+            (
+                b"abc".to_vec(),
+                bytes!("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+            ),
+            // The messages which fill the first block, or spill the length into a second one.
+            (
+                message(55),
+                bytes!("3c86963b3ff646a65ae42996e9664c747cc7e5e6"),
+            ),
+            (
+                message(56),
+                bytes!("ebdd79cfd4fd9949ef8089673d2620427f487cfb"),
+            ),
+            (
+                message(64),
+                bytes!("2581f5e9f957b44b0fa24d31996de47409dd1e0f"),
+            ),
+            (
+                message(100),
+                bytes!("8ae5d2e6b1f3a514257f2469b637454931844aeb"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_ripemd160() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = DefaultBuilder::new();
+        for (message, expected_digest) in ripemd160_test_cases() {
+            let message = message
+                .iter()
+                .map(|b| builder.constant::<ByteVariable>(*b))
+                .collect::<Vec<_>>();
+            let digest = builder.ripemd160(&message);
+            let expected_digest = builder.constant::<BytesVariable<20>>(expected_digest);
+            builder.assert_is_equal(digest, expected_digest);
+        }
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_hash160() {
+        env::set_var("RUST_LOG", "debug");
+        env_logger::try_init().unwrap_or_default();
+        dotenv::dotenv().ok();
+
+        let mut builder = DefaultBuilder::new();
+        let message = builder.read::<BytesVariable<64>>();
+        let digest = builder.hash160(&message.0);
+        builder.write(digest);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<BytesVariable<64>>((0..64).collect::<Vec<u8>>().try_into().unwrap());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        circuit.test_default_serializers();
+
+        assert_eq!(
+            output.read::<BytesVariable<20>>(),
+            bytes!::<[u8; 20]>("dd21d9434f79e153b82e7d204ea5279200d0d022")
+        );
+    }
+}
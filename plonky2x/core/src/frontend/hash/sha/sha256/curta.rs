@@ -191,6 +191,23 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         input.extend(&right.as_bytes());
         self.curta_sha256(&input)
     }
+
+    /// Computes Bitcoin's double SHA256, `sha256(sha256(input))`, of an input of fixed size.
+    pub fn curta_double_sha256(&mut self, input: &[ByteVariable]) -> Bytes32Variable {
+        let digest = self.curta_sha256(input);
+        self.curta_sha256(&digest.as_bytes())
+    }
+
+    /// Computes the double SHA256 of the concatenation of two digests, as in the nodes of Bitcoin's
+    /// transaction Merkle trees.
+    pub fn curta_double_sha256_pair(
+        &mut self,
+        left: Bytes32Variable,
+        right: Bytes32Variable,
+    ) -> Bytes32Variable {
+        let digest = self.curta_sha256_pair(left, right);
+        self.curta_sha256(&digest.as_bytes())
+    }
 }
 
 #[cfg(test)]
@@ -467,4 +484,34 @@ mod tests {
         let (proof, output) = circuit.prove(&input);
         circuit.verify(&proof, &input, &output);
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_double_sha256_curta() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let message = (0..100u8).collect::<Vec<_>>();
+        let message_variable = message
+            .iter()
+            .map(|b| builder.constant::<ByteVariable>(*b))
+            .collect::<Vec<_>>();
+        let digest = builder.curta_double_sha256(&message_variable);
+        let expected_digest =
+            builder.constant::<Bytes32Variable>(H256::from(sha256(&sha256(&message))));
+        builder.assert_is_equal(digest, expected_digest);
+
+        let left = builder.constant::<Bytes32Variable>(H256::from(sha256(&[0u8])));
+        let right = builder.constant::<Bytes32Variable>(H256::from(sha256(&[1u8])));
+        let pair_digest = builder.curta_double_sha256_pair(left, right);
+        let expected_pair_digest = builder.constant::<Bytes32Variable>(H256::from(sha256(
+            &sha256(&[sha256(&[0u8]), sha256(&[1u8])].concat()),
+        )));
+        builder.assert_is_equal(pair_digest, expected_pair_digest);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+    }
 }
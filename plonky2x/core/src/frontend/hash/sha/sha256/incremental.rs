@@ -0,0 +1,262 @@
+//! An incremental SHA-256 hasher, whose chaining state can be carried across circuits.
+//!
+//! The Curta accelerator only exposes whole-message digests, so the blocks are compressed with the
+//! gate-based compression function of `sha256`.
+
+use std::fmt::Debug;
+
+use itertools::Itertools;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2x_derive::CircuitVariable;
+use starkyx::machine::hash::sha::algorithm::SHAPure;
+use starkyx::machine::hash::sha::sha256::SHA256;
+
+use super::pad::{SHA256_CHUNK_SIZE_BYTES, SHA256_INPUT_LENGTH_BIT_SIZE};
+use super::{chaining_value_to_words, words_to_chaining_value};
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitVariable, U32Variable,
+    Variable,
+};
+
+/// The state of an incremental SHA-256 computation after a number of full 64 byte blocks.
+#[derive(Debug, Clone, Copy, CircuitVariable)]
+#[value_name(SHA256StateValue)]
+pub struct SHA256StateVariable {
+    /// The chaining value, i.e. the big-endian encoding of the eight state words.
+    pub chaining_value: Bytes32Variable,
+    /// The number of blocks compressed into `chaining_value`.
+    pub nb_blocks: U32Variable,
+}
+
+/// A SHA-256 computation in progress.
+///
+/// The bytes passed to `update` are only compressed when the state is exported with `state` or
+/// when the hash is finalized, so that `finalize_variable` can treat all of them as the
+/// variable-length tail of the message.
+#[derive(Debug, Clone)]
+pub struct SHA256Hasher {
+    state: SHA256StateVariable,
+    pending: Vec<ByteVariable>,
+}
+
+impl SHA256Hasher {
+    /// Appends `input` to the message.
+    pub fn update(&mut self, input: &[ByteVariable]) {
+        self.pending.extend_from_slice(input);
+    }
+
+    /// Compresses the bytes passed to `update` and returns the chaining state, which can be
+    /// written as an output and resumed with `sha256_resume` in another circuit.
+    ///
+    /// The number of bytes absorbed since the last call must be a multiple of 64.
+    pub fn state<L: PlonkParameters<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> SHA256StateVariable {
+        assert_eq!(
+            self.pending.len() % SHA256_CHUNK_SIZE_BYTES,
+            0,
+            "the sha256 state can only be exported on a block boundary"
+        );
+        let pending = std::mem::take(&mut self.pending);
+        let mut chaining_value = self.state.chaining_value;
+        for chunk in pending.chunks_exact(SHA256_CHUNK_SIZE_BYTES) {
+            chaining_value =
+                builder.sha256_compress(chaining_value, BytesVariable(chunk.try_into().unwrap()));
+        }
+        let nb_new_blocks =
+            builder.constant::<U32Variable>((pending.len() / SHA256_CHUNK_SIZE_BYTES) as u32);
+        self.state = SHA256StateVariable {
+            chaining_value,
+            nb_blocks: builder.add(self.state.nb_blocks, nb_new_blocks),
+        };
+        self.state
+    }
+
+    /// The number of bits of a message made of the compressed blocks and `tail_length` bytes.
+    fn message_bit_length<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        tail_length: U32Variable,
+    ) -> Variable {
+        let block_bits = builder
+            .constant::<Variable>(L::Field::from_canonical_usize(SHA256_CHUNK_SIZE_BYTES * 8));
+        let byte_bits = builder.constant::<Variable>(L::Field::from_canonical_usize(8));
+        let blocks_bit_length = builder.mul(self.state.nb_blocks.variable, block_bits);
+        let tail_bit_length = builder.mul(tail_length.variable, byte_bits);
+        builder.add(blocks_bit_length, tail_bit_length)
+    }
+
+    /// Pads and compresses the message and returns its digest.
+    pub fn finalize<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Bytes32Variable {
+        let tail_length = builder.constant::<U32Variable>(self.pending.len() as u32);
+        let bit_length = self.message_bit_length(builder, tail_length);
+        let mut length_bits = builder
+            .api
+            .split_le(bit_length.0, SHA256_INPUT_LENGTH_BIT_SIZE);
+        length_bits.reverse();
+
+        let mut tail_bits = self
+            .pending
+            .iter()
+            .flat_map(|b| b.as_be_bits())
+            .collect_vec();
+        tail_bits.push(builder._true());
+        while tail_bits.len() % (SHA256_CHUNK_SIZE_BYTES * 8)
+            != SHA256_CHUNK_SIZE_BYTES * 8 - SHA256_INPUT_LENGTH_BIT_SIZE
+        {
+            tail_bits.push(builder._false());
+        }
+        tail_bits.extend(length_bits.into_iter().map(BoolVariable::from));
+
+        let mut words = chaining_value_to_words(self.state.chaining_value);
+        for chunk in tail_bits.chunks_exact(SHA256_CHUNK_SIZE_BYTES * 8) {
+            words = builder.process_chunk_sha256(words, chunk);
+        }
+        words_to_chaining_value(words)
+    }
+
+    /// Pads and compresses the message made of the compressed blocks and the first `length` bytes
+    /// passed to `update` since then, and returns its digest.
+    ///
+    /// The cost is that of hashing all the bytes passed to `update`, and `length` is constrained to
+    /// be at most their number.
+    pub fn finalize_variable<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+        length: U32Variable,
+    ) -> Bytes32Variable {
+        let bit_length = self.message_bit_length(builder, length);
+        let padded =
+            builder.pad_sha256_variable_length_with_bit_length(&self.pending, length, bit_length);
+        let last_chunk = builder.compute_sha256_last_chunk(length);
+
+        let mut words = chaining_value_to_words(self.state.chaining_value);
+        let mut digest = self.state.chaining_value;
+        for (i, chunk) in padded.chunks_exact(SHA256_CHUNK_SIZE_BYTES).enumerate() {
+            let bits = chunk.iter().flat_map(|b| b.as_be_bits()).collect_vec();
+            words = builder.process_chunk_sha256(words, &bits);
+
+            let index = builder.constant::<U32Variable>(i as u32);
+            let is_last_chunk = builder.is_equal(index, last_chunk);
+            let chaining_value = words_to_chaining_value(words);
+            digest = builder.select(is_last_chunk, chaining_value, digest);
+        }
+        digest
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Starts an incremental SHA-256 computation.
+    pub fn sha256_init(&mut self) -> SHA256Hasher {
+        let initial_hash = SHA256::INITIAL_HASH
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect_vec();
+        let state = SHA256StateVariable {
+            chaining_value: self
+                .constant::<Bytes32Variable>(ethers::types::H256::from_slice(&initial_hash)),
+            nb_blocks: self.constant::<U32Variable>(0),
+        };
+        self.sha256_resume(state)
+    }
+
+    /// Resumes an incremental SHA-256 computation from a state exported by `SHA256Hasher::state`.
+    pub fn sha256_resume(&mut self, state: SHA256StateVariable) -> SHA256Hasher {
+        SHA256Hasher {
+            state,
+            pending: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+
+    use super::*;
+    use crate::prelude::*;
+    use crate::utils::hash::sha256;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha256_incremental() {
+        env_logger::try_init().unwrap_or_default();
+
+        let message = (0..150u8).collect::<Vec<_>>();
+
+        let mut builder = DefaultBuilder::new();
+        let message_variable = builder.read::<BytesVariable<150>>();
+        let mut hasher = builder.sha256_init();
+        hasher.update(&message_variable.0[..10]);
+        hasher.update(&message_variable.0[10..]);
+        let digest = hasher.finalize(&mut builder);
+        builder.write(digest);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<BytesVariable<150>>(message.clone().try_into().unwrap());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(
+            output.read::<Bytes32Variable>(),
+            H256::from(sha256(&message))
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha256_incremental_across_circuits() {
+        env_logger::try_init().unwrap_or_default();
+
+        let message = (0..200u8).map(|i| i.wrapping_mul(7)).collect::<Vec<_>>();
+
+        // The first circuit absorbs two blocks and outputs the chaining state.
+        let mut builder = DefaultBuilder::new();
+        let head = builder.read::<BytesVariable<128>>();
+        let mut hasher = builder.sha256_init();
+        hasher.update(&head.0);
+        let state = hasher.state(&mut builder);
+        builder.write(state);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<BytesVariable<128>>(message[..128].try_into().unwrap());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        let state_value = output.read::<SHA256StateVariable>();
+        assert_eq!(state_value.nb_blocks, 2);
+
+        // The second circuit resumes from the state and hashes a tail of variable length.
+        let mut builder = DefaultBuilder::new();
+        let state = builder.read::<SHA256StateVariable>();
+        let tail = builder.read::<BytesVariable<100>>();
+        let length = builder.read::<U32Variable>();
+        let mut hasher = builder.sha256_resume(state);
+        hasher.update(&tail.0);
+        let digest = hasher.finalize_variable(&mut builder, length);
+        builder.write(digest);
+
+        let circuit = builder.build();
+        let mut tail_value = message[128..].to_vec();
+        tail_value.resize(100, 0xff);
+        let mut input = circuit.input();
+        input.write::<SHA256StateVariable>(state_value);
+        input.write::<BytesVariable<100>>(tail_value.try_into().unwrap());
+        input.write::<U32Variable>((message.len() - 128) as u32);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(
+            output.read::<Bytes32Variable>(),
+            H256::from(sha256(&message))
+        );
+    }
+}
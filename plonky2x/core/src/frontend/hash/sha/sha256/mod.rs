@@ -7,11 +7,36 @@ use itertools::Itertools;
 use crate::backend::circuit::PlonkParameters;
 use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::hash::common::{and_arr, not_arr, xor2_arr, xor3_arr};
-use crate::frontend::vars::{BoolVariable, ByteVariable, Bytes32Variable, CircuitVariable};
+use crate::frontend::vars::{
+    BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitVariable,
+};
 
 pub mod curta;
+pub mod incremental;
 pub mod pad;
 
+/// Splits a chaining value into its eight state words, as big-endian bits.
+pub(crate) fn chaining_value_to_words(chaining_value: Bytes32Variable) -> [[BoolVariable; 32]; 8] {
+    chaining_value
+        .as_bytes()
+        .iter()
+        .flat_map(|byte| byte.as_be_bits())
+        .chunks(32)
+        .into_iter()
+        .map(|word| word.collect_vec().try_into().unwrap())
+        .collect_vec()
+        .try_into()
+        .unwrap()
+}
+
+/// Encodes eight state words, given as big-endian bits, as a chaining value.
+pub(crate) fn words_to_chaining_value(words: [[BoolVariable; 32]; 8]) -> Bytes32Variable {
+    // Ok to use `from_variables_unsafe` as the words are made of 256 constrained bits.
+    Bytes32Variable::from_variables_unsafe(
+        &words.iter().flatten().map(|bit| bit.variable).collect_vec(),
+    )
+}
+
 /// Implements SHA256 implementation for CircuitBuilder
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
     fn const_be_bits(&mut self, u: u32) -> [BoolVariable; 32] {
@@ -37,98 +62,101 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             .flat_map(|b| b.as_be_bits().to_vec())
             .collect_vec();
         let mut sha256_hash = self.get_inital_hash();
-        let round_constants = self.get_round_constants();
 
         // Process the input with 512 bit chunks aka 64 byte chunks
         for chunk in msg_input_bits.chunks_exact(512) {
-            let mut u: Vec<BoolVariable> = Vec::new();
+            sha256_hash = self.process_chunk_sha256(sha256_hash, chunk);
+        }
 
-            for bit in chunk.iter() {
-                // 0 .. 16 chunk size * 32 bits7
-                u.push(*bit);
-            }
-            for _ in 512..64 * 32 {
-                // 16 * 8 ... 64 * 8 because of L
-                u.push(self._false());
-            }
+        sha256_hash.iter().flat_map(|x| x.to_vec()).collect()
+    }
 
-            let mut w = self.reshape(u);
-
-            for i in 16..64 {
-                let s0 = xor3_arr(
-                    self._right_rotate(w[i - 15], 7),
-                    self._right_rotate(w[i - 15], 18),
-                    self._shr(w[i - 15], 3),
-                    self,
-                );
-
-                let s1 = xor3_arr(
-                    self._right_rotate(w[i - 2], 17),
-                    self._right_rotate(w[i - 2], 19),
-                    self._shr(w[i - 2], 10),
-                    self,
-                );
-
-                let inter1 = self.add_arr(w[i - 16], s0);
-                let inter2 = self.add_arr(inter1, w[i - 7]);
-                w[i] = self.add_arr(s1, inter2);
-            }
-            let mut a = sha256_hash[0];
-            let mut b = sha256_hash[1];
-            let mut c = sha256_hash[2];
-            let mut d = sha256_hash[3];
-            let mut e = sha256_hash[4];
-            let mut f = sha256_hash[5];
-            let mut g = sha256_hash[6];
-            let mut h = sha256_hash[7];
-
-            for i in 0..64 {
-                let sum1 = xor3_arr(
-                    self._right_rotate(e, 6),
-                    self._right_rotate(e, 11),
-                    self._right_rotate(e, 25),
-                    self,
-                );
-                let ch = xor2_arr(
-                    and_arr(e, f, self),
-                    and_arr(not_arr(e, self), g, self),
-                    self,
-                );
-                let temp1 = self.add_arr(h, sum1);
-                let temp2 = self.add_arr(temp1, ch);
-                let temp3 = self.add_arr(temp2, round_constants[i]);
-                let temp4 = self.add_arr(temp3, w[i]);
-                let final_temp1 = temp4;
-
-                let sum0 = xor3_arr(
-                    self._right_rotate(a, 2),
-                    self._right_rotate(a, 13),
-                    self._right_rotate(a, 22),
-                    self,
-                );
-
-                let maj = xor3_arr(
-                    and_arr(a, b, self),
-                    and_arr(a, c, self),
-                    and_arr(b, c, self),
-                    self,
-                );
-                let final_temp2 = self.add_arr(sum0, maj);
-
-                h = g;
-                g = f;
-                f = e;
-                e = self.add_arr(d, final_temp1);
-                d = c;
-                c = b;
-                b = a;
-                a = self.add_arr(final_temp1, final_temp2);
-            }
+    /// Applies the SHA-256 compression function to a 512 bit chunk, given as big-endian bits.
+    pub(crate) fn process_chunk_sha256(
+        &mut self,
+        sha256_hash: [[BoolVariable; 32]; 8],
+        chunk: &[BoolVariable],
+    ) -> [[BoolVariable; 32]; 8] {
+        assert_eq!(chunk.len(), 512);
+        let round_constants = self.get_round_constants();
+        let mut u: Vec<BoolVariable> = Vec::new();
 
-            sha256_hash = self.zip_add(sha256_hash, [a, b, c, d, e, f, g, h]);
+        for bit in chunk.iter() {
+            // 0 .. 16 chunk size * 32 bits7
+            u.push(*bit);
+        }
+        for _ in 512..64 * 32 {
+            // 16 * 8 ... 64 * 8 because of L
+            u.push(self._false());
         }
 
-        sha256_hash.iter().flat_map(|x| x.to_vec()).collect()
+        let mut w = self.reshape(u);
+
+        for i in 16..64 {
+            let s0 = xor3_arr(
+                self._right_rotate(w[i - 15], 7),
+                self._right_rotate(w[i - 15], 18),
+                self._shr(w[i - 15], 3),
+                self,
+            );
+
+            let s1 = xor3_arr(
+                self._right_rotate(w[i - 2], 17),
+                self._right_rotate(w[i - 2], 19),
+                self._shr(w[i - 2], 10),
+                self,
+            );
+
+            w[i] = self.add_many_arr(&[s1, w[i - 16], s0, w[i - 7]]);
+        }
+        let mut a = sha256_hash[0];
+        let mut b = sha256_hash[1];
+        let mut c = sha256_hash[2];
+        let mut d = sha256_hash[3];
+        let mut e = sha256_hash[4];
+        let mut f = sha256_hash[5];
+        let mut g = sha256_hash[6];
+        let mut h = sha256_hash[7];
+
+        for i in 0..64 {
+            let sum1 = xor3_arr(
+                self._right_rotate(e, 6),
+                self._right_rotate(e, 11),
+                self._right_rotate(e, 25),
+                self,
+            );
+            let ch = xor2_arr(
+                and_arr(e, f, self),
+                and_arr(not_arr(e, self), g, self),
+                self,
+            );
+            let final_temp1 = self.add_many_arr(&[h, sum1, ch, round_constants[i], w[i]]);
+
+            let sum0 = xor3_arr(
+                self._right_rotate(a, 2),
+                self._right_rotate(a, 13),
+                self._right_rotate(a, 22),
+                self,
+            );
+
+            let maj = xor3_arr(
+                and_arr(a, b, self),
+                and_arr(a, c, self),
+                and_arr(b, c, self),
+                self,
+            );
+
+            h = g;
+            g = f;
+            f = e;
+            e = self.add_arr(d, final_temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = self.add_many_arr(&[final_temp1, sum0, maj]);
+        }
+
+        self.zip_add(sha256_hash, [a, b, c, d, e, f, g, h])
     }
 
     pub fn sha256(&mut self, input: &[ByteVariable]) -> Bytes32Variable {
@@ -150,6 +178,23 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         left_bytes.extend(&right.as_bytes());
         self.sha256(&left_bytes)
     }
+
+    /// Applies the SHA-256 compression function to a 64 byte block, starting from `state`, the
+    /// big-endian encoding of the eight state words, instead of the standard initial hash.
+    ///
+    /// No padding is applied, so this can continue a hash from a midstate or build custom
+    /// Merkle-Damgard constructions. The Curta SHA-256 AIR always starts from the standard initial
+    /// hash, so the compression function is constrained with gates.
+    pub fn sha256_compress(
+        &mut self,
+        state: Bytes32Variable,
+        block: BytesVariable<64>,
+    ) -> Bytes32Variable {
+        let words = chaining_value_to_words(state);
+        let bits = block.0.iter().flat_map(|b| b.as_be_bits()).collect_vec();
+        let words = self.process_chunk_sha256(words, &bits);
+        words_to_chaining_value(words)
+    }
 }
 
 #[cfg(test)]
@@ -248,6 +293,110 @@ mod tests {
         circuit.test_default_serializers();
     }
 
+    /// The SHA-256 compression function as it was before the round additions were folded into
+    /// `add_many_arr`, with every intermediate sum decomposed into bits by `add_arr`.
+    fn process_chunk_sha256_chained(
+        builder: &mut CircuitBuilder<L, D>,
+        sha256_hash: [[BoolVariable; 32]; 8],
+        chunk: &[BoolVariable],
+    ) -> [[BoolVariable; 32]; 8] {
+        let round_constants = builder.get_round_constants();
+        let mut u = chunk.to_vec();
+        for _ in 512..64 * 32 {
+            u.push(builder._false());
+        }
+        let mut w = builder.reshape(u);
+
+        for i in 16..64 {
+            let s0 = xor3_arr(
+                builder._right_rotate(w[i - 15], 7),
+                builder._right_rotate(w[i - 15], 18),
+                builder._shr(w[i - 15], 3),
+                builder,
+            );
+            let s1 = xor3_arr(
+                builder._right_rotate(w[i - 2], 17),
+                builder._right_rotate(w[i - 2], 19),
+                builder._shr(w[i - 2], 10),
+                builder,
+            );
+            let inter1 = builder.add_arr(w[i - 16], s0);
+            let inter2 = builder.add_arr(inter1, w[i - 7]);
+            w[i] = builder.add_arr(s1, inter2);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = sha256_hash;
+        for i in 0..64 {
+            let sum1 = xor3_arr(
+                builder._right_rotate(e, 6),
+                builder._right_rotate(e, 11),
+                builder._right_rotate(e, 25),
+                builder,
+            );
+            let ch = xor2_arr(
+                and_arr(e, f, builder),
+                and_arr(not_arr(e, builder), g, builder),
+                builder,
+            );
+            let temp1 = builder.add_arr(h, sum1);
+            let temp2 = builder.add_arr(temp1, ch);
+            let temp3 = builder.add_arr(temp2, round_constants[i]);
+            let final_temp1 = builder.add_arr(temp3, w[i]);
+
+            let sum0 = xor3_arr(
+                builder._right_rotate(a, 2),
+                builder._right_rotate(a, 13),
+                builder._right_rotate(a, 22),
+                builder,
+            );
+            let maj = xor3_arr(
+                and_arr(a, b, builder),
+                and_arr(a, c, builder),
+                and_arr(b, c, builder),
+                builder,
+            );
+            let final_temp2 = builder.add_arr(sum0, maj);
+
+            h = g;
+            g = f;
+            f = e;
+            e = builder.add_arr(d, final_temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = builder.add_arr(final_temp1, final_temp2);
+        }
+
+        builder.zip_add(sha256_hash, [a, b, c, d, e, f, g, h])
+    }
+
+    #[test]
+    fn test_sha256_gate_count() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let message = builder.read::<BytesVariable<32>>();
+        let num_gates = builder.api.num_gates();
+        builder.sha256(&message.0);
+        let cost = builder.api.num_gates() - num_gates;
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let message = builder.read::<BytesVariable<32>>();
+        let num_gates = builder.api.num_gates();
+        let padded = builder.pad_message_sha256(&message.0);
+        let bits = padded.iter().flat_map(|b| b.as_be_bits()).collect_vec();
+        let mut state = builder.get_inital_hash();
+        for chunk in bits.chunks_exact(512) {
+            state = process_chunk_sha256_chained(&mut builder, state, chunk);
+        }
+        let chained_cost = builder.api.num_gates() - num_gates;
+
+        assert!(
+            cost < chained_cost,
+            "sha256 costs {} gates, {} with chained additions",
+            cost,
+            chained_cost
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "ci", ignore)]
     fn test_sha256_bench() {
@@ -282,4 +431,44 @@ mod tests {
             circuit.test_default_serializers();
         }
     }
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha256_compress() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut rng = thread_rng();
+        let state = (0..8).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+        let block = (0..64).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+
+        let mut expected_state: [u32; 8] = state.clone().try_into().unwrap();
+        sha2::compress256(
+            &mut expected_state,
+            &[sha2::digest::generic_array::GenericArray::clone_from_slice(
+                &block,
+            )],
+        );
+        let encode = |words: &[u32]| {
+            let bytes = words
+                .iter()
+                .flat_map(|w| w.to_be_bytes())
+                .collect::<Vec<_>>();
+            ethers::types::H256::from_slice(&bytes)
+        };
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let state_variable = builder.read::<Bytes32Variable>();
+        let block_variable = builder.read::<BytesVariable<64>>();
+        let result = builder.sha256_compress(state_variable, block_variable);
+        builder.write(result);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<Bytes32Variable>(encode(&state));
+        input.write::<BytesVariable<64>>(block.try_into().unwrap());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<Bytes32Variable>(), encode(&expected_state));
+    }
 }
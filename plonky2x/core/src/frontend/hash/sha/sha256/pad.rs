@@ -64,6 +64,24 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         &mut self,
         input: &[ByteVariable],
         input_byte_length: U32Variable,
+    ) -> Vec<ByteVariable> {
+        let bits_per_byte = self.constant::<U32Variable>(8);
+        let input_bit_length = self.mul(input_byte_length, bits_per_byte);
+        self.pad_sha256_variable_length_with_bit_length(
+            input,
+            input_byte_length,
+            input_bit_length.variable,
+        )
+    }
+
+    /// Pad the given variable length input according to the SHA-256 spec, writing
+    /// message_bit_length in the length bytes. This is used to pad the tail of a message whose
+    /// first chunks were already compressed.
+    pub(crate) fn pad_sha256_variable_length_with_bit_length(
+        &mut self,
+        input: &[ByteVariable],
+        input_byte_length: U32Variable,
+        message_bit_length: Variable,
     ) -> Vec<ByteVariable> {
         let true_t = self._true();
         let false_t = self._false();
@@ -84,13 +102,10 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         // Compute the length bytes (big-endian representation of the length in bits).
         let zero_byte = self.constant::<ByteVariable>(0x00);
 
-        let bits_per_byte = self.constant::<U32Variable>(8);
-        let input_bit_length = self.mul(input_byte_length, bits_per_byte);
-
         // Get the length bits in LE order, padded to 64 bits.
         let mut length_bits = self
             .api
-            .split_le(input_bit_length.variable.0, SHA256_INPUT_LENGTH_BIT_SIZE);
+            .split_le(message_bit_length.0, SHA256_INPUT_LENGTH_BIT_SIZE);
         // Convert length to BE bits
         length_bits.reverse();
 
@@ -1,2 +1,196 @@
+use itertools::Itertools;
+use starkyx::machine::hash::sha::algorithm::SHAPure;
+use starkyx::machine::hash::sha::sha512::SHA512;
+
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::hash::common::{and_arr, not_arr, xor2_arr, xor3_arr};
+use crate::frontend::vars::{BoolVariable, ByteVariable, BytesVariable};
+
 pub mod curta;
+pub mod incremental;
 pub mod pad;
+
+/// Splits a chaining value into its eight state words, as big-endian bits.
+pub(crate) fn chaining_value_to_words(
+    chaining_value: BytesVariable<64>,
+) -> [[BoolVariable; 64]; 8] {
+    chaining_value
+        .0
+        .iter()
+        .flat_map(|byte| byte.as_be_bits())
+        .chunks(64)
+        .into_iter()
+        .map(|word| word.collect_vec().try_into().unwrap())
+        .collect_vec()
+        .try_into()
+        .unwrap()
+}
+
+/// Encodes eight state words, given as big-endian bits, as a chaining value.
+pub(crate) fn words_to_chaining_value(words: [[BoolVariable; 64]; 8]) -> BytesVariable<64> {
+    BytesVariable(
+        words
+            .iter()
+            .flatten()
+            .chunks(8)
+            .into_iter()
+            .map(|bits| ByteVariable(bits.copied().collect_vec().try_into().unwrap()))
+            .collect_vec()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+/// Implements the gate-based SHA-512 compression function for CircuitBuilder
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    fn const_be_bits_u64(&mut self, u: u64) -> [BoolVariable; 64] {
+        u.to_be_bytes()
+            .iter()
+            .flat_map(|b| self.constant::<ByteVariable>(*b).as_be_bits().to_vec())
+            .collect::<Vec<BoolVariable>>()
+            .try_into()
+            .unwrap()
+    }
+
+    /// Applies the SHA-512 compression function to a 1024 bit chunk, given as big-endian bits.
+    pub(crate) fn process_chunk_sha512(
+        &mut self,
+        sha512_hash: [[BoolVariable; 64]; 8],
+        chunk: &[BoolVariable],
+    ) -> [[BoolVariable; 64]; 8] {
+        assert_eq!(chunk.len(), 1024);
+        let round_constants = SHA512::ROUND_CONSTANTS.map(|x| self.const_be_bits_u64(x));
+
+        let mut w: Vec<[BoolVariable; 64]> = chunk
+            .chunks_exact(64)
+            .map(|word| word.try_into().unwrap())
+            .collect();
+        for i in 16..80 {
+            let s0 = xor3_arr(
+                self._right_rotate(w[i - 15], 1),
+                self._right_rotate(w[i - 15], 8),
+                self._shr(w[i - 15], 7),
+                self,
+            );
+
+            let s1 = xor3_arr(
+                self._right_rotate(w[i - 2], 19),
+                self._right_rotate(w[i - 2], 61),
+                self._shr(w[i - 2], 6),
+                self,
+            );
+
+            let word = self.add_many_arr(&[s1, w[i - 16], s0, w[i - 7]]);
+            w.push(word);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = sha512_hash;
+        for i in 0..80 {
+            let sum1 = xor3_arr(
+                self._right_rotate(e, 14),
+                self._right_rotate(e, 18),
+                self._right_rotate(e, 41),
+                self,
+            );
+            let ch = xor2_arr(
+                and_arr(e, f, self),
+                and_arr(not_arr(e, self), g, self),
+                self,
+            );
+            let temp1 = self.add_many_arr(&[h, sum1, ch, round_constants[i], w[i]]);
+
+            let sum0 = xor3_arr(
+                self._right_rotate(a, 28),
+                self._right_rotate(a, 34),
+                self._right_rotate(a, 39),
+                self,
+            );
+            let maj = xor3_arr(
+                and_arr(a, b, self),
+                and_arr(a, c, self),
+                and_arr(b, c, self),
+                self,
+            );
+
+            h = g;
+            g = f;
+            f = e;
+            e = self.add_arr(d, temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = self.add_many_arr(&[temp1, sum0, maj]);
+        }
+
+        self.zip_add(sha512_hash, [a, b, c, d, e, f, g, h])
+    }
+
+    /// Applies the SHA-512 compression function to a 128 byte block, starting from `state`, the
+    /// big-endian encoding of the eight state words.
+    ///
+    /// No padding is applied. The Curta SHA-512 AIR always starts from the standard initial hash,
+    /// so the compression function is constrained with gates.
+    pub fn sha512_compress(
+        &mut self,
+        state: BytesVariable<64>,
+        block: BytesVariable<128>,
+    ) -> BytesVariable<64> {
+        let words = chaining_value_to_words(state);
+        let bits = block.0.iter().flat_map(|b| b.as_be_bits()).collect_vec();
+        let words = self.process_chunk_sha512(words, &bits);
+        words_to_chaining_value(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    use crate::prelude::DefaultParameters;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    #[cfg_attr(feature = "ci", ignore)]
+    fn test_sha512_compress() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut rng = thread_rng();
+        let state = (0..8).map(|_| rng.gen::<u64>()).collect::<Vec<_>>();
+        let block = (0..128).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+
+        let mut expected_state: [u64; 8] = state.clone().try_into().unwrap();
+        sha2::compress512(
+            &mut expected_state,
+            &[sha2::digest::generic_array::GenericArray::clone_from_slice(
+                &block,
+            )],
+        );
+        let encode = |words: &[u64]| -> [u8; 64] {
+            words
+                .iter()
+                .flat_map(|w| w.to_be_bytes())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        };
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let state_variable = builder.read::<BytesVariable<64>>();
+        let block_variable = builder.read::<BytesVariable<128>>();
+        let result = builder.sha512_compress(state_variable, block_variable);
+        builder.write(result);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<BytesVariable<64>>(encode(&state));
+        input.write::<BytesVariable<128>>(block.try_into().unwrap());
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<BytesVariable<64>>(), encode(&expected_state));
+    }
+}
@@ -47,6 +47,11 @@ impl<L: PlonkParameters<D>, H: Hint<L, D>, const D: usize> AsyncHint<L, D> for A
 
         rx.await.unwrap()
     }
+
+    /// The id of the wrapped hint, so that hints which override [`Hint::id`] keep their id.
+    fn id() -> String {
+        format!("Async<{}>", H::id())
+    }
 }
 
 #[cfg(test)]
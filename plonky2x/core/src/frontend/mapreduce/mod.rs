@@ -33,7 +33,9 @@ use crate::frontend::builder::CircuitBuilder;
 use crate::frontend::mapreduce::generator::MapReduceDynamicGenerator;
 use crate::frontend::vars::CircuitVariable;
 use crate::prelude::{ArrayVariable, PlonkParameters, Variable};
-use crate::utils::poseidon::{mapreduce_merkle_tree_root, MapReducePoseidonBuilderMethods};
+use crate::utils::poseidon::{
+    mapreduce_merkle_tree_root_with_hasher, MapReducePoseidonBuilderMethods,
+};
 use crate::utils::proof::ProofWithPublicInputsTargetUtils;
 
 /// The input to the map or reduce circuit.
@@ -66,12 +68,13 @@ struct MapReduceOutputVariable<Ctx: CircuitVariable, Output: CircuitVariable> {
 }
 
 impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
-    fn build_map<Ctx, Input, Output, MapFn, const B: usize>(
+    fn build_map<Ctx, Input, Output, H, MapFn, const B: usize>(
         &mut self,
         map_fn: &MapFn,
     ) -> CircuitBuild<L, D>
     where
         Ctx: CircuitVariable,
+        H: AlgebraicHasher<L::Field>,
         Input: CircuitVariable,
         Output: CircuitVariable,
         MapFn: Fn(Ctx, ArrayVariable<Input, B>, &mut CircuitBuilder<L, D>) -> Output,
@@ -96,7 +99,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
             .iter()
             .flat_map(|i| i.variables())
             .collect_vec();
-        let acc = builder.poseidon_hash(&input_variables);
+        let acc = builder.poseidon_hash_with::<H>(&input_variables);
 
         // Write result.
         let result = MapReduceOutputVariable {
@@ -108,13 +111,14 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         builder.build()
     }
 
-    fn build_reduce<Ctx, Output, ReduceFn>(
+    fn build_reduce<Ctx, Output, H, ReduceFn>(
         &mut self,
         child_circuit: &CircuitBuild<L, D>,
         reduce_fn: &ReduceFn,
     ) -> CircuitBuild<L, D>
     where
         Ctx: CircuitVariable,
+        H: AlgebraicHasher<L::Field>,
         Output: CircuitVariable,
         ReduceFn: Fn(Ctx, Output, Output, &mut CircuitBuilder<L, D>) -> Output,
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
@@ -143,7 +147,8 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         );
 
         // Compute the accumulator hash for the inputs.
-        let acc = builder.poseidon_hash_pair(input_left.clone().acc, input_right.clone().acc);
+        let acc =
+            builder.poseidon_hash_pair_with::<H>(input_left.clone().acc, input_right.clone().acc);
 
         // Write result.
         let result = MapReduceOutputVariable {
@@ -156,6 +161,10 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         builder.build()
     }
 
+    /// Runs `map_fn` on each chunk of `B` inputs and combines the results with `reduce_fn`.
+    ///
+    /// The inputs are committed to with the hasher of the plonky2 config; see
+    /// `mapreduce_with_hasher` to choose the accumulator hash.
     pub fn mapreduce<Ctx, Input, Output, Serializer, const B: usize, MapFn, ReduceFn>(
         &mut self,
         ctx: Ctx,
@@ -173,6 +182,48 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         <Input as CircuitVariable>::ValueType<<L as PlonkParameters<D>>::Field>: Sync + Send,
         MapFn: Fn(Ctx, ArrayVariable<Input, B>, &mut CircuitBuilder<L, D>) -> Output,
         ReduceFn: Fn(Ctx, Output, Output, &mut CircuitBuilder<L, D>) -> Output,
+    {
+        self.mapreduce_with_hasher::<
+            Ctx,
+            Input,
+            Output,
+            Serializer,
+            <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher,
+            B,
+            MapFn,
+            ReduceFn,
+        >(ctx, inputs, map_fn, reduce_fn)
+    }
+
+    /// Same as `mapreduce`, with the inputs accumulator computed with the algebraic hasher `H`,
+    /// e.g. `Poseidon2Hash`.
+    pub fn mapreduce_with_hasher<
+        Ctx,
+        Input,
+        Output,
+        Serializer,
+        H,
+        const B: usize,
+        MapFn,
+        ReduceFn,
+    >(
+        &mut self,
+        ctx: Ctx,
+        inputs: Vec<Input::ValueType<L::Field>>,
+        map_fn: MapFn,
+        reduce_fn: ReduceFn,
+    ) -> Output
+    where
+        Ctx: CircuitVariable,
+        Input: CircuitVariable,
+        Output: CircuitVariable,
+        Serializer: CircuitSerializer,
+        H: AlgebraicHasher<L::Field>,
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
+            AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
+        <Input as CircuitVariable>::ValueType<<L as PlonkParameters<D>>::Field>: Sync + Send,
+        MapFn: Fn(Ctx, ArrayVariable<Input, B>, &mut CircuitBuilder<L, D>) -> Output,
+        ReduceFn: Fn(Ctx, Output, Output, &mut CircuitBuilder<L, D>) -> Output,
     {
         // Sanity checks.
         assert_eq!(inputs.len() % B, 0, "inputs length must be a multiple of B");
@@ -183,9 +234,13 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
         // Compute the expected inputs accumulator.
         let expected_acc =
-            self.constant::<PoseidonHashOutVariable>(mapreduce_merkle_tree_root::<L, Input, B, D>(
-                &inputs,
-            ));
+            self.constant::<PoseidonHashOutVariable>(mapreduce_merkle_tree_root_with_hasher::<
+                L,
+                H,
+                Input,
+                B,
+                D,
+            >(&inputs));
 
         // The gate and witness generator serializers.
         let gate_serializer = Serializer::gate_registry::<L, D>();
@@ -193,7 +248,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
 
         // Build a map circuit which maps from I -> O using the closure `m`.
         debug!("building map");
-        let map_circuit = self.build_map(&map_fn);
+        let map_circuit = self.build_map::<Ctx, Input, Output, H, MapFn, B>(&map_fn);
         debug!("succesfully built map circuit: id={}", map_circuit.id());
 
         // Save map circuit and map circuit input target to build folder.
@@ -212,7 +267,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
                 &reduce_circuits[i - 1]
             };
             let reduce_circuit =
-                self.build_reduce::<Ctx, Output, ReduceFn>(child_circuit, &reduce_fn);
+                self.build_reduce::<Ctx, Output, H, ReduceFn>(child_circuit, &reduce_fn);
             let reduce_circuit_id = reduce_circuit.id();
             let reduce_circuit_path = format!("./build/{}.circuit", reduce_circuit_id);
             reduce_circuit.save(
@@ -257,6 +312,10 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         output.output
     }
 
+    /// Runs `map_fn` on each chunk of `B` inputs and combines the results with `reduce_fn`.
+    ///
+    /// The inputs are committed to with the hasher of the plonky2 config; see
+    /// `mapreduce_dynamic_with_hasher` to choose the accumulator hash.
     pub fn mapreduce_dynamic<Ctx, Input, Output, Serializer, const B: usize, MapFn, ReduceFn>(
         &mut self,
         ctx: Ctx,
@@ -274,6 +333,48 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         <Input as CircuitVariable>::ValueType<<L as PlonkParameters<D>>::Field>: Sync + Send,
         MapFn: Fn(Ctx, ArrayVariable<Input, B>, &mut CircuitBuilder<L, D>) -> Output,
         ReduceFn: Fn(Ctx, Output, Output, &mut CircuitBuilder<L, D>) -> Output,
+    {
+        self.mapreduce_dynamic_with_hasher::<
+            Ctx,
+            Input,
+            Output,
+            Serializer,
+            <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher,
+            B,
+            MapFn,
+            ReduceFn,
+        >(ctx, inputs, map_fn, reduce_fn)
+    }
+
+    /// Same as `mapreduce_dynamic`, with the inputs accumulator computed with the algebraic hasher `H`,
+    /// e.g. `Poseidon2Hash`.
+    pub fn mapreduce_dynamic_with_hasher<
+        Ctx,
+        Input,
+        Output,
+        Serializer,
+        H,
+        const B: usize,
+        MapFn,
+        ReduceFn,
+    >(
+        &mut self,
+        ctx: Ctx,
+        inputs: Vec<Input>,
+        map_fn: MapFn,
+        reduce_fn: ReduceFn,
+    ) -> Output
+    where
+        Ctx: CircuitVariable,
+        Input: CircuitVariable,
+        Output: CircuitVariable,
+        Serializer: CircuitSerializer,
+        H: AlgebraicHasher<L::Field>,
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
+            AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
+        <Input as CircuitVariable>::ValueType<<L as PlonkParameters<D>>::Field>: Sync + Send,
+        MapFn: Fn(Ctx, ArrayVariable<Input, B>, &mut CircuitBuilder<L, D>) -> Output,
+        ReduceFn: Fn(Ctx, Output, Output, &mut CircuitBuilder<L, D>) -> Output,
     {
         // Sanity checks.
         assert_eq!(inputs.len() % B, 0, "inputs length must be a multiple of B");
@@ -283,14 +384,14 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
         );
 
         // Compute the expected inputs accumulator.
-        let expected_acc = self.mapreduce_merkle_tree_root::<Input, B>(&inputs);
+        let expected_acc = self.mapreduce_merkle_tree_root_with_hasher::<H, Input, B>(&inputs);
 
         // The gate and witness generator serializers.
         let gate_serializer = Serializer::gate_registry::<L, D>();
         let generator_serializer = Serializer::generator_registry::<L, D>();
 
         // Build a map circuit which maps from I -> O using the closure `m`.
-        let map_circuit = self.build_map(&map_fn);
+        let map_circuit = self.build_map::<Ctx, Input, Output, H, MapFn, B>(&map_fn);
         debug!("succesfully built map circuit: id={}", map_circuit.id());
 
         // Save map circuit and map circuit input target to build folder.
@@ -309,7 +410,7 @@ impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
                 &reduce_circuits[i - 1]
             };
             let reduce_circuit =
-                self.build_reduce::<Ctx, Output, ReduceFn>(child_circuit, &reduce_fn);
+                self.build_reduce::<Ctx, Output, H, ReduceFn>(child_circuit, &reduce_fn);
             let reduce_circuit_id = reduce_circuit.id();
             let reduce_circuit_path = format!("./build/{}.circuit", reduce_circuit_id);
             reduce_circuit.save(
@@ -361,6 +462,7 @@ pub(crate) mod tests {
     use plonky2::field::types::Field;
 
     use crate::backend::circuit::DefaultSerializer;
+    use crate::frontend::hash::poseidon::poseidon2::Poseidon2Hash;
     use crate::prelude::{CircuitBuilder, DefaultParameters, Variable};
 
     type F = GoldilocksField;
@@ -405,4 +507,36 @@ pub(crate) mod tests {
         let result = output.read::<Variable>();
         println!("{}", result);
     }
+
+    #[test]
+    fn test_mapreduce_circuit_with_poseidon2() {
+        env_logger::try_init().unwrap_or_default();
+
+        let mut builder = CircuitBuilder::<L, D>::new();
+        let ctx = builder.constant::<Variable>(F::from_canonical_u64(8));
+        let inputs = (0..8).map(F::from_canonical_u64).collect::<Vec<_>>();
+
+        let output = builder.mapreduce_with_hasher::<
+            Variable,
+            Variable,
+            Variable,
+            DefaultSerializer,
+            Poseidon2Hash,
+            2,
+            _,
+            _,
+        >(
+            ctx,
+            inputs,
+            |_, inputs, builder| builder.add(inputs[0], inputs[1]),
+            |_, left, right, builder| builder.add(left, right),
+        );
+        builder.write(output);
+
+        let circuit = builder.build();
+        let input = circuit.input();
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+        assert_eq!(output.read::<Variable>(), F::from_canonical_u64(28));
+    }
 }
@@ -0,0 +1,452 @@
+//! Fixed-point decimal arithmetic.
+//!
+//! A `FixedPointVariable<DECIMALS>` stores a value `x` as the integer `x * 10^DECIMALS` inside a
+//! `U256Variable`, which is the representation used by Solidity contracts (e.g. WAD math with
+//! `DECIMALS = 18`). Multiplication and division are computed with a full 512-bit intermediate
+//! product. With `Rounding::Down` and `Rounding::Up` they match OpenZeppelin's
+//! `Math.mulDiv(x, y, denominator, rounding)` with `Rounding.Floor` and `Rounding.Ceil`: if the
+//! denominator is zero or the result does not fit in 256 bits, the circuit is unsatisfiable
+//! (the analogue of a revert). `Rounding::HalfEven` has no OpenZeppelin counterpart.
+
+use ethers::types::U256;
+use plonky2::hash::hash_types::RichField;
+
+use super::uint256::U256Variable;
+use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
+use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
+use crate::prelude::{
+    Add, BoolVariable, CircuitBuilder, CircuitVariable, LessThanOrEqual, One, PlonkParameters, Sub,
+    Variable, Zero,
+};
+
+/// The rounding mode used by `mul_div` and fixed-point multiplication/division.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round towards zero (Solidity's default integer division, OpenZeppelin's `Floor`).
+    Down,
+    /// Round away from zero (OpenZeppelin's `Ceil`).
+    Up,
+    /// Round to the nearest value, breaking ties towards the even value (banker's rounding). This
+    /// is not one of OpenZeppelin's rounding modes.
+    HalfEven,
+}
+
+/// A fixed-point decimal with `DECIMALS` decimal digits, stored as a scaled `U256Variable`.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedPointVariable<const DECIMALS: u32> {
+    /// The scaled integer representation `x * 10^DECIMALS`.
+    pub raw: U256Variable,
+}
+
+impl<const DECIMALS: u32> FixedPointVariable<DECIMALS> {
+    /// The scaling factor `10^DECIMALS`.
+    pub fn scale() -> U256 {
+        assert!(DECIMALS <= 77, "10^DECIMALS must fit in a U256");
+        U256::exp10(DECIMALS as usize)
+    }
+
+    /// Interprets a raw scaled value (e.g. a storage slot holding a WAD) as a fixed-point number.
+    pub fn from_raw(raw: U256Variable) -> Self {
+        Self { raw }
+    }
+
+    /// Converts an integer to a fixed-point number, i.e. computes `value * 10^DECIMALS`. The
+    /// circuit is unsatisfiable if the result overflows.
+    pub fn from_u256<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        value: U256Variable,
+    ) -> Self {
+        let one = builder.one::<U256Variable>();
+        let scale = builder.constant::<U256Variable>(Self::scale());
+        let raw = builder.mul_div(value, scale, one, Rounding::Down);
+        Self { raw }
+    }
+
+    /// Converts the fixed-point number to an integer using the given rounding mode.
+    pub fn to_u256<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        rounding: Rounding,
+    ) -> U256Variable {
+        let one = builder.one::<U256Variable>();
+        let scale = builder.constant::<U256Variable>(Self::scale());
+        builder.mul_div(self.raw, one, scale, rounding)
+    }
+
+    /// Converts the number to a fixed-point representation with `NEW_DECIMALS` decimals (e.g.
+    /// from a 6-decimal token amount to a WAD).
+    pub fn rescale<const NEW_DECIMALS: u32, L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        rounding: Rounding,
+    ) -> FixedPointVariable<NEW_DECIMALS> {
+        let numerator =
+            builder.constant::<U256Variable>(FixedPointVariable::<NEW_DECIMALS>::scale());
+        let denominator = builder.constant::<U256Variable>(Self::scale());
+        let raw = builder.mul_div(self.raw, numerator, denominator, rounding);
+        FixedPointVariable { raw }
+    }
+
+    /// Computes `self * rhs`, i.e. `mulDiv(self.raw, rhs.raw, 10^DECIMALS, rounding)`.
+    pub fn mul<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        rhs: Self,
+        builder: &mut CircuitBuilder<L, D>,
+        rounding: Rounding,
+    ) -> Self {
+        let scale = builder.constant::<U256Variable>(Self::scale());
+        let raw = builder.mul_div(self.raw, rhs.raw, scale, rounding);
+        Self { raw }
+    }
+
+    /// Computes `self / rhs`, i.e. `mulDiv(self.raw, 10^DECIMALS, rhs.raw, rounding)`.
+    pub fn div<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        rhs: Self,
+        builder: &mut CircuitBuilder<L, D>,
+        rounding: Rounding,
+    ) -> Self {
+        let scale = builder.constant::<U256Variable>(Self::scale());
+        let raw = builder.mul_div(self.raw, scale, rhs.raw, rounding);
+        Self { raw }
+    }
+
+    /// Returns whether `self < rhs`.
+    pub fn lt<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        rhs: Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> BoolVariable {
+        builder.lt(self.raw, rhs.raw)
+    }
+
+    /// Returns whether `self <= rhs`.
+    pub fn lte<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        rhs: Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> BoolVariable {
+        builder.lte(self.raw, rhs.raw)
+    }
+
+    /// Returns whether `self > rhs`.
+    pub fn gt<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        rhs: Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> BoolVariable {
+        builder.gt(self.raw, rhs.raw)
+    }
+
+    /// Returns whether `self >= rhs`.
+    pub fn gte<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        rhs: Self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> BoolVariable {
+        builder.gte(self.raw, rhs.raw)
+    }
+}
+
+impl<const DECIMALS: u32> CircuitVariable for FixedPointVariable<DECIMALS> {
+    /// The raw scaled value.
+    type ValueType<F: RichField> = U256;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            raw: U256Variable::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        self.raw.variables()
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        Self {
+            raw: U256Variable::from_variables_unsafe(variables),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.raw.assert_is_valid(builder)
+    }
+
+    fn nb_elements() -> usize {
+        U256Variable::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        U256Variable::elements(value)
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        U256Variable::from_elements(elements)
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize, const DECIMALS: u32> Zero<L, D>
+    for FixedPointVariable<DECIMALS>
+{
+    fn zero(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self {
+            raw: builder.zero(),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize, const DECIMALS: u32> One<L, D>
+    for FixedPointVariable<DECIMALS>
+{
+    fn one(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self {
+            raw: builder.constant::<U256Variable>(Self::scale()),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize, const DECIMALS: u32> Add<L, D>
+    for FixedPointVariable<DECIMALS>
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        Self {
+            raw: builder.add(self.raw, rhs.raw),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize, const DECIMALS: u32> Sub<L, D>
+    for FixedPointVariable<DECIMALS>
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        Self {
+            raw: builder.sub(self.raw, rhs.raw),
+        }
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize, const DECIMALS: u32> LessThanOrEqual<L, D>
+    for FixedPointVariable<DECIMALS>
+{
+    #[must_use]
+    fn lte(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> BoolVariable {
+        builder.lte(self.raw, rhs.raw)
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Computes `x * y / denominator` with a full 512-bit intermediate product. With
+    /// `Rounding::Down` and `Rounding::Up` this matches OpenZeppelin's
+    /// `Math.mulDiv(x, y, denominator, rounding)` with `Rounding.Floor` and `Rounding.Ceil`. The
+    /// circuit is unsatisfiable if `denominator == 0` or if the result overflows a U256.
+    pub fn mul_div(
+        &mut self,
+        x: U256Variable,
+        y: U256Variable,
+        denominator: U256Variable,
+        rounding: Rounding,
+    ) -> U256Variable {
+        let to_biguint = |value: U256Variable| BigUintTarget {
+            limbs: value.limbs.iter().map(|x| U32Target::from(*x)).collect(),
+        };
+        let x = to_biguint(x);
+        let y = to_biguint(y);
+        let denominator = to_biguint(denominator);
+
+        // `div_rem_biguint` enforces `rem < denominator`, so a zero denominator is unsatisfiable.
+        let product = self.api.mul_biguint(&x, &y);
+        let (quotient, rem) = self.api.div_rem_biguint(&product, &denominator);
+
+        // The quotient must fit in 256 bits.
+        for limb in quotient.limbs.iter().skip(8) {
+            self.api.assert_zero(limb.target);
+        }
+
+        let round_up = match rounding {
+            Rounding::Down => self.api._false(),
+            Rounding::Up => {
+                let zero = self.api.zero_biguint();
+                let rem_is_zero = self.api.is_equal_biguint(&rem, &zero);
+                self.api.not(rem_is_zero)
+            }
+            Rounding::HalfEven => {
+                // Round up iff `2 * rem > denominator`, or `2 * rem == denominator` and the
+                // quotient is odd.
+                let twice_rem = self.api.add_biguint(&rem, &rem);
+                let denominator_lte_twice_rem = self.api.cmp_biguint(&denominator, &twice_rem);
+                let is_tie = self.api.is_equal_biguint(&twice_rem, &denominator);
+                let not_tie = self.api.not(is_tie);
+                let above_half = self.api.and(denominator_lte_twice_rem, not_tie);
+                let is_odd = self.api.split_le(quotient.limbs[0].target, 32)[0];
+                let tie_and_odd = self.api.and(is_tie, is_odd);
+                self.api.or(above_half, tie_and_odd)
+            }
+        };
+
+        let quotient = BigUintTarget {
+            limbs: quotient.limbs[..8].to_vec(),
+        };
+        let round_up = BigUintTarget {
+            limbs: vec![U32Target::from_target_unsafe(round_up.target)],
+        };
+        let result = self.api.add_biguint(&quotient, &round_up);
+
+        // Rounding up must not overflow either.
+        self.api.assert_zero(result.limbs[8].target);
+
+        let mut limbs = self.zero::<U256Variable>().limbs;
+        for i in 0..8 {
+            limbs[i] = result.limbs[i].into();
+        }
+        U256Variable { limbs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::{U256, U512};
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::prelude::*;
+
+    /// A native implementation of OpenZeppelin's `Math.mulDiv`, extended with `HalfEven`.
+    fn mul_div_native(x: U256, y: U256, denominator: U256, rounding: Rounding) -> U256 {
+        let product = x.full_mul(y);
+        let denominator = U512::from(denominator);
+        let quotient = U256::try_from(product / denominator).unwrap();
+        let rem = product % denominator;
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => !rem.is_zero(),
+            Rounding::HalfEven => {
+                let twice_rem = rem * 2;
+                twice_rem > denominator || (twice_rem == denominator && quotient.bit(0))
+            }
+        };
+        if round_up {
+            quotient + 1
+        } else {
+            quotient
+        }
+    }
+
+    fn random_u256(limbs: usize) -> U256 {
+        let mut rng = OsRng;
+        let mut value = [0u64; 4];
+        for limb in value.iter_mut().take(limbs) {
+            *limb = rng.gen();
+        }
+        U256(value)
+    }
+
+    #[test]
+    fn test_mul_div() {
+        let mut builder = DefaultBuilder::new();
+        let x = builder.read::<U256Variable>();
+        let y = builder.read::<U256Variable>();
+        let denominator = builder.read::<U256Variable>();
+        let down = builder.mul_div(x, y, denominator, Rounding::Down);
+        let up = builder.mul_div(x, y, denominator, Rounding::Up);
+        let half_even = builder.mul_div(x, y, denominator, Rounding::HalfEven);
+        builder.write(down);
+        builder.write(up);
+        builder.write(half_even);
+        let circuit = builder.build();
+
+        let test_cases = [
+            (random_u256(4), random_u256(3), random_u256(4)),
+            (random_u256(2), random_u256(2), random_u256(1)),
+            (U256::from(5), U256::from(1), U256::from(2)),
+            (U256::from(7), U256::from(1), U256::from(2)),
+            (U256::MAX, U256::MAX, U256::MAX),
+        ];
+
+        for (x, y, denominator) in test_cases {
+            let mut input = circuit.input();
+            input.write::<U256Variable>(x);
+            input.write::<U256Variable>(y);
+            input.write::<U256Variable>(denominator);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+
+            for rounding in [Rounding::Down, Rounding::Up, Rounding::HalfEven] {
+                assert_eq!(
+                    output.read::<U256Variable>(),
+                    mul_div_native(x, y, denominator, rounding)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_point_wad() {
+        type Wad = FixedPointVariable<18>;
+
+        let mut builder = DefaultBuilder::new();
+        let a = builder.read::<Wad>();
+        let b = builder.read::<Wad>();
+        let product = a.mul(b, &mut builder, Rounding::Down);
+        let quotient = a.div(b, &mut builder, Rounding::Up);
+        let sum = builder.add(a, b);
+        let lte = builder.lte(a, b);
+        let comparisons = [
+            a.lt(b, &mut builder),
+            a.gt(b, &mut builder),
+            a.gte(b, &mut builder),
+            b.gte(b, &mut builder),
+            b.lt(b, &mut builder),
+        ];
+        let integer = a.to_u256(&mut builder, Rounding::HalfEven);
+        let usdc = a.rescale::<6, DefaultParameters, 2>(&mut builder, Rounding::Down);
+        builder.write(product);
+        builder.write(quotient);
+        builder.write(sum);
+        builder.write(lte);
+        for comparison in comparisons {
+            builder.write(comparison);
+        }
+        builder.write(integer);
+        builder.write(usdc);
+        let circuit = builder.build();
+
+        let wad = U256::exp10(18);
+        let a = U256::from(3) * wad / 2;
+        let b = U256::from(7) * wad / 4;
+
+        let mut input = circuit.input();
+        input.write::<Wad>(a);
+        input.write::<Wad>(b);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(
+            output.read::<Wad>(),
+            mul_div_native(a, b, wad, Rounding::Down)
+        );
+        assert_eq!(
+            output.read::<Wad>(),
+            mul_div_native(a, wad, b, Rounding::Up)
+        );
+        assert_eq!(output.read::<Wad>(), a + b);
+        assert!(output.read::<BoolVariable>());
+        for expected in [true, false, false, true, false] {
+            assert_eq!(output.read::<BoolVariable>(), expected);
+        }
+        assert_eq!(output.read::<U256Variable>(), U256::from(2));
+        assert_eq!(
+            output.read::<FixedPointVariable<6>>(),
+            U256::from(1_500_000)
+        );
+    }
+}
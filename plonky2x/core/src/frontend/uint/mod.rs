@@ -1,5 +1,6 @@
 use core::fmt::Debug;
 
+pub mod fixed_point;
 pub mod uint128;
 pub mod uint256;
 pub mod uint32;
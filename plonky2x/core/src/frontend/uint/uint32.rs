@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use itertools::Itertools;
 use plonky2::iop::target::BoolTarget;
 
+use crate::frontend::builder::bits::BitView;
 use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
 use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target};
 use crate::frontend::uint::num::u32::gadgets::multiple_comparison::list_lte_circuit;
@@ -221,6 +222,84 @@ impl<L: PlonkParameters<D>, const D: usize> Sub<L, D> for U32Variable {
     }
 }
 
+impl<L: PlonkParameters<D>, const D: usize> BitAnd<L, D> for U32Variable {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        let mut lhs = BitView::from_limbs(&[self]);
+        let mut rhs = BitView::from_limbs(&[rhs]);
+        lhs.and(&mut rhs, builder).limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> BitOr<L, D> for U32Variable {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        let mut lhs = BitView::from_limbs(&[self]);
+        let mut rhs = BitView::from_limbs(&[rhs]);
+        lhs.or(&mut rhs, builder).limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> BitXor<L, D> for U32Variable {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        let mut lhs = BitView::from_limbs(&[self]);
+        let mut rhs = BitView::from_limbs(&[rhs]);
+        lhs.xor(&mut rhs, builder).limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> Not<L, D> for U32Variable {
+    type Output = Self;
+
+    fn not(self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        BitView::from_limbs(&[self]).not(builder).limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> Shl<L, D, usize> for U32Variable {
+    type Output = Self;
+
+    fn shl(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        BitView::from_limbs(&[self])
+            .shl(rhs, builder)
+            .limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> Shr<L, D, usize> for U32Variable {
+    type Output = Self;
+
+    fn shr(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        BitView::from_limbs(&[self])
+            .shr(rhs, builder)
+            .limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> RotateLeft<L, D, usize> for U32Variable {
+    type Output = Self;
+
+    fn rotate_left(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        BitView::from_limbs(&[self])
+            .rotate_left(rhs, builder)
+            .limbs(builder)[0]
+    }
+}
+
+impl<L: PlonkParameters<D>, const D: usize> RotateRight<L, D, usize> for U32Variable {
+    type Output = Self;
+
+    fn rotate_right(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+        BitView::from_limbs(&[self])
+            .rotate_right(rhs, builder)
+            .limbs(builder)[0]
+    }
+}
+
 impl U32Variable {
     pub fn to_u64<L: PlonkParameters<D>, const D: usize>(
         &self,
@@ -356,6 +435,47 @@ mod tests {
         circuit.data.verify(proof).unwrap();
     }
 
+    #[test]
+    fn test_u32_bitwise() {
+        let mut builder = CircuitBuilder::<L, D>::new();
+
+        let mut rng = rand::thread_rng();
+        let operand_a: u32 = rng.gen();
+        let operand_b: u32 = rng.gen();
+
+        let a = U32Variable::constant(&mut builder, operand_a);
+        let b = U32Variable::constant(&mut builder, operand_b);
+
+        let mut results = vec![
+            (builder.and(a, b), operand_a & operand_b),
+            (builder.or(a, b), operand_a | operand_b),
+            (builder.xor(a, b), operand_a ^ operand_b),
+            (builder.not(a), !operand_a),
+        ];
+        for shift in [0usize, 1, 7, 16, 31] {
+            results.push((builder.shl(a, shift), operand_a << shift));
+            results.push((builder.shr(a, shift), operand_a >> shift));
+            results.push((
+                builder.rotate_left(a, shift),
+                operand_a.rotate_left(shift as u32),
+            ));
+            results.push((
+                builder.rotate_right(a, shift),
+                operand_a.rotate_right(shift as u32),
+            ));
+        }
+        for (result, expected_result) in results {
+            let expected_result_var = U32Variable::constant(&mut builder, expected_result);
+            builder.assert_is_equal(result.variable, expected_result_var.variable);
+        }
+
+        let circuit = builder.build();
+        let pw = PartialWitness::new();
+
+        let proof = circuit.data.prove(pw).unwrap();
+        circuit.data.verify(proof).unwrap();
+    }
+
     #[test]
     fn test_u32_sub() {
         let mut builder = CircuitBuilder::<L, D>::new();
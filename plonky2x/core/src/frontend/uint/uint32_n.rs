@@ -318,6 +318,87 @@ macro_rules! make_uint32_n {
                 builder.or(lte_acc, equal_so_far)
             }
         }
+        impl<L: PlonkParameters<D>, const D: usize> BitAnd<L, D> for $a {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let mut lhs = BitView::from_limbs(&self.limbs);
+                let mut rhs = BitView::from_limbs(&rhs.limbs);
+                let limbs = lhs.and(&mut rhs, builder).limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> BitOr<L, D> for $a {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let mut lhs = BitView::from_limbs(&self.limbs);
+                let mut rhs = BitView::from_limbs(&rhs.limbs);
+                let limbs = lhs.or(&mut rhs, builder).limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> BitXor<L, D> for $a {
+            type Output = Self;
+
+            fn bitxor(self, rhs: Self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let mut lhs = BitView::from_limbs(&self.limbs);
+                let mut rhs = BitView::from_limbs(&rhs.limbs);
+                let limbs = lhs.xor(&mut rhs, builder).limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> Not<L, D> for $a {
+            type Output = Self;
+
+            fn not(self, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let limbs = BitView::from_limbs(&self.limbs).not(builder).limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> Shl<L, D, usize> for $a {
+            type Output = Self;
+
+            fn shl(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let limbs = BitView::from_limbs(&self.limbs).shl(rhs, builder).limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> Shr<L, D, usize> for $a {
+            type Output = Self;
+
+            fn shr(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let limbs = BitView::from_limbs(&self.limbs).shr(rhs, builder).limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> RotateLeft<L, D, usize> for $a {
+            type Output = Self;
+
+            fn rotate_left(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let limbs = BitView::from_limbs(&self.limbs)
+                    .rotate_left(rhs, builder)
+                    .limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
+
+        impl<L: PlonkParameters<D>, const D: usize> RotateRight<L, D, usize> for $a {
+            type Output = Self;
+
+            fn rotate_right(self, rhs: usize, builder: &mut CircuitBuilder<L, D>) -> Self::Output {
+                let limbs = BitView::from_limbs(&self.limbs)
+                    .rotate_right(rhs, builder)
+                    .limbs(builder);
+                Self { limbs: limbs.try_into().unwrap() }
+            }
+        }
     };
 }
 
@@ -428,6 +509,48 @@ macro_rules! make_uint32_n_tests {
                 circuit.data.verify(proof).unwrap();
             }
 
+            #[test]
+            fn test_u32n_bitwise() {
+                let mut rng = OsRng;
+
+                let a = <$b as Uint<$c>>::from_u32_limbs([(); $c].map(|_| rng.gen()));
+                let b = <$b as Uint<$c>>::from_u32_limbs([(); $c].map(|_| rng.gen()));
+                let num_bits = $c * 32;
+
+                let mut builder = CircuitBuilder::<L, D>::new();
+
+                let a_var = $a::constant(&mut builder, a);
+                let b_var = $a::constant(&mut builder, b);
+
+                let mut results = vec![
+                    (builder.and(a_var, b_var), a & b),
+                    (builder.or(a_var, b_var), a | b),
+                    (builder.xor(a_var, b_var), a ^ b),
+                    (builder.not(a_var), !a),
+                ];
+                for shift in [1usize, 7, 32, 45, num_bits - 1] {
+                    let (shl, shr) = (a << shift, a >> shift);
+                    let (rotl, rotr) = (
+                        shl | (a >> (num_bits - shift)),
+                        shr | (a << (num_bits - shift)),
+                    );
+                    results.push((builder.shl(a_var, shift), shl));
+                    results.push((builder.shr(a_var, shift), shr));
+                    results.push((builder.rotate_left(a_var, shift), rotl));
+                    results.push((builder.rotate_right(a_var, shift), rotr));
+                }
+                for (result, expected_value) in results {
+                    let expected_result_var = $a::constant(&mut builder, expected_value);
+                    builder.assert_is_equal(result, expected_result_var);
+                }
+
+                let circuit = builder.build();
+                let pw = PartialWitness::new();
+
+                let proof = circuit.data.prove(pw).unwrap();
+                circuit.data.verify(proof).unwrap();
+            }
+
             #[test]
             fn test_u256_mul() {
                 const D: usize = 2;
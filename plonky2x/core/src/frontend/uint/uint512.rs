@@ -3,12 +3,14 @@ use ethers::types::U512;
 use plonky2::hash::hash_types::RichField;
 
 use super::Uint;
+use crate::frontend::builder::bits::BitView;
 use crate::frontend::uint::num::biguint::{BigUintTarget, CircuitBuilderBiguint};
 use crate::frontend::uint::num::u32::gadgets::arithmetic_u32::U32Target;
 use crate::frontend::vars::{EvmVariable, SSZVariable, U32Variable};
 use crate::prelude::{
-    Add, BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitBuilder,
-    CircuitVariable, Div, LessThanOrEqual, Mul, One, PlonkParameters, Rem, Sub, Variable, Zero,
+    Add, BitAnd, BitOr, BitXor, BoolVariable, ByteVariable, Bytes32Variable, BytesVariable,
+    CircuitBuilder, CircuitVariable, Div, LessThanOrEqual, Mul, Not, One, PlonkParameters, Rem,
+    RotateLeft, RotateRight, Shl, Shr, Sub, Variable, Zero,
 };
 use crate::{make_uint32_n, make_uint32_n_tests};
 
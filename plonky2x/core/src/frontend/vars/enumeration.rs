@@ -0,0 +1,125 @@
+use itertools::Itertools;
+
+use super::{CircuitVariable, Variable};
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+
+/// A tagged union of circuit variables, encoded as a selector and a payload padded to the size of
+/// the largest variant.
+///
+/// This trait is implemented by `#[derive(CircuitVariable)]` on enums: for an enum `Msg` whose
+/// variants are unit variants or hold a single circuit variable, the derive generates a
+/// `MsgVariable` with `Variant = Msg` and a `MsgValue<F>` value type.
+pub trait EnumVariable: CircuitVariable {
+    /// The in-circuit view of a single variant.
+    type Variant;
+
+    /// The number of variants.
+    fn nb_variants() -> usize;
+
+    /// The index of the active variant.
+    fn selector(&self) -> Variable;
+
+    /// Interprets the payload as each of the variants, in declaration order. Only the variant at
+    /// index `selector` is meaningful; the others are reinterpretations of its variables.
+    fn variants(&self) -> Vec<Self::Variant>;
+
+    /// Creates a variable holding the given variant.
+    fn from_variant<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        variant: Self::Variant,
+    ) -> Self;
+}
+
+impl<L: PlonkParameters<D>, const D: usize> CircuitBuilder<L, D> {
+    /// Evaluates `f` on every variant of `value` and returns the result for the active variant.
+    ///
+    /// The closure is called once per variant at circuit-building time, so it is typically a
+    /// `match` over `E::Variant` with one arm per variant. The constraints of every arm are added
+    /// to the circuit, which is what makes the selection possible in-circuit.
+    pub fn match_enum<E: EnumVariable, R: CircuitVariable>(
+        &mut self,
+        value: &E,
+        mut f: impl FnMut(&mut Self, E::Variant) -> R,
+    ) -> R {
+        let results = value
+            .variants()
+            .into_iter()
+            .map(|variant| f(self, variant))
+            .collect_vec();
+        self.select_array(&results, value.selector())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers::types::H256;
+
+    use crate::prelude::*;
+
+    #[derive(CircuitVariable)]
+    pub enum Msg {
+        Transfer((U64Variable, Bytes32Variable)),
+        Burn(U64Variable),
+        Ping,
+    }
+
+    #[derive(CircuitVariable)]
+    pub enum Flag {
+        On,
+        Off,
+    }
+
+    fn amount<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        msg: &MsgVariable,
+    ) -> U64Variable {
+        builder.match_enum(msg, |builder, variant| match variant {
+            Msg::Transfer((amount, _)) => amount,
+            Msg::Burn(amount) => {
+                let zero = builder.zero::<U64Variable>();
+                builder.sub(zero, amount)
+            }
+            Msg::Ping => builder.zero(),
+        })
+    }
+
+    #[test]
+    fn test_enum_variable() {
+        let mut builder = DefaultBuilder::new();
+
+        for _ in 0..3 {
+            let msg = builder.read::<MsgVariable>();
+            let amount = amount(&mut builder, &msg);
+            builder.write(amount);
+            let is_burn = msg.is_burn(&mut builder);
+            builder.write(is_burn);
+        }
+
+        let flag = builder.read::<FlagVariable>();
+        let is_off = flag.is_off(&mut builder);
+        builder.write(is_off);
+
+        let value = builder.constant::<U64Variable>(7);
+        let burn = MsgVariable::from_variant(&mut builder, Msg::Burn(value));
+        builder.write(burn);
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<MsgVariable>(MsgValue::Transfer((10, H256::from_low_u64_be(1))));
+        input.write::<MsgVariable>(MsgValue::Burn(3));
+        input.write::<MsgVariable>(MsgValue::Ping);
+        input.write::<FlagVariable>(FlagValue::Off);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<U64Variable>(), 10);
+        assert!(!output.read::<BoolVariable>());
+        assert_eq!(output.read::<U64Variable>(), 3u64.wrapping_neg());
+        assert!(output.read::<BoolVariable>());
+        assert_eq!(output.read::<U64Variable>(), 0);
+        assert!(!output.read::<BoolVariable>());
+        assert!(output.read::<BoolVariable>());
+        assert!(matches!(output.read::<MsgVariable>(), MsgValue::Burn(7)));
+    }
+}
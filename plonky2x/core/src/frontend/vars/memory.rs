@@ -0,0 +1,416 @@
+use core::any::Any;
+use core::marker::PhantomData;
+use std::collections::HashMap;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::generator::{GeneratedValues, WitnessGenerator, WitnessGeneratorRef};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartitionWitness, Witness};
+use plonky2::plonk::circuit_data::CommonCircuitData;
+use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
+
+use super::{BoolVariable, CircuitVariable, U32Variable, Variable};
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+use crate::frontend::uint::uint64::U64Variable;
+
+/// A read-write memory of `SIZE` cells holding values of type `V`, addressed by variables.
+///
+/// Every cell starts out as the all-zero value of `V`. Reads and writes are recorded in a log
+/// held by the builder, and checked for consistency with an offline memory-checking argument when
+/// the circuit is built: the log is sorted by `(address, timestamp)` using `CircuitBuilder::sort`,
+/// and every read must return the value of the previous access to the same address (or zero if
+/// there is none). This costs amortized O(1) constraints per operation, compared to O(SIZE) for
+/// `select_array`.
+///
+/// Note that this is not a `CircuitVariable`, but a handle to the log of a memory in the builder
+/// that created it.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryVariable<V: CircuitVariable, const SIZE: usize> {
+    /// The index of the log of the memory in the builder.
+    id: usize,
+    _marker: PhantomData<V>,
+}
+
+impl<V: CircuitVariable, const SIZE: usize> MemoryVariable<V, SIZE> {
+    /// Creates a new zero-initialized memory.
+    pub fn new<L: PlonkParameters<D>, const D: usize>(builder: &mut CircuitBuilder<L, D>) -> Self {
+        assert!(SIZE <= u32::MAX as usize, "memory size must fit in a u32");
+        let id = builder.memories.len();
+        builder.memories.push(Box::new(MemoryLog::<V, SIZE> {
+            ops: Vec::new(),
+            is_write: Vec::new(),
+        }));
+        Self {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a new memory initialized with the given values.
+    pub fn from_values<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        values: &[V],
+    ) -> Self {
+        assert!(values.len() <= SIZE);
+        let memory = Self::new(builder);
+        for (i, value) in values.iter().enumerate() {
+            let address = builder.constant::<Variable>(L::Field::from_canonical_usize(i));
+            memory.write(builder, address, value.clone());
+        }
+        memory
+    }
+
+    /// The number of operations issued so far.
+    pub fn len<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &CircuitBuilder<L, D>,
+    ) -> usize {
+        builder.memories[self.id]
+            .as_any()
+            .downcast_ref::<MemoryLog<V, SIZE>>()
+            .expect("memory belongs to another builder")
+            .ops
+            .len()
+    }
+
+    /// Whether no operations have been issued so far.
+    pub fn is_empty<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &CircuitBuilder<L, D>,
+    ) -> bool {
+        self.len(builder) == 0
+    }
+
+    /// Private helper method to append an operation to the log of the memory.
+    fn push<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        address: Variable,
+        is_write: bool,
+        value: V,
+    ) {
+        // The sorting key is `timestamp + 2^32 * address`, and the address must be in range.
+        let address = U32Variable::from_variables(builder, &[address]);
+        let size = builder.constant::<U32Variable>(SIZE as u32);
+        let in_range = builder.lt(address, size);
+        let t = builder._true();
+        builder.assert_is_equal(in_range, t);
+
+        let timestamp = builder.constant::<U32Variable>(self.len(builder) as u32);
+        let key = U64Variable {
+            limbs: [timestamp, address],
+        };
+        let is_write_variable = if is_write {
+            builder._true()
+        } else {
+            builder._false()
+        };
+
+        let log = builder.memories[self.id]
+            .as_any_mut()
+            .downcast_mut::<MemoryLog<V, SIZE>>()
+            .expect("memory belongs to another builder");
+        log.ops.push((key, is_write_variable, value));
+        log.is_write.push(is_write);
+    }
+
+    /// Reads the value stored at `address`.
+    pub fn read<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        address: Variable,
+    ) -> V {
+        // The value is computed by the `MemoryGenerator` of the memory and constrained when the
+        // circuit is built.
+        let value = builder.init::<V>();
+        self.push(builder, address, false, value.clone());
+        value
+    }
+
+    /// Writes `value` at `address`.
+    pub fn write<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+        address: Variable,
+        value: V,
+    ) {
+        self.push(builder, address, true, value);
+    }
+}
+
+/// The log of the operations of a memory, constrained when the circuit is built.
+pub(crate) trait MemoryConstraint<L: PlonkParameters<D>, const D: usize>:
+    Send + Sync
+{
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Constrains all reads to be consistent with the writes that preceded them, and adds the
+    /// generator computing the values of the reads.
+    fn constrain(&self, builder: &mut CircuitBuilder<L, D>);
+}
+
+/// The log of all operations of a `MemoryVariable` as `((timestamp, address), is_write, value)`.
+#[derive(Debug, Clone)]
+struct MemoryLog<V: CircuitVariable, const SIZE: usize> {
+    ops: Vec<(U64Variable, BoolVariable, V)>,
+    /// Whether each operation is a write, known at build time.
+    is_write: Vec<bool>,
+}
+
+impl<L: PlonkParameters<D>, const D: usize, V: CircuitVariable, const SIZE: usize>
+    MemoryConstraint<L, D> for MemoryLog<V, SIZE>
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn constrain(&self, builder: &mut CircuitBuilder<L, D>) {
+        if self.ops.is_empty() {
+            return;
+        }
+
+        let generator = MemoryGenerator::<L::Field, D> {
+            value_size: V::nb_elements(),
+            ops: self
+                .ops
+                .iter()
+                .zip(self.is_write.iter())
+                .map(|((key, _, value), is_write)| MemoryOp {
+                    is_write: *is_write,
+                    address: key.limbs[1].variable.0,
+                    value: value.targets(),
+                })
+                .collect(),
+            _marker: PhantomData,
+        };
+        builder
+            .api
+            .add_generators(vec![WitnessGeneratorRef::new(generator)]);
+
+        let sorted = builder.sort(self.ops.as_slice(), |_, op| op.0);
+
+        let zero = builder.zero::<Variable>();
+        let default = V::from_variables_unsafe(&vec![zero; V::nb_elements()]);
+
+        for i in 0..sorted.len() {
+            let (key, is_write, value) = sorted[i].clone();
+
+            // The expected value of a read is the value of the previous access to the same
+            // address, or the default value if this is the first access.
+            let expected = if i == 0 {
+                default.clone()
+            } else {
+                let (prev_key, _, prev_value) = sorted[i - 1].clone();
+                let same_address = builder.is_equal(key.limbs[1], prev_key.limbs[1]);
+                builder.select(same_address, prev_value, default.clone())
+            };
+
+            let actual = builder.select(is_write, expected.clone(), value);
+            builder.assert_is_equal(actual, expected);
+        }
+    }
+}
+
+/// An operation of a memory, as seen by its `MemoryGenerator`.
+#[derive(Debug, Clone)]
+struct MemoryOp {
+    is_write: bool,
+    address: Target,
+    value: Vec<Target>,
+}
+
+#[derive(Debug)]
+pub struct MemoryGenerator<F: RichField + Extendable<D>, const D: usize> {
+    value_size: usize,
+    ops: Vec<MemoryOp>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> WitnessGenerator<F, D>
+    for MemoryGenerator<F, D>
+{
+    fn id(&self) -> String {
+        "MemoryGenerator".to_string()
+    }
+
+    fn watch_list(&self) -> Vec<Target> {
+        let mut targets = Vec::new();
+        for op in self.ops.iter() {
+            targets.push(op.address);
+            if op.is_write {
+                targets.extend(op.value.iter());
+            }
+        }
+        targets
+    }
+
+    fn run(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) -> bool {
+        // Replay the log in program order, stopping at the first operation whose inputs are not
+        // known yet. A read only depends on the writes before it, so its value is emitted as soon
+        // as they are known, and writes of values computed from earlier reads can run later, when
+        // the generator is woken up by their values.
+        let mut cells = HashMap::<u64, Vec<F>>::new();
+        for op in self.ops.iter() {
+            let address = match witness.try_get_target(op.address) {
+                Some(address) => address.to_canonical_u64(),
+                None => return false,
+            };
+            if op.is_write {
+                if !witness.contains_all(&op.value) {
+                    return false;
+                }
+                cells.insert(address, witness.get_targets(&op.value));
+            } else if !witness.contains_all(&op.value) {
+                let value = cells
+                    .get(&address)
+                    .cloned()
+                    .unwrap_or_else(|| vec![F::ZERO; self.value_size]);
+                for (target, value) in op.value.iter().zip(value) {
+                    out_buffer.set_target(*target, value);
+                }
+            }
+        }
+        true
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.value_size)?;
+        dst.write_usize(self.ops.len())?;
+        for op in self.ops.iter() {
+            dst.write_bool(op.is_write)?;
+            dst.write_target(op.address)?;
+            dst.write_target_vec(&op.value)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let value_size = src.read_usize()?;
+        let nb_ops = src.read_usize()?;
+        let mut ops = Vec::with_capacity(nb_ops);
+        for _ in 0..nb_ops {
+            ops.push(MemoryOp {
+                is_write: src.read_bool()?,
+                address: src.read_target()?,
+                value: src.read_target_vec()?,
+            });
+        }
+        Ok(Self {
+            value_size,
+            ops,
+            _marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::circuit::{CircuitBuild, GateRegistry, HintRegistry};
+    use crate::prelude::*;
+    use crate::utils;
+
+    type L = DefaultParameters;
+    const D: usize = 2;
+
+    #[test]
+    fn test_memory() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let x = builder.read::<U64Variable>();
+
+        let initial = builder.constant_vec::<U64Variable>(&[10, 20, 30, 40]);
+        let memory = MemoryVariable::<U64Variable, 16>::from_values(&mut builder, &initial);
+
+        // mem[b] = mem[a] + x; mem[a] = x; output mem[a], mem[b], mem[15].
+        let value = memory.read(&mut builder, a);
+        let sum = builder.add(value, x);
+        memory.write(&mut builder, b, sum);
+        memory.write(&mut builder, a, x);
+        let fifteen = builder.constant::<Variable>(GoldilocksField::from_canonical_u64(15));
+        let out_a = memory.read(&mut builder, a);
+        let out_b = memory.read(&mut builder, b);
+        let out_untouched = memory.read(&mut builder, fifteen);
+        assert_eq!(memory.len(&builder), 9);
+
+        builder.write(out_a);
+        builder.write(out_b);
+        builder.write(out_untouched);
+
+        let circuit = builder.build();
+
+        // The memory is checked without an explicit call, and the circuit can be proven several
+        // times, including after serialization.
+        let gate_serializer = GateRegistry::<L, D>::new();
+        let hint_serializer = HintRegistry::<L, D>::new();
+        let bytes = circuit
+            .serialize(&gate_serializer, &hint_serializer)
+            .unwrap();
+        let deserialized =
+            CircuitBuild::<L, D>::deserialize(&bytes, &gate_serializer, &hint_serializer).unwrap();
+
+        for (circuit, (a, b, x)) in [(&circuit, (2, 7, 5)), (&deserialized, (3, 3, 1))] {
+            let mut input = circuit.input();
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(a));
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(b));
+            input.write::<U64Variable>(x);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+
+            let initial = [10, 20, 30, 40, 0, 0, 0, 0, 0];
+            let expected_b = if a == b { x } else { initial[a as usize] + x };
+            assert_eq!(output.read::<U64Variable>(), x);
+            assert_eq!(output.read::<U64Variable>(), expected_b);
+            assert_eq!(output.read::<U64Variable>(), 0);
+        }
+    }
+
+    #[test]
+    fn test_memory_read_modify_write() {
+        utils::setup_logger();
+        let mut builder = DefaultBuilder::new();
+
+        let a = builder.read::<Variable>();
+        let b = builder.read::<Variable>();
+        let x = builder.read::<U64Variable>();
+
+        let memory = MemoryVariable::<U64Variable, 4>::new(&mut builder);
+
+        // Every write depends on a read of the same memory: mem[a] += x three times, then
+        // mem[b] = 2 * mem[a] and output mem[b].
+        for _ in 0..3 {
+            let value = memory.read(&mut builder, a);
+            let sum = builder.add(value, x);
+            memory.write(&mut builder, a, sum);
+        }
+        let value = memory.read(&mut builder, a);
+        let double = builder.add(value, value);
+        memory.write(&mut builder, b, double);
+        let out = memory.read(&mut builder, b);
+        builder.write(out);
+
+        let circuit = builder.build();
+
+        for (a, b, x) in [(1, 2, 5), (3, 3, 7)] {
+            let mut input = circuit.input();
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(a));
+            input.write::<Variable>(GoldilocksField::from_canonical_u64(b));
+            input.write::<U64Variable>(x);
+            let (proof, mut output) = circuit.prove(&input);
+            circuit.verify(&proof, &input, &output);
+            assert_eq!(output.read::<U64Variable>(), 6 * x);
+        }
+    }
+}
@@ -4,6 +4,9 @@ mod byte;
 mod bytes;
 mod bytes32;
 mod collections;
+mod enumeration;
+mod memory;
+mod option;
 
 mod stream;
 mod variable;
@@ -14,7 +17,10 @@ pub use boolean::*;
 pub use byte::*;
 pub use bytes::*;
 pub use bytes32::*;
+pub use enumeration::*;
 use itertools::Itertools;
+pub use memory::*;
+pub use option::*;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::target::Target;
 use plonky2::iop::witness::{Witness, WitnessWrite};
@@ -0,0 +1,195 @@
+use plonky2::hash::hash_types::RichField;
+
+use super::{BoolVariable, CircuitVariable, Variable};
+use crate::backend::circuit::PlonkParameters;
+use crate::frontend::builder::CircuitBuilder;
+
+/// An optional circuit variable.
+///
+/// A `None` is encoded with `is_some = false` and an all-zero value, so that every `OptionVariable`
+/// has a unique encoding and the value is always valid.
+#[derive(Debug, Clone, Copy)]
+pub struct OptionVariable<V: CircuitVariable> {
+    pub is_some: BoolVariable,
+    pub value: V,
+}
+
+impl<V: CircuitVariable> OptionVariable<V> {
+    /// Creates an option holding `value`.
+    pub fn some<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        value: V,
+    ) -> Self {
+        Self {
+            is_some: builder._true(),
+            value,
+        }
+    }
+
+    /// Creates an empty option.
+    pub fn none<L: PlonkParameters<D>, const D: usize>(builder: &mut CircuitBuilder<L, D>) -> Self {
+        Self {
+            is_some: builder._false(),
+            value: zero_value(builder),
+        }
+    }
+
+    /// Creates an option holding `value` if `is_some` is true, and an empty option otherwise.
+    pub fn new<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+        is_some: BoolVariable,
+        value: V,
+    ) -> Self {
+        let zero = zero_value(builder);
+        Self {
+            is_some,
+            value: builder.select(is_some, value, zero),
+        }
+    }
+
+    /// Whether the option is empty.
+    pub fn is_none<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> BoolVariable {
+        builder.not(self.is_some)
+    }
+
+    /// Returns the value, asserting that the option is not empty.
+    pub fn unwrap<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> V {
+        let t = builder._true();
+        builder.assert_is_equal(self.is_some, t);
+        self.value
+    }
+
+    /// Returns the value, or `default` if the option is empty.
+    pub fn unwrap_or<L: PlonkParameters<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+        default: V,
+    ) -> V {
+        builder.select(self.is_some, self.value, default)
+    }
+
+    /// Applies `f` to the value of the option.
+    ///
+    /// Since the circuit cannot branch, `f` is always applied, including to the zero value of an
+    /// empty option, and its result is discarded if the option is empty.
+    pub fn map<L: PlonkParameters<D>, const D: usize, U: CircuitVariable>(
+        self,
+        builder: &mut CircuitBuilder<L, D>,
+        f: impl FnOnce(&mut CircuitBuilder<L, D>, V) -> U,
+    ) -> OptionVariable<U> {
+        let mapped = f(builder, self.value);
+        let zero = zero_value(builder);
+        OptionVariable {
+            is_some: self.is_some,
+            value: builder.select(self.is_some, mapped, zero),
+        }
+    }
+}
+
+/// The all-zero value of `V`.
+fn zero_value<V: CircuitVariable, L: PlonkParameters<D>, const D: usize>(
+    builder: &mut CircuitBuilder<L, D>,
+) -> V {
+    let zero = builder.zero::<Variable>();
+    V::from_variables_unsafe(&vec![zero; V::nb_elements()])
+}
+
+impl<V: CircuitVariable> CircuitVariable for OptionVariable<V> {
+    type ValueType<F: RichField> = Option<V::ValueType<F>>;
+
+    fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+        builder: &mut CircuitBuilder<L, D>,
+    ) -> Self {
+        Self {
+            is_some: BoolVariable::init_unsafe(builder),
+            value: V::init_unsafe(builder),
+        }
+    }
+
+    fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.is_some.variables();
+        variables.extend(self.value.variables());
+        variables
+    }
+
+    fn from_variables_unsafe(variables: &[Variable]) -> Self {
+        assert_eq!(variables.len(), Self::nb_elements());
+        Self {
+            is_some: BoolVariable::from_variables_unsafe(&variables[..1]),
+            value: V::from_variables_unsafe(&variables[1..]),
+        }
+    }
+
+    fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<L, D>,
+    ) {
+        self.is_some.assert_is_valid(builder);
+        // An empty option must hold the zero value, which makes the value valid in both cases.
+        let zero = zero_value::<V, L, D>(builder);
+        let value = builder.select(self.is_some, self.value.clone(), zero);
+        builder.assert_is_equal(value, self.value.clone());
+        self.value.assert_is_valid(builder);
+    }
+
+    fn nb_elements() -> usize {
+        1 + V::nb_elements()
+    }
+
+    fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+        match value {
+            Some(value) => {
+                let mut elements = vec![F::ONE];
+                elements.extend(V::elements(value));
+                elements
+            }
+            None => vec![F::ZERO; Self::nb_elements()],
+        }
+    }
+
+    fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+        assert_eq!(elements.len(), Self::nb_elements());
+        if BoolVariable::from_elements(&elements[..1]) {
+            Some(V::from_elements(&elements[1..]))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_option_variable() {
+        let mut builder = DefaultBuilder::new();
+
+        let default = builder.constant::<U64Variable>(42);
+        for _ in 0..2 {
+            let x = builder.read::<OptionVariable<U64Variable>>();
+            let doubled = x.map(&mut builder, |builder, v| builder.add(v, v));
+            builder.write(doubled);
+            let value = doubled.unwrap_or(&mut builder, default);
+            builder.write(value);
+        }
+
+        let circuit = builder.build();
+        let mut input = circuit.input();
+        input.write::<OptionVariable<U64Variable>>(Some(5));
+        input.write::<OptionVariable<U64Variable>>(None);
+        let (proof, mut output) = circuit.prove(&input);
+        circuit.verify(&proof, &input, &output);
+
+        assert_eq!(output.read::<OptionVariable<U64Variable>>(), Some(10));
+        assert_eq!(output.read::<U64Variable>(), 10);
+        assert_eq!(output.read::<OptionVariable<U64Variable>>(), None);
+        assert_eq!(output.read::<U64Variable>(), 42);
+    }
+}
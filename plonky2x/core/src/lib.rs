@@ -33,7 +33,8 @@ pub mod prelude {
     pub use crate::frontend::uint::uint64::U64Variable;
     pub use crate::frontend::vars::{
         ArrayVariable, BoolVariable, ByteVariable, Bytes32Variable, BytesVariable, CircuitVariable,
-        OutputVariableStream, U32Variable, ValueStream, Variable, VariableStream,
+        EnumVariable, OptionVariable, OutputVariableStream, U32Variable, ValueStream, Variable,
+        VariableStream,
     };
     pub use crate::utils::{address, bytes, bytes32, hex};
 }
@@ -0,0 +1,893 @@
+//! Native BLS12-381 arithmetic, used by the hints and the tests of the in-circuit gadgets.
+//!
+//! The degree 12 extension is represented as `Fp2[w] / (w^6 - (u + 1))`, which is also the
+//! representation used in the circuits, and G2 lives on the M-type sextic twist
+//! `y^2 = x^3 + 4(u + 1)`. Points at infinity are represented by `None`.
+
+use lazy_static::lazy_static;
+use num::{BigUint, One, Zero};
+use sha2::{Digest, Sha256};
+
+lazy_static! {
+    /// The modulus of the BLS12-381 base field.
+    pub static ref BLS12_381_MODULUS: BigUint = BigUint::parse_bytes(
+        b"1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab",
+        16
+    )
+    .unwrap();
+    /// The order of G1 and G2.
+    pub static ref BLS12_381_ORDER: BigUint = BigUint::parse_bytes(
+        b"73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        16
+    )
+    .unwrap();
+    /// The generator of G1.
+    pub static ref BLS12_381_G1_GENERATOR: G1Affine = G1Affine {
+        x: BigUint::parse_bytes(
+            b"17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb",
+            16
+        )
+        .unwrap(),
+        y: BigUint::parse_bytes(
+            b"08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1",
+            16
+        )
+        .unwrap(),
+    };
+    /// The generator of G2.
+    pub static ref BLS12_381_G2_GENERATOR: G2Affine = G2Affine {
+        x: Fp2::new(
+            BigUint::parse_bytes(
+                b"024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+                16
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e",
+                16
+            )
+            .unwrap(),
+        ),
+        y: Fp2::new(
+            BigUint::parse_bytes(
+                b"0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801",
+                16
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be",
+                16
+            )
+            .unwrap(),
+        ),
+    };
+    /// The cube root of unity `beta` of the base field such that the endomorphism
+    /// `(x, y) -> (beta x, y)` of G1 is the multiplication by `-x^2`.
+    pub static ref BLS12_381_G1_BETA: BigUint = BigUint::parse_bytes(
+        b"5f19672fdf76ce51ba69c6076a0f77eaddb3a93be6f89688de17d813620a00022e01fffffffefffe",
+        16
+    )
+    .unwrap();
+    /// `(u + 1)^(i (p - 1) / 6)` for `i` in `0..6`, the constants of the Frobenius map.
+    pub static ref BLS12_381_FROBENIUS_COEFFS: [Fp2; 6] =
+        frobenius_coeffs(&(&*BLS12_381_MODULUS - 1u32));
+    /// `(u + 1)^(i (p^2 - 1) / 6)` for `i` in `0..6`, the constants of the squared Frobenius map.
+    pub static ref BLS12_381_FROBENIUS2_COEFFS: [Fp2; 6] =
+        frobenius_coeffs(&(&*BLS12_381_MODULUS * &*BLS12_381_MODULUS - 1u32));
+    /// The constants `(u + 1)^(-(p - 1) / 3)` and `(u + 1)^(-(p - 1) / 2)` of the endomorphism
+    /// `psi` of the twist.
+    pub static ref BLS12_381_PSI_COEFFS: (Fp2, Fp2) = {
+        let p_minus_one = &*BLS12_381_MODULUS - 1u32;
+        (
+            Fp2::xi().pow(&(&p_minus_one / 3u32)).inverse(),
+            Fp2::xi().pow(&(&p_minus_one / 2u32)).inverse(),
+        )
+    };
+    /// The coefficients `A' = 240 u` and `B' = 1012 (1 + u)` of the curve `E'` which is
+    /// 3-isogenous to the twist, and the non-square `Z = -(2 + u)` of the simplified SWU map.
+    pub static ref BLS12_381_SSWU_A: Fp2 = Fp2::new(BigUint::zero(), BigUint::from(240u32));
+    pub static ref BLS12_381_SSWU_B: Fp2 = Fp2::new(BigUint::from(1012u32), BigUint::from(1012u32));
+    pub static ref BLS12_381_SSWU_Z: Fp2 =
+        Fp2::new(&*BLS12_381_MODULUS - 2u32, &*BLS12_381_MODULUS - 1u32);
+    /// The coefficients of the 3-isogeny from `E'` to the twist, from RFC 9380 appendix E.3, in
+    /// increasing degree. The denominators are monic and their leading coefficient is included.
+    pub static ref BLS12_381_ISO3_X_NUM: [Fp2; 4] = [
+        fp2_from_hex(
+            "5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6",
+            "5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97d6",
+        ),
+        fp2_from_hex(
+            "0",
+            "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71a",
+        ),
+        fp2_from_hex(
+            "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71e",
+            "8ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38d",
+        ),
+        fp2_from_hex(
+            "171d6541fa38ccfaed6dea691f5fb614cb14b4e7f4e810aa22d6108f142b85757098e38d0f671c7188e2aaaaaaaa5ed1",
+            "0",
+        ),
+    ];
+    pub static ref BLS12_381_ISO3_X_DEN: [Fp2; 3] = [
+        fp2_from_hex(
+            "0",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa63",
+        ),
+        fp2_from_hex(
+            "c",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa9f",
+        ),
+        Fp2::one(),
+    ];
+    pub static ref BLS12_381_ISO3_Y_NUM: [Fp2; 4] = [
+        fp2_from_hex(
+            "1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706",
+            "1530477c7ab4113b59a4c18b076d11930f7da5d4a07f649bf54439d87d27e500fc8c25ebf8c92f6812cfc71c71c6d706",
+        ),
+        fp2_from_hex(
+            "0",
+            "5c759507e8e333ebb5b7a9a47d7ed8532c52d39fd3a042a88b58423c50ae15d5c2638e343d9c71c6238aaaaaaaa97be",
+        ),
+        fp2_from_hex(
+            "11560bf17baa99bc32126fced787c88f984f87adf7ae0c7f9a208c6b4f20a4181472aaa9cb8d555526a9ffffffffc71c",
+            "8ab05f8bdd54cde190937e76bc3e447cc27c3d6fbd7063fcd104635a790520c0a395554e5c6aaaa9354ffffffffe38f",
+        ),
+        fp2_from_hex(
+            "124c9ad43b6cf79bfbf7043de3811ad0761b0f37a1e26286b0e977c69aa274524e79097a56dc4bd9e1b371c71c718b10",
+            "0",
+        ),
+    ];
+    pub static ref BLS12_381_ISO3_Y_DEN: [Fp2; 4] = [
+        fp2_from_hex(
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa8fb",
+        ),
+        fp2_from_hex(
+            "0",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffa9d3",
+        ),
+        fp2_from_hex(
+            "12",
+            "1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaa99",
+        ),
+        Fp2::one(),
+    ];
+}
+
+/// The absolute value of the BLS parameter `x = -0xd201000000010000`.
+pub const BLS12_381_X_ABS: u64 = 0xd201000000010000;
+
+/// The domain separation tag of the proof-of-possession BLS signatures used by Ethereum.
+pub const BLS12_381_ETH_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+fn fp2_from_hex(c0: &str, c1: &str) -> Fp2 {
+    Fp2::new(
+        BigUint::parse_bytes(c0.as_bytes(), 16).unwrap(),
+        BigUint::parse_bytes(c1.as_bytes(), 16).unwrap(),
+    )
+}
+
+fn frobenius_coeffs(exponent: &BigUint) -> [Fp2; 6] {
+    core::array::from_fn(|i| Fp2::xi().pow(&(exponent * i / 6u32)))
+}
+
+fn fp_reduce(x: BigUint) -> BigUint {
+    x % &*BLS12_381_MODULUS
+}
+
+fn fp_neg(x: &BigUint) -> BigUint {
+    fp_reduce(&*BLS12_381_MODULUS - fp_reduce(x.clone()))
+}
+
+fn fp_sub(a: &BigUint, b: &BigUint) -> BigUint {
+    fp_reduce(a + fp_neg(b))
+}
+
+/// Returns the inverse of a non-zero element of the base field.
+pub fn fp_inverse(x: &BigUint) -> BigUint {
+    x.modpow(&(&*BLS12_381_MODULUS - 2u32), &BLS12_381_MODULUS)
+}
+
+/// Returns a square root of `x`, if it is a square.
+pub fn fp_sqrt(x: &BigUint) -> Option<BigUint> {
+    // The modulus is 3 modulo 4.
+    let root = x.modpow(&((&*BLS12_381_MODULUS + 1u32) / 4u32), &BLS12_381_MODULUS);
+    (fp_reduce(&root * &root) == fp_reduce(x.clone())).then_some(root)
+}
+
+/// Returns whether `y` is larger than `p - y`, which defines the sign of compressed points.
+pub fn fp_is_lexicographically_largest(y: &BigUint) -> bool {
+    y > &((&*BLS12_381_MODULUS - 1u32) / 2u32)
+}
+
+/// An element `c0 + c1 u` of `Fp2 = Fp[u] / (u^2 + 1)`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Fp2 {
+    pub c0: BigUint,
+    pub c1: BigUint,
+}
+
+impl Fp2 {
+    pub fn new(c0: BigUint, c1: BigUint) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::new(BigUint::one(), BigUint::zero())
+    }
+
+    /// The non-residue `u + 1` which defines the extensions of degree 6 and 12.
+    pub fn xi() -> Self {
+        Self::new(BigUint::one(), BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(
+            fp_reduce(&self.c0 + &other.c0),
+            fp_reduce(&self.c1 + &other.c1),
+        )
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        Self::new(fp_sub(&self.c0, &other.c0), fp_sub(&self.c1, &other.c1))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(fp_neg(&self.c0), fp_neg(&self.c1))
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0.clone(), fp_neg(&self.c1))
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self::new(
+            fp_sub(&(&self.c0 * &other.c0), &(&self.c1 * &other.c1)),
+            fp_reduce(&self.c0 * &other.c1 + &self.c1 * &other.c0),
+        )
+    }
+
+    pub fn mul_by_fp(&self, other: &BigUint) -> Self {
+        Self::new(fp_reduce(&self.c0 * other), fp_reduce(&self.c1 * other))
+    }
+
+    pub fn mul_by_xi(&self) -> Self {
+        Self::new(fp_sub(&self.c0, &self.c1), fp_reduce(&self.c0 + &self.c1))
+    }
+
+    pub fn pow(&self, exponent: &BigUint) -> Self {
+        let mut result = Self::one();
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul(&result);
+            if exponent.bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// Returns the inverse of a non-zero element.
+    pub fn inverse(&self) -> Self {
+        let norm = fp_reduce(&self.c0 * &self.c0 + &self.c1 * &self.c1);
+        let norm_inverse = fp_inverse(&norm);
+        Self::new(
+            fp_reduce(&self.c0 * &norm_inverse),
+            fp_neg(&fp_reduce(&self.c1 * &norm_inverse)),
+        )
+    }
+
+    /// Returns a square root of `self`, if it is a square.
+    pub fn sqrt(&self) -> Option<Self> {
+        let root = if self.c1.is_zero() {
+            match fp_sqrt(&self.c0) {
+                Some(root) => Self::new(root, BigUint::zero()),
+                None => Self::new(BigUint::zero(), fp_sqrt(&fp_neg(&self.c0))?),
+            }
+        } else {
+            // x0^2 - x1^2 = c0 and 2 x0 x1 = c1, so x0^2 = (c0 +- sqrt(c0^2 + c1^2)) / 2.
+            let norm = fp_reduce(&self.c0 * &self.c0 + &self.c1 * &self.c1);
+            let gamma = fp_sqrt(&norm)?;
+            let half = fp_inverse(&BigUint::from(2u32));
+            let delta = fp_reduce((&self.c0 + &gamma) * &half);
+            let x0 = match fp_sqrt(&delta) {
+                Some(x0) => x0,
+                None => fp_sqrt(&fp_reduce(fp_sub(&self.c0, &gamma) * &half))?,
+            };
+            let x1 = fp_reduce(&self.c1 * fp_inverse(&fp_reduce(&x0 * 2u32)));
+            Self::new(x0, x1)
+        };
+        (root.mul(&root) == *self).then_some(root)
+    }
+
+    /// The sign of `self` as defined by RFC 9380, i.e. the parity of `c0`, or of `c1` if `c0` is
+    /// zero.
+    pub fn sgn0(&self) -> bool {
+        self.c0.bit(0) || (self.c0.is_zero() && self.c1.bit(0))
+    }
+
+    /// Returns whether `self` is larger than `-self` in the order of compressed G2 points, which
+    /// compares the `c1` coefficients first.
+    pub fn is_lexicographically_largest(&self) -> bool {
+        if self.c1.is_zero() {
+            fp_is_lexicographically_largest(&self.c0)
+        } else {
+            fp_is_lexicographically_largest(&self.c1)
+        }
+    }
+}
+
+/// An element `sum c_i w^i` of `Fp12 = Fp2[w] / (w^6 - (u + 1))`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Fp12(pub [Fp2; 6]);
+
+impl Fp12 {
+    pub fn one() -> Self {
+        let mut coeffs: [Fp2; 6] = Default::default();
+        coeffs[0] = Fp2::one();
+        Self(coeffs)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut coeffs: [Fp2; 6] = Default::default();
+        for i in 0..6 {
+            for j in 0..6 {
+                let product = self.0[i].mul(&other.0[j]);
+                if i + j >= 6 {
+                    coeffs[i + j - 6] = coeffs[i + j - 6].add(&product.mul_by_xi());
+                } else {
+                    coeffs[i + j] = coeffs[i + j].add(&product);
+                }
+            }
+        }
+        Self(coeffs)
+    }
+
+    pub fn pow(&self, exponent: &BigUint) -> Self {
+        let mut result = Self::one();
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul(&result);
+            if exponent.bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// Returns `self^(p^6)`, which is the inverse of the elements of the cyclotomic subgroup.
+    pub fn conjugate(&self) -> Self {
+        Self(core::array::from_fn(|i| {
+            if i % 2 == 0 {
+                self.0[i].clone()
+            } else {
+                self.0[i].neg()
+            }
+        }))
+    }
+
+    /// Returns `self^p`.
+    pub fn frobenius(&self) -> Self {
+        Self(core::array::from_fn(|i| {
+            self.0[i].conjugate().mul(&BLS12_381_FROBENIUS_COEFFS[i])
+        }))
+    }
+
+    /// Returns `self^(p^2)`.
+    pub fn frobenius2(&self) -> Self {
+        Self(core::array::from_fn(|i| {
+            self.0[i].mul(&BLS12_381_FROBENIUS2_COEFFS[i])
+        }))
+    }
+
+    /// Returns the inverse of a non-zero element.
+    pub fn inverse(&self) -> Self {
+        let p = &*BLS12_381_MODULUS;
+        self.pow(&(p.pow(12) - 2u32))
+    }
+}
+
+/// An affine point of G1, on `y^2 = x^3 + 4`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct G1Affine {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl G1Affine {
+    pub fn is_on_curve(&self) -> bool {
+        fp_reduce(&self.y * &self.y) == fp_reduce(&self.x * &self.x * &self.x + 4u32)
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: fp_neg(&self.y),
+        }
+    }
+
+    /// The endomorphism `(x, y) -> (beta x, y)` of G1.
+    pub fn sigma(&self) -> Self {
+        Self {
+            x: fp_reduce(&self.x * &*BLS12_381_G1_BETA),
+            y: self.y.clone(),
+        }
+    }
+
+    /// Returns the 48 byte compressed encoding of the point.
+    pub fn to_compressed(&self) -> [u8; 48] {
+        let mut bytes = [0u8; 48];
+        let x = self.x.to_bytes_be();
+        bytes[48 - x.len()..].copy_from_slice(&x);
+        bytes[0] |= 0x80;
+        if fp_is_lexicographically_largest(&self.y) {
+            bytes[0] |= 0x20;
+        }
+        bytes
+    }
+
+    /// Decodes a 48 byte compressed point which is not the point at infinity.
+    pub fn from_compressed(bytes: &[u8; 48]) -> Option<Self> {
+        let mut x = *bytes;
+        let flags = x[0];
+        x[0] &= 0x1f;
+        let x = BigUint::from_bytes_be(&x);
+        if flags & 0xc0 != 0x80 || x >= *BLS12_381_MODULUS {
+            return None;
+        }
+        let mut y = fp_sqrt(&fp_reduce(&x * &x * &x + 4u32))?;
+        if fp_is_lexicographically_largest(&y) != (flags & 0x20 != 0) {
+            y = fp_neg(&y);
+        }
+        Some(Self { x, y })
+    }
+}
+
+/// Adds two points of G1.
+pub fn g1_add(a: &Option<G1Affine>, b: &Option<G1Affine>) -> Option<G1Affine> {
+    let (a, b) = match (a, b) {
+        (None, _) => return b.clone(),
+        (_, None) => return a.clone(),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let slope = if a.x == b.x {
+        if fp_reduce(&a.y + &b.y).is_zero() {
+            return None;
+        }
+        fp_reduce(&a.x * &a.x * 3u32 * fp_inverse(&fp_reduce(&a.y * 2u32)))
+    } else {
+        fp_reduce(fp_sub(&b.y, &a.y) * fp_inverse(&fp_sub(&b.x, &a.x)))
+    };
+    let x = fp_sub(&fp_sub(&fp_reduce(&slope * &slope), &a.x), &b.x);
+    let y = fp_sub(&fp_reduce(&slope * fp_sub(&a.x, &x)), &a.y);
+    Some(G1Affine { x, y })
+}
+
+/// Multiplies a point of G1 by a scalar.
+pub fn g1_mul(a: &G1Affine, scalar: &BigUint) -> Option<G1Affine> {
+    let mut result = None;
+    for i in (0..scalar.bits()).rev() {
+        result = g1_add(&result, &result);
+        if scalar.bit(i) {
+            result = g1_add(&result, &Some(a.clone()));
+        }
+    }
+    result
+}
+
+/// An affine point of G2, on the twist `y^2 = x^3 + 4(u + 1)`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct G2Affine {
+    pub x: Fp2,
+    pub y: Fp2,
+}
+
+impl G2Affine {
+    pub fn is_on_curve(&self) -> bool {
+        let b = Fp2::new(BigUint::from(4u32), BigUint::from(4u32));
+        self.y.mul(&self.y) == self.x.mul(&self.x).mul(&self.x).add(&b)
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: self.y.neg(),
+        }
+    }
+
+    /// The endomorphism `psi`, which acts on G2 as the multiplication by `x`.
+    pub fn psi(&self) -> Self {
+        let (cx, cy) = &*BLS12_381_PSI_COEFFS;
+        Self {
+            x: self.x.conjugate().mul(cx),
+            y: self.y.conjugate().mul(cy),
+        }
+    }
+
+    /// Returns the 96 byte compressed encoding of the point, i.e. `x.c1 || x.c0` with the flags in
+    /// the first byte.
+    pub fn to_compressed(&self) -> [u8; 96] {
+        let mut bytes = [0u8; 96];
+        let c1 = self.x.c1.to_bytes_be();
+        let c0 = self.x.c0.to_bytes_be();
+        bytes[48 - c1.len()..48].copy_from_slice(&c1);
+        bytes[96 - c0.len()..].copy_from_slice(&c0);
+        bytes[0] |= 0x80;
+        if self.y.is_lexicographically_largest() {
+            bytes[0] |= 0x20;
+        }
+        bytes
+    }
+
+    /// Decodes a 96 byte compressed point which is not the point at infinity.
+    pub fn from_compressed(bytes: &[u8; 96]) -> Option<Self> {
+        let mut c1 = [0u8; 48];
+        c1.copy_from_slice(&bytes[..48]);
+        let flags = c1[0];
+        c1[0] &= 0x1f;
+        let x = Fp2::new(
+            BigUint::from_bytes_be(&bytes[48..]),
+            BigUint::from_bytes_be(&c1),
+        );
+        if flags & 0xc0 != 0x80 || x.c0 >= *BLS12_381_MODULUS || x.c1 >= *BLS12_381_MODULUS {
+            return None;
+        }
+        let b = Fp2::new(BigUint::from(4u32), BigUint::from(4u32));
+        let mut y = x.mul(&x).mul(&x).add(&b).sqrt()?;
+        if y.is_lexicographically_largest() != (flags & 0x20 != 0) {
+            y = y.neg();
+        }
+        Some(Self { x, y })
+    }
+}
+
+/// Adds two points of G2.
+pub fn g2_add(a: &Option<G2Affine>, b: &Option<G2Affine>) -> Option<G2Affine> {
+    let (a, b) = match (a, b) {
+        (None, _) => return b.clone(),
+        (_, None) => return a.clone(),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let slope = if a.x == b.x {
+        if a.y.add(&b.y).is_zero() {
+            return None;
+        }
+        let three = Fp2::new(BigUint::from(3u32), BigUint::zero());
+        three.mul(&a.x).mul(&a.x).mul(&a.y.add(&a.y).inverse())
+    } else {
+        b.y.sub(&a.y).mul(&b.x.sub(&a.x).inverse())
+    };
+    let x = slope.mul(&slope).sub(&a.x).sub(&b.x);
+    let y = slope.mul(&a.x.sub(&x)).sub(&a.y);
+    Some(G2Affine { x, y })
+}
+
+/// Multiplies a point of G2 by a scalar.
+pub fn g2_mul(a: &G2Affine, scalar: &BigUint) -> Option<G2Affine> {
+    let mut result = None;
+    for i in (0..scalar.bits()).rev() {
+        result = g2_add(&result, &result);
+        if scalar.bit(i) {
+            result = g2_add(&result, &Some(a.clone()));
+        }
+    }
+    result
+}
+
+/// Expands `msg` into `len` uniform bytes with `expand_message_xmd` over SHA-256 (RFC 9380,
+/// section 5.3.1).
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    assert!(dst.len() < 256 && len < 256 * 32);
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b_0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut bytes = b_i.to_vec();
+    for i in 2..=(len + 31) / 32 {
+        let xor = b_0
+            .iter()
+            .zip(b_i.iter())
+            .map(|(a, b)| a ^ b)
+            .collect::<Vec<_>>();
+        b_i = Sha256::new()
+            .chain_update(xor)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        bytes.extend_from_slice(&b_i);
+    }
+    bytes.truncate(len);
+    bytes
+}
+
+/// Hashes `msg` to two elements of `Fp2`, reducing 64 byte big-endian chunks modulo `p`.
+pub fn hash_to_field(msg: &[u8], dst: &[u8]) -> [Fp2; 2] {
+    let bytes = expand_message_xmd(msg, dst, 256);
+    let fp = |i: usize| fp_reduce(BigUint::from_bytes_be(&bytes[64 * i..64 * (i + 1)]));
+    [Fp2::new(fp(0), fp(1)), Fp2::new(fp(2), fp(3))]
+}
+
+fn sswu_rhs(x: &Fp2) -> Fp2 {
+    x.mul(x)
+        .add(&BLS12_381_SSWU_A)
+        .mul(x)
+        .add(&BLS12_381_SSWU_B)
+}
+
+/// The simplified SWU map to the curve `E'` (RFC 9380, section 6.6.2), returning the point and
+/// whether `g(x1)` was a square, which is the hint of the circuit.
+pub fn map_to_curve_sswu(u: &Fp2) -> (Fp2, Fp2, bool) {
+    let (a, b, z) = (&*BLS12_381_SSWU_A, &*BLS12_381_SSWU_B, &*BLS12_381_SSWU_Z);
+    let z_u2 = z.mul(u).mul(u);
+    let tv1 = z_u2.mul(&z_u2).add(&z_u2);
+    let x1 = if tv1.is_zero() {
+        b.mul(&z.mul(a).inverse())
+    } else {
+        b.neg()
+            .mul(&a.inverse())
+            .mul(&Fp2::one().add(&tv1.inverse()))
+    };
+    let (x, y, is_square) = match sswu_rhs(&x1).sqrt() {
+        Some(y) => (x1, y, true),
+        None => {
+            let x2 = z_u2.mul(&x1);
+            let y = sswu_rhs(&x2).sqrt().unwrap();
+            (x2, y, false)
+        }
+    };
+    let y = if y.sgn0() == u.sgn0() { y } else { y.neg() };
+    (x, y, is_square)
+}
+
+fn poly_eval(coeffs: &[Fp2], x: &Fp2) -> Fp2 {
+    coeffs
+        .iter()
+        .rev()
+        .fold(Fp2::zero(), |acc, c| acc.mul(x).add(c))
+}
+
+/// The 3-isogeny from `E'` to the twist.
+pub fn iso_map(x: &Fp2, y: &Fp2) -> G2Affine {
+    let x_num = poly_eval(&*BLS12_381_ISO3_X_NUM, x);
+    let x_den = poly_eval(&*BLS12_381_ISO3_X_DEN, x);
+    let y_num = poly_eval(&*BLS12_381_ISO3_Y_NUM, x);
+    let y_den = poly_eval(&*BLS12_381_ISO3_Y_DEN, x);
+    G2Affine {
+        x: x_num.mul(&x_den.inverse()),
+        y: y.mul(&y_num).mul(&y_den.inverse()),
+    }
+}
+
+/// Multiplies a point of the twist by the BLS parameter `x`, which is negative.
+fn g2_mul_by_x(a: &Option<G2Affine>) -> Option<G2Affine> {
+    let multiple = a
+        .as_ref()
+        .and_then(|a| g2_mul(a, &BigUint::from(BLS12_381_X_ABS)));
+    multiple.map(|point| point.neg())
+}
+
+fn g2_sub(a: &Option<G2Affine>, b: &Option<G2Affine>) -> Option<G2Affine> {
+    g2_add(a, &b.as_ref().map(G2Affine::neg))
+}
+
+/// Clears the cofactor of a point of the twist with the method of Budroni and Pintore (RFC 9380,
+/// appendix G.3), which maps it to G2.
+pub fn clear_cofactor(point: &Option<G2Affine>) -> Option<G2Affine> {
+    let psi = |a: &Option<G2Affine>| a.as_ref().map(G2Affine::psi);
+    let t1 = g2_mul_by_x(point);
+    let t2 = psi(point);
+    let t3 = psi(&psi(&g2_add(point, point)));
+    let t3 = g2_sub(&t3, &t2);
+    let t2 = g2_mul_by_x(&g2_add(&t1, &t2));
+    let t3 = g2_sub(&g2_add(&t3, &t2), &t1);
+    g2_sub(&t3, point)
+}
+
+/// Hashes `msg` to G2 with the suite `BLS12381G2_XMD:SHA-256_SSWU_RO_` of RFC 9380.
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> Option<G2Affine> {
+    let [u0, u1] = hash_to_field(msg, dst);
+    let (x0, y0, _) = map_to_curve_sswu(&u0);
+    let (x1, y1, _) = map_to_curve_sswu(&u1);
+    let sum = g2_add(&Some(iso_map(&x0, &y0)), &Some(iso_map(&x1, &y1)));
+    clear_cofactor(&sum)
+}
+
+/// The line through `t` with slope `slope`, evaluated at `p` and scaled by `w^3`, which is
+/// `(slope x_t - y_t) - slope x_p w^2 + y_p w^3`.
+fn line(slope: &Fp2, t: &G2Affine, p: &G1Affine) -> Fp12 {
+    let mut coeffs: [Fp2; 6] = Default::default();
+    coeffs[0] = slope.mul(&t.x).sub(&t.y);
+    coeffs[2] = slope.mul_by_fp(&p.x).neg();
+    coeffs[3] = Fp2::new(p.y.clone(), BigUint::zero());
+    Fp12(coeffs)
+}
+
+/// Computes the product of the Miller loops of the optimal ate pairing for the given pairs.
+pub fn miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let three = Fp2::new(BigUint::from(3u32), BigUint::zero());
+    let mut f = Fp12::one();
+    let mut ts = pairs.iter().map(|(_, q)| q.clone()).collect::<Vec<_>>();
+    for i in (0..63).rev() {
+        f = f.mul(&f);
+        for ((p, _), t) in pairs.iter().zip(ts.iter_mut()) {
+            let slope = three.mul(&t.x).mul(&t.x).mul(&t.y.add(&t.y).inverse());
+            f = f.mul(&line(&slope, t, p));
+            *t = g2_add(&Some(t.clone()), &Some(t.clone())).unwrap();
+        }
+        if (BLS12_381_X_ABS >> i) & 1 == 1 {
+            for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+                let slope = q.y.sub(&t.y).mul(&q.x.sub(&t.x).inverse());
+                f = f.mul(&line(&slope, t, p));
+                *t = g2_add(&Some(t.clone()), &Some(q.clone())).unwrap();
+            }
+        }
+    }
+    // The parameter `x` is negative.
+    f.conjugate()
+}
+
+/// Returns `f^x` for `f` in the cyclotomic subgroup.
+fn cyclotomic_exp_by_x(f: &Fp12) -> Fp12 {
+    f.pow(&BigUint::from(BLS12_381_X_ABS)).conjugate()
+}
+
+/// Raises the output of the Miller loop to the power `3 (p^12 - 1) / r`.
+///
+/// The cube is not a problem for pairing checks since 3 does not divide `r`.
+pub fn final_exponentiation(f: &Fp12) -> Fp12 {
+    // The easy part, `(p^6 - 1)(p^2 + 1)`.
+    let f = f.conjugate().mul(&f.inverse());
+    let f = f.frobenius2().mul(&f);
+
+    // The hard part, `3 (p^4 - p^2 + 1) / r = (x - 1)^2 (x + p) (x^2 + p^2 - 1) + 3`.
+    let a = cyclotomic_exp_by_x(&f).mul(&f.conjugate());
+    let a = cyclotomic_exp_by_x(&a).mul(&a.conjugate());
+    let b = cyclotomic_exp_by_x(&a).mul(&a.frobenius());
+    let c = cyclotomic_exp_by_x(&cyclotomic_exp_by_x(&b))
+        .mul(&b.frobenius2())
+        .mul(&b.conjugate());
+    c.mul(&f.mul(&f).mul(&f))
+}
+
+/// Computes the (cubed) optimal ate pairing.
+pub fn pairing(p: &G1Affine, q: &G2Affine) -> Fp12 {
+    final_exponentiation(&miller_loop(&[(p.clone(), q.clone())]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bls12_381_compressed_generators() {
+        let g1 = BLS12_381_G1_GENERATOR.to_compressed();
+        assert_eq!(
+            hex::encode(g1),
+            "97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"
+        );
+        assert_eq!(
+            G1Affine::from_compressed(&g1).unwrap(),
+            *BLS12_381_G1_GENERATOR
+        );
+
+        let g2 = BLS12_381_G2_GENERATOR.to_compressed();
+        assert_eq!(
+            hex::encode(g2),
+            "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"
+        );
+        assert_eq!(
+            G2Affine::from_compressed(&g2).unwrap(),
+            *BLS12_381_G2_GENERATOR
+        );
+
+        let point = g2_mul(&BLS12_381_G2_GENERATOR, &BigUint::from(12345u32)).unwrap();
+        assert_eq!(
+            G2Affine::from_compressed(&point.to_compressed()).unwrap(),
+            point
+        );
+    }
+
+    #[test]
+    fn test_bls12_381_frobenius() {
+        let a = Fp12(core::array::from_fn(|i| {
+            Fp2::new(BigUint::from(3 * i + 1), BigUint::from(7 * i + 2))
+        }));
+        let p = &*BLS12_381_MODULUS;
+        assert_eq!(a.frobenius(), a.pow(p));
+        assert_eq!(a.frobenius2(), a.pow(&(p * p)));
+        assert_eq!(a.conjugate(), a.pow(&p.pow(6)));
+    }
+
+    #[test]
+    fn test_bls12_381_psi() {
+        let point = g2_mul(&BLS12_381_G2_GENERATOR, &BigUint::from(999u32)).unwrap();
+        let multiple = g2_mul(&point, &BigUint::from(BLS12_381_X_ABS)).unwrap();
+        assert_eq!(point.psi(), multiple.neg());
+    }
+
+    #[test]
+    fn test_bls12_381_sigma() {
+        let point = g1_mul(&BLS12_381_G1_GENERATOR, &BigUint::from(999u32)).unwrap();
+        let x2 = BigUint::from(BLS12_381_X_ABS).pow(2);
+        let multiple = g1_mul(&point, &x2).unwrap();
+        assert_eq!(point.sigma(), multiple.neg());
+        assert_eq!(
+            BLS12_381_G1_BETA.modpow(&BigUint::from(3u32), &BLS12_381_MODULUS),
+            BigUint::one()
+        );
+    }
+
+    #[test]
+    fn test_bls12_381_pairing() {
+        let (a, b) = (BigUint::from(1234u32), BigUint::from(5678u32));
+        let p = g1_mul(&BLS12_381_G1_GENERATOR, &a).unwrap();
+        let q = g2_mul(&BLS12_381_G2_GENERATOR, &b).unwrap();
+
+        let e = pairing(&BLS12_381_G1_GENERATOR, &BLS12_381_G2_GENERATOR);
+        assert_ne!(e, Fp12::one());
+        assert_eq!(e.pow(&BLS12_381_ORDER), Fp12::one());
+        assert_eq!(pairing(&p, &q), e.pow(&(&a * &b)));
+
+        // e(aG1, bG2) e(-G1, abG2) = 1.
+        let ab_q = g2_mul(&BLS12_381_G2_GENERATOR, &(&a * &b)).unwrap();
+        let f = miller_loop(&[(p, q), (BLS12_381_G1_GENERATOR.neg(), ab_q)]);
+        assert_eq!(final_exponentiation(&f), Fp12::one());
+    }
+
+    #[test]
+    fn test_bls12_381_hash_to_g2() {
+        // RFC 9380, appendix J.10.1.
+        let dst = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+        let point = hash_to_g2(b"", dst).unwrap();
+        let expected = G2Affine {
+            x: fp2_from_hex(
+                "0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a",
+                "05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+            ),
+            y: fp2_from_hex(
+                "0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92",
+                "12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+            ),
+        };
+        assert_eq!(point, expected);
+    }
+
+    #[test]
+    fn test_bls12_381_eth_signatures() {
+        // The `sign` vectors of the Ethereum consensus specs.
+        let cases = [
+            (
+                "47b8192d77bf871b62e87859d653922725724a5c031afeabc60bcef5ff665138",
+                [0u8; 32],
+                "b23c46be3a001c63ca711f87a005c200cc550b9429d5f4eb38d74322144f1b63926da3388979e5321012fb1a0526bcd100b5ef5fe72628ce4cd5e904aeaa3279527843fae5ca9ca675f4f51ed8f83bbf7155da9ecc9663100a885d5dc6df96d9",
+            ),
+            (
+                "328388aff0d4a5b7dc9205abd374e7e98f3cd9f3418edb4eafda5fb16473d216",
+                [0xabu8; 32],
+                "ae82747ddeefe4fd64cf9cedb9b04ae3e8a43420cd255e3c7cd06a8d88b7c7f8638543719981c5d16fa3527c468c25f0026704a6951bde891360c7e8d12ddee0559004ccdbe6046b55bae1b257ee97f7cdb955773d7cf29adf3ccbb9975e4eb9",
+            ),
+        ];
+        for (sk, msg, signature) in cases {
+            let sk = BigUint::parse_bytes(sk.as_bytes(), 16).unwrap();
+            let point = hash_to_g2(&msg, BLS12_381_ETH_DST).unwrap();
+            let expected = g2_mul(&point, &sk).unwrap();
+            assert_eq!(hex::encode(expected.to_compressed()), signature);
+        }
+    }
+}
@@ -0,0 +1,500 @@
+//! Native BN254 (alt_bn128) arithmetic, used by the hints and the tests of the in-circuit gadgets.
+//!
+//! The degree 12 extension is represented as `Fp2[w] / (w^6 - (u + 9))`, which is also the
+//! representation used in the circuits, and G2 lives on the D-type sextic twist
+//! `y^2 = x^3 + 3 / (u + 9)` as in EIP-197. Points at infinity are represented by `None`.
+
+use lazy_static::lazy_static;
+use num::{BigUint, One, Zero};
+
+lazy_static! {
+    /// The modulus of the BN254 base field.
+    pub static ref BN254_MODULUS: BigUint = BigUint::parse_bytes(
+        b"21888242871839275222246405745257275088696311157297823662689037894645226208583",
+        10
+    )
+    .unwrap();
+    /// The order of G1 and G2, i.e. the modulus of the scalar field.
+    pub static ref BN254_ORDER: BigUint = BigUint::parse_bytes(
+        b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        10
+    )
+    .unwrap();
+    /// The generator `(1, 2)` of G1.
+    pub static ref BN254_G1_GENERATOR: G1Affine = G1Affine {
+        x: BigUint::from(1u32),
+        y: BigUint::from(2u32),
+    };
+    /// The generator of G2 used by EIP-197.
+    pub static ref BN254_G2_GENERATOR: G2Affine = G2Affine {
+        x: Fp2::new(
+            BigUint::parse_bytes(
+                b"10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                10
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"11559732032986387107991004021392285783925812861821192530917403151452391805634",
+                10
+            )
+            .unwrap(),
+        ),
+        y: Fp2::new(
+            BigUint::parse_bytes(
+                b"8495653923123431417604973247489272438418190587263600148770280649306958101930",
+                10
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"4082367875863433681332203403145435568316851327593401208105741076214120093531",
+                10
+            )
+            .unwrap(),
+        ),
+    };
+    /// The constant `3 / (u + 9)` of the twist.
+    pub static ref BN254_TWIST_B: Fp2 = Fp2::new(BigUint::from(3u32), BigUint::zero())
+        .mul(&Fp2::xi().inverse());
+    /// `(u + 9)^(i (p^k - 1) / 6)` for `i` in `0..6`, the constants of the Frobenius maps
+    /// `a -> a^(p^k)` for `k` in `1..=3`.
+    pub static ref BN254_FROBENIUS_COEFFS: [[Fp2; 6]; 3] = core::array::from_fn(|k| {
+        let exponent = BN254_MODULUS.pow(k as u32 + 1) - 1u32;
+        core::array::from_fn(|i| Fp2::xi().pow(&(&exponent * i / 6u32)))
+    });
+    /// The constants `(u + 9)^((p - 1) / 3)` and `(u + 9)^((p - 1) / 2)` of the Frobenius
+    /// endomorphism of the twist.
+    pub static ref BN254_PI_COEFFS: (Fp2, Fp2) = {
+        let p_minus_one = &*BN254_MODULUS - 1u32;
+        (
+            Fp2::xi().pow(&(&p_minus_one / 3u32)),
+            Fp2::xi().pow(&(&p_minus_one / 2u32)),
+        )
+    };
+    /// The constant `(u + 9)^((p^2 - 1) / 3)` of the squared Frobenius endomorphism of the twist,
+    /// which maps `(x, y)` to `(c x, -y)`.
+    pub static ref BN254_PI2_COEFF: Fp2 =
+        Fp2::xi().pow(&((&*BN254_MODULUS * &*BN254_MODULUS - 1u32) / 3u32));
+}
+
+/// The BN parameter `x`, for which `p = 36x^4 + 36x^3 + 24x^2 + 6x + 1`.
+pub const BN254_X: u64 = 4965661367192848881;
+
+/// The length `6x + 2` of the Miller loop of the optimal ate pairing.
+pub const BN254_ATE_LOOP_COUNT: u128 = 6 * BN254_X as u128 + 2;
+
+fn fp_reduce(x: BigUint) -> BigUint {
+    x % &*BN254_MODULUS
+}
+
+fn fp_neg(x: &BigUint) -> BigUint {
+    fp_reduce(&*BN254_MODULUS - fp_reduce(x.clone()))
+}
+
+fn fp_sub(a: &BigUint, b: &BigUint) -> BigUint {
+    fp_reduce(a + fp_neg(b))
+}
+
+/// Returns the inverse of a non-zero element of the base field.
+pub fn fp_inverse(x: &BigUint) -> BigUint {
+    x.modpow(&(&*BN254_MODULUS - 2u32), &BN254_MODULUS)
+}
+
+/// An element `c0 + c1 u` of `Fp2 = Fp[u] / (u^2 + 1)`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Fp2 {
+    pub c0: BigUint,
+    pub c1: BigUint,
+}
+
+impl Fp2 {
+    pub fn new(c0: BigUint, c1: BigUint) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub fn one() -> Self {
+        Self::new(BigUint::one(), BigUint::zero())
+    }
+
+    /// The non-residue `u + 9` which defines the extensions of degree 6 and 12.
+    pub fn xi() -> Self {
+        Self::new(BigUint::from(9u32), BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(
+            fp_reduce(&self.c0 + &other.c0),
+            fp_reduce(&self.c1 + &other.c1),
+        )
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        Self::new(fp_sub(&self.c0, &other.c0), fp_sub(&self.c1, &other.c1))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(fp_neg(&self.c0), fp_neg(&self.c1))
+    }
+
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0.clone(), fp_neg(&self.c1))
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        Self::new(
+            fp_sub(&(&self.c0 * &other.c0), &(&self.c1 * &other.c1)),
+            fp_reduce(&self.c0 * &other.c1 + &self.c1 * &other.c0),
+        )
+    }
+
+    pub fn mul_by_fp(&self, other: &BigUint) -> Self {
+        Self::new(fp_reduce(&self.c0 * other), fp_reduce(&self.c1 * other))
+    }
+
+    pub fn mul_by_xi(&self) -> Self {
+        Self::new(
+            fp_sub(&(&self.c0 * 9u32), &self.c1),
+            fp_reduce(&self.c0 + &self.c1 * 9u32),
+        )
+    }
+
+    pub fn pow(&self, exponent: &BigUint) -> Self {
+        let mut result = Self::one();
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul(&result);
+            if exponent.bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// Returns the inverse of a non-zero element.
+    pub fn inverse(&self) -> Self {
+        let norm = fp_reduce(&self.c0 * &self.c0 + &self.c1 * &self.c1);
+        let norm_inverse = fp_inverse(&norm);
+        Self::new(
+            fp_reduce(&self.c0 * &norm_inverse),
+            fp_neg(&fp_reduce(&self.c1 * &norm_inverse)),
+        )
+    }
+}
+
+/// An element `sum c_i w^i` of `Fp12 = Fp2[w] / (w^6 - (u + 9))`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Fp12(pub [Fp2; 6]);
+
+impl Fp12 {
+    pub fn one() -> Self {
+        let mut coeffs: [Fp2; 6] = Default::default();
+        coeffs[0] = Fp2::one();
+        Self(coeffs)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut coeffs: [Fp2; 6] = Default::default();
+        for i in 0..6 {
+            for j in 0..6 {
+                let product = self.0[i].mul(&other.0[j]);
+                if i + j >= 6 {
+                    coeffs[i + j - 6] = coeffs[i + j - 6].add(&product.mul_by_xi());
+                } else {
+                    coeffs[i + j] = coeffs[i + j].add(&product);
+                }
+            }
+        }
+        Self(coeffs)
+    }
+
+    pub fn pow(&self, exponent: &BigUint) -> Self {
+        let mut result = Self::one();
+        for i in (0..exponent.bits()).rev() {
+            result = result.mul(&result);
+            if exponent.bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+
+    /// Returns `self^(p^6)`, which is the inverse of the elements of the cyclotomic subgroup.
+    pub fn conjugate(&self) -> Self {
+        Self(core::array::from_fn(|i| {
+            if i % 2 == 0 {
+                self.0[i].clone()
+            } else {
+                self.0[i].neg()
+            }
+        }))
+    }
+
+    /// Returns `self^(p^k)` for `k` in `1..=3`.
+    pub fn frobenius_map(&self, k: usize) -> Self {
+        Self(core::array::from_fn(|i| {
+            let c = if k % 2 == 1 {
+                self.0[i].conjugate()
+            } else {
+                self.0[i].clone()
+            };
+            c.mul(&BN254_FROBENIUS_COEFFS[k - 1][i])
+        }))
+    }
+
+    /// Returns the inverse of a non-zero element.
+    pub fn inverse(&self) -> Self {
+        self.pow(&(BN254_MODULUS.pow(12) - 2u32))
+    }
+}
+
+/// An affine point of G1, on `y^2 = x^3 + 3`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct G1Affine {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl G1Affine {
+    pub fn is_on_curve(&self) -> bool {
+        fp_reduce(&self.y * &self.y) == fp_reduce(&self.x * &self.x * &self.x + 3u32)
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: fp_neg(&self.y),
+        }
+    }
+}
+
+/// Adds two points of G1.
+pub fn g1_add(a: &Option<G1Affine>, b: &Option<G1Affine>) -> Option<G1Affine> {
+    let (a, b) = match (a, b) {
+        (None, _) => return b.clone(),
+        (_, None) => return a.clone(),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let slope = if a.x == b.x {
+        if fp_reduce(&a.y + &b.y).is_zero() {
+            return None;
+        }
+        fp_reduce(&a.x * &a.x * 3u32 * fp_inverse(&fp_reduce(&a.y * 2u32)))
+    } else {
+        fp_reduce(fp_sub(&b.y, &a.y) * fp_inverse(&fp_sub(&b.x, &a.x)))
+    };
+    let x = fp_sub(&fp_sub(&fp_reduce(&slope * &slope), &a.x), &b.x);
+    let y = fp_sub(&fp_reduce(&slope * fp_sub(&a.x, &x)), &a.y);
+    Some(G1Affine { x, y })
+}
+
+/// Multiplies a point of G1 by a scalar.
+pub fn g1_mul(a: &G1Affine, scalar: &BigUint) -> Option<G1Affine> {
+    let mut result = None;
+    for i in (0..scalar.bits()).rev() {
+        result = g1_add(&result, &result);
+        if scalar.bit(i) {
+            result = g1_add(&result, &Some(a.clone()));
+        }
+    }
+    result
+}
+
+/// An affine point of G2, on the twist `y^2 = x^3 + 3 / (u + 9)`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct G2Affine {
+    pub x: Fp2,
+    pub y: Fp2,
+}
+
+impl G2Affine {
+    pub fn is_on_curve(&self) -> bool {
+        self.y.mul(&self.y) == self.x.mul(&self.x).mul(&self.x).add(&BN254_TWIST_B)
+    }
+
+    pub fn neg(&self) -> Self {
+        Self {
+            x: self.x.clone(),
+            y: self.y.neg(),
+        }
+    }
+
+    /// The Frobenius endomorphism of the twist, which acts on G2 as the multiplication by `p`.
+    pub fn frobenius(&self) -> Self {
+        let (cx, cy) = &*BN254_PI_COEFFS;
+        Self {
+            x: self.x.conjugate().mul(cx),
+            y: self.y.conjugate().mul(cy),
+        }
+    }
+
+    /// Returns `-pi^2(self)`, where `pi^2(x, y) = (c x, -y)`.
+    pub fn neg_frobenius2(&self) -> Self {
+        Self {
+            x: self.x.mul(&BN254_PI2_COEFF),
+            y: self.y.clone(),
+        }
+    }
+}
+
+/// Adds two points of G2.
+pub fn g2_add(a: &Option<G2Affine>, b: &Option<G2Affine>) -> Option<G2Affine> {
+    let (a, b) = match (a, b) {
+        (None, _) => return b.clone(),
+        (_, None) => return a.clone(),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let slope = if a.x == b.x {
+        if a.y.add(&b.y).is_zero() {
+            return None;
+        }
+        let three = Fp2::new(BigUint::from(3u32), BigUint::zero());
+        three.mul(&a.x).mul(&a.x).mul(&a.y.add(&a.y).inverse())
+    } else {
+        b.y.sub(&a.y).mul(&b.x.sub(&a.x).inverse())
+    };
+    let x = slope.mul(&slope).sub(&a.x).sub(&b.x);
+    let y = slope.mul(&a.x.sub(&x)).sub(&a.y);
+    Some(G2Affine { x, y })
+}
+
+/// Multiplies a point of G2 by a scalar.
+pub fn g2_mul(a: &G2Affine, scalar: &BigUint) -> Option<G2Affine> {
+    let mut result = None;
+    for i in (0..scalar.bits()).rev() {
+        result = g2_add(&result, &result);
+        if scalar.bit(i) {
+            result = g2_add(&result, &Some(a.clone()));
+        }
+    }
+    result
+}
+
+/// The line through `t` with slope `slope` on the twist, untwisted and evaluated at `p`, which is
+/// `y_p - slope x_p w + (slope x_t - y_t) w^3`.
+fn line(slope: &Fp2, t: &G2Affine, p: &G1Affine) -> Fp12 {
+    let mut coeffs: [Fp2; 6] = Default::default();
+    coeffs[0] = Fp2::new(p.y.clone(), BigUint::zero());
+    coeffs[1] = slope.mul_by_fp(&p.x).neg();
+    coeffs[3] = slope.mul(&t.x).sub(&t.y);
+    Fp12(coeffs)
+}
+
+/// Adds `q` to `t` and multiplies `f` by the line through them.
+fn add_step(f: &Fp12, t: &mut G2Affine, q: &G2Affine, p: &G1Affine) -> Fp12 {
+    let slope = q.y.sub(&t.y).mul(&q.x.sub(&t.x).inverse());
+    let f = f.mul(&line(&slope, t, p));
+    *t = g2_add(&Some(t.clone()), &Some(q.clone())).unwrap();
+    f
+}
+
+/// Computes the product of the Miller loops of the optimal ate pairing for the given pairs.
+pub fn miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let three = Fp2::new(BigUint::from(3u32), BigUint::zero());
+    let mut f = Fp12::one();
+    let mut ts = pairs.iter().map(|(_, q)| q.clone()).collect::<Vec<_>>();
+    let nb_bits = 128 - BN254_ATE_LOOP_COUNT.leading_zeros();
+    for i in (0..nb_bits - 1).rev() {
+        f = f.mul(&f);
+        for ((p, _), t) in pairs.iter().zip(ts.iter_mut()) {
+            let slope = three.mul(&t.x).mul(&t.x).mul(&t.y.add(&t.y).inverse());
+            f = f.mul(&line(&slope, t, p));
+            *t = g2_add(&Some(t.clone()), &Some(t.clone())).unwrap();
+        }
+        if (BN254_ATE_LOOP_COUNT >> i) & 1 == 1 {
+            for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+                f = add_step(&f, t, q, p);
+            }
+        }
+    }
+    for ((p, q), t) in pairs.iter().zip(ts.iter_mut()) {
+        f = add_step(&f, t, &q.frobenius(), p);
+        f = add_step(&f, t, &q.neg_frobenius2(), p);
+    }
+    f
+}
+
+/// Returns `f^-x` for `f` in the cyclotomic subgroup.
+fn cyclotomic_exp_by_neg_x(f: &Fp12) -> Fp12 {
+    f.pow(&BigUint::from(BN254_X)).conjugate()
+}
+
+/// Raises the output of the Miller loop to the power `m (p^12 - 1) / r`, where
+/// `m = 2x (6x^2 + 3x + 1)` is coprime to `r`, which is equivalent to the usual final
+/// exponentiation for pairing checks.
+///
+/// The hard part follows Fuentes-Castañeda, Knapp and Rodríguez-Henríquez,
+/// "Faster hashing to G2".
+pub fn final_exponentiation(f: &Fp12) -> Fp12 {
+    // The easy part, `(p^6 - 1)(p^2 + 1)`.
+    let f = f.conjugate().mul(&f.inverse());
+    let f = f.frobenius_map(2).mul(&f);
+
+    // The hard part.
+    let y0 = cyclotomic_exp_by_neg_x(&f);
+    let y1 = y0.mul(&y0);
+    let y2 = y1.mul(&y1);
+    let y3 = y2.mul(&y1);
+    let y4 = cyclotomic_exp_by_neg_x(&y3);
+    let y5 = y4.mul(&y4);
+    let y6 = cyclotomic_exp_by_neg_x(&y5).conjugate();
+    let y3 = y3.conjugate();
+    let y7 = y6.mul(&y4);
+    let y8 = y7.mul(&y3);
+    let y9 = y8.mul(&y1);
+    let y10 = y8.mul(&y4);
+    let y11 = y10.mul(&f);
+    let y13 = y9.frobenius_map(1).mul(&y11);
+    let y14 = y8.frobenius_map(2).mul(&y13);
+    let y15 = f.conjugate().mul(&y9).frobenius_map(3);
+    y15.mul(&y14)
+}
+
+/// Computes the optimal ate pairing, raised to the power `m` of [`final_exponentiation`].
+pub fn pairing(p: &G1Affine, q: &G2Affine) -> Fp12 {
+    final_exponentiation(&miller_loop(&[(p.clone(), q.clone())]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bn254_frobenius() {
+        let a = Fp12(core::array::from_fn(|i| {
+            Fp2::new(BigUint::from(5 * i + 3), BigUint::from(11 * i + 1))
+        }));
+        let p = &*BN254_MODULUS;
+        for k in 1..=3 {
+            assert_eq!(a.frobenius_map(k), a.pow(&p.pow(k as u32)));
+        }
+        assert_eq!(a.conjugate(), a.pow(&p.pow(6)));
+    }
+
+    #[test]
+    fn test_bn254_twist_endomorphisms() {
+        let q = g2_mul(&BN254_G2_GENERATOR, &BigUint::from(4321u32)).unwrap();
+        assert!(q.is_on_curve());
+        let p = &*BN254_MODULUS % &*BN254_ORDER;
+        assert_eq!(q.frobenius(), g2_mul(&q, &p).unwrap());
+        assert_eq!(q.neg_frobenius2(), g2_mul(&q, &(&p * &p)).unwrap().neg());
+    }
+
+    #[test]
+    fn test_bn254_pairing() {
+        let (a, b) = (BigUint::from(2468u32), BigUint::from(1357u32));
+        let p = g1_mul(&BN254_G1_GENERATOR, &a).unwrap();
+        let q = g2_mul(&BN254_G2_GENERATOR, &b).unwrap();
+
+        let e = pairing(&BN254_G1_GENERATOR, &BN254_G2_GENERATOR);
+        assert_ne!(e, Fp12::one());
+        assert_eq!(e.pow(&BN254_ORDER), Fp12::one());
+        assert_eq!(pairing(&p, &q), e.pow(&(&a * &b)));
+
+        // e(aG1, bG2) e(-G1, abG2) = 1.
+        let ab_q = g2_mul(&BN254_G2_GENERATOR, &(&a * &b)).unwrap();
+        let f = miller_loop(&[(p, q), (BN254_G1_GENERATOR.neg(), ab_q)]);
+        assert_eq!(final_exponentiation(&f), Fp12::one());
+    }
+}
@@ -1,4 +1,6 @@
 use std::sync::Once;
+pub mod bls12_381;
+pub mod bn254;
 pub mod eth;
 pub mod hash;
 pub mod lido;
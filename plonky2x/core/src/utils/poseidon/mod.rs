@@ -1,7 +1,11 @@
+pub mod poseidon2;
+pub mod poseidon_bn254;
+pub mod poseidon_bn254_constants;
+
 use itertools::Itertools;
 use plonky2::hash::hash_types::HashOut;
 use plonky2::hash::hashing::hash_n_to_hash_no_pad;
-use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
 
 use crate::frontend::hash::poseidon::poseidon256::PoseidonHashOutVariable;
 use crate::prelude::{CircuitBuilder, CircuitVariable, PlonkParameters};
@@ -18,6 +22,26 @@ where
     <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
         AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
 {
+    mapreduce_merkle_tree_root_with_hasher::<
+        L,
+        <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher,
+        Input,
+        B,
+        D,
+    >(inputs)
+}
+
+/// Computes the root of the merkle tree whose leaves are the hashes of each chunk of `B` inputs,
+/// using the algebraic hasher `H`.
+pub fn mapreduce_merkle_tree_root_with_hasher<
+    L: PlonkParameters<D>,
+    H: AlgebraicHasher<L::Field>,
+    Input: CircuitVariable,
+    const B: usize,
+    const D: usize,
+>(
+    inputs: &[Input::ValueType<L::Field>],
+) -> HashOut<L::Field> {
     assert_eq!(inputs.len() % B, 0, "inputs length must be a multiple of B");
     let inputs = inputs.to_vec();
 
@@ -28,12 +52,7 @@ where
         for j in 0..B {
             input.extend(Input::elements::<L::Field>(inputs[i * B + j].clone()));
         }
-        let h = hash_n_to_hash_no_pad::<
-            L::Field,
-            <<<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher as Hasher<
-                <L as PlonkParameters<D>>::Field,
-            >>::Permutation,
-        >(&input);
+        let h = hash_n_to_hash_no_pad::<L::Field, H::Permutation>(&input);
         leafs.push(h.elements);
     }
 
@@ -51,12 +70,7 @@ where
             let mut input = Vec::new();
             input.extend(&left);
             input.extend(&right);
-            let h = hash_n_to_hash_no_pad::<
-                L::Field,
-                <<<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher as Hasher<
-                    <L as PlonkParameters<D>>::Field,
-                >>::Permutation,
-            >(&input);
+            let h = hash_n_to_hash_no_pad::<L::Field, H::Permutation>(&input);
             tmp.push(h.elements);
         }
         leafs = tmp;
@@ -73,6 +87,16 @@ pub trait MapReducePoseidonBuilderMethods<L: PlonkParameters<D>, const D: usize>
     where
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
             AlgebraicHasher<<L as PlonkParameters<D>>::Field>;
+
+    /// Same as `mapreduce_merkle_tree_root`, with the algebraic hasher `H`.
+    fn mapreduce_merkle_tree_root_with_hasher<
+        H: AlgebraicHasher<L::Field>,
+        Input: CircuitVariable,
+        const B: usize,
+    >(
+        &mut self,
+        inputs: &[Input],
+    ) -> PoseidonHashOutVariable;
 }
 
 impl<L: PlonkParameters<D>, const D: usize> MapReducePoseidonBuilderMethods<L, D>
@@ -86,6 +110,21 @@ impl<L: PlonkParameters<D>, const D: usize> MapReducePoseidonBuilderMethods<L, D
         <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher:
             AlgebraicHasher<<L as PlonkParameters<D>>::Field>,
     {
+        self.mapreduce_merkle_tree_root_with_hasher::<
+            <<L as PlonkParameters<D>>::Config as GenericConfig<D>>::Hasher,
+            Input,
+            B,
+        >(inputs)
+    }
+
+    fn mapreduce_merkle_tree_root_with_hasher<
+        H: AlgebraicHasher<L::Field>,
+        Input: CircuitVariable,
+        const B: usize,
+    >(
+        &mut self,
+        inputs: &[Input],
+    ) -> PoseidonHashOutVariable {
         assert_eq!(inputs.len() % B, 0, "inputs length must be a multiple of B");
         let inputs = inputs.to_vec();
 
@@ -98,7 +137,7 @@ impl<L: PlonkParameters<D>, const D: usize> MapReducePoseidonBuilderMethods<L, D
                 .take(B)
                 .flat_map(|input| input.variables())
                 .collect_vec();
-            let hash = self.poseidon_hash(&input_variables);
+            let hash = self.poseidon_hash_with::<H>(&input_variables);
             leafs.push(hash);
         }
 
@@ -113,7 +152,7 @@ impl<L: PlonkParameters<D>, const D: usize> MapReducePoseidonBuilderMethods<L, D
             for i in 0..leafs.len() / 2 {
                 let left = leafs[i * 2].clone();
                 let right = leafs[i * 2 + 1].clone();
-                let hash = self.poseidon_hash_pair(left, right);
+                let hash = self.poseidon_hash_pair_with::<H>(left, right);
                 tmp.push(hash);
             }
             leafs = tmp;
@@ -0,0 +1,304 @@
+//! A native implementation of the Poseidon2 permutation over Goldilocks.
+//!
+//! The instance has width 12, the `x^7` S-box, 8 full rounds and 22 partial rounds. The round
+//! constants are sampled from the Grain LFSR of the Poseidon reference implementation, seeded
+//! with these parameters.
+//!
+//! The linear layers and the S-box are generic over the field so that they can be shared with the
+//! constraints of `Poseidon2Gate`, which evaluates them over the extension field.
+
+use lazy_static::lazy_static;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::{Field, Field64};
+
+/// The width of the permutation.
+pub const POSEIDON2_WIDTH: usize = 12;
+
+/// The number of elements absorbed per permutation.
+pub const POSEIDON2_RATE: usize = 8;
+
+/// The number of full rounds before and after the partial rounds.
+pub const POSEIDON2_HALF_N_FULL_ROUNDS: usize = 4;
+
+/// The number of partial rounds.
+pub const POSEIDON2_N_PARTIAL_ROUNDS: usize = 22;
+
+/// The diagonal of `M_I - 1`, where `M_I` is the matrix of the internal linear layer.
+pub const POSEIDON2_INTERNAL_DIAG: [u64; POSEIDON2_WIDTH] = [
+    0xc3b6c08e23ba9300,
+    0xd84b5de94a324fb6,
+    0x0d0c371c5b35b84f,
+    0x7964f570e7188037,
+    0x5daf18bbd996604b,
+    0x6743bc47b9595257,
+    0x5528b9362c59bb70,
+    0xac45e25b7127b68b,
+    0xa2077d7dfbb606b5,
+    0xf3faac6faee378ae,
+    0x0c6388b51545e883,
+    0xd27dbb6944917b60,
+];
+
+/// The 4x4 MDS matrix from which the external linear layer is built.
+pub const POSEIDON2_M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+/// The round constants of the permutation.
+#[derive(Debug, Clone)]
+pub struct Poseidon2RoundConstants {
+    /// The constants of the full rounds, in order.
+    pub external: [[u64; POSEIDON2_WIDTH]; 2 * POSEIDON2_HALF_N_FULL_ROUNDS],
+    /// The constants of the partial rounds, which are only added to the first element.
+    pub internal: [u64; POSEIDON2_N_PARTIAL_ROUNDS],
+}
+
+lazy_static! {
+    pub static ref POSEIDON2_ROUND_CONSTANTS: Poseidon2RoundConstants = {
+        let mut lfsr = GrainLfsr::new();
+        let mut external = [[0; POSEIDON2_WIDTH]; 2 * POSEIDON2_HALF_N_FULL_ROUNDS];
+        let mut internal = [0; POSEIDON2_N_PARTIAL_ROUNDS];
+        for round in external[..POSEIDON2_HALF_N_FULL_ROUNDS].iter_mut() {
+            round
+                .iter_mut()
+                .for_each(|c| *c = lfsr.next_field_element());
+        }
+        internal
+            .iter_mut()
+            .for_each(|c| *c = lfsr.next_field_element());
+        for round in external[POSEIDON2_HALF_N_FULL_ROUNDS..].iter_mut() {
+            round
+                .iter_mut()
+                .for_each(|c| *c = lfsr.next_field_element());
+        }
+        Poseidon2RoundConstants { external, internal }
+    };
+}
+
+/// The Grain LFSR used by the Poseidon reference implementation to sample round constants.
+struct GrainLfsr {
+    state: [bool; 80],
+}
+
+impl GrainLfsr {
+    fn new() -> Self {
+        let mut bits = Vec::with_capacity(80);
+        let mut push = |value: usize, nb_bits: usize| {
+            bits.extend((0..nb_bits).rev().map(|i| (value >> i) & 1 == 1));
+        };
+        // A prime field, the `x^alpha` S-box, the field size, the width and the number of rounds.
+        push(1, 2);
+        push(0, 4);
+        push(64, 12);
+        push(POSEIDON2_WIDTH, 12);
+        push(2 * POSEIDON2_HALF_N_FULL_ROUNDS, 10);
+        push(POSEIDON2_N_PARTIAL_ROUNDS, 10);
+        bits.extend([true; 30]);
+
+        let mut lfsr = Self {
+            state: bits.try_into().unwrap(),
+        };
+        for _ in 0..160 {
+            lfsr.next_bit();
+        }
+        lfsr
+    }
+
+    fn next_bit(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.rotate_left(1);
+        self.state[79] = bit;
+        bit
+    }
+
+    /// The self-shrinking output of the LFSR: a bit is kept iff the bit before it is set.
+    fn next_shrunk_bit(&mut self) -> bool {
+        loop {
+            let keep = self.next_bit();
+            let bit = self.next_bit();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    /// Samples a canonical Goldilocks element by rejection.
+    fn next_field_element(&mut self) -> u64 {
+        loop {
+            let value = (0..64).fold(0u64, |acc, _| (acc << 1) | self.next_shrunk_bit() as u64);
+            if value < GoldilocksField::ORDER {
+                return value;
+            }
+        }
+    }
+}
+
+/// The S-box `x^7`.
+pub fn poseidon2_sbox<F: Field>(x: F) -> F {
+    let x2 = x.square();
+    let x4 = x2.square();
+    x4 * x2 * x
+}
+
+/// Applies the external linear layer, `circ(2 * M4, M4, M4)`, to the state.
+pub fn poseidon2_external_linear_layer<F: Field>(state: &mut [F; POSEIDON2_WIDTH]) {
+    let mut chunks = [F::ZERO; POSEIDON2_WIDTH];
+    for (chunk, input) in chunks.chunks_exact_mut(4).zip(state.chunks_exact(4)) {
+        for (out, row) in chunk.iter_mut().zip(POSEIDON2_M4.iter()) {
+            *out = row
+                .iter()
+                .zip(input.iter())
+                .map(|(m, x)| *x * F::from_canonical_u64(*m))
+                .sum();
+        }
+    }
+    let mut sums = [F::ZERO; 4];
+    for chunk in chunks.chunks_exact(4) {
+        for (sum, x) in sums.iter_mut().zip(chunk.iter()) {
+            *sum += *x;
+        }
+    }
+    for (i, x) in state.iter_mut().enumerate() {
+        *x = chunks[i] + sums[i % 4];
+    }
+}
+
+/// Applies the internal linear layer, `1 + diag(POSEIDON2_INTERNAL_DIAG)`, to the state.
+pub fn poseidon2_internal_linear_layer<F: Field>(state: &mut [F; POSEIDON2_WIDTH]) {
+    let sum: F = state.iter().copied().sum();
+    for (x, diag) in state.iter_mut().zip(POSEIDON2_INTERNAL_DIAG.iter()) {
+        *x = *x * F::from_canonical_u64(*diag) + sum;
+    }
+}
+
+/// Adds the constants of a full round to the state.
+pub fn poseidon2_add_round_constants<F: Field>(state: &mut [F; POSEIDON2_WIDTH], round: usize) {
+    for (x, c) in state
+        .iter_mut()
+        .zip(POSEIDON2_ROUND_CONSTANTS.external[round].iter())
+    {
+        *x += F::from_canonical_u64(*c);
+    }
+}
+
+/// Applies the Poseidon2 permutation to the state.
+pub fn poseidon2_permute<F: Field>(mut state: [F; POSEIDON2_WIDTH]) -> [F; POSEIDON2_WIDTH] {
+    poseidon2_external_linear_layer(&mut state);
+
+    for round in 0..POSEIDON2_HALF_N_FULL_ROUNDS {
+        poseidon2_add_round_constants(&mut state, round);
+        state = state.map(poseidon2_sbox);
+        poseidon2_external_linear_layer(&mut state);
+    }
+
+    for c in POSEIDON2_ROUND_CONSTANTS.internal.iter() {
+        state[0] = poseidon2_sbox(state[0] + F::from_canonical_u64(*c));
+        poseidon2_internal_linear_layer(&mut state);
+    }
+
+    for round in POSEIDON2_HALF_N_FULL_ROUNDS..2 * POSEIDON2_HALF_N_FULL_ROUNDS {
+        poseidon2_add_round_constants(&mut state, round);
+        state = state.map(poseidon2_sbox);
+        poseidon2_external_linear_layer(&mut state);
+    }
+
+    state
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use plonky2::field::types::Sample;
+
+    use super::*;
+
+    type F = GoldilocksField;
+
+    /// The output of the permutation on `[0, 1, ..., 11]`, from the known-answer test of the
+    /// Goldilocks instance with width 12 of the reference implementation
+    /// (https://github.com/HorizenLabs/poseidon2).
+    pub(crate) const POSEIDON2_REFERENCE_OUTPUT: [u64; POSEIDON2_WIDTH] = [
+        0x01eaef96bdf1c0c1,
+        0x1f0d2cc525b2540c,
+        0x6282c1dfe1e0358d,
+        0xe780d721f698e1e6,
+        0x280c0b6f753d833b,
+        0x1b942dd5023156ab,
+        0x43f0df3fcccb8398,
+        0xe8e8190585489025,
+        0x56bdbf72f77ada22,
+        0x7911c32bf9dcd705,
+        0xec467926508fbe67,
+        0x6a50450ddf85a6ed,
+    ];
+
+    #[test]
+    fn test_poseidon2_round_constants() {
+        let constants = &*POSEIDON2_ROUND_CONSTANTS;
+        let all = constants
+            .external
+            .iter()
+            .flatten()
+            .chain(constants.internal.iter())
+            .collect::<Vec<_>>();
+        assert!(all.iter().all(|c| **c < F::ORDER));
+        let mut deduped = all.clone();
+        deduped.sort();
+        deduped.dedup();
+        assert_eq!(deduped.len(), all.len());
+    }
+
+    #[test]
+    fn test_poseidon2_reference_round_constants() {
+        // The first constants of the reference implementation.
+        let constants = &*POSEIDON2_ROUND_CONSTANTS;
+        assert_eq!(constants.external[0][0], 0x13dcf33aba214f46);
+        assert_eq!(constants.external[0][1], 0x30b3b654a1da6d83);
+        assert_eq!(constants.internal[0], 0x4adf842aa75d4316);
+    }
+
+    #[test]
+    fn test_poseidon2_reference_output() {
+        let input = core::array::from_fn::<_, POSEIDON2_WIDTH, _>(F::from_canonical_usize);
+        let output = poseidon2_permute(input);
+        assert_eq!(
+            output,
+            POSEIDON2_REFERENCE_OUTPUT.map(F::from_canonical_u64)
+        );
+    }
+
+    #[test]
+    fn test_poseidon2_internal_linear_layer() {
+        // The internal layer is `x -> diag * x + sum(x)`, check it against the dense matrix.
+        let input = F::rand_array::<POSEIDON2_WIDTH>();
+        let mut state = input;
+        poseidon2_internal_linear_layer(&mut state);
+        for (i, x) in state.iter().enumerate() {
+            let expected = (0..POSEIDON2_WIDTH)
+                .map(|j| {
+                    let mut m = F::ONE;
+                    if i == j {
+                        m += F::from_canonical_u64(POSEIDON2_INTERNAL_DIAG[i]);
+                    }
+                    m * input[j]
+                })
+                .sum::<F>();
+            assert_eq!(*x, expected);
+        }
+    }
+
+    #[test]
+    fn test_poseidon2_permute() {
+        let input = F::rand_array::<POSEIDON2_WIDTH>();
+        let output = poseidon2_permute(input);
+        assert_eq!(output, poseidon2_permute(input));
+        assert_ne!(output, input);
+
+        // Flipping a single input element changes every output element.
+        let mut flipped = input;
+        flipped[POSEIDON2_WIDTH - 1] += F::ONE;
+        let flipped_output = poseidon2_permute(flipped);
+        assert!(output
+            .iter()
+            .zip(flipped_output.iter())
+            .all(|(a, b)| a != b));
+    }
+}
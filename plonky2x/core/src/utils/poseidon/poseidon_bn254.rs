@@ -0,0 +1,158 @@
+//! A native implementation of circomlib's Poseidon over the BN254 scalar field.
+//!
+//! `poseidon_bn254_hash` of `n` inputs uses a state of width `n + 1`, whose first element is zero,
+//! and returns the first element of the permuted state. This is `Poseidon(n)` of circomlib and
+//! `PoseidonT{n + 1}.hash` of the Solidity implementations, for `n = 2` and `n = 3`.
+//!
+//! The width 4 permutation is the one of `backend::wrapper::poseidon_bn128`.
+
+use ff::PrimeField;
+use lazy_static::lazy_static;
+use num::BigUint;
+
+use super::poseidon_bn254_constants::{POSEIDON_BN254_T3_MDS, POSEIDON_BN254_T3_ROUND_CONSTANTS};
+use crate::backend::wrapper::poseidon_bn128::{permution, PoseidonState};
+use crate::backend::wrapper::utils::Fr;
+
+/// The number of full rounds, half of which are before the partial rounds.
+pub const POSEIDON_BN254_FULL_ROUNDS: usize = 8;
+
+/// The parameters of the Poseidon permutation for a given width.
+#[derive(Debug, Clone)]
+pub struct PoseidonBN254Params {
+    pub width: usize,
+    pub partial_rounds: usize,
+    /// The round constants, `width` per round.
+    pub round_constants: Vec<BigUint>,
+    pub mds: Vec<Vec<BigUint>>,
+}
+
+impl PoseidonBN254Params {
+    fn new<const WIDTH: usize>(
+        partial_rounds: usize,
+        round_constants: &[&str],
+        mds: &[[&str; WIDTH]; WIDTH],
+    ) -> Self {
+        let parse = |s: &&str| s.parse::<BigUint>().unwrap();
+        Self {
+            width: WIDTH,
+            partial_rounds,
+            round_constants: round_constants.iter().map(parse).collect(),
+            mds: mds
+                .iter()
+                .map(|row| row.iter().map(parse).collect())
+                .collect(),
+        }
+    }
+
+    /// Whether `round` is a full round.
+    pub fn is_full_round(&self, round: usize) -> bool {
+        round < POSEIDON_BN254_FULL_ROUNDS / 2
+            || round >= POSEIDON_BN254_FULL_ROUNDS / 2 + self.partial_rounds
+    }
+
+    /// The total number of rounds.
+    pub fn nb_rounds(&self) -> usize {
+        POSEIDON_BN254_FULL_ROUNDS + self.partial_rounds
+    }
+}
+
+lazy_static! {
+    /// The modulus of the BN254 scalar field.
+    pub static ref POSEIDON_BN254_MODULUS: BigUint =
+        "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            .parse()
+            .unwrap();
+    /// The parameters of the width 3 permutation.
+    pub static ref POSEIDON_BN254_T3_PARAMS: PoseidonBN254Params = PoseidonBN254Params::new(
+        57,
+        &POSEIDON_BN254_T3_ROUND_CONSTANTS,
+        &POSEIDON_BN254_T3_MDS,
+    );
+}
+
+/// Converts an element of the scalar field of `backend::wrapper::utils` to a `BigUint`.
+pub fn poseidon_bn254_fr_to_biguint(x: &Fr) -> BigUint {
+    BigUint::from_bytes_le(x.to_repr().as_ref())
+}
+
+/// Applies the Poseidon permutation to a state of width 3 or 4.
+pub fn poseidon_bn254_permute(state: &[BigUint]) -> Vec<BigUint> {
+    let p = &*POSEIDON_BN254_MODULUS;
+    let params = match state.len() {
+        3 => &*POSEIDON_BN254_T3_PARAMS,
+        4 => {
+            let mut state: PoseidonState = state
+                .iter()
+                .map(|x| Fr::from_str_vartime(&(x % p).to_string()).unwrap())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+            permution(&mut state);
+            return state.iter().map(poseidon_bn254_fr_to_biguint).collect();
+        }
+        _ => panic!("poseidon over bn254 is only supported for widths 3 and 4"),
+    };
+    let five = BigUint::from(5u32);
+
+    let mut state = state.iter().map(|x| x % p).collect::<Vec<_>>();
+    for (round, constants) in params
+        .round_constants
+        .chunks_exact(params.width)
+        .enumerate()
+    {
+        for (x, c) in state.iter_mut().zip(constants.iter()) {
+            *x = (&*x + c) % p;
+        }
+        if params.is_full_round(round) {
+            for x in state.iter_mut() {
+                *x = x.modpow(&five, p);
+            }
+        } else {
+            state[0] = state[0].modpow(&five, p);
+        }
+        state = params
+            .mds
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(state.iter())
+                    .map(|(m, x)| m * x)
+                    .sum::<BigUint>()
+                    % p
+            })
+            .collect();
+    }
+    state
+}
+
+/// Computes the Poseidon hash of 2 or 3 field elements, as circomlib's `Poseidon(inputs.len())`.
+pub fn poseidon_bn254_hash(inputs: &[BigUint]) -> BigUint {
+    let mut state = vec![BigUint::from(0u32)];
+    state.extend_from_slice(inputs);
+    poseidon_bn254_permute(&state).swap_remove(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poseidon_bn254_hash() {
+        // The test vectors of circomlib's `Poseidon(2)` and `Poseidon(3)`.
+        let hash = poseidon_bn254_hash(&[BigUint::from(1u32), BigUint::from(2u32)]);
+        assert_eq!(
+            hash.to_string(),
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+        );
+        let hash = poseidon_bn254_hash(&[
+            BigUint::from(1u32),
+            BigUint::from(2u32),
+            BigUint::from(3u32),
+        ]);
+        assert_eq!(
+            hash.to_string(),
+            "6542985608222806190361240322586112750744169038454362455181422643027100751666"
+        );
+    }
+}
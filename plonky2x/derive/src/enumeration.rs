@@ -0,0 +1,303 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{DataEnum, Fields, Type};
+
+struct EnumData {
+    variants: Vec<(Ident, Option<Type>)>,
+}
+
+fn parse_enum_data(data: DataEnum) -> EnumData {
+    let variants = data
+        .variants
+        .into_iter()
+        .map(|v| {
+            let ty = match v.fields {
+                Fields::Unit => None,
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                    Some(fields.unnamed.into_iter().next().unwrap().ty)
+                }
+                _ => panic!("enum variants must be unit variants or have a single unnamed field"),
+            };
+            (v.ident, ty)
+        })
+        .collect::<Vec<_>>();
+    if variants.is_empty() {
+        panic!("Cannot derive CircuitVariable for an enum with no variants");
+    }
+    EnumData { variants }
+}
+
+fn to_snake_case(ident: &Ident) -> String {
+    let mut snake = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// Derives a tagged-union circuit variable for an enum whose variants hold circuit variables.
+///
+/// For an enum `Msg { A(X), B(Y) }`, this generates:
+/// - `MsgValue<F>`, the enum of values with the same variants,
+/// - `MsgVariable`, a selector plus a payload padded to the size of the largest variant, which
+///   implements `CircuitVariable` and `EnumVariable<Variant = Msg>`,
+/// - `MsgVariable::is_a` and `MsgVariable::is_b`, which check the selector.
+pub(crate) fn derive_enum_variable(
+    name: &Ident,
+    value_ident: &Ident,
+    variable_ident: &Ident,
+    value_derive: &[Ident],
+    data: DataEnum,
+) -> TokenStream {
+    let data = parse_enum_data(data);
+    let nb_variants = data.variants.len();
+
+    let value_variants = data.variants.iter().map(|(ident, ty)| match ty {
+        Some(ty) => quote! { #ident(<#ty as CircuitVariable>::ValueType<F>), },
+        None => quote! { #ident, },
+    });
+    // If no variant holds a variable, the field parameter would be unused.
+    let phantom_variant = if data.variants.iter().all(|(_, ty)| ty.is_none()) {
+        quote! {
+            #[doc(hidden)]
+            _Phantom(std::marker::PhantomData<F>, std::convert::Infallible),
+        }
+    } else {
+        quote! {}
+    };
+
+    let payload_len_recurse = data
+        .variants
+        .iter()
+        .filter_map(|(_, ty)| ty.as_ref())
+        .map(|ty| quote! { <#ty as CircuitVariable>::nb_elements() });
+    let payload_len = quote! { [0 #(, #payload_len_recurse)*].into_iter().max().unwrap() };
+
+    let is_variant = data.variants.iter().enumerate().map(|(i, (ident, _))| {
+        let fn_ident = format_ident!("is_{}", to_snake_case(ident));
+        let doc = format!("Whether the variable holds the `{}` variant.", ident);
+        quote! {
+            #[doc = #doc]
+            pub fn #fn_ident<L: PlonkParameters<D>, const D: usize>(
+                &self,
+                builder: &mut CircuitBuilder<L, D>,
+            ) -> BoolVariable {
+                let index = builder.constant::<Variable>(L::Field::from_canonical_usize(#i));
+                builder.is_equal(self.selector, index)
+            }
+        }
+    });
+
+    let assert_variant_is_valid = data.variants.iter().enumerate().map(|(i, (_, ty))| {
+        let len = match ty {
+            Some(ty) => quote! { <#ty as CircuitVariable>::nb_elements() },
+            None => quote! { 0 },
+        };
+        let assert_payload = match ty {
+            Some(ty) => quote! {
+                <#ty as CircuitVariable>::from_variables_unsafe(&masked[..len])
+                    .assert_is_valid(builder);
+            },
+            None => quote! {},
+        };
+        quote! {
+            let index = builder.constant::<Variable>(L::Field::from_canonical_usize(#i));
+            let is_active = builder.is_equal(self.selector, index);
+            nb_active = builder.add(nb_active, is_active.variable);
+            let masked = self
+                .payload
+                .iter()
+                .map(|v| builder.select(is_active, *v, zero))
+                .collect::<Vec<_>>();
+            let len = #len;
+            #assert_payload
+            for v in masked[len..].iter() {
+                builder.assert_is_equal(*v, zero);
+            }
+        }
+    });
+
+    let elements_arms = data.variants.iter().enumerate().map(|(i, (ident, ty))| {
+        let payload = match ty {
+            Some(ty) => quote! { <#ty as CircuitVariable>::elements(v) },
+            None => quote! { vec![] },
+        };
+        let pattern = match ty {
+            Some(_) => quote! { #value_ident::#ident(v) },
+            None => quote! { #value_ident::#ident },
+        };
+        quote! {
+            #pattern => (#i, #payload),
+        }
+    });
+    let elements_phantom_arm = if data.variants.iter().all(|(_, ty)| ty.is_none()) {
+        quote! { #value_ident::_Phantom(_, never) => match never {}, }
+    } else {
+        quote! {}
+    };
+
+    let from_elements_arms = data.variants.iter().enumerate().map(|(i, (ident, ty))| {
+        let variant = match ty {
+            Some(ty) => quote! {
+                #value_ident::#ident(<#ty as CircuitVariable>::from_elements(
+                    &payload[..<#ty as CircuitVariable>::nb_elements()],
+                ))
+            },
+            None => quote! { #value_ident::#ident },
+        };
+        quote! {
+            if selector == F::from_canonical_usize(#i) {
+                return #variant;
+            }
+        }
+    });
+
+    let variants = data.variants.iter().map(|(ident, ty)| match ty {
+        Some(ty) => quote! {
+            #name::#ident(<#ty as CircuitVariable>::from_variables_unsafe(
+                &self.payload[..<#ty as CircuitVariable>::nb_elements()],
+            )),
+        },
+        None => quote! { #name::#ident, },
+    });
+
+    let from_variant_arms = data.variants.iter().enumerate().map(|(i, (ident, ty))| {
+        let (pattern, payload) = match ty {
+            Some(_) => (quote! { #name::#ident(v) }, quote! { v.variables() }),
+            None => (quote! { #name::#ident }, quote! { vec![] }),
+        };
+        quote! {
+            #pattern => (#i, #payload),
+        }
+    });
+
+    let value_derive_recurs = value_derive.iter().map(|d| quote! { #d, });
+    let variable_doc = format!("The circuit variable for the tagged union `{}`.", name);
+
+    quote! {
+        #[derive(#(#value_derive_recurs)*)]
+        pub enum #value_ident<F: RichField> {
+            #(#value_variants)*
+            #phantom_variant
+        }
+
+        #[doc = #variable_doc]
+        #[derive(Debug, Clone)]
+        pub struct #variable_ident {
+            /// The index of the active variant.
+            pub selector: Variable,
+            /// The variables of the active variant, padded with zeros.
+            pub payload: Vec<Variable>,
+        }
+
+        impl #variable_ident {
+            /// The size of the payload, i.e. the size of the largest variant.
+            pub fn payload_len() -> usize {
+                #payload_len
+            }
+
+            #(#is_variant)*
+        }
+
+        impl CircuitVariable for #variable_ident {
+            type ValueType<F: RichField> = #value_ident<F>;
+
+            fn init_unsafe<L: PlonkParameters<D>, const D: usize>(
+                builder: &mut CircuitBuilder<L, D>,
+            ) -> Self {
+                Self {
+                    selector: Variable::init_unsafe(builder),
+                    payload: (0..Self::payload_len())
+                        .map(|_| Variable::init_unsafe(builder))
+                        .collect(),
+                }
+            }
+
+            fn variables(&self) -> Vec<Variable> {
+                let mut vars_vec = vec![self.selector];
+                vars_vec.extend_from_slice(&self.payload);
+                vars_vec
+            }
+
+            fn from_variables_unsafe(variables: &[Variable]) -> Self {
+                assert_eq!(variables.len(), Self::nb_elements());
+                Self {
+                    selector: variables[0],
+                    payload: variables[1..].to_vec(),
+                }
+            }
+
+            fn assert_is_valid<L: PlonkParameters<D>, const D: usize>(
+                &self,
+                builder: &mut CircuitBuilder<L, D>,
+            ) {
+                // Exactly one variant is active, its payload is valid and its padding is zero.
+                let zero = builder.zero::<Variable>();
+                let one = builder.one::<Variable>();
+                let mut nb_active = zero;
+                #(#assert_variant_is_valid)*
+                builder.assert_is_equal(nb_active, one);
+            }
+
+            fn nb_elements() -> usize {
+                1 + Self::payload_len()
+            }
+
+            fn elements<F: RichField>(value: Self::ValueType<F>) -> Vec<F> {
+                let (selector, mut payload): (usize, Vec<F>) = match value {
+                    #(#elements_arms)*
+                    #elements_phantom_arm
+                };
+                payload.resize(Self::payload_len(), F::ZERO);
+                let mut elements_vec = vec![F::from_canonical_usize(selector)];
+                elements_vec.extend(payload);
+                elements_vec
+            }
+
+            fn from_elements<F: RichField>(elements: &[F]) -> Self::ValueType<F> {
+                let selector = elements[0];
+                let payload = &elements[1..];
+                #(#from_elements_arms)*
+                panic!("invalid selector for {}", stringify!(#name));
+            }
+        }
+
+        impl EnumVariable for #variable_ident {
+            type Variant = #name;
+
+            fn nb_variants() -> usize {
+                #nb_variants
+            }
+
+            fn selector(&self) -> Variable {
+                self.selector
+            }
+
+            fn variants(&self) -> Vec<Self::Variant> {
+                vec![#(#variants)*]
+            }
+
+            fn from_variant<L: PlonkParameters<D>, const D: usize>(
+                builder: &mut CircuitBuilder<L, D>,
+                variant: Self::Variant,
+            ) -> Self {
+                let (selector, mut payload): (usize, Vec<Variable>) = match variant {
+                    #(#from_variant_arms)*
+                };
+                let zero = builder.zero::<Variable>();
+                payload.resize(Self::payload_len(), zero);
+                Self {
+                    selector: builder.constant::<Variable>(L::Field::from_canonical_usize(selector)),
+                    payload,
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ extern crate proc_macro;
 mod assert_is_valid;
 mod constant;
 mod elements;
+mod enumeration;
 mod init;
 mod value;
 mod variables;
@@ -11,6 +12,7 @@ mod witness;
 use assert_is_valid::assert_is_valid;
 use constant::constant;
 use elements::{elements, from_elements, nb_elements};
+use enumeration::derive_enum_variable;
 use init::init_unsafe;
 use proc_macro2::Ident;
 use quote::quote;
@@ -26,20 +28,23 @@ struct StructData {
     fields: Vec<(Option<Ident>, Type, Visibility)>,
 }
 
-#[proc_macro_derive(CircuitVariable, attributes(value_name, value_derive))]
+#[proc_macro_derive(CircuitVariable, attributes(value_name, value_derive, variable_name))]
 pub fn derive_circuit_variable(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
     let name = input.ident;
-    let data = parse_struct_data(input.data);
 
     let mut value_ident = Ident::new(&format!("{}Value", name), name.span());
+    let mut variable_ident = Ident::new(&format!("{}Variable", name), name.span());
     let mut value_derive = vec![parse_quote!(Debug), parse_quote!(Clone)];
 
     for attr in &input.attrs {
         if attr.path().is_ident("value_name") {
             value_ident = attr.parse_args::<Ident>().unwrap();
         }
+        if attr.path().is_ident("variable_name") {
+            variable_ident = attr.parse_args::<Ident>().unwrap();
+        }
         if attr.path().is_ident("value_derive") {
             match attr.meta {
                 Meta::Path(ref path) => value_derive.push(path.get_ident().unwrap().clone()),
@@ -59,6 +64,17 @@ pub fn derive_circuit_variable(input: proc_macro::TokenStream) -> proc_macro::To
         }
     }
 
+    if let Data::Enum(data) = input.data {
+        if !input.generics.params.is_empty() {
+            panic!("Cannot derive CircuitVariable for enums with generic parameters");
+        }
+        let expanded =
+            derive_enum_variable(&name, &value_ident, &variable_ident, &value_derive, data);
+        return proc_macro::TokenStream::from(expanded);
+    }
+
+    let data = parse_struct_data(input.data);
+
     let mut generics = input.generics;
     make_where_clause(&data, &mut generics);
 
@@ -138,7 +154,7 @@ fn parse_struct_data(data: Data) -> StructData {
                 })
                 .collect(),
         },
-        Data::Enum(_) => unimplemented!("enums not supported"),
+        Data::Enum(_) => unreachable!("enums are handled by derive_enum_variable"),
         Data::Union(_) => unimplemented!("unions not supported"),
     }
 }